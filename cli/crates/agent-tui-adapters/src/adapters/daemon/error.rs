@@ -10,6 +10,10 @@ use serde_json::Value;
 use serde_json::json;
 use thiserror::Error;
 
+const RECORDING_SUGGESTION: &str = "Run 'record stop' or 'trace stop' before starting another capture, and check that the directory exists and is writable.";
+const REPLAY_SUGGESTION: &str = "Pass an asciinema v2 .cast file or a raw terminal byte log; check that the file exists and is readable.";
+const REGION_SUGGESTION: &str = "Regions are pane titles, pane ids (p1, p2, ...), 'focused', or left/right/top/bottom. Run 'screenshot --panes' to list panes.";
+
 /// Adapter-layer trait for presenting errors to external consumers.
 ///
 /// This trait keeps presentation concerns (error codes, categories, suggestions)
@@ -29,7 +33,10 @@ impl ErrorPresentation for SessionError {
             SessionError::AlreadyExists(_) => error_codes::SESSION_ALREADY_EXISTS,
             SessionError::NoActiveSession => error_codes::NO_ACTIVE_SESSION,
             SessionError::InvalidKey(_) => error_codes::INVALID_KEY,
+            SessionError::RegionNotFound { .. } => error_codes::REGION_NOT_FOUND,
             SessionError::LimitReached(_) => error_codes::SESSION_LIMIT,
+            SessionError::Recording { .. } => error_codes::RECORDING_ERROR,
+            SessionError::Replay { .. } => error_codes::REPLAY_ERROR,
             SessionError::Terminal(_) => error_codes::PTY_ERROR,
             SessionError::Persistence { .. } => error_codes::PERSISTENCE_ERROR,
         }
@@ -45,7 +52,13 @@ impl ErrorPresentation for SessionError {
             SessionError::AlreadyExists(id) => json!({ "session_id": id }),
             SessionError::NoActiveSession => json!({}),
             SessionError::InvalidKey(key) => json!({ "key": key }),
+            SessionError::RegionNotFound { region, available } => {
+                json!({ "region": region, "available": available })
+            }
             SessionError::LimitReached(max) => json!({ "max_sessions": max }),
+            SessionError::Recording { path, reason } | SessionError::Replay { path, reason } => {
+                json!({ "path": path, "reason": reason })
+            }
             SessionError::Terminal(terminal_err) => json!({
                 "operation": terminal_err.operation(),
                 "reason": terminal_err.reason()
@@ -69,6 +82,9 @@ impl ErrorPresentation for SessionError {
             SessionError::InvalidKey(_) => {
                 "Supported keys: Enter, Tab, Escape, Backspace, Delete, ArrowUp/Down/Left/Right, Home, End, PageUp/Down, F1-F12. Modifiers: Ctrl+, Alt+, Shift+".to_string()
             }
+            SessionError::RegionNotFound { .. } => REGION_SUGGESTION.to_string(),
+            SessionError::Recording { .. } => RECORDING_SUGGESTION.to_string(),
+            SessionError::Replay { .. } => REPLAY_SUGGESTION.to_string(),
             SessionError::LimitReached(_) => {
                 "Kill unused sessions with 'kill <session_id>' or increase limit with AGENT_TUI_MAX_SESSIONS env var.".to_string()
             }
@@ -100,6 +116,10 @@ impl ErrorPresentation for SessionError {
                 TerminalError::Resize { .. } => {
                     "Failed to resize terminal. Try again or restart the session.".to_string()
                 }
+                TerminalError::Signal { .. } => {
+                    "Failed to signal the process. The session may have ended. Run 'sessions' to check status."
+                        .to_string()
+                }
             },
             SessionError::Persistence { .. } => {
                 "Persistence error is non-fatal. Session continues to operate normally.".to_string()
@@ -130,9 +150,21 @@ pub enum DomainError {
     #[error("Invalid key: {key}")]
     InvalidKey { key: String },
 
+    #[error("Region not found: {region}")]
+    RegionNotFound {
+        region: String,
+        available: Vec<String>,
+    },
+
     #[error("Session limit reached: maximum {max} sessions allowed")]
     SessionLimitReached { max: usize },
 
+    #[error("Cannot record to {path}: {reason}")]
+    Recording { path: String, reason: String },
+
+    #[error("Cannot replay {path}: {reason}")]
+    Replay { path: String, reason: String },
+
     #[error("Lock timeout{}", session_id.as_ref().map(|id| format!(" for session: {}", id)).unwrap_or_default())]
     LockTimeout { session_id: Option<String> },
 
@@ -163,7 +195,10 @@ impl DomainError {
             DomainError::SessionAlreadyExists { .. } => error_codes::SESSION_ALREADY_EXISTS,
             DomainError::NoActiveSession => error_codes::NO_ACTIVE_SESSION,
             DomainError::InvalidKey { .. } => error_codes::INVALID_KEY,
+            DomainError::RegionNotFound { .. } => error_codes::REGION_NOT_FOUND,
             DomainError::SessionLimitReached { .. } => error_codes::SESSION_LIMIT,
+            DomainError::Recording { .. } => error_codes::RECORDING_ERROR,
+            DomainError::Replay { .. } => error_codes::REPLAY_ERROR,
             DomainError::LockTimeout { .. } => error_codes::LOCK_TIMEOUT,
             DomainError::TerminalError { .. } => error_codes::PTY_ERROR,
             DomainError::WaitTimeout { .. } => error_codes::WAIT_TIMEOUT,
@@ -189,9 +224,15 @@ impl DomainError {
             DomainError::InvalidKey { key } => {
                 json!({ "key": key })
             }
+            DomainError::RegionNotFound { region, available } => {
+                json!({ "region": region, "available": available })
+            }
             DomainError::SessionLimitReached { max } => {
                 json!({ "max_sessions": max })
             }
+            DomainError::Recording { path, reason } | DomainError::Replay { path, reason } => {
+                json!({ "path": path, "reason": reason })
+            }
             DomainError::LockTimeout { session_id } => match session_id {
                 Some(id) => json!({ "session_id": id }),
                 None => json!({}),
@@ -236,6 +277,9 @@ impl DomainError {
             DomainError::InvalidKey { .. } => {
                 "Supported keys: Enter, Tab, Escape, Backspace, Delete, ArrowUp/Down/Left/Right, Home, End, PageUp/Down, F1-F12. Modifiers: Ctrl+, Alt+, Shift+".to_string()
             }
+            DomainError::RegionNotFound { .. } => REGION_SUGGESTION.to_string(),
+            DomainError::Recording { .. } => RECORDING_SUGGESTION.to_string(),
+            DomainError::Replay { .. } => REPLAY_SUGGESTION.to_string(),
             DomainError::SessionLimitReached { .. } => {
                 "Kill unused sessions with 'kill <session_id>' or increase limit with AGENT_TUI_MAX_SESSIONS env var.".to_string()
             }
@@ -278,7 +322,12 @@ impl From<SessionError> for DomainError {
             SessionError::AlreadyExists(id) => DomainError::SessionAlreadyExists { session_id: id },
             SessionError::NoActiveSession => DomainError::NoActiveSession,
             SessionError::InvalidKey(key) => DomainError::InvalidKey { key },
+            SessionError::RegionNotFound { region, available } => {
+                DomainError::RegionNotFound { region, available }
+            }
             SessionError::LimitReached(max) => DomainError::SessionLimitReached { max },
+            SessionError::Recording { path, reason } => DomainError::Recording { path, reason },
+            SessionError::Replay { path, reason } => DomainError::Replay { path, reason },
             SessionError::Terminal(terminal_err) => DomainError::TerminalError {
                 operation: terminal_err.operation().to_string(),
                 reason: terminal_err.reason().to_string(),
@@ -399,6 +448,41 @@ mod tests {
         assert!(err.suggestion().contains("Enter"));
     }
 
+    #[test]
+    fn test_session_error_region_not_found() {
+        let err = SessionError::RegionNotFound {
+            region: "Files".into(),
+            available: vec!["Log".into()],
+        };
+        assert_eq!(err.code(), error_codes::REGION_NOT_FOUND);
+        assert_eq!(err.category(), ErrorCategory::NotFound);
+        assert_eq!(err.context()["available"][0], "Log");
+        assert!(err.suggestion().contains("--panes"));
+    }
+
+    #[test]
+    fn test_session_error_recording() {
+        let err = SessionError::Recording {
+            path: "/tmp/run.cast".into(),
+            reason: "already recording to /tmp/a.cast".into(),
+        };
+        assert_eq!(err.code(), error_codes::RECORDING_ERROR);
+        assert_eq!(err.category(), ErrorCategory::InvalidInput);
+        assert!(!err.is_retryable());
+        assert!(err.suggestion().contains("record stop"));
+    }
+
+    #[test]
+    fn test_session_error_replay() {
+        let err = SessionError::Replay {
+            path: "/tmp/run.cast".into(),
+            reason: "line 3: expected [time, code, data]".into(),
+        };
+        assert_eq!(err.code(), error_codes::REPLAY_ERROR);
+        assert_eq!(err.category(), ErrorCategory::InvalidInput);
+        assert_eq!(DomainError::from(err).context()["path"], "/tmp/run.cast");
+    }
+
     #[test]
     fn test_session_error_is_retryable() {
         assert!(!SessionError::NotFound("x".into()).is_retryable());
@@ -5,12 +5,15 @@ use crate::adapters::rpc::RpcResponse;
 
 use super::common;
 use super::common::session_error_response;
+use crate::adapters::parse_session_input;
 use crate::adapters::parse_terminal_write_input;
 use crate::adapters::shutdown_output_to_response;
 use crate::adapters::terminal_write_output_to_response;
+use crate::adapters::tty_state_output_to_response;
 use crate::domain::ShutdownInput;
 use crate::usecases::ShutdownUseCase;
 use crate::usecases::TerminalWriteUseCase;
+use crate::usecases::TtyStateUseCase;
 
 pub fn handle_terminal_write_uc<U: TerminalWriteUseCase>(
     usecase: &U,
@@ -29,6 +32,17 @@ pub fn handle_terminal_write_uc<U: TerminalWriteUseCase>(
     }
 }
 
+pub fn handle_tty_state_uc<U: TtyStateUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "tty_state").entered();
+    let req_id = request.id;
+    let input = parse_session_input(&request);
+
+    match usecase.execute(input) {
+        Ok(output) => tty_state_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
+
 pub fn handle_shutdown_uc<U: ShutdownUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
     let _span = common::handler_span(&request, "shutdown").entered();
     let req_id = request.id;
@@ -5,13 +5,19 @@ use crate::adapters::rpc::RpcResponse;
 
 use super::common;
 use super::common::session_error_response;
+use crate::adapters::fill_output_to_response;
+use crate::adapters::parse_fill_input;
 use crate::adapters::parse_keydown_input;
 use crate::adapters::parse_keystroke_input;
 use crate::adapters::parse_keyup_input;
+use crate::adapters::parse_select_input;
 use crate::adapters::parse_type_input;
+use crate::adapters::select_output_to_response;
+use crate::usecases::FillUseCase;
 use crate::usecases::KeydownUseCase;
 use crate::usecases::KeystrokeUseCase;
 use crate::usecases::KeyupUseCase;
+use crate::usecases::SelectUseCase;
 use crate::usecases::TypeUseCase;
 
 pub fn handle_keystroke_uc<U: KeystrokeUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
@@ -69,3 +75,31 @@ pub fn handle_keyup_uc<U: KeyupUseCase>(usecase: &U, request: RpcRequest) -> Rpc
         Err(e) => session_error_response(req_id, e),
     }
 }
+
+pub fn handle_select_uc<U: SelectUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "select").entered();
+    let req_id = request.id;
+    let input = match parse_select_input(&request) {
+        Ok(i) => i,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => select_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
+
+pub fn handle_fill_uc<U: FillUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "fill").entered();
+    let req_id = request.id;
+    let input = match parse_fill_input(&request) {
+        Ok(i) => i,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => fill_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
@@ -13,22 +13,38 @@ use crate::adapters::kill_output_to_response;
 use crate::adapters::parse_assert_input;
 use crate::adapters::parse_attach_input;
 use crate::adapters::parse_cleanup_input;
+use crate::adapters::parse_record_start_input;
+use crate::adapters::parse_replay_input;
 use crate::adapters::parse_resize_input;
 use crate::adapters::parse_session_input;
+use crate::adapters::parse_signal_input;
 use crate::adapters::parse_spawn_input;
+use crate::adapters::parse_trace_start_input;
+use crate::adapters::record_start_output_to_response;
+use crate::adapters::record_stop_output_to_response;
+use crate::adapters::replay_output_to_response;
 use crate::adapters::resize_output_to_response;
 use crate::adapters::restart_output_to_response;
 use crate::adapters::session_error_response;
 use crate::adapters::sessions_output_to_response;
+use crate::adapters::signal_output_to_response;
 use crate::adapters::spawn_output_to_response;
+use crate::adapters::trace_start_output_to_response;
+use crate::adapters::trace_stop_output_to_response;
 use crate::usecases::AssertUseCase;
 use crate::usecases::AttachUseCase;
 use crate::usecases::CleanupUseCase;
 use crate::usecases::KillUseCase;
+use crate::usecases::RecordStartUseCase;
+use crate::usecases::RecordStopUseCase;
+use crate::usecases::ReplayUseCase;
 use crate::usecases::ResizeUseCase;
 use crate::usecases::RestartUseCase;
 use crate::usecases::SessionsUseCase;
+use crate::usecases::SignalUseCase;
 use crate::usecases::SpawnUseCase;
+use crate::usecases::TraceStartUseCase;
+use crate::usecases::TraceStopUseCase;
 use crate::usecases::ports::SessionError;
 
 pub fn handle_spawn<U: SpawnUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
@@ -44,6 +60,19 @@ pub fn handle_spawn<U: SpawnUseCase>(usecase: &U, request: RpcRequest) -> RpcRes
     }
 }
 
+pub fn handle_replay<U: ReplayUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "replay").entered();
+    let input = match parse_replay_input(&request) {
+        Ok(input) => input,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => replay_output_to_response(request.id, output),
+        Err(e) => session_error_response(request.id, e),
+    }
+}
+
 pub fn handle_kill<U: KillUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
     let _span = common::handler_span(&request, "kill").entered();
     let input = parse_session_input(&request);
@@ -83,6 +112,65 @@ pub fn handle_resize<U: ResizeUseCase>(usecase: &U, request: RpcRequest) -> RpcR
     }
 }
 
+pub fn handle_signal<U: SignalUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "signal").entered();
+    let input = match parse_signal_input(&request) {
+        Ok(input) => input,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => signal_output_to_response(request.id, output),
+        Err(e) => session_error_response(request.id, e),
+    }
+}
+
+pub fn handle_record_start<U: RecordStartUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "record_start").entered();
+    let input = match parse_record_start_input(&request) {
+        Ok(input) => input,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => record_start_output_to_response(request.id, output),
+        Err(e) => session_error_response(request.id, e),
+    }
+}
+
+pub fn handle_record_stop<U: RecordStopUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "record_stop").entered();
+    let input = parse_session_input(&request);
+
+    match usecase.execute(input) {
+        Ok(output) => record_stop_output_to_response(request.id, output),
+        Err(e) => session_error_response(request.id, e),
+    }
+}
+
+pub fn handle_trace_start<U: TraceStartUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "trace_start").entered();
+    let input = match parse_trace_start_input(&request) {
+        Ok(input) => input,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => trace_start_output_to_response(request.id, output),
+        Err(e) => session_error_response(request.id, e),
+    }
+}
+
+pub fn handle_trace_stop<U: TraceStopUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "trace_stop").entered();
+    let input = parse_session_input(&request);
+
+    match usecase.execute(input) {
+        Ok(output) => trace_stop_output_to_response(request.id, output),
+        Err(e) => session_error_response(request.id, e),
+    }
+}
+
 pub fn handle_attach<U: AttachUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
     let _span = common::handler_span(&request, "attach").entered();
     let req_id = request.id;
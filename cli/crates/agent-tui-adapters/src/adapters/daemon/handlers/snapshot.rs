@@ -5,9 +5,26 @@ use crate::adapters::rpc::RpcResponse;
 
 use super::common;
 use super::common::session_error_response;
+use crate::adapters::assert_screen_output_to_response;
+use crate::adapters::elements_output_to_response;
+use crate::adapters::find_output_to_response;
+use crate::adapters::panes_output_to_response;
+use crate::adapters::parse_assert_screen_input;
+use crate::adapters::parse_find_input;
+use crate::adapters::parse_session_input;
+use crate::adapters::parse_snapshot_diff_input;
 use crate::adapters::parse_snapshot_input;
+use crate::adapters::parse_table_input;
+use crate::adapters::snapshot_diff_output_to_response;
 use crate::adapters::snapshot_output_to_response;
+use crate::adapters::table_output_to_response;
+use crate::usecases::AssertScreenUseCase;
+use crate::usecases::ElementsUseCase;
+use crate::usecases::FindUseCase;
+use crate::usecases::PanesUseCase;
+use crate::usecases::SnapshotDiffUseCase;
 use crate::usecases::SnapshotUseCase;
+use crate::usecases::TableUseCase;
 
 pub fn handle_snapshot_uc<U: SnapshotUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
     let _span = common::handler_span(&request, "snapshot").entered();
@@ -19,3 +36,84 @@ pub fn handle_snapshot_uc<U: SnapshotUseCase>(usecase: &U, request: RpcRequest)
         Err(e) => session_error_response(request.id, e),
     }
 }
+
+pub fn handle_assert_screen_uc<U: AssertScreenUseCase>(
+    usecase: &U,
+    request: RpcRequest,
+) -> RpcResponse {
+    let _span = common::handler_span(&request, "assert_screen").entered();
+    let req_id = request.id;
+    let input = match parse_assert_screen_input(&request) {
+        Ok(i) => i,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => assert_screen_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
+
+pub fn handle_snapshot_diff_uc<U: SnapshotDiffUseCase>(
+    usecase: &U,
+    request: RpcRequest,
+) -> RpcResponse {
+    let _span = common::handler_span(&request, "snapshot_diff").entered();
+    let req_id = request.id;
+    let input = match parse_snapshot_diff_input(&request) {
+        Ok(i) => i,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => snapshot_diff_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
+
+pub fn handle_find_uc<U: FindUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "find").entered();
+    let req_id = request.id;
+    let input = match parse_find_input(&request) {
+        Ok(i) => i,
+        Err(resp) => return resp,
+    };
+
+    match usecase.execute(input) {
+        Ok(output) => find_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
+
+pub fn handle_elements_uc<U: ElementsUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "elements").entered();
+    let req_id = request.id;
+    let input = parse_session_input(&request);
+
+    match usecase.execute(input) {
+        Ok(output) => elements_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
+
+pub fn handle_panes_uc<U: PanesUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "panes").entered();
+    let req_id = request.id;
+    let input = parse_session_input(&request);
+
+    match usecase.execute(input) {
+        Ok(output) => panes_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
+
+pub fn handle_table_uc<U: TableUseCase>(usecase: &U, request: RpcRequest) -> RpcResponse {
+    let _span = common::handler_span(&request, "table").entered();
+    let req_id = request.id;
+    let input = parse_table_input(&request);
+
+    match usecase.execute(input) {
+        Ok(output) => table_output_to_response(req_id, output),
+        Err(e) => session_error_response(req_id, e),
+    }
+}
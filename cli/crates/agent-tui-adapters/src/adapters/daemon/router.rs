@@ -30,30 +30,63 @@ impl<'a, R: SessionRepository + 'static> Router<'a, R> {
             ),
 
             "spawn" => handlers::session::handle_spawn(&self.usecases.session.spawn, request),
+            "replay" => handlers::session::handle_replay(&self.usecases.session.replay, request),
             "kill" => handlers::session::handle_kill(&self.usecases.session.kill, request),
             "restart" => handlers::session::handle_restart(&self.usecases.session.restart, request),
             "sessions" => {
                 handlers::session::handle_sessions(&self.usecases.session.sessions, request)
             }
             "resize" => handlers::session::handle_resize(&self.usecases.session.resize, request),
+            "signal" => handlers::session::handle_signal(&self.usecases.session.signal, request),
+            "record_start" => {
+                handlers::session::handle_record_start(&self.usecases.session.record_start, request)
+            }
+            "record_stop" => {
+                handlers::session::handle_record_stop(&self.usecases.session.record_stop, request)
+            }
+            "trace_start" => {
+                handlers::session::handle_trace_start(&self.usecases.session.trace_start, request)
+            }
+            "trace_stop" => {
+                handlers::session::handle_trace_stop(&self.usecases.session.trace_stop, request)
+            }
             "attach" => handlers::session::handle_attach(&self.usecases.session.attach, request),
             "cleanup" => handlers::session::handle_cleanup(&self.usecases.session.cleanup, request),
             "assert" => handlers::session::handle_assert(&self.usecases.session.assert, request),
             "snapshot" => {
                 handlers::snapshot::handle_snapshot_uc(&self.usecases.snapshot.snapshot, request)
             }
+            "assert_screen" => handlers::snapshot::handle_assert_screen_uc(
+                &self.usecases.snapshot.assert_screen,
+                request,
+            ),
+            "snapshot_diff" => {
+                handlers::snapshot::handle_snapshot_diff_uc(&self.usecases.snapshot.diff, request)
+            }
+            "find" => handlers::snapshot::handle_find_uc(&self.usecases.snapshot.find, request),
+            "elements" => {
+                handlers::snapshot::handle_elements_uc(&self.usecases.snapshot.elements, request)
+            }
+            "panes" => handlers::snapshot::handle_panes_uc(&self.usecases.snapshot.panes, request),
+            "table" => handlers::snapshot::handle_table_uc(&self.usecases.snapshot.table, request),
             "keystroke" => {
                 handlers::input::handle_keystroke_uc(&self.usecases.input.keystroke, request)
             }
             "keydown" => handlers::input::handle_keydown_uc(&self.usecases.input.keydown, request),
             "keyup" => handlers::input::handle_keyup_uc(&self.usecases.input.keyup, request),
             "type" => handlers::input::handle_type_uc(&self.usecases.input.type_text, request),
+            "select" => handlers::input::handle_select_uc(&self.usecases.input.select, request),
+            "fill" => handlers::input::handle_fill_uc(&self.usecases.input.fill, request),
             "wait" => handlers::wait::handle_wait_uc(&self.usecases.wait, request),
 
             "pty_write" => handlers::diagnostics::handle_terminal_write_uc(
                 &self.usecases.diagnostics.terminal_write,
                 request,
             ),
+            "tty_state" => handlers::diagnostics::handle_tty_state_uc(
+                &self.usecases.diagnostics.tty_state,
+                request,
+            ),
             "shutdown" => handlers::diagnostics::handle_shutdown_uc(
                 &self.usecases.diagnostics.shutdown,
                 request,
@@ -71,9 +104,20 @@ impl<'a, R: SessionRepository + 'static> Router<'a, R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::ImageFormat;
+    use crate::domain::ProcessSignal;
+    use crate::domain::Recording;
+    use crate::domain::ReplayFormat;
+    use crate::domain::ReplayPacing;
+    use crate::domain::ReplaySummary;
     use crate::domain::SessionId;
     use crate::domain::SessionInfo;
+    use crate::domain::TraceSummary;
+    use crate::domain::TtyState;
     use crate::domain::core::CursorPosition;
+    use crate::domain::core::LogicalScreen;
+    use crate::domain::core::ScreenSnapshot;
+    use crate::domain::core::ScreenState;
     use crate::usecases::ports::Clock;
     use crate::usecases::ports::LivePreviewSnapshot;
     use crate::usecases::ports::SessionError;
@@ -120,10 +164,28 @@ mod tests {
             String::new()
         }
 
+        fn logical_screen(&self) -> LogicalScreen {
+            LogicalScreen::from_rows(
+                10,
+                vec![
+                    ("/usr/local".to_string(), true),
+                    ("/bin/tool".to_string(), false),
+                ],
+            )
+        }
+
         fn screen_render(&self) -> String {
             String::new()
         }
 
+        fn screen_image(&self, format: ImageFormat) -> Vec<u8> {
+            format.as_str().as_bytes().to_vec()
+        }
+
+        fn screen_snapshot(&self) -> ScreenSnapshot {
+            ScreenSnapshot::from_text("ready", 10, 2, self.cursor())
+        }
+
         fn terminal_write(&self, _data: &[u8]) -> Result<(), SessionError> {
             Ok(())
         }
@@ -180,6 +242,10 @@ mod tests {
             Ok(())
         }
 
+        fn signal(&self, _signal: ProcessSignal) -> Result<(), SessionError> {
+            Ok(())
+        }
+
         fn cursor(&self) -> CursorPosition {
             CursorPosition {
                 row: 0,
@@ -208,6 +274,52 @@ mod tests {
                 stream_seq: 0,
             }
         }
+
+        fn tty_state(&self) -> Option<TtyState> {
+            Some(TtyState {
+                echo: false,
+                icanon: true,
+                isig: true,
+            })
+        }
+
+        fn screen_idle_ms(&self) -> u64 {
+            0
+        }
+
+        fn screen_version(&self) -> u64 {
+            7
+        }
+
+        fn screen_state(&self) -> ScreenState {
+            ScreenState {
+                version: 7,
+                rows: vec!["ready".to_string(), String::new()],
+                cursor: self.cursor(),
+            }
+        }
+
+        fn screen_state_at(&self, _version: u64) -> Option<ScreenState> {
+            None
+        }
+
+        fn record_start(&self, _path: &str, _include_input: bool) -> Result<(), SessionError> {
+            Ok(())
+        }
+
+        fn record_stop(&self) -> Option<Recording> {
+            None
+        }
+
+        fn trace_start(&self, _path: &str) -> Result<(), SessionError> {
+            Ok(())
+        }
+
+        fn trace_stop(&self) -> Result<Option<TraceSummary>, SessionError> {
+            Ok(None)
+        }
+
+        fn record_wait(&self, _condition: &str, _found: bool, _elapsed_ms: u64, _before: &str) {}
     }
 
     #[derive(Default)]
@@ -231,6 +343,25 @@ mod tests {
             Ok((SessionId::new(id), 42))
         }
 
+        fn spawn_replay(
+            &self,
+            _path: &str,
+            _pacing: ReplayPacing,
+            session_id: Option<String>,
+            cols: u16,
+            rows: u16,
+        ) -> Result<(SessionId, ReplaySummary), SessionError> {
+            let id = session_id.unwrap_or_else(|| "test-session".to_string());
+            let summary = ReplaySummary {
+                format: ReplayFormat::Cast,
+                cols,
+                rows,
+                events: 0,
+                duration_ms: 0,
+            };
+            Ok((SessionId::new(id), summary))
+        }
+
         fn get(&self, session_id: &SessionId) -> Result<SessionHandle, SessionError> {
             Ok(Arc::new(TestSession {
                 id: session_id.clone(),
@@ -411,6 +542,276 @@ mod tests {
         assert_eq!(parsed["result"]["condition"], "session:nonexistent");
     }
 
+    #[test]
+    fn test_router_tty_state_reports_termios_flags() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "tty_state".to_string(), None);
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert!(parsed.get("error").is_none() || parsed["error"].is_null());
+        assert_eq!(parsed["result"]["session_id"], "active");
+        assert_eq!(parsed["result"]["available"], true);
+        assert_eq!(parsed["result"]["tty"]["echo"], false);
+        assert_eq!(parsed["result"]["tty"]["mode"], "canonical");
+        assert_eq!(parsed["result"]["tty"]["password_prompt"], true);
+    }
+
+    #[test]
+    fn test_router_find_unwrapped_reports_physical_span() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(
+            1,
+            "find".to_string(),
+            Some(json!({ "text": "local/bin", "unwrap": true })),
+        );
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let matches = parsed["result"]["matches"].as_array().unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0]["row"], 0);
+        assert_eq!(matches[0]["col"], 5);
+        assert_eq!(matches[0]["end_row"], 1);
+        assert_eq!(matches[0]["end_col"], 3);
+    }
+
+    #[test]
+    fn test_router_snapshot_diff_without_history_is_full() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "snapshot_diff".to_string(), Some(json!({ "since": 3 })));
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        let result = &parsed["result"];
+        assert_eq!(result["full"], true);
+        assert_eq!(result["from_version"], 3);
+        assert_eq!(result["screen_version"], 7);
+        assert_eq!(result["rows"][0]["new"], "ready");
+        assert!(result["cursor_delta"].is_null());
+    }
+
+    #[test]
+    fn test_router_elements_returns_tree_with_screen_version() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "elements".to_string(), None);
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["result"]["session_id"], "active");
+        assert_eq!(parsed["result"]["screen_version"], 7);
+        assert!(parsed["result"]["elements"].is_array());
+    }
+
+    #[test]
+    fn test_router_panes_lists_panes() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "panes".to_string(), None);
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["result"]["session_id"], "active");
+        assert_eq!(parsed["result"]["panes"], json!([]));
+    }
+
+    #[test]
+    fn test_router_table_without_table_is_empty() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "table".to_string(), None);
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["result"]["session_id"], "active");
+        assert_eq!(parsed["result"]["screen_version"], 7);
+        assert_eq!(parsed["result"]["rows"], json!([]));
+        assert!(parsed["result"]["highlighted"].is_null());
+    }
+
+    #[test]
+    fn test_router_snapshot_image_is_base64_encoded() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "snapshot".to_string(), Some(json!({ "image": "png" })));
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["result"]["image"]["format"], "png");
+        assert_eq!(parsed["result"]["image"]["data"], "cG5n");
+    }
+
+    #[test]
+    fn test_router_snapshot_unknown_region_is_region_not_found() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(
+            1,
+            "snapshot".to_string(),
+            Some(json!({ "region": "Files" })),
+        );
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["error"]["code"], -32019);
+        assert_eq!(parsed["error"]["data"]["context"]["region"], "Files");
+    }
+
+    #[test]
+    fn test_router_select_without_highlight_reports_not_found() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "select".to_string(), Some(json!({ "item": "Save" })));
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["result"]["found"], false);
+        assert_eq!(parsed["result"]["steps"], 0);
+        assert!(parsed["result"]["selected"].is_null());
+    }
+
+    #[test]
+    fn test_router_fill_rejects_field_with_two_actions() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(
+            1,
+            "fill".to_string(),
+            Some(json!({ "fields": [{ "label": "Name", "answer": "x", "keys": ["Enter"] }] })),
+        );
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+
+        assert_eq!(parsed["error"]["code"], -32602);
+        assert!(
+            parsed["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("'Name'")
+        );
+    }
+
+    #[test]
+    fn test_router_record_start_requires_absolute_path() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(
+            1,
+            "record_start".to_string(),
+            Some(json!({ "path": "out.cast" })),
+        );
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn test_router_assert_screen_rejects_invalid_masks() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(
+            1,
+            "assert_screen".to_string(),
+            Some(json!({ "golden": "ok\n", "masks": ["rect 1,2"] })),
+        );
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed["error"]["code"], -32602);
+        assert!(
+            parsed["error"]["message"]
+                .as_str()
+                .unwrap()
+                .contains("invalid mask 'rect 1,2'")
+        );
+    }
+
+    #[test]
+    fn test_router_trace_start_requires_absolute_path() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(
+            1,
+            "trace_start".to_string(),
+            Some(json!({ "path": "trace.zip" })),
+        );
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn test_router_replay_rejects_speed_with_step() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(
+            1,
+            "replay".to_string(),
+            Some(json!({ "path": "/tmp/run.cast", "speed": 2.0, "step": true })),
+        );
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(parsed["error"]["code"], -32602);
+    }
+
+    #[test]
+    fn test_router_find_requires_text() {
+        let usecases = create_test_usecases();
+        let router = Router::new(&usecases);
+
+        let request = RpcRequest::new(1, "find".to_string(), Some(json!({})));
+        let response = router.route(request);
+
+        let json_str = serde_json::to_string(&response).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert!(parsed["error"].is_object());
+    }
+
     #[test]
     fn test_router_shutdown_returns_acknowledged() {
         let usecases = create_test_usecases();
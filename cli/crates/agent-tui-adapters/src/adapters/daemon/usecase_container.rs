@@ -3,20 +3,35 @@
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
+use crate::usecases::AssertScreenUseCaseImpl;
 use crate::usecases::AssertUseCaseImpl;
 use crate::usecases::AttachUseCaseImpl;
 use crate::usecases::CleanupUseCaseImpl;
+use crate::usecases::ElementsUseCaseImpl;
+use crate::usecases::FillUseCaseImpl;
+use crate::usecases::FindUseCaseImpl;
 use crate::usecases::KeydownUseCaseImpl;
 use crate::usecases::KeystrokeUseCaseImpl;
 use crate::usecases::KeyupUseCaseImpl;
 use crate::usecases::KillUseCaseImpl;
+use crate::usecases::PanesUseCaseImpl;
+use crate::usecases::RecordStartUseCaseImpl;
+use crate::usecases::RecordStopUseCaseImpl;
+use crate::usecases::ReplayUseCaseImpl;
 use crate::usecases::ResizeUseCaseImpl;
 use crate::usecases::RestartUseCaseImpl;
+use crate::usecases::SelectUseCaseImpl;
 use crate::usecases::SessionsUseCaseImpl;
 use crate::usecases::ShutdownUseCaseImpl;
+use crate::usecases::SignalUseCaseImpl;
+use crate::usecases::SnapshotDiffUseCaseImpl;
 use crate::usecases::SnapshotUseCaseImpl;
 use crate::usecases::SpawnUseCaseImpl;
+use crate::usecases::TableUseCaseImpl;
 use crate::usecases::TerminalWriteUseCaseImpl;
+use crate::usecases::TraceStartUseCaseImpl;
+use crate::usecases::TraceStopUseCaseImpl;
+use crate::usecases::TtyStateUseCaseImpl;
 use crate::usecases::TypeUseCaseImpl;
 use crate::usecases::WaitUseCaseImpl;
 use crate::usecases::ports::Clock;
@@ -33,17 +48,29 @@ pub struct UseCaseContainer<R: SessionRepository + 'static> {
 
 pub struct SessionUseCases<R: SessionRepository + 'static> {
     pub spawn: SpawnUseCaseImpl<R>,
+    pub replay: ReplayUseCaseImpl<R>,
     pub kill: KillUseCaseImpl<R>,
     pub sessions: SessionsUseCaseImpl<R>,
     pub restart: RestartUseCaseImpl<R>,
     pub attach: AttachUseCaseImpl<R>,
     pub resize: ResizeUseCaseImpl<R>,
+    pub signal: SignalUseCaseImpl<R>,
+    pub record_start: RecordStartUseCaseImpl<R>,
+    pub record_stop: RecordStopUseCaseImpl<R>,
+    pub trace_start: TraceStartUseCaseImpl<R>,
+    pub trace_stop: TraceStopUseCaseImpl<R>,
     pub cleanup: CleanupUseCaseImpl<R>,
     pub assert: AssertUseCaseImpl<R>,
 }
 
 pub struct SnapshotUseCases<R: SessionRepository + 'static> {
     pub snapshot: SnapshotUseCaseImpl<R>,
+    pub diff: SnapshotDiffUseCaseImpl<R>,
+    pub assert_screen: AssertScreenUseCaseImpl<R>,
+    pub find: FindUseCaseImpl<R>,
+    pub elements: ElementsUseCaseImpl<R>,
+    pub panes: PanesUseCaseImpl<R>,
+    pub table: TableUseCaseImpl<R>,
 }
 
 pub struct InputUseCases<R: SessionRepository + 'static> {
@@ -51,10 +78,13 @@ pub struct InputUseCases<R: SessionRepository + 'static> {
     pub type_text: TypeUseCaseImpl<R>,
     pub keydown: KeydownUseCaseImpl<R>,
     pub keyup: KeyupUseCaseImpl<R>,
+    pub select: SelectUseCaseImpl<R>,
+    pub fill: FillUseCaseImpl<R>,
 }
 
 pub struct DiagnosticsUseCases<R: SessionRepository + 'static> {
     pub terminal_write: TerminalWriteUseCaseImpl<R>,
+    pub tty_state: TtyStateUseCaseImpl<R>,
     pub shutdown: ShutdownUseCaseImpl,
 }
 
@@ -68,25 +98,40 @@ impl<R: SessionRepository + 'static> UseCaseContainer<R> {
         Self {
             session: SessionUseCases {
                 spawn: SpawnUseCaseImpl::new(Arc::clone(&repository)),
+                replay: ReplayUseCaseImpl::new(Arc::clone(&repository)),
                 kill: KillUseCaseImpl::new(Arc::clone(&repository)),
                 sessions: SessionsUseCaseImpl::new(Arc::clone(&repository)),
                 restart: RestartUseCaseImpl::new(Arc::clone(&repository)),
                 attach: AttachUseCaseImpl::new(Arc::clone(&repository)),
                 resize: ResizeUseCaseImpl::new(Arc::clone(&repository)),
+                signal: SignalUseCaseImpl::new(Arc::clone(&repository)),
+                record_start: RecordStartUseCaseImpl::new(Arc::clone(&repository)),
+                record_stop: RecordStopUseCaseImpl::new(Arc::clone(&repository)),
+                trace_start: TraceStartUseCaseImpl::new(Arc::clone(&repository)),
+                trace_stop: TraceStopUseCaseImpl::new(Arc::clone(&repository)),
                 cleanup: CleanupUseCaseImpl::new(Arc::clone(&repository)),
                 assert: AssertUseCaseImpl::new(Arc::clone(&repository)),
             },
             snapshot: SnapshotUseCases {
                 snapshot: SnapshotUseCaseImpl::new(Arc::clone(&repository)),
+                diff: SnapshotDiffUseCaseImpl::new(Arc::clone(&repository)),
+                assert_screen: AssertScreenUseCaseImpl::new(Arc::clone(&repository)),
+                find: FindUseCaseImpl::new(Arc::clone(&repository)),
+                elements: ElementsUseCaseImpl::new(Arc::clone(&repository)),
+                panes: PanesUseCaseImpl::new(Arc::clone(&repository)),
+                table: TableUseCaseImpl::new(Arc::clone(&repository)),
             },
             input: InputUseCases {
                 keystroke: KeystrokeUseCaseImpl::new(Arc::clone(&repository)),
                 type_text: TypeUseCaseImpl::new(Arc::clone(&repository)),
                 keydown: KeydownUseCaseImpl::new(Arc::clone(&repository)),
                 keyup: KeyupUseCaseImpl::new(Arc::clone(&repository)),
+                select: SelectUseCaseImpl::new(Arc::clone(&repository), Arc::clone(&clock)),
+                fill: FillUseCaseImpl::new(Arc::clone(&repository), Arc::clone(&clock)),
             },
             diagnostics: DiagnosticsUseCases {
                 terminal_write: TerminalWriteUseCaseImpl::new(Arc::clone(&repository)),
+                tty_state: TtyStateUseCaseImpl::new(Arc::clone(&repository)),
                 shutdown: ShutdownUseCaseImpl::new(shutdown_flag, shutdown_notifier),
             },
             wait: WaitUseCaseImpl::new(repository, clock),
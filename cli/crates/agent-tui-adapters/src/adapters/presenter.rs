@@ -13,6 +13,10 @@ pub enum OutputFormat {
     #[default]
     Text,
     Json,
+    /// Compact screenshot layout for LLM agents; rejected by other commands
+    Agent,
+    /// Styled HTML screenshot; rejected by other commands
+    Html,
 }
 
 pub trait Presenter {
@@ -56,6 +60,7 @@ pub struct ClientErrorView {
 pub struct WaitResult {
     pub found: bool,
     pub elapsed_ms: u64,
+    pub diagnostics: Option<MatchDiagnosticsView>,
 }
 
 impl WaitResult {
@@ -63,10 +68,106 @@ impl WaitResult {
         Self {
             found: value.bool_or("found", false),
             elapsed_ms: value.u64_or("elapsed_ms", 0),
+            diagnostics: MatchDiagnosticsView::from_json(value),
         }
     }
 }
 
+pub struct NearestMatch {
+    pub text: String,
+    pub row: u64,
+    pub col: u64,
+    pub distance: u64,
+}
+
+pub struct MatchDiagnosticsView {
+    pub screen: String,
+    pub screen_idle_ms: u64,
+    pub nearest_matches: Vec<NearestMatch>,
+    /// Where text that should be gone is still on screen (`text_gone` waits).
+    pub still_visible: Vec<NearestMatch>,
+}
+
+impl MatchDiagnosticsView {
+    /// Reads the `diagnostics` object attached to failed wait/assert responses.
+    pub fn from_json(value: &RpcValue) -> Option<Self> {
+        let diagnostics = value.get("diagnostics")?;
+        diagnostics.get("screen")?;
+        let matches = |key: &str| -> Vec<NearestMatch> {
+            diagnostics
+                .get(key)
+                .and_then(|v| v.as_array())
+                .map(|matches| {
+                    matches
+                        .iter()
+                        .map(|m| NearestMatch {
+                            text: m.str_or("text", "").to_string(),
+                            row: m.u64_or("row", 0),
+                            col: m.u64_or("col", 0),
+                            distance: m.u64_or("distance", 0),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Some(Self {
+            screen: diagnostics.str_or("screen", "").to_string(),
+            screen_idle_ms: diagnostics.u64_or("screen_idle_ms", 0),
+            nearest_matches: matches("nearest_matches"),
+            still_visible: matches("still_visible"),
+        })
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        let mut value = serde_json::json!({
+            "screen": self.screen,
+            "screen_idle_ms": self.screen_idle_ms,
+            "nearest_matches": self
+                .nearest_matches
+                .iter()
+                .map(|m| serde_json::json!({
+                    "text": m.text,
+                    "row": m.row,
+                    "col": m.col,
+                    "distance": m.distance
+                }))
+                .collect::<Vec<_>>()
+        });
+        if !self.still_visible.is_empty() {
+            value["still_visible"] = self
+                .still_visible
+                .iter()
+                .map(|m| serde_json::json!({ "text": m.text, "row": m.row, "col": m.col }))
+                .collect();
+        }
+        value
+    }
+}
+
+fn print_match_diagnostics(diagnostics: &MatchDiagnosticsView) {
+    println!("  Screen unchanged for {}ms", diagnostics.screen_idle_ms);
+    if !diagnostics.still_visible.is_empty() {
+        println!("  Still on screen:");
+        for m in &diagnostics.still_visible {
+            println!("    row {}, col {}: {:?}", m.row, m.col, m.text);
+        }
+    } else if diagnostics.nearest_matches.is_empty() {
+        println!("  No similar text on screen");
+    } else {
+        println!("  Closest matches:");
+        for m in &diagnostics.nearest_matches {
+            println!(
+                "    row {}, col {}: {:?} (distance {})",
+                m.row, m.col, m.text, m.distance
+            );
+        }
+    }
+    println!("  Screen:");
+    for line in diagnostics.screen.lines() {
+        println!("    {}", line);
+    }
+}
+
 pub struct AssertResult {
     pub passed: bool,
     pub condition: String,
@@ -165,6 +266,9 @@ impl Presenter for TextPresenter {
             println!("Found after {}ms", result.elapsed_ms);
         } else {
             println!("Timeout after {}ms - not found", result.elapsed_ms);
+            if let Some(diagnostics) = result.diagnostics.as_ref() {
+                print_match_diagnostics(diagnostics);
+            }
         }
     }
 
@@ -312,10 +416,13 @@ impl Presenter for JsonPresenter {
     }
 
     fn present_wait_result(&self, result: &WaitResult) {
-        let output = serde_json::json!({
+        let mut output = serde_json::json!({
             "found": result.found,
             "elapsed_ms": result.elapsed_ms
         });
+        if let Some(diagnostics) = result.diagnostics.as_ref() {
+            output["diagnostics"] = diagnostics.to_json();
+        }
         println!(
             "{}",
             serde_json::to_string_pretty(&output).unwrap_or_default()
@@ -359,7 +466,7 @@ impl Presenter for JsonPresenter {
 pub fn create_presenter(format: &OutputFormat) -> Box<dyn Presenter> {
     match format {
         OutputFormat::Json => Box::new(JsonPresenter),
-        OutputFormat::Text => Box::new(TextPresenter),
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => Box::new(TextPresenter),
     }
 }
 
@@ -430,6 +537,221 @@ impl SessionListResult {
     }
 }
 
+const AGENT_CURSOR_MARK: char = '▌';
+const AGENT_HIGHLIGHT_OPEN: char = '«';
+const AGENT_HIGHLIGHT_CLOSE: char = '»';
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct AgentScreenOptions {
+    /// Blank out box-drawing characters (U+2500..U+257F) before layout.
+    pub strip_borders: bool,
+}
+
+pub struct HighlightSpan {
+    pub row: u64,
+    pub col: u64,
+    pub len: u64,
+}
+
+/// Screenshot laid out for LLM agents: numbered rows, a column ruler, collapsed
+/// blank runs, and inline cursor/highlight marks.
+pub struct AgentScreenView {
+    pub screen: String,
+    pub cursor: Option<(u64, u64)>,
+    pub highlights: Vec<HighlightSpan>,
+    pub screen_version: Option<u64>,
+    /// Screen position of the first cell when the screenshot is a pane interior;
+    /// rows, columns and marks are then relative to it.
+    pub origin: Option<(u64, u64)>,
+}
+
+impl AgentScreenView {
+    pub fn from_json(value: &RpcValue) -> Self {
+        let origin = value
+            .get("region")
+            .map(|pane| (pane.u64_or("row", 0) + 1, pane.u64_or("col", 0) + 1));
+        let (top, left) = origin.unwrap_or((0, 0));
+        let cursor = value
+            .get("cursor")
+            .filter(|cursor| cursor.bool_or("visible", false))
+            .map(|cursor| (cursor.u64_or("row", 0), cursor.u64_or("col", 0)))
+            .filter(|&(row, col)| row >= top && col >= left)
+            .map(|(row, col)| (row - top, col - left));
+        let highlights = value
+            .get("highlights")
+            .and_then(|v| v.as_array())
+            .map(|spans| {
+                spans
+                    .iter()
+                    .filter_map(|span| {
+                        let (row, col) = (span.u64_or("row", 0), span.u64_or("col", 0));
+                        let end = col + span.u64_or("len", 0);
+                        (row >= top && end > left).then(|| HighlightSpan {
+                            row: row - top,
+                            col: col.saturating_sub(left),
+                            len: end - col.max(left),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self {
+            screen: value.str_or("screenshot", "").to_string(),
+            cursor,
+            highlights,
+            screen_version: value.get("screen_version").and_then(|v| v.as_u64()),
+            origin,
+        }
+    }
+
+    pub fn present(&self, presenter: &dyn Presenter, options: AgentScreenOptions) {
+        presenter.present_raw(&self.render(options));
+    }
+
+    pub fn render(&self, options: AgentScreenOptions) -> String {
+        let rows: Vec<Vec<char>> = self
+            .screen
+            .split('\n')
+            .map(|line| {
+                let line: String = line
+                    .chars()
+                    .map(|ch| {
+                        if options.strip_borders && is_box_drawing(ch) {
+                            ' '
+                        } else {
+                            ch
+                        }
+                    })
+                    .collect();
+                line.trim_end().chars().collect()
+            })
+            .collect();
+
+        let width = rows
+            .iter()
+            .map(Vec::len)
+            .chain(self.cursor.map(|(_, col)| to_usize(col) + 1))
+            .max()
+            .unwrap_or(0);
+        let number_width = rows.len().saturating_sub(1).to_string().len();
+        let gutter = " ".repeat(number_width + 1);
+
+        let mut out = String::new();
+        let tens: String = (0..width)
+            .map(|col| match col % 10 {
+                0 if col > 0 => digit(col / 10 % 10),
+                _ => ' ',
+            })
+            .collect();
+        if width > 10 {
+            out.push_str(&format!("{}{}\n", gutter, tens.trim_end()));
+        }
+        let ones: String = (0..width).map(|col| digit(col % 10)).collect();
+        out.push_str(&format!("{}{}\n", gutter, ones));
+
+        let mut row = 0;
+        while row < rows.len() {
+            let blank_end = (row..rows.len())
+                .find(|&idx| !self.is_blank_row(idx, &rows[idx]))
+                .unwrap_or(rows.len());
+            if blank_end - row >= 2 {
+                out.push_str(&format!(
+                    "{:>number_width$}┆ {} blank rows\n",
+                    "",
+                    blank_end - row
+                ));
+                row = blank_end;
+                continue;
+            }
+            out.push_str(&format!(
+                "{:>number_width$}│{}\n",
+                row,
+                self.render_row(row, &rows[row])
+            ));
+            row += 1;
+        }
+
+        let tokens = estimate_tokens(&out);
+        let cursor = match self.cursor {
+            Some((row, col)) => format!("cursor {},{}", row, col),
+            None => "cursor hidden".to_string(),
+        };
+        out.push_str(&format!(
+            "-- {} rows · {} · {} highlighted",
+            rows.len(),
+            cursor,
+            self.highlights.len()
+        ));
+        if let Some(version) = self.screen_version {
+            out.push_str(&format!(" · v{}", version));
+        }
+        if let Some((row, col)) = self.origin {
+            out.push_str(&format!(" · region at {},{}", row, col));
+        }
+        out.push_str(&format!(" · ~{} tokens", tokens));
+        out
+    }
+
+    fn is_blank_row(&self, row: usize, chars: &[char]) -> bool {
+        chars.is_empty()
+            && self
+                .cursor
+                .is_none_or(|(cursor_row, _)| to_usize(cursor_row) != row)
+    }
+
+    fn render_row(&self, row: usize, chars: &[char]) -> String {
+        let spans: Vec<(usize, usize)> = self
+            .highlights
+            .iter()
+            .filter(|span| to_usize(span.row) == row && to_usize(span.col) < chars.len())
+            .map(|span| {
+                let start = to_usize(span.col);
+                (start, (start + to_usize(span.len)).min(chars.len()))
+            })
+            .collect();
+        let cursor_col = self
+            .cursor
+            .filter(|(cursor_row, _)| to_usize(*cursor_row) == row)
+            .map(|(_, col)| to_usize(col));
+
+        let len = chars.len().max(cursor_col.map_or(0, |col| col + 1));
+        let mut line = String::with_capacity(len + 4);
+        for col in 0..len {
+            if spans.iter().any(|&(_, end)| end == col) {
+                line.push(AGENT_HIGHLIGHT_CLOSE);
+            }
+            if cursor_col == Some(col) {
+                line.push(AGENT_CURSOR_MARK);
+            }
+            if spans.iter().any(|&(start, _)| start == col) {
+                line.push(AGENT_HIGHLIGHT_OPEN);
+            }
+            line.push(chars.get(col).copied().unwrap_or(' '));
+        }
+        if spans.iter().any(|&(_, end)| end == len) {
+            line.push(AGENT_HIGHLIGHT_CLOSE);
+        }
+        line.trim_end().to_string()
+    }
+}
+
+/// Rough token count (about four characters per token).
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+fn is_box_drawing(ch: char) -> bool {
+    ('\u{2500}'..='\u{257F}').contains(&ch)
+}
+
+fn digit(value: usize) -> char {
+    char::from_digit((value % 10) as u32, 10).unwrap_or(' ')
+}
+
+fn to_usize(value: u64) -> usize {
+    usize::try_from(value).unwrap_or(usize::MAX)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -478,6 +800,7 @@ mod tests {
         let result = WaitResult {
             found: true,
             elapsed_ms: 150,
+            diagnostics: None,
         };
         assert!(result.found);
         assert_eq!(result.elapsed_ms, 150);
@@ -512,11 +835,52 @@ mod tests {
         let result = WaitResult {
             found: true,
             elapsed_ms: 100,
+            diagnostics: None,
         };
 
         presenter.present_wait_result(&result);
     }
 
+    #[test]
+    fn test_wait_result_from_json_reads_diagnostics() {
+        let value = RpcValue::new(serde_json::json!({
+            "found": false,
+            "elapsed_ms": 500,
+            "diagnostics": {
+                "screen": "[Continue?]",
+                "screen_idle_ms": 320,
+                "nearest_matches": [
+                    { "text": "Continue?", "row": 0, "col": 1, "distance": 1 }
+                ]
+            }
+        }));
+
+        let result = WaitResult::from_json(&value);
+        let diagnostics = result.diagnostics.expect("diagnostics");
+        assert_eq!(diagnostics.screen_idle_ms, 320);
+        assert_eq!(diagnostics.nearest_matches.len(), 1);
+        assert_eq!(diagnostics.nearest_matches[0].text, "Continue?");
+        assert_eq!(diagnostics.nearest_matches[0].col, 1);
+
+        assert!(diagnostics.still_visible.is_empty());
+
+        let found = WaitResult::from_json(&RpcValue::new(serde_json::json!({ "found": true })));
+        assert!(found.diagnostics.is_none());
+
+        let gone = WaitResult::from_json(&RpcValue::new(serde_json::json!({
+            "found": false,
+            "diagnostics": {
+                "screen": "  Loading",
+                "nearest_matches": [],
+                "still_visible": [{ "text": "Loading", "row": 0, "col": 2 }]
+            }
+        })));
+        let diagnostics = gone.diagnostics.expect("diagnostics");
+        assert_eq!(diagnostics.still_visible.len(), 1);
+        assert_eq!(diagnostics.still_visible[0].col, 2);
+        assert_eq!(diagnostics.to_json()["still_visible"][0]["text"], "Loading");
+    }
+
     #[test]
     fn test_json_presenter_assert_result() {
         let presenter = JsonPresenter;
@@ -538,4 +902,92 @@ mod tests {
 
         presenter.present_cleanup(&result);
     }
+
+    fn agent_view(screen: &str) -> AgentScreenView {
+        AgentScreenView {
+            screen: screen.to_string(),
+            cursor: None,
+            highlights: Vec::new(),
+            screen_version: None,
+            origin: None,
+        }
+    }
+
+    #[test]
+    fn test_agent_screen_numbers_rows_and_collapses_blank_runs() {
+        let view = agent_view("Title\n\n\n\nFooter");
+        let rendered = view.render(AgentScreenOptions::default());
+
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "  012345");
+        assert_eq!(lines[1], "0│Title");
+        assert_eq!(lines[2], " ┆ 3 blank rows");
+        assert_eq!(lines[3], "4│Footer");
+        assert!(lines[4].starts_with("-- 5 rows · cursor hidden · 0 highlighted"));
+    }
+
+    #[test]
+    fn test_agent_screen_ruler_marks_tens() {
+        let view = agent_view("0123456789abcdef");
+        let rendered = view.render(AgentScreenOptions::default());
+
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "            1");
+        assert_eq!(lines[1], "  0123456789012345");
+    }
+
+    #[test]
+    fn test_agent_screen_marks_cursor_and_highlights_inline() {
+        let mut view = agent_view("  One\n> Two\nName: ");
+        view.cursor = Some((2, 6));
+        view.highlights = vec![HighlightSpan {
+            row: 1,
+            col: 2,
+            len: 3,
+        }];
+        view.screen_version = Some(42);
+
+        let rendered = view.render(AgentScreenOptions::default());
+
+        assert!(rendered.contains("1│> «Two»\n"));
+        assert!(rendered.contains("2│Name: ▌\n"));
+        assert!(rendered.contains("cursor 2,6 · 1 highlighted · v42 · ~"));
+    }
+
+    #[test]
+    fn test_agent_screen_strips_box_borders() {
+        let view = agent_view("┌─ Files ─┐\n│ a.txt   │\n└─────────┘");
+        let rendered = view.render(AgentScreenOptions {
+            strip_borders: true,
+        });
+
+        assert!(rendered.contains("0│   Files\n"));
+        assert!(rendered.contains("1│  a.txt\n"));
+        assert!(!rendered.contains('─'));
+    }
+
+    #[test]
+    fn test_agent_screen_from_region_is_relative_to_pane() {
+        let view = AgentScreenView::from_json(&RpcValue::new(serde_json::json!({
+            "screenshot": " started\n ready",
+            "cursor": { "row": 2, "col": 20, "visible": true },
+            "highlights": [{ "row": 1, "col": 0, "len": 20 }],
+            "region": { "id": "p2", "row": 0, "col": 12, "width": 13, "height": 4 }
+        })));
+
+        assert_eq!(view.origin, Some((1, 13)));
+        assert_eq!(view.cursor, Some((1, 7)));
+        assert_eq!((view.highlights[0].row, view.highlights[0].col), (0, 0));
+        assert_eq!(view.highlights[0].len, 7);
+        assert!(
+            view.render(AgentScreenOptions::default())
+                .contains("region at 1,13")
+        );
+    }
+
+    #[test]
+    fn test_estimate_tokens_rounds_up() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcde"), 2);
+    }
 }
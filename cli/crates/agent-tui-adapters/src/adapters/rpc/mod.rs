@@ -12,34 +12,70 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde_json::json;
 
+use super::snapshot_adapters::element_to_json;
+use super::snapshot_adapters::match_diagnostics_to_json;
+use super::snapshot_adapters::pane_to_json;
 use super::snapshot_adapters::session_info_to_json;
+use super::snapshot_adapters::table_to_json;
 use crate::adapters::daemon::DomainError;
 use crate::domain::AssertInput;
 use crate::domain::AssertOutput;
+use crate::domain::AssertScreenInput;
+use crate::domain::AssertScreenOutput;
 use crate::domain::AttachInput;
 use crate::domain::AttachOutput;
 use crate::domain::CleanupInput;
 use crate::domain::CleanupOutput;
+use crate::domain::ElementsOutput;
+use crate::domain::FillAction;
+use crate::domain::FillField;
+use crate::domain::FillInput;
+use crate::domain::FillOutput;
+use crate::domain::FindInput;
+use crate::domain::FindOutput;
+use crate::domain::ImageFormat;
 use crate::domain::KeydownInput;
 use crate::domain::KeystrokeInput;
 use crate::domain::KeyupInput;
 use crate::domain::KillOutput;
+use crate::domain::PanesOutput;
+use crate::domain::ProcessSignal;
+use crate::domain::RecordStartInput;
+use crate::domain::RecordStartOutput;
+use crate::domain::RecordStopOutput;
+use crate::domain::ReplayInput;
+use crate::domain::ReplayOutput;
+use crate::domain::ReplayPacing;
 use crate::domain::ResizeInput;
 use crate::domain::ResizeOutput;
 use crate::domain::RestartOutput;
+use crate::domain::SelectInput;
+use crate::domain::SelectOutput;
 use crate::domain::SessionId;
 use crate::domain::SessionInput;
 use crate::domain::SessionsOutput;
 use crate::domain::ShutdownOutput;
+use crate::domain::SignalInput;
+use crate::domain::SignalOutput;
+use crate::domain::SnapshotDiffInput;
+use crate::domain::SnapshotDiffOutput;
 use crate::domain::SnapshotInput;
 use crate::domain::SnapshotOutput;
 use crate::domain::SpawnInput;
 use crate::domain::SpawnOutput;
+use crate::domain::TableInput;
+use crate::domain::TableOutput;
 use crate::domain::TerminalWriteInput;
 use crate::domain::TerminalWriteOutput;
+use crate::domain::TraceStartInput;
+use crate::domain::TraceStartOutput;
+use crate::domain::TraceStopOutput;
+use crate::domain::TtyStateOutput;
 use crate::domain::TypeInput;
 use crate::domain::WaitInput;
 use crate::domain::WaitOutput;
+use crate::domain::core::Golden;
+use crate::domain::core::GoldenMask;
 use crate::usecases::ports::SessionError;
 
 use crate::domain::session_types::TerminalSize;
@@ -146,6 +182,67 @@ pub fn spawn_output_to_response(id: u64, output: SpawnOutput) -> RpcResponse {
     )
 }
 
+#[allow(clippy::result_large_err)]
+pub fn parse_replay_input(request: &RpcRequest) -> Result<ReplayInput, RpcResponse> {
+    let rpc_params: params::ReplayParams = request
+        .params
+        .as_ref()
+        .ok_or_else(|| RpcResponse::error(request.id, -32602, "Missing params"))
+        .and_then(|p| {
+            params::ReplayParams::deserialize(p).map_err(|e| {
+                RpcResponse::error(request.id, -32602, &format!("Invalid params: {}", e))
+            })
+        })?;
+    if !std::path::Path::new(&rpc_params.path).is_absolute() {
+        return Err(RpcResponse::error(
+            request.id,
+            -32602,
+            &format!("Replay path must be absolute: {}", rpc_params.path),
+        ));
+    }
+    let pacing = match (rpc_params.speed, rpc_params.step) {
+        (Some(_), true) => {
+            return Err(RpcResponse::error(
+                request.id,
+                -32602,
+                "speed and step cannot be combined",
+            ));
+        }
+        (Some(speed), false) if !(speed.is_finite() && speed > 0.0) => {
+            return Err(RpcResponse::error(
+                request.id,
+                -32602,
+                &format!("speed must be a positive number: {speed}"),
+            ));
+        }
+        (speed, false) => ReplayPacing::Speed(speed.unwrap_or(1.0)),
+        (None, true) => ReplayPacing::Step,
+    };
+
+    Ok(ReplayInput {
+        path: rpc_params.path,
+        pacing,
+        session_id: parse_session_id(rpc_params.session),
+        cols: rpc_params.cols.clamp(MIN_TERMINAL_COLS, MAX_TERMINAL_COLS),
+        rows: rpc_params.rows.clamp(MIN_TERMINAL_ROWS, MAX_TERMINAL_ROWS),
+    })
+}
+
+pub fn replay_output_to_response(id: u64, output: ReplayOutput) -> RpcResponse {
+    let replay = output.replay;
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "format": replay.format.as_str(),
+            "cols": replay.cols,
+            "rows": replay.rows,
+            "events": replay.events,
+            "duration_ms": replay.duration_ms
+        }),
+    )
+}
+
 pub fn parse_snapshot_input(request: &RpcRequest) -> SnapshotInput {
     let rpc_params: params::SnapshotParams = request
         .params
@@ -159,6 +256,9 @@ pub fn parse_snapshot_input(request: &RpcRequest) -> SnapshotInput {
         strip_ansi: rpc_params.strip_ansi,
         include_cursor: rpc_params.include_cursor,
         include_render: rpc_params.include_render,
+        unwrap: rpc_params.unwrap,
+        include_highlights: rpc_params.include_highlights,
+        image: rpc_params.image.as_deref().and_then(ImageFormat::parse),
     }
 }
 
@@ -177,7 +277,8 @@ pub fn snapshot_output_to_response(
 
     let mut result = json!({
         "session_id": output.session_id.as_str(),
-        "screenshot": screenshot
+        "screenshot": screenshot,
+        "screen_version": output.screen_version
     });
 
     if let Some(cursor) = output.cursor {
@@ -192,6 +293,188 @@ pub fn snapshot_output_to_response(
         result["rendered"] = json!(rendered);
     }
 
+    if let Some(highlights) = output.highlights {
+        result["highlights"] = json!(
+            highlights
+                .iter()
+                .map(|span| json!({ "row": span.row, "col": span.col, "len": span.len }))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    if let Some(pane) = &output.region {
+        result["region"] = pane_to_json(pane);
+    }
+
+    if let Some(image) = output.image {
+        result["image"] = json!({
+            "format": image.format.as_str(),
+            "data": STANDARD.encode(image.data)
+        });
+    }
+
+    if let Some(logical_screen) = output.logical_screen {
+        result["lines"] = json!(
+            logical_screen
+                .lines
+                .iter()
+                .map(|line| json!({ "row": line.start_row, "rows": line.rows }))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    RpcResponse::success(id, result)
+}
+
+#[allow(clippy::result_large_err)]
+pub fn parse_snapshot_diff_input(request: &RpcRequest) -> Result<SnapshotDiffInput, RpcResponse> {
+    let since = request
+        .param_u64_opt("since")
+        .ok_or_else(|| RpcResponse::error(request.id, -32602, "Missing param: since"))?;
+
+    Ok(SnapshotDiffInput {
+        session_id: parse_session_selector(request.param_str("session").map(String::from)),
+        since,
+    })
+}
+
+pub fn snapshot_diff_output_to_response(id: u64, output: SnapshotDiffOutput) -> RpcResponse {
+    let diff = output.diff;
+    let cursor_delta = diff.cursor_from.map(|from| {
+        json!({
+            "rows": i32::from(diff.cursor.row) - i32::from(from.row),
+            "cols": i32::from(diff.cursor.col) - i32::from(from.col)
+        })
+    });
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "from_version": diff.from_version,
+            "screen_version": diff.to_version,
+            "full": diff.full,
+            "rows": diff.rows.iter().map(|change| json!({
+                "row": change.row,
+                "old": change.old,
+                "new": change.new
+            })).collect::<Vec<_>>(),
+            "cursor": {
+                "row": diff.cursor.row,
+                "col": diff.cursor.col,
+                "visible": diff.cursor.visible
+            },
+            "cursor_delta": cursor_delta
+        }),
+    )
+}
+
+#[allow(clippy::result_large_err)]
+pub fn parse_assert_screen_input(request: &RpcRequest) -> Result<AssertScreenInput, RpcResponse> {
+    let rpc_params: params::AssertScreenParams = request
+        .params
+        .as_ref()
+        .ok_or_else(|| RpcResponse::error(request.id, -32602, "Missing params"))
+        .and_then(|p| {
+            params::AssertScreenParams::deserialize(p).map_err(|e| {
+                RpcResponse::error(request.id, -32602, &format!("Invalid params: {}", e))
+            })
+        })?;
+    let golden = Golden::parse(&rpc_params.golden)
+        .map_err(|e| RpcResponse::error(request.id, -32602, &format!("Invalid golden: {}", e)))?;
+    let masks = rpc_params
+        .masks
+        .iter()
+        .map(|spec| GoldenMask::parse(spec))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| RpcResponse::error(request.id, -32602, &e.to_string()))?;
+
+    Ok(AssertScreenInput {
+        session_id: parse_session_selector(rpc_params.session),
+        golden,
+        masks,
+        styles: rpc_params.styles,
+    })
+}
+
+pub fn assert_screen_output_to_response(id: u64, output: AssertScreenOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "matched": output.matched,
+            "styles": output.styles,
+            "diff": output.diffs.iter().map(|diff| json!({
+                "row": diff.row,
+                "kind": diff.kind.as_str(),
+                "expected": diff.expected,
+                "actual": diff.actual
+            })).collect::<Vec<_>>(),
+            "golden": output.golden
+        }),
+    )
+}
+
+#[allow(clippy::result_large_err)]
+pub fn parse_find_input(request: &RpcRequest) -> Result<FindInput, RpcResponse> {
+    let text = request.require_str("text")?.to_string();
+
+    Ok(FindInput {
+        session_id: parse_session_selector(request.param_str("session").map(String::from)),
+        text,
+        unwrap: request.param_bool("unwrap", false),
+        region: request.param_str("region").map(String::from),
+    })
+}
+
+pub fn find_output_to_response(id: u64, output: FindOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "matches": output.matches.iter().map(|span| json!({
+                "row": span.row,
+                "col": span.col,
+                "end_row": span.end_row,
+                "end_col": span.end_col
+            })).collect::<Vec<_>>()
+        }),
+    )
+}
+
+pub fn elements_output_to_response(id: u64, output: ElementsOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "screen_version": output.screen_version,
+            "elements": output.elements.iter().map(element_to_json).collect::<Vec<_>>()
+        }),
+    )
+}
+
+pub fn panes_output_to_response(id: u64, output: PanesOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "screen_version": output.screen_version,
+            "panes": output.panes.iter().map(pane_to_json).collect::<Vec<_>>()
+        }),
+    )
+}
+
+pub fn parse_table_input(request: &RpcRequest) -> TableInput {
+    TableInput {
+        session_id: parse_session_selector(request.param_str("session").map(String::from)),
+        region: request.param_str("region").map(String::from),
+    }
+}
+
+pub fn table_output_to_response(id: u64, output: TableOutput) -> RpcResponse {
+    let mut result = table_to_json(output.table.as_ref());
+    result["session_id"] = json!(output.session_id.as_str());
+    result["screen_version"] = json!(output.screen_version);
+    result["region"] = json!(output.region.as_ref().map(pane_to_json));
     RpcResponse::success(id, result)
 }
 
@@ -205,6 +488,110 @@ pub fn parse_keystroke_input(request: &RpcRequest) -> Result<KeystrokeInput, Rpc
     })
 }
 
+#[allow(clippy::result_large_err)]
+pub fn parse_select_input(request: &RpcRequest) -> Result<SelectInput, RpcResponse> {
+    let item = request.require_str("item")?.to_string();
+
+    Ok(SelectInput {
+        session_id: parse_session_selector(request.param_str("session").map(String::from)),
+        item,
+        up_key: request.param_str("up_key").unwrap_or("ArrowUp").to_string(),
+        down_key: request
+            .param_str("down_key")
+            .unwrap_or("ArrowDown")
+            .to_string(),
+        submit: request.param_bool("submit", false),
+        region: request.param_str("region").map(String::from),
+        max_steps: u32::try_from(request.param_u64("max_steps", 200)).unwrap_or(u32::MAX),
+        settle_ms: request.param_u64("settle_ms", 1000),
+    })
+}
+
+pub fn select_output_to_response(id: u64, output: SelectOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "found": output.found,
+            "selected": output.highlight.as_ref().map(|h| h.text.as_str()),
+            "row": output.highlight.as_ref().map(|h| h.row),
+            "steps": output.steps,
+            "submitted": output.submitted
+        }),
+    )
+}
+
+#[allow(clippy::result_large_err)]
+pub fn parse_fill_input(request: &RpcRequest) -> Result<FillInput, RpcResponse> {
+    let rpc_params: params::FillParams = request
+        .params
+        .as_ref()
+        .ok_or_else(|| RpcResponse::error(request.id, -32602, "Missing params"))
+        .and_then(|p| {
+            params::FillParams::deserialize(p).map_err(|e| {
+                RpcResponse::error(request.id, -32602, &format!("Invalid params: {}", e))
+            })
+        })?;
+
+    let fields = rpc_params
+        .fields
+        .into_iter()
+        .map(|field| {
+            let action = match (field.answer, field.keys, field.select) {
+                (Some(answer), None, None) => FillAction::Answer(answer),
+                (None, Some(keys), None) => FillAction::Keys(keys),
+                (None, None, Some(item)) => FillAction::Select(item),
+                _ => {
+                    return Err(RpcResponse::error(
+                        request.id,
+                        -32602,
+                        &format!(
+                            "Field '{}' needs exactly one of answer, keys or select",
+                            field.label
+                        ),
+                    ));
+                }
+            };
+            Ok(FillField {
+                label: field.label,
+                action,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(FillInput {
+        session_id: parse_session_selector(rpc_params.session),
+        fields,
+        timeout_ms: rpc_params.timeout_ms.unwrap_or(60_000),
+        prompt_timeout_ms: rpc_params.prompt_timeout_ms.unwrap_or(5_000),
+    })
+}
+
+pub fn fill_output_to_response(id: u64, output: FillOutput) -> RpcResponse {
+    let transcript: Vec<_> = output
+        .steps
+        .iter()
+        .map(|step| {
+            json!({
+                "label": step.label,
+                "prompt": step.prompt,
+                "row": step.row,
+                "answered": step.answered,
+                "elapsed_ms": step.elapsed_ms
+            })
+        })
+        .collect();
+    RpcResponse::success(
+        id,
+        json!({
+            "completed": output.completed(),
+            "transcript": transcript,
+            "unexpected": output.unexpected,
+            "missing": output.missing,
+            "elapsed_ms": output.elapsed_ms
+        }),
+    )
+}
+
 #[allow(clippy::result_large_err)]
 pub fn parse_type_input(request: &RpcRequest) -> Result<TypeInput, RpcResponse> {
     let text = request.require_str("text")?.to_string();
@@ -266,17 +653,20 @@ pub fn parse_wait_input(request: &RpcRequest) -> Result<WaitInput, RpcResponse>
         text: rpc_params.text,
         timeout_ms: rpc_params.timeout_ms,
         condition,
+        unwrap: rpc_params.unwrap,
+        region: rpc_params.region,
     })
 }
 
 pub fn wait_output_to_response(id: u64, output: WaitOutput) -> RpcResponse {
-    RpcResponse::success(
-        id,
-        json!({
-            "found": output.found,
-            "elapsed_ms": output.elapsed_ms
-        }),
-    )
+    let mut result = json!({
+        "found": output.found,
+        "elapsed_ms": output.elapsed_ms
+    });
+    if let Some(diagnostics) = output.diagnostics.as_ref() {
+        result["diagnostics"] = match_diagnostics_to_json(diagnostics);
+    }
+    RpcResponse::success(id, result)
 }
 
 pub fn kill_output_to_response(id: u64, output: KillOutput) -> RpcResponse {
@@ -329,6 +719,146 @@ pub fn resize_output_to_response(id: u64, output: ResizeOutput) -> RpcResponse {
     )
 }
 
+#[allow(clippy::result_large_err)]
+pub fn parse_signal_input(request: &RpcRequest) -> Result<SignalInput, RpcResponse> {
+    let rpc_params: params::SignalParams = request
+        .params
+        .as_ref()
+        .ok_or_else(|| RpcResponse::error(request.id, -32602, "Missing params"))
+        .and_then(|p| {
+            params::SignalParams::deserialize(p).map_err(|e| {
+                RpcResponse::error(request.id, -32602, &format!("Invalid params: {}", e))
+            })
+        })?;
+    let signal = ProcessSignal::parse(&rpc_params.signal).ok_or_else(|| {
+        RpcResponse::error(
+            request.id,
+            -32602,
+            &format!(
+                "Invalid params: unsupported signal '{}' (expected one of {})",
+                rpc_params.signal,
+                ProcessSignal::NAMES.join(", ")
+            ),
+        )
+    })?;
+
+    Ok(SignalInput {
+        session_id: parse_session_selector(rpc_params.session),
+        signal,
+    })
+}
+
+pub fn signal_output_to_response(id: u64, output: SignalOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "success": output.success,
+            "session_id": output.session_id.as_str(),
+            "signal": output.signal.as_str()
+        }),
+    )
+}
+
+#[allow(clippy::result_large_err)]
+pub fn parse_record_start_input(request: &RpcRequest) -> Result<RecordStartInput, RpcResponse> {
+    let rpc_params: params::RecordStartParams = request
+        .params
+        .as_ref()
+        .ok_or_else(|| RpcResponse::error(request.id, -32602, "Missing params"))
+        .and_then(|p| {
+            params::RecordStartParams::deserialize(p).map_err(|e| {
+                RpcResponse::error(request.id, -32602, &format!("Invalid params: {}", e))
+            })
+        })?;
+    if !std::path::Path::new(&rpc_params.path).is_absolute() {
+        return Err(RpcResponse::error(
+            request.id,
+            -32602,
+            &format!("Recording path must be absolute: {}", rpc_params.path),
+        ));
+    }
+
+    Ok(RecordStartInput {
+        session_id: parse_session_selector(rpc_params.session),
+        path: rpc_params.path,
+        include_input: rpc_params.include_input,
+    })
+}
+
+pub fn record_start_output_to_response(id: u64, output: RecordStartOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "path": output.path
+        }),
+    )
+}
+
+pub fn record_stop_output_to_response(id: u64, output: RecordStopOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "recording": output.recording.map(|recording| json!({
+                "path": recording.path,
+                "events": recording.events,
+                "duration_ms": recording.duration_ms
+            }))
+        }),
+    )
+}
+
+#[allow(clippy::result_large_err)]
+pub fn parse_trace_start_input(request: &RpcRequest) -> Result<TraceStartInput, RpcResponse> {
+    let rpc_params: params::TraceStartParams = request
+        .params
+        .as_ref()
+        .ok_or_else(|| RpcResponse::error(request.id, -32602, "Missing params"))
+        .and_then(|p| {
+            params::TraceStartParams::deserialize(p).map_err(|e| {
+                RpcResponse::error(request.id, -32602, &format!("Invalid params: {}", e))
+            })
+        })?;
+    if !std::path::Path::new(&rpc_params.path).is_absolute() {
+        return Err(RpcResponse::error(
+            request.id,
+            -32602,
+            &format!("Trace path must be absolute: {}", rpc_params.path),
+        ));
+    }
+
+    Ok(TraceStartInput {
+        session_id: parse_session_selector(rpc_params.session),
+        path: rpc_params.path,
+    })
+}
+
+pub fn trace_start_output_to_response(id: u64, output: TraceStartOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "path": output.path
+        }),
+    )
+}
+
+pub fn trace_stop_output_to_response(id: u64, output: TraceStopOutput) -> RpcResponse {
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "trace": output.trace.map(|trace| json!({
+                "path": trace.path,
+                "steps": trace.steps,
+                "output_bytes": trace.output_bytes,
+                "duration_ms": trace.duration_ms
+            }))
+        }),
+    )
+}
+
 pub fn restart_output_to_response(id: u64, output: RestartOutput) -> RpcResponse {
     RpcResponse::success(
         id,
@@ -392,17 +922,19 @@ pub fn parse_assert_input(request: &RpcRequest) -> Result<AssertInput, RpcRespon
         session_id: parse_session_selector(request.param_str("session").map(String::from)),
         condition_type,
         value,
+        unwrap: request.param_bool("unwrap", false),
     })
 }
 
 pub fn assert_output_to_response(id: u64, output: AssertOutput) -> RpcResponse {
-    RpcResponse::success(
-        id,
-        json!({
-            "passed": output.passed,
-            "condition": output.condition
-        }),
-    )
+    let mut result = json!({
+        "passed": output.passed,
+        "condition": output.condition
+    });
+    if let Some(diagnostics) = output.diagnostics.as_ref() {
+        result["diagnostics"] = match_diagnostics_to_json(diagnostics);
+    }
+    RpcResponse::success(id, result)
 }
 
 pub fn shutdown_output_to_response(id: u64, output: ShutdownOutput) -> RpcResponse {
@@ -420,6 +952,26 @@ pub fn terminal_write_output_to_response(id: u64, output: TerminalWriteOutput) -
     )
 }
 
+pub fn tty_state_output_to_response(id: u64, output: TtyStateOutput) -> RpcResponse {
+    let tty = output.tty_state.map(|state| {
+        json!({
+            "echo": state.echo,
+            "icanon": state.icanon,
+            "isig": state.isig,
+            "mode": state.input_mode(),
+            "password_prompt": state.is_password_prompt()
+        })
+    });
+    RpcResponse::success(
+        id,
+        json!({
+            "session_id": output.session_id.as_str(),
+            "available": tty.is_some(),
+            "tty": tty
+        }),
+    )
+}
+
 #[allow(clippy::result_large_err)]
 pub fn parse_terminal_write_input(request: &RpcRequest) -> Result<TerminalWriteInput, RpcResponse> {
     let rpc_params: params::PtyWriteParams = request
@@ -471,6 +1023,27 @@ mod tests {
         assert!(input.include_cursor);
     }
 
+    #[test]
+    fn test_parse_signal_input() {
+        let request = make_request(
+            1,
+            "signal",
+            Some(json!({"signal": "sigint", "session": "s1"})),
+        );
+        let input = parse_signal_input(&request).expect("signal input");
+        assert_eq!(input.signal, ProcessSignal::Int);
+        assert_eq!(
+            input
+                .session_id
+                .map(|id| id.as_str().to_string())
+                .as_deref(),
+            Some("s1")
+        );
+
+        let request = make_request(2, "signal", Some(json!({"signal": "USR1"})));
+        assert!(parse_signal_input(&request).is_err());
+    }
+
     #[test]
     fn test_parse_session_selector_defaults_to_active() {
         assert_eq!(parse_session_selector(None), None);
@@ -513,6 +1086,92 @@ mod tests {
         assert_eq!(input.timeout_ms, 5000);
     }
 
+    #[test]
+    fn test_wait_output_to_response_includes_diagnostics_on_timeout() {
+        let output = WaitOutput {
+            found: false,
+            elapsed_ms: 1000,
+            diagnostics: Some(crate::domain::MatchDiagnostics {
+                screen: "Continue?".to_string(),
+                nearest_matches: vec![crate::domain::TextMatch {
+                    text: "Continue?".to_string(),
+                    row: 0,
+                    col: 0,
+                    distance: 1,
+                }],
+                still_visible: Vec::new(),
+                screen_idle_ms: 75,
+            }),
+        };
+        let value = serde_json::to_value(wait_output_to_response(1, output)).unwrap();
+        let diagnostics = &value["result"]["diagnostics"];
+        assert_eq!(diagnostics["screen"], "Continue?");
+        assert_eq!(diagnostics["screen_idle_ms"], 75);
+        assert_eq!(diagnostics["nearest_matches"][0]["distance"], 1);
+        assert!(diagnostics.get("still_visible").is_none());
+    }
+
+    #[test]
+    fn test_wait_output_to_response_includes_text_still_visible() {
+        let output = WaitOutput {
+            found: false,
+            elapsed_ms: 1000,
+            diagnostics: Some(crate::domain::MatchDiagnostics {
+                screen: "  Loading".to_string(),
+                nearest_matches: Vec::new(),
+                still_visible: vec![crate::domain::TextMatch {
+                    text: "Loading".to_string(),
+                    row: 0,
+                    col: 2,
+                    distance: 0,
+                }],
+                screen_idle_ms: 10,
+            }),
+        };
+        let value = serde_json::to_value(wait_output_to_response(1, output)).unwrap();
+        let diagnostics = &value["result"]["diagnostics"];
+        assert_eq!(
+            diagnostics["still_visible"],
+            serde_json::json!([{ "text": "Loading", "row": 0, "col": 2 }])
+        );
+        assert_eq!(diagnostics["nearest_matches"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn test_table_output_to_response_keys_rows_by_header() {
+        use crate::domain::core::Table;
+        use crate::domain::core::TableColumn;
+        use crate::domain::core::TableRow;
+
+        let column = |name: &str, col: u16| TableColumn {
+            name: name.to_string(),
+            col,
+            width: 5,
+        };
+        let output = TableOutput {
+            session_id: SessionId::new("s1"),
+            screen_version: 3,
+            region: None,
+            table: Some(Table {
+                header_row: 0,
+                columns: vec![column("PID", 0), column("NAME", 5)],
+                rows: vec![TableRow {
+                    row: 1,
+                    cells: vec!["42".to_string(), "vim".to_string()],
+                }],
+                highlighted: Some(0),
+            }),
+        };
+        let value = serde_json::to_value(table_output_to_response(1, output)).unwrap();
+        let result = &value["result"];
+        assert_eq!(result["session_id"], "s1");
+        assert_eq!(result["rows"][0], json!({"PID": "42", "NAME": "vim"}));
+        assert_eq!(result["screen_rows"], json!([1]));
+        assert_eq!(result["columns"][1]["col"], 5);
+        assert_eq!(result["highlighted"], 0);
+        assert!(result["region"].is_null());
+    }
+
     #[test]
     fn test_parse_wait_input_requires_text() {
         let request = make_request(1, "wait", Some(json!({"condition": "text"})));
@@ -39,6 +39,25 @@ impl Default for SpawnParams {
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayParams {
+    /// Absolute path of the `.cast` file or raw byte log to play.
+    pub path: String,
+    /// Playback speed factor; defaults to 1.0 (recorded timing).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub speed: Option<f64>,
+    /// Advance on input instead of following the recorded timing.
+    #[serde(default)]
+    pub step: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    /// Terminal size for raw logs; casts use the size in their header.
+    #[serde(default = "default_cols")]
+    pub cols: u16,
+    #[serde(default = "default_rows")]
+    pub rows: u16,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SnapshotParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -51,6 +70,33 @@ pub struct SnapshotParams {
     pub include_cursor: bool,
     #[serde(default)]
     pub include_render: bool,
+    #[serde(default)]
+    pub unwrap: bool,
+    #[serde(default)]
+    pub include_highlights: bool,
+    /// Render the screen as an image ("svg" or "png") or an HTML fragment ("html").
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotDiffParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    pub since: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssertScreenParams {
+    /// Golden file contents: optional `%mask`/`%style` header, then screen rows.
+    pub golden: String,
+    /// Extra masks: `regex PATTERN` or `rect ROW,COL WIDTHxHEIGHT`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub masks: Vec<String>,
+    #[serde(default)]
+    pub styles: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,6 +106,65 @@ pub struct KeyParams {
     pub session: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FindParams {
+    pub text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    #[serde(default)]
+    pub unwrap: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectParams {
+    pub item: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub up_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub down_key: Option<String>,
+    #[serde(default)]
+    pub submit: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_steps: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillParams {
+    pub fields: Vec<FillFieldParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prompt_timeout_ms: Option<u64>,
+}
+
+/// One prompt; exactly one of `answer`, `keys` or `select` is set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillFieldParams {
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub answer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keys: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub select: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TypeParams {
     pub text: String,
@@ -77,6 +182,10 @@ pub struct WaitParams {
     pub timeout_ms: u64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub condition: Option<String>,
+    #[serde(default)]
+    pub unwrap: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
 }
 
 fn default_timeout_ms() -> u64 {
@@ -90,6 +199,8 @@ impl Default for WaitParams {
             text: None,
             timeout_ms: default_timeout_ms(),
             condition: None,
+            unwrap: false,
+            region: None,
         }
     }
 }
@@ -102,6 +213,32 @@ pub struct ResizeParams {
     pub session: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignalParams {
+    /// Signal name such as `INT` or `SIGTERM`.
+    pub signal: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordStartParams {
+    /// Absolute path of the `.cast` file to write.
+    pub path: String,
+    #[serde(default)]
+    pub include_input: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TraceStartParams {
+    /// Absolute path of the `.zip` bundle written by `trace_stop`.
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct SessionParams {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -127,6 +264,9 @@ mod tests {
             strip_ansi: true,
             include_cursor: false,
             include_render: true,
+            unwrap: false,
+            include_highlights: false,
+            image: None,
         };
 
         let json = serde_json::to_value(&params).unwrap();
@@ -142,5 +282,6 @@ mod tests {
         assert_eq!(params.timeout_ms, 30000);
         assert!(params.text.is_none());
         assert!(params.condition.is_none());
+        assert!(!params.unwrap);
     }
 }
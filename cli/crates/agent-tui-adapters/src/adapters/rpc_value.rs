@@ -79,6 +79,10 @@ impl<'a> RpcValueRef<'a> {
         self.0.as_u64()
     }
 
+    pub fn as_i64(&self) -> Option<i64> {
+        self.0.as_i64()
+    }
+
     pub fn as_bool(&self) -> Option<bool> {
         self.0.as_bool()
     }
@@ -105,6 +109,10 @@ impl<'a> RpcArrayRef<'a> {
         self.0.iter().map(RpcValueRef)
     }
 
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
@@ -1,5 +1,9 @@
 //! Snapshot adapter helpers.
 
+use crate::domain::MatchDiagnostics;
+use crate::domain::core::Element;
+use crate::domain::core::Pane;
+use crate::domain::core::Table;
 use crate::domain::session_types::SessionInfo;
 
 pub(crate) fn session_info_to_json(info: &SessionInfo) -> serde_json::Value {
@@ -8,7 +12,100 @@ pub(crate) fn session_info_to_json(info: &SessionInfo) -> serde_json::Value {
         "command": info.command,
         "pid": info.pid,
         "running": info.running,
+        "exit_code": info.exit_code,
         "created_at": info.created_at,
         "size": { "cols": info.size.cols(), "rows": info.size.rows() }
     })
 }
+
+pub(crate) fn match_diagnostics_to_json(diagnostics: &MatchDiagnostics) -> serde_json::Value {
+    let mut value = serde_json::json!({
+        "screen": diagnostics.screen,
+        "screen_idle_ms": diagnostics.screen_idle_ms,
+        "nearest_matches": diagnostics
+            .nearest_matches
+            .iter()
+            .map(|m| serde_json::json!({
+                "text": m.text,
+                "row": m.row,
+                "col": m.col,
+                "distance": m.distance
+            }))
+            .collect::<Vec<_>>()
+    });
+    if !diagnostics.still_visible.is_empty() {
+        value["still_visible"] = diagnostics
+            .still_visible
+            .iter()
+            .map(|m| serde_json::json!({ "text": m.text, "row": m.row, "col": m.col }))
+            .collect();
+    }
+    value
+}
+
+pub(crate) fn element_to_json(element: &Element) -> serde_json::Value {
+    serde_json::json!({
+        "ref": element.ref_id,
+        "kind": element.kind.as_str(),
+        "label": element.label,
+        "row": element.row,
+        "col": element.col,
+        "width": element.width,
+        "height": element.height,
+        "value": element.value,
+        "checked": element.checked,
+        "selected": element.selected,
+        "focused": element.focused,
+        "children": element.children.iter().map(element_to_json).collect::<Vec<_>>()
+    })
+}
+
+pub(crate) fn pane_to_json(pane: &Pane) -> serde_json::Value {
+    serde_json::json!({
+        "id": pane.id,
+        "title": pane.title,
+        "row": pane.row,
+        "col": pane.col,
+        "width": pane.width,
+        "height": pane.height,
+        "parent": pane.parent,
+        "focused": pane.focused
+    })
+}
+
+/// Table fields for the `table` response; rows are objects keyed by column name.
+pub(crate) fn table_to_json(table: Option<&Table>) -> serde_json::Value {
+    let Some(table) = table else {
+        return serde_json::json!({
+            "header_row": null,
+            "columns": [],
+            "rows": [],
+            "screen_rows": [],
+            "highlighted": null
+        });
+    };
+    let rows: Vec<serde_json::Value> = table
+        .rows
+        .iter()
+        .map(|row| {
+            let cells: serde_json::Map<String, serde_json::Value> = table
+                .columns
+                .iter()
+                .zip(&row.cells)
+                .map(|(column, cell)| (column.name.clone(), serde_json::Value::from(cell.as_str())))
+                .collect();
+            serde_json::Value::Object(cells)
+        })
+        .collect();
+    serde_json::json!({
+        "header_row": table.header_row,
+        "columns": table.columns.iter().map(|column| serde_json::json!({
+            "name": column.name,
+            "col": column.col,
+            "width": column.width
+        })).collect::<Vec<_>>(),
+        "rows": rows,
+        "screen_rows": table.rows.iter().map(|row| row.row).collect::<Vec<_>>(),
+        "highlighted": table.highlighted
+    })
+}
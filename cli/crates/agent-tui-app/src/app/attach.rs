@@ -888,6 +888,7 @@ fn stream_output_loop(
                     );
                 }
             }
+            #[allow(clippy::collapsible_match)]
             Some(AttachStreamEvent::Dropped(dropped_bytes)) => {
                 if report_drops && dropped_bytes > 0 {
                     eprintln!(
@@ -4,6 +4,7 @@ use clap::ArgGroup;
 use clap::Args;
 use clap::Parser;
 use clap::Subcommand;
+use clap::ValueEnum;
 use clap::ValueHint;
 pub use clap_complete::Shell;
 use std::path::PathBuf;
@@ -30,6 +31,9 @@ const AFTER_LONG_HELP: &str = r#"WORKFLOW:
 OUTPUT:
     --format json  Machine-readable JSON (recommended for automation)
     --format text  Human-readable text (default)
+    --format agent Compact screenshots with row numbers and a column ruler
+    --format html  Screenshots as a styled HTML fragment for reports
+                   (agent and html are screenshot only; other commands reject them)
 
 CONFIGURATION:
     AGENT_TUI_TRANSPORT         IPC transport (unix or ws; default: unix)
@@ -90,7 +94,7 @@ pub struct Cli {
     )]
     pub session: Option<String>,
 
-    /// Output format (text or json)
+    /// Output format (text, json, agent or html for screenshots)
     #[arg(
         short,
         long,
@@ -135,18 +139,33 @@ Run a new TUI application in a virtual terminal.
 
 Creates a new PTY session with the specified command and returns a session ID.
 The session runs in the background and can be interacted with using other commands.
-Use `--` before COMMAND args that start with `-` (e.g., `run -- vim -n`).")]
+Use `--` before COMMAND args that start with `-` (e.g., `run -- vim -n`).
+
+With --replay, no process is started: the session plays back a recording
+(an asciinema v2 .cast file or a raw terminal byte log) so screenshot, wait,
+find and the live preview can be used against a past run. Casts keep their
+recorded timing (scaled by --speed) and size; raw logs have no timing and use
+--cols/--rows. With --step, output only advances when input is sent: up to the
+next recorded keystroke for casts with input events, otherwise one output
+event (one line of a raw log) per press or type.")]
     #[command(after_long_help = "\
 EXAMPLES:
     agent-tui run bash
     agent-tui run htop
     agent-tui run \"npx create-next-app\"
     agent-tui run vim -- file.txt
-    agent-tui run --cols 80 --rows 24 nano")]
+    agent-tui run --cols 80 --rows 24 nano
+    agent-tui run --record run.cast htop
+    agent-tui run --replay run.cast --speed 4
+    agent-tui run --replay run.cast --step")]
     Run {
         /// Command to run inside the virtual terminal
-        #[arg(value_name = "COMMAND", value_hint = ValueHint::CommandName)]
-        command: String,
+        #[arg(
+            value_name = "COMMAND",
+            value_hint = ValueHint::CommandName,
+            required_unless_present = "replay"
+        )]
+        command: Option<String>,
 
         /// Arguments for the command (use -- to pass flags through)
         #[arg(trailing_var_arg = true, value_name = "ARG")]
@@ -173,19 +192,108 @@ EXAMPLES:
             help_heading = "Terminal Size"
         )]
         rows: u16,
+
+        /// Record the session to an asciinema v2 .cast file (stop with `record stop`)
+        #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, help_heading = "Recording")]
+        record: Option<PathBuf>,
+
+        /// Also record typed text and key presses as input events
+        #[arg(long, requires = "record", help_heading = "Recording")]
+        record_input: bool,
+
+        /// Play back a .cast file or raw byte log instead of running a command
+        #[arg(
+            long,
+            value_name = "FILE",
+            value_hint = ValueHint::FilePath,
+            conflicts_with_all = ["command", "cwd", "record"],
+            help_heading = "Replay"
+        )]
+        replay: Option<PathBuf>,
+
+        // The replay-only flags conflict with COMMAND instead of requiring
+        // --replay: clap skips `requires` on args that conflict with a present one.
+        /// Playback speed factor for --replay (default: 1, recorded timing)
+        #[arg(
+            long,
+            value_name = "N",
+            conflicts_with = "command",
+            value_parser = parse_speed,
+            help_heading = "Replay"
+        )]
+        speed: Option<f64>,
+
+        /// Advance the replay only when input is sent
+        #[arg(
+            long,
+            conflicts_with_all = ["command", "speed"],
+            help_heading = "Replay"
+        )]
+        step: bool,
     },
 
     /// Capture a screenshot of the current session
     #[command(long_about = "\
 View the current screenshot state.
 
-Returns the current terminal screenshot content.")]
+Returns the current terminal screenshot content and its screen version.
+Pass a previous version with --since to get only the rows that changed
+since then, plus the cursor movement.")]
     #[command(after_long_help = "\
 EXAMPLES:
     agent-tui screenshot              # Just the screenshot
-    agent-tui screenshot --strip-ansi # Plain text without colors")]
+    agent-tui screenshot --strip-ansi # Plain text without colors
+    agent-tui screenshot --unwrap     # Join soft-wrapped rows
+    agent-tui screenshot --since 4821 # Only rows changed since version 4821
+    agent-tui screenshot --elements   # Detected widgets with refs
+    agent-tui screenshot --panes      # Bordered panes usable as --region
+    agent-tui screenshot --table      # Columnar list as rows keyed by header
+    agent-tui screenshot --region Files
+    agent-tui screenshot --image png --out screen.png
+    agent-tui --format agent screenshot --strip-borders
+    agent-tui --format html screenshot > screen.html
+
+AGENT FORMAT:
+    --format agent numbers rows, adds a column ruler, collapses blank runs,
+    marks the cursor as ▌ and inverse (highlighted) cells as «text», and ends
+    with a summary line including a token estimate. Marks are inserted inline,
+    so use the ruler on unmarked rows or the summary for exact columns.
+
+ELEMENTS:
+    --elements returns a tree of detected widgets instead of the screen text:
+    boxes (with border titles), buttons like [ OK ] or <Cancel>, checkboxes
+    and radios ([x], ( ), ◉), inputs (prompt under the cursor, ___ fields),
+    and lists whose selection is drawn in inverse video or marked with ❯.
+    Refs (e1, e2, ...) follow reading order and stay the same while the
+    layout does not change. Detection is heuristic.
+
+PANES:
+    Boxes drawn with ┌─┐│└┘ (or ASCII +-|) are segmented into panes with
+    their border titles and nesting. The focused pane is the one whose border
+    stands out (bold, colored, heavy, or an inverse title). --region, and the
+    --region option of wait and find, accept a pane title, an id (p1, p2, ...),
+    or focused, left, right, top, bottom.
+
+TABLE:
+    --table reads aligned columns (process lists, docker ps, file managers)
+    from the screen or from --region. Column boundaries come from the header
+    and whitespace that lines up in every row below it; the table ends at the
+    first blank line. The highlighted row is the one drawn in inverse video
+    (or, failing that, the only row with a background color).
+
+IMAGE:
+    --image svg|png --out FILE draws the whole screen with the session's
+    colors, bold/underline/inverse styles and the cursor, using a built-in
+    bitmap font (no system fonts needed). The output is deterministic, so
+    images can be compared byte for byte in golden tests.
+
+HTML FORMAT:
+    --format html prints the whole screen as one <pre> element with inline
+    styles: a <span> per run of cells sharing colors and bold/underline, and
+    the cursor cell marked with class=\"cursor\". Paste it into CI reports or
+    PR comments where ANSI escapes would be unreadable.")]
     Screenshot {
-        /// Limit capture to a named region (if supported)
+        /// Only capture inside a pane (title, id, focused, left/right/top/bottom)
         #[arg(long, value_name = "REGION", help_heading = "Filtering")]
         region: Option<String>,
 
@@ -196,6 +304,62 @@ EXAMPLES:
         /// Include cursor position in output
         #[arg(long, help_heading = "Output Options")]
         include_cursor: bool,
+
+        /// Join rows the terminal soft-wrapped into logical lines
+        #[arg(long, help_heading = "Output Options")]
+        unwrap: bool,
+
+        /// Blank out box-drawing borders (with --format agent)
+        #[arg(long, help_heading = "Output Options")]
+        strip_borders: bool,
+
+        /// Return detected widgets (boxes, buttons, toggles, inputs, lists) with refs
+        #[arg(
+            long,
+            conflicts_with_all = ["unwrap", "include_cursor", "region", "strip_borders", "since"],
+            help_heading = "Output Options"
+        )]
+        elements: bool,
+
+        /// List detected panes usable as --region
+        #[arg(
+            long,
+            conflicts_with_all = ["unwrap", "include_cursor", "region", "strip_borders", "since", "elements"],
+            help_heading = "Output Options"
+        )]
+        panes: bool,
+
+        /// Return the table on screen (or in --region) as rows keyed by header
+        #[arg(
+            long,
+            conflicts_with_all = ["unwrap", "include_cursor", "strip_borders", "since", "elements", "panes"],
+            help_heading = "Output Options"
+        )]
+        table: bool,
+
+        /// Only show rows changed since this screen version
+        #[arg(
+            long,
+            value_name = "VERSION",
+            conflicts_with_all = ["unwrap", "include_cursor", "region", "strip_borders"],
+            help_heading = "Output Options"
+        )]
+        since: Option<u64>,
+
+        /// Render the screen to an image file (needs --out)
+        #[arg(
+            long,
+            value_enum,
+            value_name = "FORMAT",
+            requires = "out",
+            conflicts_with_all = ["unwrap", "include_cursor", "region", "strip_borders", "since", "elements", "panes", "table"],
+            help_heading = "Image"
+        )]
+        image: Option<ImageFormat>,
+
+        /// File to write the --image to
+        #[arg(long, value_name = "FILE", requires = "image", help_heading = "Image")]
+        out: Option<PathBuf>,
     },
     /// Resize the session terminal
     #[command(long_about = "\
@@ -257,6 +421,108 @@ EXAMPLES:
         text: String,
     },
 
+    /// Move a menu or list highlight onto an item
+    #[command(long_about = "\
+Move the highlight of a menu or list onto the row containing ITEM.
+
+The daemon finds the highlighted row (inverse video, else a background color,
+else a pointer like ❯), then presses the down key until ITEM is highlighted,
+the list wraps, or the highlight stops at the end of the list. At the end it
+turns around and presses the up key. Exits with status 1 if ITEM is never
+highlighted.")]
+    #[command(after_long_help = "\
+EXAMPLES:
+    agent-tui select \"Settings\"
+    agent-tui select --enter \"Open file\"
+    agent-tui select --up k --down j \"main.rs\"
+    agent-tui select --region Branches \"develop\"")]
+    Select {
+        /// Text the highlighted row must contain
+        #[arg(value_name = "ITEM", allow_hyphen_values = true)]
+        item: String,
+
+        /// Press Enter once the item is highlighted
+        #[arg(long)]
+        enter: bool,
+
+        /// Key that moves the highlight up
+        #[arg(
+            long,
+            value_name = "KEY",
+            default_value = "ArrowUp",
+            help_heading = "Keys"
+        )]
+        up: String,
+
+        /// Key that moves the highlight down
+        #[arg(
+            long,
+            value_name = "KEY",
+            default_value = "ArrowDown",
+            help_heading = "Keys"
+        )]
+        down: String,
+
+        /// Only look for the highlight inside a pane
+        #[arg(long, value_name = "REGION")]
+        region: Option<String>,
+
+        /// Give up after this many key presses
+        #[arg(long, value_name = "N", default_value_t = 200)]
+        max_steps: u32,
+    },
+
+    /// Answer a series of prompts from a file
+    #[command(long_about = "\
+Answer the prompts of a setup wizard or form from a JSON or YAML file.
+
+FILE maps prompt labels to answers. A label matches any screen line that
+contains it, ignoring case. Each value is one of:
+    TEXT              Type TEXT and press Enter (\"\" accepts the default)
+    [KEY, ...]        Press the keys as given, with no extra Enter
+    {select: OPTION}  Move the list highlight onto OPTION and press Enter
+
+Prompts are answered in the order they appear on screen: whichever pending
+label is on the cursor line (or lowest on screen) goes next. The run stops
+when every label is answered, the process exits, the timeout passes, or the
+screen sits quiet on a prompt no label matches. Exits with status 1 unless
+every label was answered.")]
+    #[command(after_long_help = "\
+EXAMPLES:
+    agent-tui fill answers.yaml
+    agent-tui fill --prompt-timeout 10000 answers.json
+    echo '{\"Project name\": \"demo\"}' | agent-tui fill -
+
+FILE (YAML):
+    Project name: demo
+    Framework: {select: Svelte}
+    Use TypeScript: [ArrowRight, Enter]
+    Install dependencies: \"\"")]
+    Fill {
+        /// JSON or YAML answers file (- reads stdin)
+        #[arg(value_name = "FILE")]
+        file: PathBuf,
+
+        /// Give up on the whole form after this long
+        #[arg(
+            short,
+            long,
+            default_value_t = 60_000,
+            value_name = "MILLIS",
+            help_heading = "Timing"
+        )]
+        timeout: u64,
+
+        /// Stop once the screen sits this long on a prompt no label matches
+        #[arg(
+            long,
+            default_value_t = 5_000,
+            value_name = "MILLIS",
+            help_heading = "Timing"
+        )]
+        prompt_timeout: u64,
+    },
+
     /// Wait for text or screenshot stability
     #[command(long_about = "\
 Wait for a condition to be met before continuing.
@@ -268,6 +534,8 @@ WAIT CONDITIONS:
     <text>       Wait for text to appear on screenshot
     --stable     Wait for screenshot to stop changing
     -g, --gone   Modifier: wait for text to disappear
+    --echo-off   Wait for terminal echo to be disabled (password prompts)
+    --unwrap     Modifier: match text across soft-wrapped rows
 
 ASSERT MODE:
     --assert            Exit with code 0 if condition met, 1 if timeout.
@@ -277,12 +545,88 @@ EXAMPLES:
     agent-tui wait \"Continue\"           # Wait for text
     agent-tui wait --stable             # Wait for screenshot stability
     agent-tui wait \"Loading\" --gone     # Wait for text to disappear
-    agent-tui wait -t 5000 \"Done\"       # 5 second timeout")]
+    agent-tui wait --echo-off           # Wait for a password prompt
+    agent-tui wait -t 5000 \"Done\"       # 5 second timeout
+    agent-tui wait --region focused \"Done\" # Only inside the focused pane")]
     Wait {
         #[command(flatten)]
         params: WaitParams,
     },
 
+    /// Find text on the screen and report its positions
+    #[command(long_about = "\
+Find every occurrence of text on the current screen.
+
+Reports the start and end (inclusive) row/col of each match. With --unwrap,
+text that soft-wraps across rows is matched as one line; positions are
+still reported as physical rows and columns.")]
+    #[command(after_long_help = "\
+EXAMPLES:
+    agent-tui find \"Continue\"
+    agent-tui find --unwrap \"/usr/local/bin/tool\"
+    agent-tui find --region Files \"main.rs\"")]
+    Find {
+        /// Text to search for
+        #[arg(value_name = "TEXT", allow_hyphen_values = true)]
+        text: String,
+
+        /// Match across soft-wrapped rows
+        #[arg(long, help_heading = "Matching")]
+        unwrap: bool,
+
+        /// Only search inside a pane (title, id, focused, left/right/top/bottom)
+        #[arg(long, value_name = "REGION", help_heading = "Matching")]
+        region: Option<String>,
+    },
+
+    /// Compare the screen against a golden file
+    #[command(long_about = "\
+Compare the current screen against a stored golden file.
+
+The golden file holds the expected screen text, one row per line (trailing
+spaces and blank rows are ignored). On a mismatch the differing rows are
+printed and the command exits with code 1. --update writes the current screen
+to the file instead, creating it if needed.
+
+Volatile areas can be masked so they never cause a mismatch. A golden file may
+start with a header of directives closed by a '%%' line:
+
+    %mask regex \\d\\d:\\d\\d:\\d\\d     each match becomes '*' on both sides
+    %mask rect 0,60 20x1          cells from row 0, col 60, 20 wide, 1 tall
+    %%
+
+--mask-regex and --mask-rect add masks for one run; with --update they are
+saved into the header. --styles also compares bold/underline/inverse and colors
+per cell run, recorded as '%style' header lines; a golden with style lines
+always checks styles.")]
+    #[command(after_long_help = "\
+EXAMPLES:
+    agent-tui assert-screen --golden tests/login.txt
+    agent-tui assert-screen --golden tests/login.txt --update
+    agent-tui assert-screen --golden top.txt --mask-regex '\\d+:\\d\\d' --mask-rect 0,60,20x1
+    agent-tui assert-screen --golden menu.txt --styles")]
+    AssertScreen {
+        /// Golden file to compare against (or write with --update)
+        #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        golden: PathBuf,
+
+        /// Rewrite the golden file from the current screen
+        #[arg(long)]
+        update: bool,
+
+        /// Compare cell styles (bold, underline, inverse, colors) as well as text
+        #[arg(long)]
+        styles: bool,
+
+        /// Mask every match of a regex (repeatable)
+        #[arg(long = "mask-regex", value_name = "PATTERN", help_heading = "Masks")]
+        mask_regex: Vec<String>,
+
+        /// Mask a rectangle of cells given as ROW,COL,WIDTHxHEIGHT (repeatable)
+        #[arg(long = "mask-rect", value_name = "RECT", value_parser = parse_mask_rect, help_heading = "Masks")]
+        mask_rect: Vec<String>,
+    },
+
     /// Kill the current session
     #[command(after_long_help = "\
 EXAMPLES:
@@ -290,6 +634,101 @@ EXAMPLES:
     agent-tui --session abc123 kill")]
     Kill,
 
+    /// Record the session as an asciinema v2 .cast file
+    #[command(long_about = "\
+Record the session as an asciinema v2 .cast file.
+
+The recording starts with the current screen, then captures everything the
+application prints and every resize, with timestamps. With --input, text and
+keys sent by agent-tui are recorded as input events too. The file is written
+as events happen; `record stop` closes it. Play it back with standard tools
+such as `asciinema play`.")]
+    #[command(after_long_help = "\
+EXAMPLES:
+    agent-tui record start triage.cast
+    agent-tui record start --input triage.cast
+    agent-tui record stop
+    asciinema play triage.cast")]
+    Record {
+        #[command(subcommand)]
+        command: RecordCommand,
+    },
+
+    /// Capture actions, screens and output into a trace bundle
+    #[command(long_about = "\
+Capture a step-by-step trace of the session into a single .zip bundle.
+
+While a trace runs, every action sent through agent-tui (press, type, keydown,
+keyup, resize, signal, write) and every wait result is recorded with a
+timestamp, together with the screen before and after it and the raw output
+stream. A step's \"after\" screen is taken once the output it caused has been
+quiet for 100ms; a step with no output, or one still drawing when the next
+step starts, ends with the screen seen at that point (or when the trace stops). `trace show` prints the bundle as a text report without a daemon.
+
+BUNDLE LAYOUT:
+    trace.json        Session metadata and the list of steps
+    screens/NNNN.txt  Screen text referenced by the steps (stored once each)
+    output.bin        Raw output bytes (capped at 32 MiB)")]
+    #[command(after_long_help = "\
+EXAMPLES:
+    agent-tui trace start --out login.zip
+    agent-tui type \"admin\" && agent-tui press Enter && agent-tui wait \"Welcome\"
+    agent-tui trace stop
+    agent-tui trace show login.zip")]
+    Trace {
+        #[command(subcommand)]
+        command: TraceCommand,
+    },
+
+    /// Run declarative scenario scripts
+    #[command(long_about = "\
+Run a scenario script: a YAML (or .toml) file listing steps to execute against
+the daemon in order, each timed and reported.
+
+STEPS (one action per step):
+    run: CMD | {command, args, cwd, cols, rows}   Start a session for later steps
+    press: KEY | [KEY, ...]                       Press keys
+    type: TEXT                                    Type text
+    paste: TEXT                                   Write text to the terminal as is
+    wait: TEXT | {text, gone, stable, echo_off, region, unwrap}
+    assert: TEXT | {text | gone | regex | golden}  Check the current screen
+    capture: REGEX                                Store named groups as variables
+    screenshot: FILE                              Write the screen text to FILE
+    resize: {cols, rows}                          Resize the terminal
+    signal: INT | QUIT | TSTP | TERM | HUP | KILL  Signal the process group
+    sleep: MILLIS                                 Pause
+
+Each step may also set name and timeout_ms (for wait). wait, assert, capture
+and screenshot steps may set retries; other steps run once. Top-level keys
+timeout_ms (default 10000), retries (default 0, for retryable steps),
+retry_delay_ms (default 250) and vars set defaults. Strings may use ${name} to reference vars, --var values
+and captured groups. Relative paths resolve against the script's directory.
+
+Without a run step, steps act on the current session. Sessions started by the
+script are killed at the end unless --keep is given. The run stops at the first
+failing step and exits with status 1.")]
+    #[command(after_long_help = "\
+EXAMPLES:
+    agent-tui script run login.yaml
+    agent-tui script run login.yaml --var user=admin --junit results.xml
+    agent-tui --format json script run smoke.toml
+
+SCRIPT (YAML):
+    name: login
+    vars: {user: admin}
+    steps:
+      - run: ./app --demo
+      - wait: \"Username:\"
+      - type: \"${user}\"
+      - press: Enter
+      - capture: 'Session (?P<sid>[0-9a-f]+)'
+      - assert: {golden: golden/home.txt}
+        retries: 3")]
+    Script {
+        #[command(subcommand)]
+        command: ScriptCommand,
+    },
+
     /// List and manage sessions
     #[command(long_about = "\
 Manage sessions - list, show details, attach, switch active, or cleanup.
@@ -413,6 +852,62 @@ INSTALLATION:
     },
 }
 
+/// Encodings for `screenshot --image`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+}
+
+impl ImageFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+        }
+    }
+}
+
+fn parse_speed(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        _ => Err(format!("expected a positive number, got '{value}'")),
+    }
+}
+
+/// `ROW,COL,WIDTHxHEIGHT` to the golden header form `rect ROW,COL WIDTHxHEIGHT`.
+fn parse_mask_rect(value: &str) -> Result<String, String> {
+    let invalid = || format!("expected ROW,COL,WIDTHxHEIGHT, got '{value}'");
+    let mut parts = value.split(',');
+    let (Some(row), Some(col), Some(size), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid());
+    };
+    let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+    for number in [row, col, width, height] {
+        number.trim().parse::<u16>().map_err(|_| invalid())?;
+    }
+    Ok(format!(
+        "rect {},{} {}x{}",
+        row.trim(),
+        col.trim(),
+        width.trim(),
+        height.trim()
+    ))
+}
+
+fn parse_script_var(value: &str) -> Result<(String, String), String> {
+    match value.split_once('=') {
+        Some((name, text))
+            if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+        {
+            Ok((name.to_string(), text.to_string()))
+        }
+        _ => Err(format!("expected NAME=VALUE, got '{value}'")),
+    }
+}
+
 #[derive(Debug, Subcommand)]
 pub enum SessionsCommand {
     /// List active sessions
@@ -454,6 +949,68 @@ pub enum SessionsCommand {
     },
 }
 
+#[derive(Debug, Subcommand)]
+#[command(subcommand_required = true, arg_required_else_help = true)]
+pub enum RecordCommand {
+    /// Start recording to a .cast file
+    Start {
+        /// File to write (overwritten if it exists)
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// Also record typed text and key presses as input events
+        #[arg(long)]
+        input: bool,
+    },
+
+    /// Stop recording and close the file
+    Stop,
+}
+
+#[derive(Debug, Subcommand)]
+#[command(subcommand_required = true, arg_required_else_help = true)]
+pub enum TraceCommand {
+    /// Start tracing the session
+    Start {
+        /// Bundle to write on `trace stop` (overwritten if it exists)
+        #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath, default_value = "trace.zip")]
+        out: PathBuf,
+    },
+
+    /// Stop tracing and write the bundle
+    Stop,
+
+    /// Print a step-by-step report of a trace bundle
+    Show {
+        /// Bundle written by `trace stop`
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+#[command(subcommand_required = true, arg_required_else_help = true)]
+pub enum ScriptCommand {
+    /// Run a script and report each step
+    Run {
+        /// YAML or TOML script
+        #[arg(value_name = "FILE", value_hint = ValueHint::FilePath)]
+        file: PathBuf,
+
+        /// Set a script variable as NAME=VALUE (repeatable, overrides vars)
+        #[arg(long = "var", value_name = "NAME=VALUE", value_parser = parse_script_var)]
+        vars: Vec<(String, String)>,
+
+        /// Also write a JUnit XML report to FILE
+        #[arg(long, value_name = "FILE", value_hint = ValueHint::FilePath)]
+        junit: Option<PathBuf>,
+
+        /// Leave sessions started by the script running
+        #[arg(long)]
+        keep: bool,
+    },
+}
+
 #[derive(Debug, Subcommand)]
 pub enum LiveCommand {
     /// Show the live preview API details
@@ -524,7 +1081,7 @@ All active sessions will be terminated during restart.")]
     group = ArgGroup::new("wait_condition")
         .multiple(false)
         .required(true)
-        .args(&["text", "stable"])
+        .args(&["text", "stable", "echo_off"])
 )]
 pub struct WaitParams {
     /// Text to wait for (positional)
@@ -549,6 +1106,18 @@ pub struct WaitParams {
     #[arg(short = 'g', long, requires = "text", help_heading = "Wait Condition")]
     pub gone: bool,
 
+    /// Wait for the PTY to turn off echo (e.g. a password prompt)
+    #[arg(long, group = "wait_condition", help_heading = "Wait Condition")]
+    pub echo_off: bool,
+
+    /// Match text across soft-wrapped rows
+    #[arg(long, help_heading = "Matching")]
+    pub unwrap: bool,
+
+    /// Only match inside a pane (title, id, focused, left/right/top/bottom)
+    #[arg(long, value_name = "REGION", help_heading = "Matching")]
+    pub region: Option<String>,
+
     /// Exit with status 0 if met, 1 on timeout
     #[arg(long, help_heading = "Behavior")]
     pub assert: bool,
@@ -607,13 +1176,23 @@ mod tests {
             cwd,
             cols,
             rows,
+            record,
+            record_input,
+            replay,
+            speed,
+            step,
         } = cli.command
         else {
             panic!("Expected Run command, got {:?}", cli.command);
         };
-        assert_eq!(command, "bash");
+        assert_eq!(command.as_deref(), Some("bash"));
         assert!(args.is_empty());
         assert!(cwd.is_none());
+        assert!(record.is_none());
+        assert!(!record_input);
+        assert!(replay.is_none());
+        assert!(speed.is_none());
+        assert!(!step);
 
         assert_eq!(cols, 120, "Default cols should be 120");
         assert_eq!(rows, 40, "Default rows should be 40");
@@ -633,7 +1212,46 @@ mod tests {
         };
         assert_eq!(cols, 80);
         assert_eq!(rows, 24);
-        assert_eq!(command, "vim");
+        assert_eq!(command.as_deref(), Some("vim"));
+    }
+
+    #[test]
+    fn test_run_replay_replaces_command() {
+        let cli = Cli::parse_from(["agent-tui", "run", "--replay", "run.cast", "--speed", "2.5"]);
+        let Commands::Run {
+            command,
+            replay,
+            speed,
+            step,
+            ..
+        } = cli.command
+        else {
+            panic!("Expected Run command, got {:?}", cli.command);
+        };
+        assert!(command.is_none());
+        assert_eq!(replay, Some(PathBuf::from("run.cast")));
+        assert_eq!(speed, Some(2.5));
+        assert!(!step);
+
+        for argv in [
+            vec!["agent-tui", "run", "--replay", "a.cast", "bash"],
+            vec![
+                "agent-tui",
+                "run",
+                "--replay",
+                "a.cast",
+                "--step",
+                "--speed",
+                "2",
+            ],
+            vec!["agent-tui", "run", "--replay", "a.cast", "--speed", "0"],
+            vec!["agent-tui", "run", "--step", "bash"],
+        ] {
+            assert!(
+                Cli::try_parse_from(&argv).is_err(),
+                "{argv:?} should not parse"
+            );
+        }
     }
 
     #[test]
@@ -642,7 +1260,7 @@ mod tests {
         let Commands::Run { command, args, .. } = cli.command else {
             panic!("Expected Run command, got {:?}", cli.command);
         };
-        assert_eq!(command, "vim");
+        assert_eq!(command.as_deref(), Some("vim"));
         assert_eq!(args, vec!["file.txt".to_string(), "-n".to_string()]);
     }
 
@@ -655,11 +1273,15 @@ mod tests {
             "modal",
             "--strip-ansi",
             "--include-cursor",
+            "--unwrap",
         ]);
         let Commands::Screenshot {
             region,
             strip_ansi,
             include_cursor,
+            unwrap,
+            since,
+            ..
         } = cli.command
         else {
             panic!("Expected Screenshot command, got {:?}", cli.command);
@@ -667,6 +1289,168 @@ mod tests {
         assert_eq!(region, Some("modal".to_string()));
         assert!(strip_ansi);
         assert!(include_cursor);
+        assert!(unwrap);
+        assert!(since.is_none());
+    }
+
+    #[test]
+    fn test_run_record_and_record_subcommands() {
+        let cli = Cli::parse_from([
+            "agent-tui",
+            "run",
+            "--record",
+            "out.cast",
+            "--record-input",
+            "htop",
+        ]);
+        let Commands::Run {
+            record,
+            record_input,
+            ..
+        } = cli.command
+        else {
+            panic!("Expected Run command, got {:?}", cli.command);
+        };
+        assert_eq!(record, Some(PathBuf::from("out.cast")));
+        assert!(record_input);
+        assert!(Cli::try_parse_from(["agent-tui", "run", "--record-input", "htop"]).is_err());
+
+        let cli = Cli::parse_from(["agent-tui", "record", "start", "--input", "t.cast"]);
+        let Commands::Record {
+            command: RecordCommand::Start { file, input },
+        } = cli.command
+        else {
+            panic!("Expected record start, got {:?}", cli.command);
+        };
+        assert_eq!((file, input), (PathBuf::from("t.cast"), true));
+        assert!(Cli::try_parse_from(["agent-tui", "record"]).is_err());
+    }
+
+    #[test]
+    fn test_assert_screen_collects_masks() {
+        let cli = Cli::parse_from([
+            "agent-tui",
+            "assert-screen",
+            "--golden",
+            "g.txt",
+            "--mask-regex",
+            "\\d+",
+            "--mask-rect",
+            "0,60,20x1",
+            "--mask-rect",
+            "3,0,5x2",
+        ]);
+        let Commands::AssertScreen {
+            golden,
+            update,
+            mask_regex,
+            mask_rect,
+            ..
+        } = cli.command
+        else {
+            panic!("Expected assert-screen, got {:?}", cli.command);
+        };
+        assert_eq!(golden, PathBuf::from("g.txt"));
+        assert!(!update);
+        assert_eq!(mask_regex, vec!["\\d+"]);
+        assert_eq!(mask_rect, vec!["rect 0,60 20x1", "rect 3,0 5x2"]);
+        assert!(
+            Cli::try_parse_from([
+                "agent-tui",
+                "assert-screen",
+                "--golden",
+                "g.txt",
+                "--mask-rect",
+                "0,60"
+            ])
+            .is_err()
+        );
+        assert!(Cli::try_parse_from(["agent-tui", "assert-screen"]).is_err());
+    }
+
+    #[test]
+    fn test_trace_subcommands_parse() {
+        let cli = Cli::parse_from(["agent-tui", "trace", "start"]);
+        let Commands::Trace {
+            command: TraceCommand::Start { out },
+        } = cli.command
+        else {
+            panic!("Expected trace start, got {:?}", cli.command);
+        };
+        assert_eq!(out, PathBuf::from("trace.zip"));
+
+        let cli = Cli::parse_from(["agent-tui", "trace", "show", "run.zip"]);
+        let Commands::Trace {
+            command: TraceCommand::Show { file },
+        } = cli.command
+        else {
+            panic!("Expected trace show, got {:?}", cli.command);
+        };
+        assert_eq!(file, PathBuf::from("run.zip"));
+        assert!(Cli::try_parse_from(["agent-tui", "trace"]).is_err());
+        assert!(Cli::try_parse_from(["agent-tui", "trace", "show"]).is_err());
+    }
+
+    #[test]
+    fn test_screenshot_image_requires_out() {
+        let cli = Cli::parse_from([
+            "agent-tui",
+            "screenshot",
+            "--image",
+            "png",
+            "--out",
+            "screen.png",
+        ]);
+        let Commands::Screenshot { image, out, .. } = cli.command else {
+            panic!("Expected Screenshot command, got {:?}", cli.command);
+        };
+        assert_eq!(image, Some(ImageFormat::Png));
+        assert_eq!(out, Some(PathBuf::from("screen.png")));
+
+        assert!(Cli::try_parse_from(["agent-tui", "screenshot", "--image", "svg"]).is_err());
+    }
+
+    #[test]
+    fn test_screenshot_since() {
+        let cli = Cli::parse_from(["agent-tui", "screenshot", "--since", "4821"]);
+        let Commands::Screenshot { since, .. } = cli.command else {
+            panic!("Expected Screenshot command, got {:?}", cli.command);
+        };
+        assert_eq!(since, Some(4821));
+    }
+
+    #[test]
+    fn test_screenshot_elements_conflicts_with_since() {
+        let cli = Cli::parse_from(["agent-tui", "screenshot", "--elements"]);
+        let Commands::Screenshot { elements, .. } = cli.command else {
+            panic!("Expected Screenshot command, got {:?}", cli.command);
+        };
+        assert!(elements);
+
+        let err = Cli::try_parse_from(["agent-tui", "screenshot", "--elements", "--since", "3"])
+            .err()
+            .expect("expected parse error");
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn test_screenshot_table_accepts_region() {
+        let cli = Cli::parse_from(["agent-tui", "screenshot", "--table", "--region", "Procs"]);
+        let Commands::Screenshot { table, region, .. } = cli.command else {
+            panic!("Expected Screenshot command, got {:?}", cli.command);
+        };
+        assert!(table);
+        assert_eq!(region.as_deref(), Some("Procs"));
+    }
+
+    #[test]
+    fn test_find_command() {
+        let cli = Cli::parse_from(["agent-tui", "find", "--unwrap", "local/bin"]);
+        let Commands::Find { text, unwrap, .. } = cli.command else {
+            panic!("Expected Find command, got {:?}", cli.command);
+        };
+        assert_eq!(text, "local/bin");
+        assert!(unwrap);
     }
 
     #[test]
@@ -727,6 +1511,24 @@ mod tests {
         assert!(params.gone);
     }
 
+    #[test]
+    fn test_wait_echo_off() {
+        let cli = Cli::parse_from(["agent-tui", "wait", "--echo-off"]);
+        let Commands::Wait { params } = cli.command else {
+            panic!("Expected Wait command, got {:?}", cli.command);
+        };
+        assert!(params.echo_off);
+        assert!(params.text.is_none());
+    }
+
+    #[test]
+    fn test_wait_echo_off_conflicts_with_text() {
+        let err = Cli::try_parse_from(["agent-tui", "wait", "Password", "--echo-off"])
+            .err()
+            .expect("expected parse error");
+        assert_eq!(err.kind(), ErrorKind::ArgumentConflict);
+    }
+
     #[test]
     fn test_wait_assert_flag() {
         let cli = Cli::parse_from(["agent-tui", "wait", "--assert", "Success"]);
@@ -750,6 +1552,12 @@ mod tests {
         let cli = Cli::parse_from(["agent-tui", "-f", "json", "sessions"]);
         assert_eq!(cli.format, OutputFormat::Json);
 
+        let cli = Cli::parse_from(["agent-tui", "-f", "agent", "screenshot"]);
+        assert_eq!(cli.format, OutputFormat::Agent);
+
+        let cli = Cli::parse_from(["agent-tui", "-f", "html", "screenshot"]);
+        assert_eq!(cli.format, OutputFormat::Html);
+
         assert!(Cli::try_parse_from(["agent-tui", "-f", "xml", "sessions"]).is_err());
     }
 
@@ -765,7 +1573,7 @@ mod tests {
         let Commands::Run { command, cwd, .. } = cli.command else {
             panic!("Expected Run command, got {:?}", cli.command);
         };
-        assert_eq!(command, "bash");
+        assert_eq!(command.as_deref(), Some("bash"));
         assert_eq!(cwd, Some(PathBuf::from("/tmp")));
     }
 
@@ -1099,6 +1907,28 @@ mod tests {
         assert!(!release);
     }
 
+    #[test]
+    fn test_fill_command() {
+        let cli = Cli::parse_from([
+            "agent-tui",
+            "fill",
+            "--prompt-timeout",
+            "900",
+            "answers.yaml",
+        ]);
+        let Commands::Fill {
+            file,
+            timeout,
+            prompt_timeout,
+        } = cli.command
+        else {
+            panic!("Expected Fill command, got {:?}", cli.command);
+        };
+        assert_eq!(file, PathBuf::from("answers.yaml"));
+        assert_eq!(timeout, 60_000);
+        assert_eq!(prompt_timeout, 900);
+    }
+
     #[test]
     fn test_press_release_command() {
         let cli = Cli::parse_from(["agent-tui", "press", "Shift", "--release"]);
@@ -1122,6 +1952,27 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_select_defaults_to_arrow_keys() {
+        let cli = Cli::parse_from(["agent-tui", "select", "--down", "j", "Open file"]);
+        let Commands::Select {
+            item,
+            enter,
+            up,
+            down,
+            max_steps,
+            ..
+        } = cli.command
+        else {
+            panic!("Expected Select command, got {:?}", cli.command);
+        };
+        assert_eq!(item, "Open file");
+        assert!(!enter);
+        assert_eq!(up, "ArrowUp");
+        assert_eq!(down, "j");
+        assert_eq!(max_steps, 200);
+    }
+
     #[test]
     fn test_type_command() {
         let cli = Cli::parse_from(["agent-tui", "type", "hello"]);
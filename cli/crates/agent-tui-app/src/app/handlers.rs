@@ -7,6 +7,7 @@ use std::collections::HashMap;
 use std::io;
 use std::io::IsTerminal;
 use std::net::SocketAddr;
+use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
 use std::time::Instant;
@@ -32,6 +33,7 @@ use crate::adapters::presenter::ClientErrorView;
 use crate::adapters::presenter::Presenter;
 use crate::adapters::presenter::create_presenter;
 use crate::app::attach::DetachKeys;
+use crate::app::commands::ImageFormat;
 use crate::app::commands::LiveStartArgs;
 use crate::app::commands::OutputFormat;
 use crate::app::commands::WaitParams;
@@ -58,7 +60,9 @@ macro_rules! key_handler {
             key: String,
         ) -> HandlerResult {
             let success_message = match ctx.format {
-                OutputFormat::Text => Some($success(&key)),
+                OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
+                    Some($success(&key))
+                }
                 OutputFormat::Json => None,
             };
             let params = params::KeyParams {
@@ -80,6 +84,10 @@ pub(crate) fn resolve_wait_condition(params: &WaitParams) -> Option<String> {
         return Some("stable".to_string());
     }
 
+    if params.echo_off {
+        return Some("echo_off".to_string());
+    }
+
     if params.text.is_some() && params.gone {
         return Some("text_gone".to_string());
     }
@@ -133,7 +141,7 @@ impl<'a, C: DaemonClient> HandlerContext<'a, C> {
                     .into());
                 }
             }
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
                 if success {
                     let warning = result.get("warning").and_then(|w| w.as_str());
                     self.presenter.present_success(success_msg, warning);
@@ -161,7 +169,7 @@ impl<'a, C: DaemonClient> HandlerContext<'a, C> {
             OutputFormat::Json => {
                 self.presenter.present_value(result);
             }
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
                 text_fn();
             }
         }
@@ -191,6 +199,7 @@ pub(crate) fn handle_spawn<C: DaemonClient>(
     cwd: Option<PathBuf>,
     cols: u16,
     rows: u16,
+    record: Option<(PathBuf, bool)>,
 ) -> HandlerResult {
     let cwd = cwd.map(|path| path.to_string_lossy().into_owned());
     let rpc_params = params::SpawnParams {
@@ -201,7 +210,28 @@ pub(crate) fn handle_spawn<C: DaemonClient>(
         cols,
         rows,
     };
-    let result = call_with_params(ctx.client, "spawn", rpc_params)?;
+    let mut result = call_with_params(ctx.client, "spawn", rpc_params)?;
+
+    if let Some((path, include_input)) = record {
+        let session_id = result.str_or("session_id", "").to_string();
+        let recording = start_recording(ctx, Some(session_id.clone()), &path, include_input)
+            .map_err(|err| {
+                CliError::new(
+                    ctx.format,
+                    format!(
+                        "Session {} started but recording failed: {}",
+                        session_id, err
+                    ),
+                    None,
+                    super::exit_codes::GENERAL_ERROR,
+                )
+            })?;
+        result = RpcValue::new(serde_json::json!({
+            "session_id": session_id,
+            "pid": result.u64_or("pid", 0),
+            "recording": recording.str_or("path", "")
+        }));
+    }
 
     ctx.output_json_or(&result, || {
         let session_id = result.str_or("session_id", "unknown");
@@ -212,33 +242,274 @@ pub(crate) fn handle_spawn<C: DaemonClient>(
             Colors::session_id(session_id)
         );
         println!("  PID: {}", pid);
+        if let Some(path) = result.get("recording").and_then(|v| v.as_str()) {
+            println!("  Recording: {}", path);
+        }
+    })
+}
+
+pub(crate) fn handle_replay<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    file: PathBuf,
+    speed: Option<f64>,
+    step: bool,
+    cols: u16,
+    rows: u16,
+) -> HandlerResult {
+    let path = std::path::absolute(&file).unwrap_or(file);
+    let rpc_params = params::ReplayParams {
+        path: path.to_string_lossy().into_owned(),
+        speed,
+        step,
+        session: ctx.session.clone(),
+        cols,
+        rows,
+    };
+    let result = call_with_params(ctx.client, "replay", rpc_params)?;
+
+    ctx.output_json_or(&result, || {
+        println!(
+            "{} {}",
+            Colors::success("Replay started:"),
+            Colors::session_id(result.str_or("session_id", "unknown"))
+        );
+        println!(
+            "  Source: {} ({}, {}x{}, {} events, {:.1}s)",
+            path.display(),
+            result.str_or("format", "cast"),
+            result.u64_or("cols", 0),
+            result.u64_or("rows", 0),
+            result.u64_or("events", 0),
+            result.u64_or("duration_ms", 0) as f64 / 1000.0
+        );
+        if step {
+            println!("  Step mode: each press or type advances the replay");
+        }
+    })
+}
+
+fn start_recording<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    session: Option<String>,
+    path: &Path,
+    include_input: bool,
+) -> Result<RpcValue, ClientError> {
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let rpc_params = params::RecordStartParams {
+        path: path.to_string_lossy().into_owned(),
+        include_input,
+        session,
+    };
+    call_with_params(ctx.client, "record_start", rpc_params)
+}
+
+pub(crate) fn handle_record_start<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    file: PathBuf,
+    include_input: bool,
+) -> HandlerResult {
+    let session = ctx.session.clone();
+    let result = start_recording(ctx, session, &file, include_input)?;
+
+    ctx.output_json_or(&result, || {
+        println!(
+            "{} {} to {}",
+            Colors::success("Recording session"),
+            Colors::session_id(result.str_or("session_id", "unknown")),
+            result.str_or("path", "")
+        );
     })
 }
 
+pub(crate) fn handle_record_stop<C: DaemonClient>(ctx: &mut HandlerContext<C>) -> HandlerResult {
+    let rpc_params = params::SessionParams {
+        session: ctx.session.clone(),
+    };
+    let result = call_with_params(ctx.client, "record_stop", rpc_params)?;
+
+    ctx.output_json_or(&result, || match result.get("recording") {
+        Some(recording) if recording.get("path").is_some() => {
+            println!(
+                "{} {} ({} events, {:.1}s)",
+                Colors::success("Saved recording to"),
+                recording.str_or("path", ""),
+                recording.u64_or("events", 0),
+                recording.u64_or("duration_ms", 0) as f64 / 1000.0
+            );
+        }
+        _ => println!("Session was not being recorded"),
+    })
+}
+
+pub(crate) fn handle_trace_start<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    out: PathBuf,
+) -> HandlerResult {
+    let path = std::path::absolute(&out).unwrap_or(out);
+    let rpc_params = params::TraceStartParams {
+        path: path.to_string_lossy().into_owned(),
+        session: ctx.session.clone(),
+    };
+    let result = call_with_params(ctx.client, "trace_start", rpc_params)?;
+
+    ctx.output_json_or(&result, || {
+        println!(
+            "{} {} to {}",
+            Colors::success("Tracing session"),
+            Colors::session_id(result.str_or("session_id", "unknown")),
+            result.str_or("path", "")
+        );
+    })
+}
+
+pub(crate) fn handle_trace_stop<C: DaemonClient>(ctx: &mut HandlerContext<C>) -> HandlerResult {
+    let rpc_params = params::SessionParams {
+        session: ctx.session.clone(),
+    };
+    let result = call_with_params(ctx.client, "trace_stop", rpc_params)?;
+
+    ctx.output_json_or(&result, || match result.get("trace") {
+        Some(trace) if trace.get("path").is_some() => {
+            println!(
+                "{} {} ({} steps, {} output bytes, {:.1}s)",
+                Colors::success("Saved trace to"),
+                trace.str_or("path", ""),
+                trace.u64_or("steps", 0),
+                trace.u64_or("output_bytes", 0),
+                trace.u64_or("duration_ms", 0) as f64 / 1000.0
+            );
+        }
+        _ => println!("Session was not being traced"),
+    })
+}
+
+pub(crate) fn handle_trace_show(file: &Path, format: OutputFormat) -> HandlerResult {
+    let bundle =
+        crate::infra::daemon::read_trace_bundle(&file.to_string_lossy()).map_err(|message| {
+            CliError::new(
+                format,
+                format!("Cannot read trace {}: {}", file.display(), message),
+                None,
+                super::exit_codes::IOERR,
+            )
+        })?;
+
+    if format == OutputFormat::Json {
+        let mut value = serde_json::to_value(&bundle.manifest)?;
+        value["screens"] = serde_json::to_value(&bundle.screens)?;
+        println!("{}", serde_json::to_string_pretty(&value)?);
+        return Ok(());
+    }
+
+    let manifest = &bundle.manifest;
+    println!(
+        "{} {} ({}) {}x{}, {} steps over {:.1}s",
+        Colors::bold("Trace of session"),
+        Colors::session_id(&manifest.session_id),
+        manifest.command,
+        manifest.cols,
+        manifest.rows,
+        manifest.steps.len(),
+        manifest.duration_ms as f64 / 1000.0
+    );
+    println!(
+        "Started {}, {} output bytes{}",
+        manifest.started_at,
+        manifest.output_bytes,
+        if manifest.output_truncated {
+            " (truncated)"
+        } else {
+            ""
+        }
+    );
+    for step in &manifest.steps {
+        println!();
+        println!(
+            "#{} +{:.3}s {} {}",
+            step.index,
+            step.at_ms as f64 / 1000.0,
+            Colors::bold(&step.kind),
+            step.value
+        );
+        println!("  before:");
+        print_trace_screen(bundle.screen(&step.before));
+        if step.after == step.before {
+            println!("  after: (unchanged)");
+        } else {
+            println!("  after:");
+            print_trace_screen(bundle.screen(&step.after));
+        }
+    }
+    Ok(())
+}
+
+fn print_trace_screen(screen: &str) {
+    let lines: Vec<&str> = screen.lines().collect();
+    let end = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |index| index + 1);
+    for line in &lines[..end] {
+        println!("    | {}", line.trim_end());
+    }
+}
+
 pub(crate) fn handle_snapshot<C: DaemonClient>(
     ctx: &mut HandlerContext<C>,
     region: Option<String>,
     strip_ansi: bool,
     include_cursor: bool,
+    unwrap: bool,
+    strip_borders: bool,
 ) -> HandlerResult {
+    use crate::adapters::presenter::AgentScreenOptions;
+    use crate::adapters::presenter::AgentScreenView;
+
+    let agent = ctx.format == OutputFormat::Agent;
+    let html = ctx.format == OutputFormat::Html;
     let rpc_params = params::SnapshotParams {
         session: ctx.session.clone(),
         region,
-        strip_ansi,
-        include_cursor,
+        strip_ansi: strip_ansi || agent,
+        include_cursor: include_cursor || agent,
         include_render: false,
+        unwrap,
+        include_highlights: agent,
+        image: html.then(|| "html".to_string()),
     };
     let result = call_with_params(ctx.client, "snapshot", rpc_params)?;
 
     match ctx.format {
+        OutputFormat::Html => {
+            let page = decode_image(&result).ok_or_else(|| missing_image(ctx.format))?;
+            print!("{}", String::from_utf8_lossy(&page));
+        }
+        OutputFormat::Agent => {
+            AgentScreenView::from_json(&result)
+                .present(ctx.presenter(), AgentScreenOptions { strip_borders });
+        }
         OutputFormat::Json => {
             println!("{}", result.to_pretty_json());
         }
         OutputFormat::Text => {
             println!("{}", Colors::bold("Screenshot:"));
+            if let Some(pane) = result.get("region") {
+                println!(
+                    "Region: {} ({}) @{},{} {}x{}",
+                    pane.str_or("title", "untitled"),
+                    pane.str_or("id", "?"),
+                    pane.u64_or("row", 0),
+                    pane.u64_or("col", 0),
+                    pane.u64_or("width", 0),
+                    pane.u64_or("height", 0)
+                );
+            }
             if let Some(screenshot) = result.get("screenshot").and_then(|v| v.as_str()) {
                 println!("{}", screenshot);
             }
+            if let Some(version) = result.get("screen_version").and_then(|v| v.as_u64()) {
+                println!("\nScreen version: {}", version);
+            }
             if include_cursor {
                 if let Some(cursor) = result.get("cursor") {
                     let row = cursor.u64_or("row", 0);
@@ -255,6 +526,119 @@ pub(crate) fn handle_snapshot<C: DaemonClient>(
     Ok(())
 }
 
+fn decode_image(result: &RpcValue) -> Option<Vec<u8>> {
+    use base64::Engine;
+
+    result
+        .get("image")
+        .and_then(|image| image.get("data"))
+        .and_then(|data| data.as_str())
+        .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok())
+        .filter(|data| !data.is_empty())
+}
+
+fn missing_image(format: OutputFormat) -> CliError {
+    CliError::new(
+        format,
+        "Daemon did not return an image (is it older than this CLI?)",
+        None,
+        super::exit_codes::GENERAL_ERROR,
+    )
+}
+
+pub(crate) fn handle_screenshot_image<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    format: ImageFormat,
+    out: PathBuf,
+) -> HandlerResult {
+    let rpc_params = params::SnapshotParams {
+        session: ctx.session.clone(),
+        image: Some(format.as_str().to_string()),
+        ..Default::default()
+    };
+    let result = call_with_params(ctx.client, "snapshot", rpc_params)?;
+
+    let data = decode_image(&result).ok_or_else(|| missing_image(ctx.format))?;
+    std::fs::write(&out, &data).map_err(|err| {
+        CliError::new(
+            ctx.format,
+            format!("Failed to write {}: {}", out.display(), err),
+            None,
+            super::exit_codes::CANTCREAT,
+        )
+    })?;
+
+    let summary = RpcValue::new(serde_json::json!({
+        "path": out.display().to_string(),
+        "format": format.as_str(),
+        "bytes": data.len(),
+        "screen_version": result.u64_or("screen_version", 0)
+    }));
+    ctx.output_json_or(&summary, || {
+        println!(
+            "Saved {} screenshot to {} ({} bytes)",
+            format.as_str().to_uppercase(),
+            out.display(),
+            data.len()
+        );
+    })
+}
+
+pub(crate) fn handle_snapshot_diff<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    since: u64,
+) -> HandlerResult {
+    let rpc_params = params::SnapshotDiffParams {
+        session: ctx.session.clone(),
+        since,
+    };
+    let result = call_with_params(ctx.client, "snapshot_diff", rpc_params)?;
+
+    ctx.output_json_or(&result, || {
+        let version = result.u64_or("screen_version", 0);
+        let rows = result.get("rows").and_then(|v| v.as_array());
+        let changed = rows.as_ref().map_or(0, |rows| rows.len());
+        if result.bool_or("full", false) {
+            println!(
+                "{} version {} is no longer retained; showing all rows (now {})",
+                Colors::bold("Screen:"),
+                since,
+                version
+            );
+        } else {
+            println!(
+                "{} {} row(s) changed since version {} (now {})",
+                Colors::bold("Screen:"),
+                changed,
+                result.u64_or("from_version", since),
+                version
+            );
+        }
+        if let Some(rows) = rows {
+            for change in rows.iter() {
+                let row = change.u64_or("row", 0);
+                println!("{:>4}- {}", row, change.str_or("old", ""));
+                println!("{:>4}+ {}", row, change.str_or("new", ""));
+            }
+        }
+        if let Some(cursor) = result.get("cursor") {
+            let (row, col) = (cursor.u64_or("row", 0), cursor.u64_or("col", 0));
+            let delta = result.get("cursor_delta").and_then(|delta| {
+                let rows = delta.get("rows")?.as_i64()?;
+                let cols = delta.get("cols")?.as_i64()?;
+                Some((rows, cols))
+            });
+            match delta {
+                Some((0, 0)) | None => println!("Cursor: row={}, col={}", row, col),
+                Some((rows, cols)) => println!(
+                    "Cursor: row={}, col={} (moved {:+} rows, {:+} cols)",
+                    row, col, rows, cols
+                ),
+            }
+        }
+    })
+}
+
 key_handler!(handle_press, "keystroke", |_: &String| "Key pressed"
     .to_string());
 key_handler!(handle_keydown, "keydown", |k: &String| format!(
@@ -278,6 +662,185 @@ pub(crate) fn handle_type<C: DaemonClient>(
     ctx.output_success_and_ok(&result, "Text typed", "Type failed")
 }
 
+pub(crate) fn handle_select<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    item: String,
+    enter: bool,
+    up: String,
+    down: String,
+    region: Option<String>,
+    max_steps: u32,
+) -> HandlerResult {
+    let params = params::SelectParams {
+        item: item.clone(),
+        session: ctx.session.clone(),
+        up_key: Some(up),
+        down_key: Some(down),
+        submit: enter,
+        region,
+        max_steps: Some(max_steps),
+    };
+    let result = call_with_params(ctx.client, "select", params)?;
+
+    if !result.bool_or("found", false) {
+        let message = match result.get("selected").and_then(|v| v.as_str()) {
+            Some(selected) => format!(
+                "\"{}\" was never highlighted (stopped at \"{}\")",
+                item, selected
+            ),
+            None => "No highlighted row found on screen".to_string(),
+        };
+        return Err(CliError::new(
+            ctx.format,
+            message,
+            Some(result.to_pretty_json()),
+            super::exit_codes::GENERAL_ERROR,
+        )
+        .into());
+    }
+
+    ctx.output_json_or(&result, || {
+        let steps = result.u64_or("steps", 0);
+        let mut line = format!(
+            "Selected \"{}\" after {} key{}",
+            result.str_or("selected", &item),
+            steps,
+            if steps == 1 { "" } else { "s" }
+        );
+        if result.bool_or("submitted", false) {
+            line.push_str(" and pressed Enter");
+        }
+        println!("{}", line);
+    })
+}
+
+pub(crate) fn handle_fill<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    file: PathBuf,
+    timeout: u64,
+    prompt_timeout: u64,
+) -> HandlerResult {
+    let text = if file.as_os_str() == "-" {
+        io::read_to_string(io::stdin()).context("Failed to read answers from stdin")?
+    } else {
+        std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read {}", file.display()))?
+    };
+    let fields = parse_fill_spec(&text).map_err(|message| {
+        CliError::new(
+            ctx.format,
+            format!("Invalid answers file: {}", message),
+            None,
+            super::exit_codes::USAGE,
+        )
+    })?;
+
+    let params = params::FillParams {
+        fields,
+        session: ctx.session.clone(),
+        timeout_ms: Some(timeout),
+        prompt_timeout_ms: Some(prompt_timeout),
+    };
+    let result = call_with_params(ctx.client, "fill", params)?;
+
+    let list = |key: &str| {
+        result
+            .get(key)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flat_map(|items| items.iter())
+    };
+
+    if !result.bool_or("completed", false) {
+        let mut problems = Vec::new();
+        if let Some(step) = list("transcript").find(|step| !step.bool_or("answered", true)) {
+            problems.push(format!(
+                "no option matched for \"{}\"",
+                step.str_or("label", "")
+            ));
+        }
+        for prompt in list("unexpected").filter_map(|prompt| prompt.as_str()) {
+            problems.push(format!("unexpected prompt \"{}\"", prompt));
+        }
+        let missing: Vec<&str> = list("missing").filter_map(|label| label.as_str()).collect();
+        if !missing.is_empty() {
+            problems.push(format!("never appeared: {}", missing.join(", ")));
+        }
+        return Err(CliError::new(
+            ctx.format,
+            format!("Fill stopped: {}", problems.join("; ")),
+            Some(result.to_pretty_json()),
+            super::exit_codes::GENERAL_ERROR,
+        )
+        .into());
+    }
+
+    ctx.output_json_or(&result, || {
+        for step in list("transcript") {
+            println!(
+                "{} {}  ({})",
+                Colors::success("✓"),
+                step.str_or("label", ""),
+                step.str_or("prompt", "")
+            );
+        }
+    })
+}
+
+/// Reads an answers file: a JSON or YAML map from prompt label to a text answer,
+/// a list of keys, or `{select: OPTION}`. Labels keep their file order.
+fn parse_fill_spec(text: &str) -> Result<Vec<params::FillFieldParams>, String> {
+    use serde_yaml::Value;
+
+    let scalar = |value: &Value| match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Null => Some(String::new()),
+        _ => None,
+    };
+    let spec: Value = serde_yaml::from_str(text).map_err(|e| e.to_string())?;
+    let Value::Mapping(entries) = spec else {
+        return Err("expected a map of prompt labels to answers".to_string());
+    };
+    entries
+        .iter()
+        .map(|(label, value)| {
+            let label = scalar(label).ok_or("prompt labels must be text")?;
+            let mut field = params::FillFieldParams {
+                label: label.clone(),
+                answer: None,
+                keys: None,
+                select: None,
+            };
+            match value {
+                Value::Sequence(keys) => {
+                    field.keys = Some(
+                        keys.iter()
+                            .map(|key| {
+                                scalar(key).ok_or(format!("\"{}\": keys must be text", label))
+                            })
+                            .collect::<Result<_, _>>()?,
+                    );
+                }
+                Value::Mapping(map) => {
+                    let option = map
+                        .get("select")
+                        .and_then(scalar)
+                        .filter(|_| map.len() == 1)
+                        .ok_or(format!("\"{}\": expected {{select: OPTION}}", label))?;
+                    field.select = Some(option);
+                }
+                other => {
+                    field.answer =
+                        Some(scalar(other).ok_or(format!("\"{}\": unsupported answer", label))?);
+                }
+            }
+            Ok(field)
+        })
+        .collect()
+}
+
 pub(crate) fn handle_wait<C: DaemonClient>(
     ctx: &mut HandlerContext<C>,
     wait_params: WaitParams,
@@ -288,6 +851,8 @@ pub(crate) fn handle_wait<C: DaemonClient>(
     let WaitParams {
         text,
         timeout,
+        unwrap,
+        region,
         assert,
         ..
     } = wait_params;
@@ -296,6 +861,8 @@ pub(crate) fn handle_wait<C: DaemonClient>(
         text,
         timeout_ms: timeout,
         condition: cond,
+        unwrap,
+        region,
     };
     let result = call_with_params(ctx.client, "wait", rpc_params)?;
 
@@ -313,11 +880,413 @@ pub(crate) fn handle_wait<C: DaemonClient>(
 
     match ctx.format {
         OutputFormat::Json => ctx.presenter().present_value(&result),
-        OutputFormat::Text => ctx.presenter().present_wait_result(&wait_result),
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
+            ctx.presenter().present_wait_result(&wait_result)
+        }
     }
     Ok(())
 }
 
+pub(crate) fn handle_assert_screen<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    golden: PathBuf,
+    update: bool,
+    styles: bool,
+    masks: Vec<String>,
+) -> HandlerResult {
+    let existing = match std::fs::read_to_string(&golden) {
+        Ok(text) => Some(text),
+        Err(err) if update && err.kind() == io::ErrorKind::NotFound => None,
+        Err(err) => {
+            let hint = if err.kind() == io::ErrorKind::NotFound {
+                " (run with --update to create it)"
+            } else {
+                ""
+            };
+            return Err(CliError::new(
+                ctx.format,
+                format!("Cannot read golden {}: {}{}", golden.display(), err, hint),
+                None,
+                super::exit_codes::IOERR,
+            )
+            .into());
+        }
+    };
+    let rpc_params = params::AssertScreenParams {
+        golden: existing.clone().unwrap_or_default(),
+        masks,
+        styles,
+        session: ctx.session.clone(),
+    };
+    let result = call_with_params(ctx.client, "assert_screen", rpc_params)?;
+    let matched = result.bool_or("matched", false);
+    let diffs: Vec<RpcValueRef<'_>> = result
+        .get("diff")
+        .and_then(|v| v.as_array())
+        .map(|diffs| diffs.iter().collect())
+        .unwrap_or_default();
+
+    if update {
+        let rendered = result.str_or("golden", "");
+        let changed = existing.as_deref() != Some(rendered);
+        if changed {
+            std::fs::write(&golden, rendered).map_err(|err| {
+                CliError::new(
+                    ctx.format,
+                    format!("Failed to write {}: {}", golden.display(), err),
+                    None,
+                    super::exit_codes::CANTCREAT,
+                )
+            })?;
+        }
+        return ctx.output_json_or(&result, || {
+            if changed {
+                println!("{} {}", Colors::success("Updated golden"), golden.display());
+            } else {
+                println!("Golden {} is up to date", golden.display());
+            }
+        });
+    }
+
+    if !matched {
+        if ctx.format != OutputFormat::Json {
+            println!(
+                "Screen differs from golden {} ({} rows):",
+                golden.display(),
+                diffs.len()
+            );
+            for diff in &diffs {
+                let kind = diff.str_or("kind", "text");
+                let label = if kind == "style" { " style" } else { "" };
+                println!("  row {}{}", diff.u64_or("row", 0), label);
+                println!(
+                    "    {}",
+                    Colors::error(&format!("- {}", diff.str_or("expected", "")))
+                );
+                println!(
+                    "    {}",
+                    Colors::success(&format!("+ {}", diff.str_or("actual", "")))
+                );
+            }
+        }
+        return Err(CliError::new(
+            ctx.format,
+            format!("Screen does not match golden {}", golden.display()),
+            Some(result.to_pretty_json()),
+            super::exit_codes::GENERAL_ERROR,
+        )
+        .into());
+    }
+
+    ctx.output_json_or(&result, || {
+        println!(
+            "{} {}",
+            Colors::success("Screen matches golden"),
+            golden.display()
+        );
+    })
+}
+
+pub(crate) fn handle_script_run<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    file: PathBuf,
+    vars: Vec<(String, String)>,
+    junit: Option<PathBuf>,
+    keep: bool,
+) -> HandlerResult {
+    use crate::app::script::Script;
+    use crate::app::script::ScriptRunner;
+    use crate::app::script::StepStatus;
+
+    let text = std::fs::read_to_string(&file).map_err(|err| {
+        CliError::new(
+            ctx.format,
+            format!("Cannot read script {}: {}", file.display(), err),
+            None,
+            super::exit_codes::IOERR,
+        )
+    })?;
+    let script = Script::parse(&text, &file).map_err(|message| {
+        CliError::new(
+            ctx.format,
+            format!("Invalid script {}: {}", file.display(), message),
+            None,
+            super::exit_codes::USAGE,
+        )
+    })?;
+
+    let base_dir = std::path::absolute(&file)
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    let report = ScriptRunner::new(ctx.client, ctx.session.clone(), base_dir)
+        .run(&script, &file, &vars, keep);
+
+    if let Some(path) = &junit {
+        std::fs::write(path, crate::app::script::junit_xml(&report)).map_err(|err| {
+            CliError::new(
+                ctx.format,
+                format!("Failed to write {}: {}", path.display(), err),
+                None,
+                super::exit_codes::CANTCREAT,
+            )
+        })?;
+    }
+
+    if ctx.format != OutputFormat::Json {
+        for step in &report.steps {
+            let attempts = if step.attempts > 1 {
+                format!(", {} attempts", step.attempts)
+            } else {
+                String::new()
+            };
+            match step.status {
+                StepStatus::Passed => println!(
+                    "{} {} {} {}",
+                    Colors::success("✓"),
+                    step.index,
+                    step.name,
+                    Colors::dim(&format!("({}ms{})", step.duration_ms, attempts))
+                ),
+                StepStatus::Failed => println!(
+                    "{} {} {} {}: {}",
+                    Colors::error("✗"),
+                    step.index,
+                    step.name,
+                    Colors::dim(&format!("({}ms{})", step.duration_ms, attempts)),
+                    step.message.as_deref().unwrap_or("failed")
+                ),
+                StepStatus::Skipped => println!(
+                    "{}",
+                    Colors::dim(&format!("- {} {} (skipped)", step.index, step.name))
+                ),
+            }
+        }
+    }
+
+    if let Some(step) = report.failed_step() {
+        return Err(CliError::new(
+            ctx.format,
+            format!(
+                "Script {} failed at step {} ({})",
+                report.name, step.index, step.name
+            ),
+            Some(serde_json::to_string_pretty(&report)?),
+            super::exit_codes::GENERAL_ERROR,
+        )
+        .into());
+    }
+
+    match ctx.format {
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => println!(
+            "{} {} ({} steps in {}ms)",
+            Colors::success("Script passed:"),
+            report.name,
+            report.steps.len(),
+            report.duration_ms
+        ),
+    }
+    Ok(())
+}
+
+pub(crate) fn handle_find<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    text: String,
+    unwrap: bool,
+    region: Option<String>,
+) -> HandlerResult {
+    let rpc_params = params::FindParams {
+        text: text.clone(),
+        session: ctx.session.clone(),
+        unwrap,
+        region,
+    };
+    let result = call_with_params(ctx.client, "find", rpc_params)?;
+
+    ctx.output_json_or(&result, || {
+        let matches = result.get("matches").and_then(|v| v.as_array());
+        let Some(matches) = matches.filter(|m| !m.is_empty()) else {
+            println!("No matches for \"{}\"", text);
+            return;
+        };
+        println!("Matches for \"{}\":", text);
+        for m in matches.iter() {
+            let (row, col) = (m.u64_or("row", 0), m.u64_or("col", 0));
+            let (end_row, end_col) = (m.u64_or("end_row", 0), m.u64_or("end_col", 0));
+            if end_row == row {
+                println!("  row {}, col {}-{}", row, col, end_col);
+            } else {
+                println!(
+                    "  row {}, col {} -> row {}, col {} (wrapped)",
+                    row, col, end_row, end_col
+                );
+            }
+        }
+    })
+}
+
+pub(crate) fn handle_elements<C: DaemonClient>(ctx: &mut HandlerContext<C>) -> HandlerResult {
+    let params = params::SessionParams {
+        session: ctx.session.clone(),
+    };
+    let result = call_with_params(ctx.client, "elements", params)?;
+
+    ctx.output_json_or(&result, || {
+        let elements = result.get("elements").and_then(|v| v.as_array());
+        let Some(elements) = elements.filter(|e| !e.is_empty()) else {
+            println!("No elements detected");
+            return;
+        };
+        for element in elements.iter() {
+            print_element(element, 0);
+        }
+    })
+}
+
+pub(crate) fn handle_panes<C: DaemonClient>(ctx: &mut HandlerContext<C>) -> HandlerResult {
+    let params = params::SessionParams {
+        session: ctx.session.clone(),
+    };
+    let result = call_with_params(ctx.client, "panes", params)?;
+
+    ctx.output_json_or(&result, || {
+        let panes = result.get("panes").and_then(|v| v.as_array());
+        let Some(panes) = panes.filter(|p| !p.is_empty()) else {
+            println!("No panes detected");
+            return;
+        };
+        for pane in panes.iter() {
+            let mut line = pane.str_or("id", "?").to_string();
+            let title = pane.str_or("title", "");
+            if !title.is_empty() {
+                line.push_str(&format!(" \"{}\"", title));
+            }
+            line.push_str(&format!(
+                " @{},{} {}x{}",
+                pane.u64_or("row", 0),
+                pane.u64_or("col", 0),
+                pane.u64_or("width", 0),
+                pane.u64_or("height", 0)
+            ));
+            if pane.bool_or("focused", false) {
+                line.push_str(" [focused]");
+            }
+            if let Some(parent) = pane.get("parent").and_then(|v| v.as_str()) {
+                line.push_str(&format!(" (in {})", parent));
+            }
+            println!("{}", line);
+        }
+    })
+}
+
+pub(crate) fn handle_table<C: DaemonClient>(
+    ctx: &mut HandlerContext<C>,
+    region: Option<String>,
+) -> HandlerResult {
+    let params = params::TableParams {
+        session: ctx.session.clone(),
+        region,
+    };
+    let result = call_with_params(ctx.client, "table", params)?;
+
+    ctx.output_json_or(&result, || {
+        let names: Vec<&str> = result
+            .get("columns")
+            .and_then(|v| v.as_array())
+            .map(|columns| columns.iter().map(|c| c.str_or("name", "")).collect())
+            .unwrap_or_default();
+        let rows: Vec<Vec<&str>> = result
+            .get("rows")
+            .and_then(|v| v.as_array())
+            .map(|rows| {
+                rows.iter()
+                    .map(|row| names.iter().map(|name| row.str_or(name, "")).collect())
+                    .collect()
+            })
+            .unwrap_or_default();
+        if names.is_empty() {
+            println!("No table detected");
+            return;
+        }
+
+        let widths: Vec<usize> = names
+            .iter()
+            .enumerate()
+            .map(|(idx, name)| {
+                rows.iter()
+                    .map(|row| row[idx].chars().count())
+                    .chain([name.chars().count()])
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect();
+        let format_row = |cells: &[&str]| {
+            let line: Vec<String> = cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect();
+            line.join("  ").trim_end().to_string()
+        };
+        let highlighted = result.get("highlighted").and_then(|v| v.as_u64());
+        println!("  {}", Colors::bold(&format_row(&names)));
+        for (idx, row) in rows.iter().enumerate() {
+            let marker = if highlighted == Some(idx as u64) {
+                ">"
+            } else {
+                " "
+            };
+            println!("{} {}", marker, format_row(row));
+        }
+    })
+}
+
+fn print_element(element: RpcValueRef<'_>, depth: usize) {
+    let kind = element.str_or("kind", "element");
+    let mut line = format!("{:indent$}{}", "", kind, indent = depth * 2);
+    let label = element.str_or("label", "");
+    if !label.is_empty() {
+        line.push_str(&format!(" \"{}\"", label));
+    }
+
+    let children: Vec<RpcValueRef<'_>> = element
+        .get("children")
+        .and_then(|v| v.as_array())
+        .map(|children| children.iter().collect())
+        .unwrap_or_default();
+    if kind == "list" {
+        line.push_str(&format!(" ({} items", children.len()));
+        if let Some(idx) = children.iter().position(|c| c.bool_or("selected", false)) {
+            line.push_str(&format!(", item {} selected", idx + 1));
+        }
+        line.push(')');
+    }
+    if let Some(checked) = element.get("checked").and_then(|v| v.as_bool()) {
+        line.push_str(if checked { " [x]" } else { " [ ]" });
+    }
+    if let Some(value) = element.get("value").and_then(|v| v.as_str()) {
+        line.push_str(&format!(" value=\"{}\"", value));
+    }
+    if element.bool_or("selected", false) {
+        line.push_str(" selected");
+    }
+    if element.bool_or("focused", false) {
+        line.push_str(" focused");
+    }
+    println!(
+        "{} ref={} @{},{}",
+        line,
+        element.str_or("ref", "?"),
+        element.u64_or("row", 0),
+        element.u64_or("col", 0)
+    );
+
+    for child in children {
+        print_element(child, depth + 1);
+    }
+}
+
 pub(crate) fn handle_kill<C: DaemonClient>(ctx: &mut HandlerContext<C>) -> HandlerResult {
     let params = params::SessionParams {
         session: ctx.session.clone(),
@@ -417,20 +1386,36 @@ pub(crate) fn handle_session_show<C: DaemonClient>(
         .find(|session| session.str_or("id", "") == session_id.as_str())
         .ok_or_else(|| anyhow::anyhow!("Session not found: {}", session_id))?;
 
+    let tty_result = if session.bool_or("running", false) {
+        let params = params::SessionParams {
+            session: Some(session_id.clone()),
+        };
+        call_with_params(ctx.client, "tty_state", params).ok()
+    } else {
+        None
+    };
+    let tty = tty_result
+        .as_ref()
+        .filter(|result| result.bool_or("available", false))
+        .and_then(|result| result.get("tty"));
+
     match ctx.format {
         OutputFormat::Json => {
             #[derive(serde::Serialize)]
             struct SessionShow<'a> {
                 session: RpcValueRef<'a>,
                 active_session: Option<&'a str>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                tty: Option<RpcValueRef<'a>>,
             }
             let payload = SessionShow {
                 session,
                 active_session: active_id,
+                tty,
             };
             println!("{}", serde_json::to_string_pretty(&payload)?);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
             let id = session.str_or("id", "?");
             let command = session.str_or("command", "?");
             let pid = session.u64_or("pid", 0);
@@ -453,10 +1438,10 @@ pub(crate) fn handle_session_show<C: DaemonClient>(
             } else {
                 String::new()
             };
-            let status = if running {
-                Colors::success("running")
-            } else {
-                Colors::error("stopped")
+            let status = match session.get("exit_code").and_then(|v| v.as_i64()) {
+                _ if running => Colors::success("running"),
+                Some(code) => Colors::error(&format!("exited (code {})", code)),
+                None => Colors::error("stopped"),
             };
 
             println!(
@@ -472,6 +1457,21 @@ pub(crate) fn handle_session_show<C: DaemonClient>(
             if let Some(created) = created_at {
                 println!("  Created: {}", created);
             }
+            if let Some(tty) = tty {
+                let on_off = |flag: bool| if flag { "on" } else { "off" };
+                let password = if tty.bool_or("password_prompt", false) {
+                    " (password prompt)"
+                } else {
+                    ""
+                };
+                println!(
+                    "  TTY: {}, echo {}, signals {}{}",
+                    tty.str_or("mode", "?"),
+                    on_off(tty.bool_or("echo", true)),
+                    on_off(tty.bool_or("isig", true)),
+                    password
+                );
+            }
         }
     }
 
@@ -550,7 +1550,7 @@ pub(crate) fn handle_live_start<C: DaemonClient>(
             };
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
             println!("WS: {}", state.ws_url);
             println!("UI: {}", daemon_ui_url);
         }
@@ -631,7 +1631,7 @@ pub(crate) fn handle_live_stop_standalone(format: OutputFormat) -> HandlerResult
             }
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
             match ui_result {
                 Ok(StopUiResult::Stopped) => {
                     println!("UI server stopped.");
@@ -778,7 +1778,7 @@ pub(crate) fn handle_live_status_standalone(format: OutputFormat) -> HandlerResu
 
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
             if let Some(state) = status {
                 println!("Live preview WS: {}", state.ws_url);
                 println!("Live preview UI: {}", state.resolved_ui_url());
@@ -883,7 +1883,7 @@ pub(crate) fn handle_version_standalone(format: OutputFormat) -> HandlerResult {
             };
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
             println!("{}", Colors::bold("agent-tui"));
             println!("  CLI version: {}", cli_version);
             println!("  CLI commit: {}", cli_commit);
@@ -1324,7 +2324,9 @@ pub(crate) fn handle_cleanup<C: DaemonClient>(
             OutputFormat::Json => {
                 println!("{}", serde_json::to_string_pretty(&output)?);
             }
-            OutputFormat::Text => ctx.presenter().present_cleanup(&result),
+            OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
+                ctx.presenter().present_cleanup(&result)
+            }
         }
     } else {
         let mut message = format!("Failed to clean up {} session(s)", result.failures.len());
@@ -1399,7 +2401,7 @@ pub(crate) fn handle_attach<C: DaemonClient>(
                     .into());
                 }
             }
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
                 if result.bool_or("success", false) {
                     println!("Attached to session {}", Colors::session_id(&session_id));
                 } else {
@@ -1497,7 +2499,7 @@ pub(crate) fn handle_env(format: OutputFormat) -> HandlerResult {
             };
             println!("{}", serde_json::to_string_pretty(&output)?);
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
             println!("{}", Colors::bold("Environment Configuration:"));
             let transport = vars
                 .iter()
@@ -1548,6 +2550,9 @@ pub(crate) fn handle_assert<C: DaemonClient>(
                 strip_ansi: true,
                 include_cursor: false,
                 include_render: false,
+                unwrap: false,
+                include_highlights: false,
+                image: None,
             };
             let result = call_with_params(ctx.client, "snapshot", params)?;
             result.str_or("screenshot", "").contains(cond_value)
@@ -1590,7 +2595,7 @@ pub(crate) fn handle_assert<C: DaemonClient>(
                 };
                 println!("{}", serde_json::to_string_pretty(&output)?);
             }
-            OutputFormat::Text => {
+            OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
                 let assert_result = crate::adapters::presenter::AssertResult { passed, condition };
                 ctx.presenter().present_assert_result(&assert_result);
             }
@@ -1935,6 +2940,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_fill_spec_yaml_keeps_order_and_kinds() {
+        let fields = parse_fill_spec(
+            "Project name: demo\nFramework: {select: Svelte}\nTypeScript: [ArrowRight, Enter]\nPort: 8080\n",
+        )
+        .unwrap();
+
+        let labels: Vec<&str> = fields.iter().map(|f| f.label.as_str()).collect();
+        assert_eq!(
+            labels,
+            vec!["Project name", "Framework", "TypeScript", "Port"]
+        );
+        assert_eq!(fields[0].answer.as_deref(), Some("demo"));
+        assert_eq!(fields[1].select.as_deref(), Some("Svelte"));
+        assert_eq!(
+            fields[2].keys.as_deref(),
+            Some(&["ArrowRight".to_string(), "Enter".to_string()][..])
+        );
+        assert_eq!(fields[3].answer.as_deref(), Some("8080"));
+    }
+
+    #[test]
+    fn test_parse_fill_spec_accepts_json_and_rejects_lists() {
+        let fields = parse_fill_spec(r#"{"Name": "", "Mode": {"select": "fast"}}"#).unwrap();
+        assert_eq!(fields[0].answer.as_deref(), Some(""));
+        assert_eq!(fields[1].select.as_deref(), Some("fast"));
+
+        assert!(parse_fill_spec("- one\n- two").is_err());
+        assert!(parse_fill_spec("Mode: {pick: fast}").is_err());
+    }
+
     #[test]
     fn test_handler_context_has_presenter() {
         let presenter = TextPresenter;
@@ -2007,6 +3043,16 @@ mod tests {
         assert_eq!(cond, Some("text_gone".to_string()));
     }
 
+    #[test]
+    fn test_wait_condition_echo_off() {
+        let params = WaitParams {
+            echo_off: true,
+            ..Default::default()
+        };
+        let cond = resolve_wait_condition(&params);
+        assert_eq!(cond, Some("echo_off".to_string()));
+    }
+
     #[test]
     fn test_wait_condition_none() {
         let params = WaitParams::default();
@@ -21,6 +21,7 @@ pub mod daemon;
 pub mod error;
 pub mod handlers;
 pub mod rpc_client;
+pub mod script;
 
 use crate::app::commands::OutputFormat;
 use crate::common::Colors;
@@ -40,7 +41,10 @@ use crate::app::commands::Commands;
 use crate::app::commands::DaemonCommand;
 use crate::app::commands::LiveCommand;
 use crate::app::commands::LiveStartArgs;
+use crate::app::commands::RecordCommand;
+use crate::app::commands::ScriptCommand;
 use crate::app::commands::Shell;
+use crate::app::commands::TraceCommand;
 use crate::app::handlers::HandlerContext;
 
 const PROGRAM_NAME: &str = "agent-tui";
@@ -455,6 +459,8 @@ impl Application {
             "CLI command parsed"
         );
 
+        check_format_scope(&cli.command, format).map_err(anyhow::Error::new)?;
+
         if self
             .handle_standalone_commands(&cli)
             .map_err(|e| self.wrap_error(e, format))
@@ -536,13 +542,19 @@ impl Application {
                 handlers::handle_env(cli.effective_format())?;
                 Ok(true)
             }
+            Commands::Trace {
+                command: TraceCommand::Show { file },
+            } => {
+                handlers::handle_trace_show(file, cli.effective_format())?;
+                Ok(true)
+            }
             _ => Ok(false),
         }
     }
 
     fn requires_daemon_autostart(command: &Commands) -> bool {
         match command {
-            Commands::Run { .. } => true,
+            Commands::Run { .. } | Commands::Script { .. } => true,
             Commands::Live { command } => {
                 matches!(command, None | Some(LiveCommand::Start(_)))
             }
@@ -606,19 +618,60 @@ impl Application {
             },
             Commands::Completions { .. } => unreachable!("Handled in standalone"),
 
+            Commands::Run {
+                replay: Some(file),
+                speed,
+                step,
+                cols,
+                rows,
+                ..
+            } => handlers::handle_replay(ctx, file, speed, step, cols, rows)?,
             Commands::Run {
                 command,
                 args,
                 cwd,
                 cols,
                 rows,
-            } => handlers::handle_spawn(ctx, command, args, cwd, cols, rows)?,
+                record,
+                record_input,
+                ..
+            } => handlers::handle_spawn(
+                ctx,
+                command.unwrap_or_default(),
+                args,
+                cwd,
+                cols,
+                rows,
+                record.map(|path| (path, record_input)),
+            )?,
 
             Commands::Screenshot {
                 region,
                 strip_ansi,
                 include_cursor,
-            } => handlers::handle_snapshot(ctx, region, strip_ansi, include_cursor)?,
+                unwrap,
+                strip_borders,
+                elements,
+                panes,
+                table,
+                since,
+                image,
+                out,
+            } => match (since, image.zip(out)) {
+                (_, Some((format, out))) => handlers::handle_screenshot_image(ctx, format, out)?,
+                (Some(since), None) => handlers::handle_snapshot_diff(ctx, since)?,
+                _ if elements => handlers::handle_elements(ctx)?,
+                _ if panes => handlers::handle_panes(ctx)?,
+                _ if table => handlers::handle_table(ctx, region)?,
+                _ => handlers::handle_snapshot(
+                    ctx,
+                    region,
+                    strip_ansi,
+                    include_cursor,
+                    unwrap,
+                    strip_borders,
+                )?,
+            },
 
             Commands::Resize { cols, rows } => handlers::handle_resize(ctx, cols, rows)?,
             Commands::Restart => handlers::handle_restart(ctx)?,
@@ -685,10 +738,64 @@ impl Application {
             }
 
             Commands::Type { text } => handlers::handle_type(ctx, text)?,
-
+            Commands::Select {
+                item,
+                enter,
+                up,
+                down,
+                region,
+                max_steps,
+            } => handlers::handle_select(ctx, item, enter, up, down, region, max_steps)?,
+
+            Commands::Fill {
+                file,
+                timeout,
+                prompt_timeout,
+            } => handlers::handle_fill(ctx, file, timeout, prompt_timeout)?,
             Commands::Wait { params } => handlers::handle_wait(ctx, params)?,
+            Commands::Find {
+                text,
+                unwrap,
+                region,
+            } => handlers::handle_find(ctx, text, unwrap, region)?,
+            Commands::AssertScreen {
+                golden,
+                update,
+                styles,
+                mask_regex,
+                mask_rect,
+            } => {
+                let masks = mask_regex
+                    .into_iter()
+                    .map(|pattern| format!("regex {pattern}"))
+                    .chain(mask_rect)
+                    .collect();
+                handlers::handle_assert_screen(ctx, golden, update, styles, masks)?
+            }
             Commands::Kill => handlers::handle_kill(ctx)?,
 
+            Commands::Record { command } => match command {
+                RecordCommand::Start { file, input } => {
+                    handlers::handle_record_start(ctx, file, input)?
+                }
+                RecordCommand::Stop => handlers::handle_record_stop(ctx)?,
+            },
+
+            Commands::Trace { command } => match command {
+                TraceCommand::Start { out } => handlers::handle_trace_start(ctx, out)?,
+                TraceCommand::Stop => handlers::handle_trace_stop(ctx)?,
+                TraceCommand::Show { .. } => unreachable!("Handled in standalone"),
+            },
+
+            Commands::Script { command } => match command {
+                ScriptCommand::Run {
+                    file,
+                    vars,
+                    junit,
+                    keep,
+                } => handlers::handle_script_run(ctx, file, vars, junit, keep)?,
+            },
+
             Commands::Sessions { command } => {
                 use crate::app::commands::SessionsCommand;
 
@@ -860,7 +967,7 @@ fn print_cli_error(error: &crate::app::error::CliError) {
                 );
             }
         }
-        OutputFormat::Text => {
+        OutputFormat::Text | OutputFormat::Agent | OutputFormat::Html => {
             eprintln!(
                 "{}: {} {}",
                 PROGRAM_NAME,
@@ -877,6 +984,28 @@ impl Default for Application {
     }
 }
 
+/// `--format agent` and `--format html` only change how screenshots are
+/// rendered; other commands would silently print text, so reject them there.
+fn check_format_scope(
+    command: &Commands,
+    format: OutputFormat,
+) -> Result<(), crate::app::error::CliError> {
+    let name = match format {
+        OutputFormat::Agent => "agent",
+        OutputFormat::Html => "html",
+        OutputFormat::Text | OutputFormat::Json => return Ok(()),
+    };
+    if matches!(command, Commands::Screenshot { .. }) {
+        return Ok(());
+    }
+    Err(crate::app::error::CliError::new(
+        OutputFormat::Text,
+        format!("--format {name} only applies to screenshot; use text or json for other commands"),
+        None,
+        exit_codes::USAGE,
+    ))
+}
+
 fn exit_code_for_client_error(error: &ClientError) -> i32 {
     use crate::common::error_codes::ErrorCategory;
 
@@ -899,6 +1028,26 @@ fn exit_code_for_client_error(error: &ClientError) -> i32 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn screenshot_only_formats_are_rejected_elsewhere() {
+        let parse = |args: &[&str]| {
+            let cli = Cli::parse_from(args);
+            check_format_scope(&cli.command, cli.effective_format())
+        };
+
+        assert!(parse(&["agent-tui", "--format", "agent", "screenshot"]).is_ok());
+        assert!(parse(&["agent-tui", "--format", "json", "sessions"]).is_ok());
+        let err = parse(&["agent-tui", "--format", "agent", "sessions"]).unwrap_err();
+        assert_eq!(err.exit_code, exit_codes::USAGE);
+        assert!(err.message.contains("only applies to screenshot"));
+        assert!(parse(&["agent-tui", "--format", "html", "screenshot"]).is_ok());
+        let err = parse(&["agent-tui", "--format", "html", "wait", "ready"]).unwrap_err();
+        assert_eq!(
+            err.message,
+            "--format html only applies to screenshot; use text or json for other commands"
+        );
+    }
+
     mod daemon_standalone_tests {
         use super::*;
         use crate::app::commands::Cli;
@@ -0,0 +1,1286 @@
+//! Declarative scenario scripts run by `agent-tui script run`.
+//!
+//! A script is a YAML (or TOML, by extension) document with optional `name`,
+//! `timeout_ms`, `retries`, `retry_delay_ms` and `vars`, followed by a list of
+//! `steps`. Each step holds exactly one action key plus optional `name`,
+//! `timeout_ms` and `retries` (wait, assert, capture and screenshot steps only;
+//! the script-level `retries` applies to those steps alone). Strings may reference variables as `${name}`;
+//! variables come from `vars`, `--var` and `capture` steps. Relative file
+//! paths are resolved against the script's directory.
+//!
+//! The runner drives the daemon through the same RPCs as the individual
+//! commands, so a script step behaves exactly like its CLI counterpart.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use regex::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::adapters::RpcValue;
+use crate::adapters::presenter::MatchDiagnosticsView;
+use crate::adapters::presenter::NearestMatch;
+use crate::adapters::rpc::params;
+use crate::app::rpc_client::call_with_params;
+use crate::domain::ProcessSignal;
+use crate::infra::ipc::DaemonClient;
+
+const DEFAULT_TIMEOUT_MS: u64 = 10_000;
+const DEFAULT_RETRY_DELAY_MS: u64 = 250;
+const DEFAULT_COLS: u16 = 80;
+const DEFAULT_ROWS: u16 = 24;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawScript {
+    name: Option<String>,
+    timeout_ms: Option<u64>,
+    retries: Option<u32>,
+    retry_delay_ms: Option<u64>,
+    #[serde(default)]
+    vars: BTreeMap<String, String>,
+    steps: Vec<RawStep>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawStep {
+    name: Option<String>,
+    timeout_ms: Option<u64>,
+    retries: Option<u32>,
+    run: Option<RawRun>,
+    press: Option<RawKeys>,
+    #[serde(rename = "type")]
+    type_text: Option<String>,
+    paste: Option<String>,
+    wait: Option<RawWait>,
+    assert: Option<RawAssert>,
+    capture: Option<String>,
+    screenshot: Option<String>,
+    resize: Option<RawResize>,
+    signal: Option<String>,
+    sleep: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawRun {
+    Line(String),
+    Full {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        cwd: Option<String>,
+        cols: Option<u16>,
+        rows: Option<u16>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawKeys {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawWait {
+    Text(String),
+    Full(RawWaitCondition),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawWaitCondition {
+    text: Option<String>,
+    #[serde(default)]
+    gone: bool,
+    #[serde(default)]
+    stable: bool,
+    #[serde(default)]
+    echo_off: bool,
+    #[serde(default)]
+    unwrap: bool,
+    region: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawAssert {
+    Text(String),
+    Full(RawAssertCondition),
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawAssertCondition {
+    text: Option<String>,
+    gone: Option<String>,
+    regex: Option<String>,
+    golden: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawResize {
+    cols: u16,
+    rows: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum WaitFor {
+    Text(String),
+    TextGone(String),
+    Stable,
+    EchoOff,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Check {
+    Text(String),
+    Gone(String),
+    Regex(String),
+    Golden(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Action {
+    Run {
+        command: String,
+        args: Vec<String>,
+        cwd: Option<String>,
+        cols: u16,
+        rows: u16,
+    },
+    Press(Vec<String>),
+    Type(String),
+    Paste(String),
+    Wait {
+        condition: WaitFor,
+        unwrap: bool,
+        region: Option<String>,
+    },
+    Assert(Check),
+    Capture(String),
+    Screenshot(String),
+    Resize {
+        cols: u16,
+        rows: u16,
+    },
+    Signal(ProcessSignal),
+    Sleep(u64),
+}
+
+impl Action {
+    fn kind(&self) -> &'static str {
+        match self {
+            Action::Run { .. } => "run",
+            Action::Press(_) => "press",
+            Action::Type(_) => "type",
+            Action::Paste(_) => "paste",
+            Action::Wait { .. } => "wait",
+            Action::Assert(_) => "assert",
+            Action::Capture(_) => "capture",
+            Action::Screenshot(_) => "screenshot",
+            Action::Resize { .. } => "resize",
+            Action::Signal(_) => "signal",
+            Action::Sleep(_) => "sleep",
+        }
+    }
+
+    /// Only steps that observe the screen can be retried; repeating an input
+    /// or a spawn would apply it twice.
+    fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Action::Wait { .. } | Action::Assert(_) | Action::Capture(_) | Action::Screenshot(_)
+        )
+    }
+
+    /// Short human-readable label used when a step has no `name`.
+    fn describe(&self) -> String {
+        match self {
+            Action::Run { command, args, .. } => {
+                let mut line = vec![command.as_str()];
+                line.extend(args.iter().map(String::as_str));
+                format!("run {}", line.join(" "))
+            }
+            Action::Press(keys) => format!("press {}", keys.join(" ")),
+            Action::Type(text) => format!("type {:?}", text),
+            Action::Paste(text) => format!("paste {} chars", text.chars().count()),
+            Action::Wait { condition, .. } => match condition {
+                WaitFor::Text(text) => format!("wait {:?}", text),
+                WaitFor::TextGone(text) => format!("wait {:?} gone", text),
+                WaitFor::Stable => "wait stable".to_string(),
+                WaitFor::EchoOff => "wait echo-off".to_string(),
+            },
+            Action::Assert(check) => match check {
+                Check::Text(text) => format!("assert {:?}", text),
+                Check::Gone(text) => format!("assert {:?} gone", text),
+                Check::Regex(pattern) => format!("assert /{}/", pattern),
+                Check::Golden(path) => format!("assert golden {}", path),
+            },
+            Action::Capture(pattern) => format!("capture /{}/", pattern),
+            Action::Screenshot(path) => format!("screenshot {}", path),
+            Action::Resize { cols, rows } => format!("resize {}x{}", cols, rows),
+            Action::Signal(signal) => format!("signal {}", signal.as_str()),
+            Action::Sleep(ms) => format!("sleep {}ms", ms),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ScriptStep {
+    pub name: Option<String>,
+    pub timeout_ms: Option<u64>,
+    pub retries: Option<u32>,
+    pub action: Action,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Script {
+    pub name: Option<String>,
+    pub timeout_ms: u64,
+    pub retries: u32,
+    pub retry_delay_ms: u64,
+    pub vars: BTreeMap<String, String>,
+    pub steps: Vec<ScriptStep>,
+}
+
+impl Script {
+    /// Parse a script; `path` picks TOML for `.toml` files and YAML otherwise.
+    pub(crate) fn parse(text: &str, path: &Path) -> Result<Self, String> {
+        let is_toml = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+        let raw: RawScript = if is_toml {
+            toml::from_str(text).map_err(|e| e.to_string())?
+        } else {
+            serde_yaml::from_str(text).map_err(|e| e.to_string())?
+        };
+        if raw.steps.is_empty() {
+            return Err("script has no steps".to_string());
+        }
+        let steps = raw
+            .steps
+            .into_iter()
+            .enumerate()
+            .map(|(index, step)| {
+                convert_step(step).map_err(|message| format!("step {}: {}", index + 1, message))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self {
+            name: raw.name,
+            timeout_ms: raw.timeout_ms.unwrap_or(DEFAULT_TIMEOUT_MS),
+            retries: raw.retries.unwrap_or(0),
+            retry_delay_ms: raw.retry_delay_ms.unwrap_or(DEFAULT_RETRY_DELAY_MS),
+            vars: raw.vars,
+            steps,
+        })
+    }
+}
+
+fn convert_step(raw: RawStep) -> Result<ScriptStep, String> {
+    let mut actions = Vec::new();
+    if let Some(run) = raw.run {
+        actions.push(convert_run(run)?);
+    }
+    if let Some(keys) = raw.press {
+        let keys = match keys {
+            RawKeys::One(key) => vec![key],
+            RawKeys::Many(keys) => keys,
+        };
+        if keys.is_empty() {
+            return Err("press needs at least one key".to_string());
+        }
+        actions.push(Action::Press(keys));
+    }
+    if let Some(text) = raw.type_text {
+        actions.push(Action::Type(text));
+    }
+    if let Some(text) = raw.paste {
+        actions.push(Action::Paste(text));
+    }
+    if let Some(wait) = raw.wait {
+        actions.push(convert_wait(wait)?);
+    }
+    if let Some(assert) = raw.assert {
+        actions.push(Action::Assert(convert_assert(assert)?));
+    }
+    if let Some(pattern) = raw.capture {
+        let regex = Regex::new(&pattern).map_err(|e| format!("invalid capture regex: {}", e))?;
+        if regex.capture_names().flatten().next().is_none() {
+            return Err("capture regex needs a named group such as (?P<name>...)".to_string());
+        }
+        actions.push(Action::Capture(pattern));
+    }
+    if let Some(path) = raw.screenshot {
+        actions.push(Action::Screenshot(path));
+    }
+    if let Some(RawResize { cols, rows }) = raw.resize {
+        if cols == 0 || rows == 0 {
+            return Err("resize needs non-zero cols and rows".to_string());
+        }
+        actions.push(Action::Resize { cols, rows });
+    }
+    if let Some(name) = raw.signal {
+        let signal = ProcessSignal::parse(name.trim()).ok_or_else(|| {
+            format!(
+                "unsupported signal {:?} (use one of {})",
+                name,
+                ProcessSignal::NAMES.join(", ")
+            )
+        })?;
+        actions.push(Action::Signal(signal));
+    }
+    if let Some(ms) = raw.sleep {
+        actions.push(Action::Sleep(ms));
+    }
+
+    if actions.len() != 1 {
+        return Err(format!(
+            "expected exactly one action ({}), found {}",
+            ACTION_KEYS.join(", "),
+            actions.len()
+        ));
+    }
+    let action = actions.remove(0);
+    if raw.retries.is_some() && !action.is_retryable() {
+        return Err(format!(
+            "retries is only allowed on wait, assert, capture and screenshot steps, not {}",
+            action.kind()
+        ));
+    }
+    Ok(ScriptStep {
+        name: raw.name,
+        timeout_ms: raw.timeout_ms,
+        retries: raw.retries,
+        action,
+    })
+}
+
+const ACTION_KEYS: [&str; 11] = [
+    "run",
+    "press",
+    "type",
+    "paste",
+    "wait",
+    "assert",
+    "capture",
+    "screenshot",
+    "resize",
+    "signal",
+    "sleep",
+];
+
+fn convert_run(run: RawRun) -> Result<Action, String> {
+    match run {
+        RawRun::Line(line) => {
+            let mut words = shell_words::split(&line).map_err(|e| format!("run: {}", e))?;
+            if words.is_empty() {
+                return Err("run needs a command".to_string());
+            }
+            let command = words.remove(0);
+            Ok(Action::Run {
+                command,
+                args: words,
+                cwd: None,
+                cols: DEFAULT_COLS,
+                rows: DEFAULT_ROWS,
+            })
+        }
+        RawRun::Full {
+            command,
+            args,
+            cwd,
+            cols,
+            rows,
+        } => {
+            if command.trim().is_empty() {
+                return Err("run needs a command".to_string());
+            }
+            Ok(Action::Run {
+                command,
+                args,
+                cwd,
+                cols: cols.unwrap_or(DEFAULT_COLS),
+                rows: rows.unwrap_or(DEFAULT_ROWS),
+            })
+        }
+    }
+}
+
+fn convert_wait(wait: RawWait) -> Result<Action, String> {
+    let raw = match wait {
+        RawWait::Text(text) => RawWaitCondition {
+            text: Some(text),
+            ..RawWaitCondition::default()
+        },
+        RawWait::Full(condition) => condition,
+    };
+    let condition = match (raw.text, raw.gone, raw.stable, raw.echo_off) {
+        (Some(text), false, false, false) => WaitFor::Text(text),
+        (Some(text), true, false, false) => WaitFor::TextGone(text),
+        (None, false, true, false) => WaitFor::Stable,
+        (None, false, false, true) => WaitFor::EchoOff,
+        _ => {
+            return Err("wait needs one of: text, text with gone, stable, echo_off".to_string());
+        }
+    };
+    Ok(Action::Wait {
+        condition,
+        unwrap: raw.unwrap,
+        region: raw.region,
+    })
+}
+
+fn convert_assert(assert: RawAssert) -> Result<Check, String> {
+    let raw = match assert {
+        RawAssert::Text(text) => return Ok(Check::Text(text)),
+        RawAssert::Full(condition) => condition,
+    };
+    match (raw.text, raw.gone, raw.regex, raw.golden) {
+        (Some(text), None, None, None) => Ok(Check::Text(text)),
+        (None, Some(text), None, None) => Ok(Check::Gone(text)),
+        (None, None, Some(pattern), None) => {
+            // Patterns with variables can only be checked once expanded.
+            if !pattern.contains("${") {
+                Regex::new(&pattern).map_err(|e| format!("invalid assert regex: {}", e))?;
+            }
+            Ok(Check::Regex(pattern))
+        }
+        (None, None, None, Some(path)) => Ok(Check::Golden(path)),
+        _ => Err("assert needs exactly one of: text, gone, regex, golden".to_string()),
+    }
+}
+
+/// Replace `${name}` references. Text that is not a reference is kept as is.
+pub(crate) fn expand_vars(text: &str, vars: &BTreeMap<String, String>) -> Result<String, String> {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("${") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let name_len = after
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(after.len());
+        if name_len == 0 || !after[name_len..].starts_with('}') {
+            out.push_str("${");
+            rest = after;
+            continue;
+        }
+        let name = &after[..name_len];
+        let value = vars
+            .get(name)
+            .ok_or_else(|| format!("undefined variable ${{{}}}", name))?;
+        out.push_str(value);
+        rest = &after[name_len + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum StepStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct StepReport {
+    pub index: usize,
+    pub name: String,
+    pub action: &'static str,
+    pub status: StepStatus,
+    pub attempts: u32,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct ScriptReport {
+    pub script: String,
+    pub name: String,
+    pub passed: bool,
+    pub duration_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    pub steps: Vec<StepReport>,
+    pub vars: BTreeMap<String, String>,
+}
+
+impl ScriptReport {
+    pub(crate) fn failed_step(&self) -> Option<&StepReport> {
+        self.steps
+            .iter()
+            .find(|step| step.status == StepStatus::Failed)
+    }
+}
+
+pub(crate) struct ScriptRunner<'a, C: DaemonClient> {
+    client: &'a mut C,
+    session: Option<String>,
+    base_dir: PathBuf,
+    vars: BTreeMap<String, String>,
+    spawned: Vec<String>,
+}
+
+impl<'a, C: DaemonClient> ScriptRunner<'a, C> {
+    /// `session` is the session steps act on until a `run` step starts a new one.
+    pub(crate) fn new(client: &'a mut C, session: Option<String>, base_dir: PathBuf) -> Self {
+        Self {
+            client,
+            session,
+            base_dir,
+            vars: BTreeMap::new(),
+            spawned: Vec::new(),
+        }
+    }
+
+    /// Run every step in order, stopping at the first failure. Sessions started
+    /// by `run` steps are killed afterwards unless `keep` is set.
+    pub(crate) fn run(
+        mut self,
+        script: &Script,
+        script_path: &Path,
+        overrides: &[(String, String)],
+        keep: bool,
+    ) -> ScriptReport {
+        let started = Instant::now();
+        self.vars.clone_from(&script.vars);
+        self.vars.extend(overrides.iter().cloned());
+
+        let mut steps = Vec::with_capacity(script.steps.len());
+        let mut failed = false;
+        for (index, step) in script.steps.iter().enumerate() {
+            let name = step.name.clone().unwrap_or_else(|| step.action.describe());
+            if failed {
+                steps.push(StepReport {
+                    index: index + 1,
+                    name,
+                    action: step.action.kind(),
+                    status: StepStatus::Skipped,
+                    attempts: 0,
+                    duration_ms: 0,
+                    message: None,
+                });
+                continue;
+            }
+
+            let step_started = Instant::now();
+            let max_attempts = if step.action.is_retryable() {
+                step.retries.unwrap_or(script.retries).saturating_add(1)
+            } else {
+                1
+            };
+            let timeout_ms = step.timeout_ms.unwrap_or(script.timeout_ms);
+            let mut attempts = 0;
+            let outcome = loop {
+                attempts += 1;
+                match self.run_action(&step.action, timeout_ms) {
+                    Ok(()) => break Ok(()),
+                    Err(message) if attempts >= max_attempts => break Err(message),
+                    Err(_) => pause(Duration::from_millis(script.retry_delay_ms)),
+                }
+            };
+            failed = outcome.is_err();
+            steps.push(StepReport {
+                index: index + 1,
+                name,
+                action: step.action.kind(),
+                status: if failed {
+                    StepStatus::Failed
+                } else {
+                    StepStatus::Passed
+                },
+                attempts,
+                duration_ms: elapsed_ms(step_started),
+                message: outcome.err(),
+            });
+        }
+
+        let session_id = self.session.clone();
+        if !keep {
+            for id in std::mem::take(&mut self.spawned) {
+                let _ = call_with_params(
+                    self.client,
+                    "kill",
+                    params::SessionParams { session: Some(id) },
+                );
+            }
+        }
+
+        ScriptReport {
+            script: script_path.display().to_string(),
+            name: script.name.clone().unwrap_or_else(|| {
+                script_path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().into_owned())
+                    .unwrap_or_else(|| "script".to_string())
+            }),
+            passed: !failed,
+            duration_ms: elapsed_ms(started),
+            session_id,
+            steps,
+            vars: self.vars,
+        }
+    }
+
+    fn run_action(&mut self, action: &Action, timeout_ms: u64) -> Result<(), String> {
+        match action {
+            Action::Run {
+                command,
+                args,
+                cwd,
+                cols,
+                rows,
+            } => {
+                let result = self.call(
+                    "spawn",
+                    params::SpawnParams {
+                        command: self.expand(command)?,
+                        args: args
+                            .iter()
+                            .map(|arg| self.expand(arg))
+                            .collect::<Result<_, _>>()?,
+                        cwd: cwd
+                            .as_deref()
+                            .map(|cwd| self.expand(cwd).map(|cwd| self.resolve(&cwd)))
+                            .transpose()?
+                            .map(|path| path.to_string_lossy().into_owned()),
+                        session: None,
+                        cols: *cols,
+                        rows: *rows,
+                    },
+                )?;
+                let id = result.str_or("session_id", "").to_string();
+                if id.is_empty() {
+                    return Err("spawn did not return a session id".to_string());
+                }
+                self.spawned.push(id.clone());
+                self.session = Some(id);
+                Ok(())
+            }
+            Action::Press(keys) => {
+                for key in keys {
+                    let key = self.expand(key)?;
+                    let result = self.call(
+                        "keystroke",
+                        params::KeyParams {
+                            key,
+                            session: self.session.clone(),
+                        },
+                    )?;
+                    expect_success(&result)?;
+                }
+                Ok(())
+            }
+            Action::Type(text) => {
+                let text = self.expand(text)?;
+                let result = self.call(
+                    "type",
+                    params::TypeParams {
+                        text,
+                        session: self.session.clone(),
+                    },
+                )?;
+                expect_success(&result)
+            }
+            Action::Paste(text) => {
+                let text = self.expand(text)?;
+                self.write(text.as_bytes())
+            }
+            Action::Signal(signal) => {
+                let result = self.call(
+                    "signal",
+                    params::SignalParams {
+                        signal: signal.as_str().to_string(),
+                        session: self.session.clone(),
+                    },
+                )?;
+                expect_success(&result)
+            }
+            Action::Wait {
+                condition,
+                unwrap,
+                region,
+            } => {
+                let (text, condition_name) = match condition {
+                    WaitFor::Text(text) => (Some(self.expand(text)?), None),
+                    WaitFor::TextGone(text) => (Some(self.expand(text)?), Some("text_gone")),
+                    WaitFor::Stable => (None, Some("stable")),
+                    WaitFor::EchoOff => (None, Some("echo_off")),
+                };
+                let result = self.call(
+                    "wait",
+                    params::WaitParams {
+                        session: self.session.clone(),
+                        text,
+                        timeout_ms,
+                        condition: condition_name.map(str::to_string),
+                        unwrap: *unwrap,
+                        region: region.clone(),
+                    },
+                )?;
+                if result.bool_or("found", false) {
+                    Ok(())
+                } else {
+                    Err(wait_failure_message(timeout_ms, &result))
+                }
+            }
+            Action::Assert(check) => self.assert(check),
+            Action::Capture(pattern) => {
+                let screen = self.screen()?;
+                let regex = Regex::new(pattern).map_err(|e| e.to_string())?;
+                let captures = regex
+                    .captures(&screen)
+                    .ok_or_else(|| format!("no match for /{}/ on screen", pattern))?;
+                for name in regex.capture_names().flatten() {
+                    if let Some(value) = captures.name(name) {
+                        self.vars
+                            .insert(name.to_string(), value.as_str().to_string());
+                    }
+                }
+                Ok(())
+            }
+            Action::Screenshot(path) => {
+                let path = self.resolve(&self.expand(path)?);
+                let screen = self.screen()?;
+                std::fs::write(&path, screen)
+                    .map_err(|e| format!("failed to write {}: {}", path.display(), e))
+            }
+            Action::Resize { cols, rows } => {
+                self.call(
+                    "resize",
+                    params::ResizeParams {
+                        cols: *cols,
+                        rows: *rows,
+                        session: self.session.clone(),
+                    },
+                )?;
+                Ok(())
+            }
+            Action::Sleep(ms) => {
+                pause(Duration::from_millis(*ms));
+                Ok(())
+            }
+        }
+    }
+
+    fn assert(&mut self, check: &Check) -> Result<(), String> {
+        match check {
+            Check::Text(text) => {
+                let text = self.expand(text)?;
+                if self.screen()?.contains(&text) {
+                    Ok(())
+                } else {
+                    Err(format!("{:?} not on screen", text))
+                }
+            }
+            Check::Gone(text) => {
+                let text = self.expand(text)?;
+                if self.screen()?.contains(&text) {
+                    Err(format!("{:?} is still on screen", text))
+                } else {
+                    Ok(())
+                }
+            }
+            Check::Regex(pattern) => {
+                let pattern = self.expand(pattern)?;
+                let regex = Regex::new(&pattern).map_err(|e| e.to_string())?;
+                if regex.is_match(&self.screen()?) {
+                    Ok(())
+                } else {
+                    Err(format!("no match for /{}/ on screen", pattern))
+                }
+            }
+            Check::Golden(path) => {
+                let path = self.resolve(&self.expand(path)?);
+                let golden = std::fs::read_to_string(&path)
+                    .map_err(|e| format!("cannot read golden {}: {}", path.display(), e))?;
+                let result = self.call(
+                    "assert_screen",
+                    params::AssertScreenParams {
+                        golden,
+                        masks: Vec::new(),
+                        styles: false,
+                        session: self.session.clone(),
+                    },
+                )?;
+                if result.bool_or("matched", false) {
+                    return Ok(());
+                }
+                let rows = result
+                    .get("diff")
+                    .and_then(|v| v.as_array())
+                    .map(|diffs| {
+                        diffs
+                            .iter()
+                            .map(|diff| diff.u64_or("row", 0).to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_default();
+                Err(format!(
+                    "screen differs from golden {} (rows {})",
+                    path.display(),
+                    rows
+                ))
+            }
+        }
+    }
+
+    fn screen(&mut self) -> Result<String, String> {
+        let result = self.call(
+            "snapshot",
+            params::SnapshotParams {
+                session: self.session.clone(),
+                region: None,
+                strip_ansi: true,
+                include_cursor: false,
+                include_render: false,
+                unwrap: false,
+                include_highlights: false,
+                image: None,
+            },
+        )?;
+        Ok(result.str_or("screenshot", "").to_string())
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), String> {
+        self.call(
+            "pty_write",
+            params::PtyWriteParams {
+                session: self.session.clone(),
+                data: STANDARD.encode(bytes),
+            },
+        )?;
+        Ok(())
+    }
+
+    fn call<P: Serialize>(&mut self, method: &str, params: P) -> Result<RpcValue, String> {
+        call_with_params(self.client, method, params).map_err(|e| e.to_string())
+    }
+
+    fn expand(&self, text: &str) -> Result<String, String> {
+        expand_vars(text, &self.vars)
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        self.base_dir.join(path)
+    }
+}
+
+fn expect_success(result: &RpcValue) -> Result<(), String> {
+    if result.bool_or("success", true) {
+        Ok(())
+    } else {
+        Err(result.str_or("message", "Unknown error").to_string())
+    }
+}
+
+/// The timeout plus the daemon's diagnostics: how long the screen had been
+/// idle and the closest text to what was awaited.
+fn wait_failure_message(timeout_ms: u64, result: &RpcValue) -> String {
+    let mut message = format!("timed out after {}ms", timeout_ms);
+    let Some(diagnostics) = MatchDiagnosticsView::from_json(result) else {
+        return message;
+    };
+    message.push_str(&format!(
+        "; screen unchanged for {}ms",
+        diagnostics.screen_idle_ms
+    ));
+    let positions = |matches: &[NearestMatch]| {
+        matches
+            .iter()
+            .map(|m| format!("row {}, col {}: {:?}", m.row, m.col, m.text))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    if !diagnostics.still_visible.is_empty() {
+        message.push_str(&format!(
+            "; still on screen: {}",
+            positions(&diagnostics.still_visible)
+        ));
+    } else if !diagnostics.nearest_matches.is_empty() {
+        message.push_str(&format!(
+            "; closest: {}",
+            positions(&diagnostics.nearest_matches)
+        ));
+    }
+    message
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Block for `duration`; `park_timeout` may wake early, so keep parking.
+fn pause(duration: Duration) {
+    let deadline = Instant::now() + duration;
+    loop {
+        let now = Instant::now();
+        if now >= deadline {
+            return;
+        }
+        std::thread::park_timeout(deadline - now);
+    }
+}
+
+/// Render a report as a JUnit XML document with one test case per step.
+pub(crate) fn junit_xml(report: &ScriptReport) -> String {
+    let count = |status: StepStatus| {
+        report
+            .steps
+            .iter()
+            .filter(|step| step.status == status)
+            .count()
+    };
+    let seconds = |ms: u64| format!("{:.3}", ms as f64 / 1000.0);
+    let suite = xml_escape(&report.name);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    xml.push_str(&format!(
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\" time=\"{}\">\n",
+        suite,
+        report.steps.len(),
+        count(StepStatus::Failed),
+        count(StepStatus::Skipped),
+        seconds(report.duration_ms)
+    ));
+    for step in &report.steps {
+        let open = format!(
+            "    <testcase classname=\"{}\" name=\"{:02} {}\" time=\"{}\"",
+            suite,
+            step.index,
+            xml_escape(&step.name),
+            seconds(step.duration_ms)
+        );
+        match step.status {
+            StepStatus::Passed => xml.push_str(&format!("{}/>\n", open)),
+            StepStatus::Failed => xml.push_str(&format!(
+                "{}>\n      <failure message=\"{}\"/>\n    </testcase>\n",
+                open,
+                xml_escape(step.message.as_deref().unwrap_or("failed"))
+            )),
+            StepStatus::Skipped => {
+                xml.push_str(&format!("{}>\n      <skipped/>\n    </testcase>\n", open))
+            }
+        }
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn xml_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c if c.is_control() && c != '\n' && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockClient;
+    use serde_json::json;
+
+    fn parse_yaml(text: &str) -> Result<Script, String> {
+        Script::parse(text, Path::new("flow.yaml"))
+    }
+
+    #[test]
+    fn test_parse_yaml_steps() {
+        let script = parse_yaml(
+            r#"
+name: login
+timeout_ms: 2000
+vars: {user: admin}
+steps:
+  - run: "./app --mode 'demo app'"
+  - wait: "Login:"
+  - type: "${user}"
+  - press: [Tab, Enter]
+  - wait: {stable: true}
+    retries: 2
+  - assert: {regex: 'Welcome, \w+'}
+  - capture: 'Order #(?P<order>\d+)'
+  - signal: SIGINT
+  - sleep: 100
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(script.name.as_deref(), Some("login"));
+        assert_eq!(script.timeout_ms, 2000);
+        assert_eq!(script.steps.len(), 9);
+        assert_eq!(
+            script.steps[0].action,
+            Action::Run {
+                command: "./app".to_string(),
+                args: vec!["--mode".to_string(), "demo app".to_string()],
+                cwd: None,
+                cols: 80,
+                rows: 24,
+            }
+        );
+        assert_eq!(
+            script.steps[3].action,
+            Action::Press(vec!["Tab".to_string(), "Enter".to_string()])
+        );
+        assert_eq!(script.steps[4].retries, Some(2));
+        assert!(matches!(
+            script.steps[4].action,
+            Action::Wait {
+                condition: WaitFor::Stable,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_parse_toml_steps() {
+        let script = Script::parse(
+            r#"
+retries = 1
+
+[[steps]]
+run = { command = "htop", cols = 120, rows = 40 }
+
+[[steps]]
+wait = { text = "Loading", gone = true }
+
+[[steps]]
+resize = { cols = 100, rows = 30 }
+"#,
+            Path::new("flow.toml"),
+        )
+        .unwrap();
+
+        assert_eq!(script.retries, 1);
+        assert_eq!(
+            script.steps[1].action,
+            Action::Wait {
+                condition: WaitFor::TextGone("Loading".to_string()),
+                unwrap: false,
+                region: None,
+            }
+        );
+        assert_eq!(
+            script.steps[2].action,
+            Action::Resize {
+                cols: 100,
+                rows: 30
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_ambiguous_and_invalid_steps() {
+        let err = parse_yaml("steps:\n  - type: a\n    press: Enter\n").unwrap_err();
+        assert!(
+            err.starts_with("step 1: expected exactly one action"),
+            "{err}"
+        );
+
+        let err = parse_yaml("steps:\n  - wait: {}\n").unwrap_err();
+        assert!(err.contains("wait needs one of"), "{err}");
+
+        let err = parse_yaml("steps:\n  - capture: '\\d+'\n").unwrap_err();
+        assert!(err.contains("named group"), "{err}");
+
+        let err = parse_yaml("steps:\n  - signal: USR1\n").unwrap_err();
+        assert!(err.contains("unsupported signal"), "{err}");
+
+        let err = parse_yaml("steps:\n  - type: a\n    retries: 2\n").unwrap_err();
+        assert!(err.contains("retries is only allowed"), "{err}");
+
+        assert!(parse_yaml("steps:\n  - click: OK\n").is_err());
+        assert!(parse_yaml("steps: []\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_signal_names() {
+        let script =
+            parse_yaml("steps:\n  - signal: HUP\n  - signal: sigterm\n  - signal: KILL\n").unwrap();
+
+        let signals = script
+            .steps
+            .iter()
+            .map(|step| step.action.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            signals,
+            vec![
+                Action::Signal(ProcessSignal::Hup),
+                Action::Signal(ProcessSignal::Term),
+                Action::Signal(ProcessSignal::Kill),
+            ]
+        );
+    }
+
+    fn run_script(client: &mut MockClient, text: &str) -> ScriptReport {
+        let script = parse_yaml(text).unwrap();
+        ScriptRunner::new(client, Some("s1".to_string()), PathBuf::from(".")).run(
+            &script,
+            Path::new("flow.yaml"),
+            &[],
+            true,
+        )
+    }
+
+    #[test]
+    fn test_signal_step_uses_signal_rpc() {
+        let mut client = MockClient::new();
+
+        let report = run_script(&mut client, "steps:\n  - signal: TERM\n");
+
+        assert!(report.passed);
+        assert_eq!(
+            client.params_for("signal"),
+            vec![Some(json!({ "signal": "TERM", "session": "s1" }))]
+        );
+        assert_eq!(client.call_count("pty_write"), 0);
+    }
+
+    #[test]
+    fn test_script_retries_skip_input_steps() {
+        let mut client = MockClient::new();
+        client.set_response("type", json!({ "success": false, "message": "boom" }));
+
+        let report = run_script(
+            &mut client,
+            "retries: 3\nretry_delay_ms: 0\nsteps:\n  - type: x\n",
+        );
+
+        assert!(!report.passed);
+        assert_eq!(report.steps[0].attempts, 1);
+        assert_eq!(client.call_count("type"), 1);
+    }
+
+    #[test]
+    fn test_wait_failure_message_includes_diagnostics() {
+        let mut client = MockClient::new();
+        client.set_response(
+            "wait",
+            json!({
+                "found": false,
+                "diagnostics": {
+                    "screen": "Continue?",
+                    "screen_idle_ms": 75,
+                    "nearest_matches": [
+                        { "text": "Continue?", "row": 2, "col": 4, "distance": 1 }
+                    ]
+                }
+            }),
+        );
+
+        let report = run_script(
+            &mut client,
+            "retry_delay_ms: 0\nsteps:\n  - wait: Continue\n    timeout_ms: 50\n    retries: 1\n",
+        );
+
+        assert_eq!(report.steps[0].attempts, 2);
+        assert_eq!(
+            report.steps[0].message.as_deref(),
+            Some(
+                "timed out after 50ms; screen unchanged for 75ms; closest: row 2, col 4: \"Continue?\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_wait_gone_failure_message_reports_where_text_remains() {
+        let mut client = MockClient::new();
+        client.set_response(
+            "wait",
+            json!({
+                "found": false,
+                "diagnostics": {
+                    "screen": "  Loading",
+                    "screen_idle_ms": 20,
+                    "nearest_matches": [],
+                    "still_visible": [{ "text": "Loading", "row": 0, "col": 2 }]
+                }
+            }),
+        );
+
+        let report = run_script(
+            &mut client,
+            "steps:\n  - wait: { text: Loading, gone: true }\n    timeout_ms: 50\n",
+        );
+
+        assert_eq!(
+            report.steps[0].message.as_deref(),
+            Some(
+                "timed out after 50ms; screen unchanged for 20ms; still on screen: row 0, col 2: \"Loading\""
+            )
+        );
+    }
+
+    #[test]
+    fn test_expand_vars() {
+        let vars = BTreeMap::from([("user".to_string(), "admin".to_string())]);
+
+        assert_eq!(
+            expand_vars("hi ${user}, cost $5 ${ x}", &vars).unwrap(),
+            "hi admin, cost $5 ${ x}"
+        );
+        assert_eq!(
+            expand_vars("${missing}", &vars).unwrap_err(),
+            "undefined variable ${missing}"
+        );
+    }
+
+    #[test]
+    fn test_junit_xml_marks_failures_and_skips() {
+        let step = |index, status, message: Option<&str>| StepReport {
+            index,
+            name: format!("step <{}>", index),
+            action: "wait",
+            status,
+            attempts: 1,
+            duration_ms: 1500,
+            message: message.map(str::to_string),
+        };
+        let report = ScriptReport {
+            script: "flow.yaml".to_string(),
+            name: "flow".to_string(),
+            passed: false,
+            duration_ms: 3000,
+            session_id: None,
+            steps: vec![
+                step(1, StepStatus::Passed, None),
+                step(2, StepStatus::Failed, Some("timed out \"x\"")),
+                step(3, StepStatus::Skipped, None),
+            ],
+            vars: BTreeMap::new(),
+        };
+
+        let xml = junit_xml(&report);
+
+        assert!(xml.contains("tests=\"3\" failures=\"1\" skipped=\"1\" time=\"3.000\""));
+        assert!(xml.contains("name=\"01 step &lt;1&gt;\" time=\"1.500\"/>"));
+        assert!(xml.contains("<failure message=\"timed out &quot;x&quot;\"/>"));
+        assert!(xml.contains("<skipped/>"));
+    }
+}
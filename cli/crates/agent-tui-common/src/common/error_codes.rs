@@ -5,6 +5,9 @@ pub const NO_ACTIVE_SESSION: i32 = -32002;
 pub const SESSION_LIMIT: i32 = -32006;
 pub const LOCK_TIMEOUT: i32 = -32007;
 pub const SESSION_ALREADY_EXISTS: i32 = -32018;
+pub const REGION_NOT_FOUND: i32 = -32019;
+pub const RECORDING_ERROR: i32 = -32020;
+pub const REPLAY_ERROR: i32 = -32021;
 
 pub const INVALID_KEY: i32 = -32005;
 pub const PTY_ERROR: i32 = -32008;
@@ -70,8 +73,10 @@ pub fn is_retryable(code: i32) -> bool {
 
 pub fn category_for_code(code: i32) -> ErrorCategory {
     match code {
-        SESSION_NOT_FOUND | NO_ACTIVE_SESSION => ErrorCategory::NotFound,
-        INVALID_KEY | SESSION_ALREADY_EXISTS => ErrorCategory::InvalidInput,
+        SESSION_NOT_FOUND | NO_ACTIVE_SESSION | REGION_NOT_FOUND => ErrorCategory::NotFound,
+        INVALID_KEY | SESSION_ALREADY_EXISTS | RECORDING_ERROR | REPLAY_ERROR => {
+            ErrorCategory::InvalidInput
+        }
         SESSION_LIMIT | LOCK_TIMEOUT => ErrorCategory::Busy,
         PTY_ERROR | COMMAND_NOT_FOUND | PERMISSION_DENIED | DAEMON_ERROR | PERSISTENCE_ERROR => {
             ErrorCategory::External
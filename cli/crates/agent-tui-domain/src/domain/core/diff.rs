@@ -0,0 +1,112 @@
+//! Versioned screen states and row diffs between them.
+
+use super::CursorPosition;
+
+/// Physical rows (trailing whitespace trimmed) and cursor at a screen version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenState {
+    pub version: u64,
+    pub rows: Vec<String>,
+    pub cursor: CursorPosition,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowChange {
+    pub row: u16,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScreenDiff {
+    pub from_version: u64,
+    pub to_version: u64,
+    /// The base version is no longer retained, so every non-empty row is reported.
+    pub full: bool,
+    pub rows: Vec<RowChange>,
+    pub cursor_from: Option<CursorPosition>,
+    pub cursor: CursorPosition,
+}
+
+impl ScreenDiff {
+    pub fn between(since: u64, old: Option<&ScreenState>, new: &ScreenState) -> Self {
+        let old_rows = old.map(|state| state.rows.as_slice()).unwrap_or_default();
+        let row_count = old_rows.len().max(new.rows.len());
+        let rows = (0..row_count)
+            .filter_map(|idx| {
+                let before = old_rows.get(idx).map(String::as_str).unwrap_or_default();
+                let after = new.rows.get(idx).map(String::as_str).unwrap_or_default();
+                (before != after).then(|| RowChange {
+                    row: u16::try_from(idx).unwrap_or(u16::MAX),
+                    old: before.to_string(),
+                    new: after.to_string(),
+                })
+            })
+            .collect();
+
+        Self {
+            from_version: old.map_or(since, |state| state.version),
+            to_version: new.version,
+            full: old.is_none(),
+            rows,
+            cursor_from: old.map(|state| state.cursor),
+            cursor: new.cursor,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty() && self.cursor_from == Some(self.cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(version: u64, rows: &[&str], col: u16) -> ScreenState {
+        ScreenState {
+            version,
+            rows: rows.iter().map(|row| row.to_string()).collect(),
+            cursor: CursorPosition {
+                row: 0,
+                col,
+                visible: true,
+            },
+        }
+    }
+
+    #[test]
+    fn test_between_reports_only_changed_rows() {
+        let old = state(10, &["$ ls", "a b", ""], 4);
+        let new = state(42, &["$ ls", "a b c", "$"], 1);
+
+        let diff = ScreenDiff::between(10, Some(&old), &new);
+
+        assert!(!diff.full);
+        assert_eq!((diff.from_version, diff.to_version), (10, 42));
+        assert_eq!(diff.rows.len(), 2);
+        assert_eq!(diff.rows[0].row, 1);
+        assert_eq!(diff.rows[0].old, "a b");
+        assert_eq!(diff.rows[0].new, "a b c");
+        assert_eq!(diff.cursor_from.map(|c| c.col), Some(4));
+        assert_eq!(diff.cursor.col, 1);
+    }
+
+    #[test]
+    fn test_between_without_base_is_full() {
+        let new = state(7, &["hello", ""], 0);
+        let diff = ScreenDiff::between(3, None, &new);
+
+        assert!(diff.full);
+        assert_eq!(diff.from_version, 3);
+        assert_eq!(diff.rows.len(), 1);
+        assert!(diff.cursor_from.is_none());
+    }
+
+    #[test]
+    fn test_is_empty_when_nothing_changed() {
+        let old = state(5, &["same"], 2);
+        let diff = ScreenDiff::between(5, Some(&old), &old.clone());
+        assert!(diff.is_empty());
+    }
+}
@@ -0,0 +1,746 @@
+//! Semantic widget detection over a screen snapshot.
+//!
+//! Heuristics only: frames become boxes, bracketed labels become buttons,
+//! `[x]`/`( )`/`◉` markers become checkboxes and radios, prompts and `___`
+//! runs become inputs, and inverse-video or pointer-marked rows become lists.
+
+use std::cmp::Reverse;
+
+use super::CursorPosition;
+use super::ScreenSnapshot;
+use super::frames::Frame;
+use super::frames::find_frames;
+use super::frames::is_box_drawing;
+
+const MAX_BUTTON_LABEL: usize = 24;
+pub(super) const POINTERS: &[char] = &['>', '❯', '›', '▶', '➜', '→'];
+const PROMPT_SEPARATORS: &[char] = &[':', '?', '>', '❯', '›', '$', '#', '%'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ElementKind {
+    Box,
+    List,
+    Item,
+    Button,
+    Checkbox,
+    Radio,
+    Input,
+}
+
+impl ElementKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Box => "box",
+            Self::List => "list",
+            Self::Item => "item",
+            Self::Button => "button",
+            Self::Checkbox => "checkbox",
+            Self::Radio => "radio",
+            Self::Input => "input",
+        }
+    }
+
+    fn is_container(self) -> bool {
+        matches!(self, Self::Box | Self::List | Self::Item)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Element {
+    /// Pre-order reference (`e1`, `e2`, ...), unchanged while the layout is unchanged.
+    pub ref_id: String,
+    pub kind: ElementKind,
+    pub label: String,
+    pub row: u16,
+    pub col: u16,
+    pub width: u16,
+    pub height: u16,
+    /// Text currently typed into an input.
+    pub value: Option<String>,
+    pub checked: Option<bool>,
+    pub selected: bool,
+    pub focused: bool,
+    pub children: Vec<Element>,
+}
+
+impl Element {
+    fn new(
+        kind: ElementKind,
+        row: usize,
+        col: usize,
+        width: usize,
+        height: usize,
+        label: String,
+    ) -> Self {
+        Self {
+            ref_id: String::new(),
+            kind,
+            label,
+            row: to_u16(row),
+            col: to_u16(col),
+            width: to_u16(width),
+            height: to_u16(height),
+            value: None,
+            checked: None,
+            selected: false,
+            focused: false,
+            children: Vec::new(),
+        }
+    }
+
+    fn bottom(&self) -> usize {
+        usize::from(self.row) + usize::from(self.height)
+    }
+
+    fn right(&self) -> usize {
+        usize::from(self.col) + usize::from(self.width)
+    }
+
+    fn area(&self) -> u32 {
+        u32::from(self.width) * u32::from(self.height)
+    }
+
+    fn contains(&self, other: &Element) -> bool {
+        self.row <= other.row
+            && self.col <= other.col
+            && other.bottom() <= self.bottom()
+            && other.right() <= self.right()
+    }
+
+    fn overlaps(&self, row: usize, col: usize, len: usize) -> bool {
+        (usize::from(self.row)..self.bottom()).contains(&row)
+            && usize::from(self.col) < col + len
+            && col < self.right()
+    }
+
+    /// Depth-first iteration over this element and its descendants.
+    pub fn walk(&self) -> Vec<&Element> {
+        let mut out = vec![self];
+        for child in &self.children {
+            out.extend(child.walk());
+        }
+        out
+    }
+}
+
+/// Analyzes `snapshot` into a tree of elements ordered by position.
+pub fn detect_elements(snapshot: &ScreenSnapshot) -> Vec<Element> {
+    let grid: Vec<Vec<char>> = snapshot
+        .cells
+        .iter()
+        .map(|row| row.iter().map(|cell| cell.ch).collect())
+        .collect();
+    let frames = find_frames(snapshot);
+
+    let mut widgets: Vec<Element> = grid
+        .iter()
+        .enumerate()
+        .flat_map(|(row, chars)| scan_row(row, chars))
+        .filter(|widget| !on_border(&frames, widget))
+        .collect();
+    mark_focus(&mut widgets, snapshot);
+    if let Some(input) = cursor_input(&grid, &frames, snapshot.cursor, &widgets) {
+        widgets.push(input);
+    }
+
+    let mut elements: Vec<Element> = frames
+        .iter()
+        .map(|frame| {
+            Element::new(
+                ElementKind::Box,
+                usize::from(frame.row),
+                usize::from(frame.col),
+                usize::from(frame.width),
+                usize::from(frame.height),
+                frame.title.clone().unwrap_or_default(),
+            )
+        })
+        .collect();
+    elements.extend(detect_lists(&grid, &frames, snapshot, &widgets));
+    elements.extend(widgets);
+    build_tree(elements)
+}
+
+fn scan_row(row: usize, chars: &[char]) -> Vec<Element> {
+    let mut found = Vec::new();
+    let mut col = 0;
+    while col < chars.len() {
+        match match_widget(row, chars, col) {
+            Some(element) => {
+                col = element.right().max(col + 1);
+                found.push(element);
+            }
+            None => col += 1,
+        }
+    }
+    found
+}
+
+fn match_widget(row: usize, chars: &[char], col: usize) -> Option<Element> {
+    if let Some((kind, checked)) = toggle_glyph(chars[col])
+        && chars.get(col + 1) == Some(&' ')
+    {
+        let (label, end) = label_after(chars, col + 1);
+        if !label.is_empty() {
+            let mut element = Element::new(kind, row, col, end - col, 1, label);
+            element.checked = Some(checked);
+            return Some(element);
+        }
+    }
+
+    if chars[col] == '_' && (col == 0 || chars[col - 1] != '_') {
+        let run = chars[col..].iter().take_while(|&&ch| ch == '_').count();
+        if run >= 3 {
+            let mut element = Element::new(
+                ElementKind::Input,
+                row,
+                col,
+                run,
+                1,
+                label_before(chars, col),
+            );
+            element.value = Some(String::new());
+            return Some(element);
+        }
+        return None;
+    }
+
+    let end = closing_bracket(chars, col)?;
+    let inner = &chars[col + 1..end];
+
+    if let Some((kind, checked)) = bracket_toggle(chars[col], inner) {
+        let (label, label_end) = label_after(chars, end + 1);
+        let width = if label.is_empty() { end + 1 } else { label_end } - col;
+        let mut element = Element::new(kind, row, col, width, 1, label);
+        element.checked = Some(checked);
+        return Some(element);
+    }
+
+    let text: String = inner.iter().collect();
+    if text.contains("___") {
+        let mut element = Element::new(
+            ElementKind::Input,
+            row,
+            col,
+            end + 1 - col,
+            1,
+            label_before(chars, col),
+        );
+        element.value = Some(text.trim_matches(['_', ' ']).to_string());
+        return Some(element);
+    }
+
+    let label = text.trim();
+    let is_button = chars[col] != '('
+        && !chars.get(end + 1).is_some_and(|ch| ch.is_alphanumeric())
+        && label.chars().any(char::is_alphanumeric)
+        && !label.chars().all(|ch| ch.is_ascii_digit())
+        && !label.contains(['[', ']', '<', '>']);
+    is_button.then(|| {
+        Element::new(
+            ElementKind::Button,
+            row,
+            col,
+            end + 1 - col,
+            1,
+            label.to_string(),
+        )
+    })
+}
+
+/// Index of the bracket closing the one at `col`, rejecting brackets glued to a word.
+fn closing_bracket(chars: &[char], col: usize) -> Option<usize> {
+    let close = match chars[col] {
+        '[' => ']',
+        '(' => ')',
+        '<' => '>',
+        _ => return None,
+    };
+    if col > 0 && chars[col - 1].is_alphanumeric() {
+        return None;
+    }
+    let limit = chars.len().min(col + MAX_BUTTON_LABEL + 2);
+    let end = (col + 1..limit).find(|&idx| chars[idx] == close || chars[idx] == chars[col])?;
+    (chars[end] == close).then_some(end)
+}
+
+fn toggle_glyph(ch: char) -> Option<(ElementKind, bool)> {
+    match ch {
+        '☐' => Some((ElementKind::Checkbox, false)),
+        '☑' | '☒' => Some((ElementKind::Checkbox, true)),
+        '○' | '◯' => Some((ElementKind::Radio, false)),
+        '◉' | '●' => Some((ElementKind::Radio, true)),
+        _ => None,
+    }
+}
+
+fn bracket_toggle(open: char, inner: &[char]) -> Option<(ElementKind, bool)> {
+    let [mark] = inner else {
+        return None;
+    };
+    match open {
+        '[' if [' ', 'x', 'X', '✓', '✔', '*'].contains(mark) => {
+            Some((ElementKind::Checkbox, *mark != ' '))
+        }
+        '(' if [' ', 'x', 'X', '*', '•', '●', 'o'].contains(mark) => {
+            Some((ElementKind::Radio, *mark != ' '))
+        }
+        _ => None,
+    }
+}
+
+fn starts_toggle(chars: &[char], col: usize) -> bool {
+    toggle_glyph(chars[col]).is_some()
+        || closing_bracket(chars, col)
+            .is_some_and(|end| bracket_toggle(chars[col], &chars[col + 1..end]).is_some())
+}
+
+/// Label following a widget: one optional space, then text up to a double space,
+/// border, or the next toggle. Returns the label and its exclusive end column.
+fn label_after(chars: &[char], start: usize) -> (String, usize) {
+    let begin = if chars.get(start) == Some(&' ') {
+        start + 1
+    } else {
+        start
+    };
+    let mut end = begin;
+    let mut idx = begin;
+    while idx < chars.len() {
+        let ch = chars[idx];
+        if is_box_drawing(ch)
+            || (ch == ' ' && chars.get(idx + 1) == Some(&' '))
+            || (idx > begin && starts_toggle(chars, idx))
+        {
+            break;
+        }
+        if ch != ' ' {
+            end = idx + 1;
+        }
+        idx += 1;
+    }
+    (chars[begin..end].iter().collect(), end.max(begin))
+}
+
+/// Text to the left of `col` up to a double space or border, without a trailing colon.
+fn label_before(chars: &[char], col: usize) -> String {
+    let mut begin = col;
+    while begin > 0 {
+        let ch = chars[begin - 1];
+        if is_box_drawing(ch) || (ch == ' ' && begin >= 2 && chars[begin - 2] == ' ') {
+            break;
+        }
+        begin -= 1;
+    }
+    let text: String = chars[begin..col].iter().collect();
+    text.trim().trim_end_matches(':').trim_end().to_string()
+}
+
+fn on_border(frames: &[Frame], element: &Element) -> bool {
+    frames.iter().any(|frame| {
+        (element.row == frame.row || element.row == frame.bottom())
+            && element.col <= frame.right()
+            && usize::from(frame.col) < element.right()
+    })
+}
+
+fn mark_focus(widgets: &mut [Element], snapshot: &ScreenSnapshot) {
+    let spans = snapshot.inverse_spans();
+    let cursor = snapshot.cursor;
+    for widget in widgets.iter_mut() {
+        let highlighted = spans.iter().any(|span| {
+            widget.overlaps(
+                usize::from(span.row),
+                usize::from(span.col),
+                usize::from(span.len),
+            )
+        });
+        let under_cursor =
+            cursor.visible && widget.overlaps(usize::from(cursor.row), usize::from(cursor.col), 1);
+        widget.focused = highlighted || under_cursor;
+    }
+}
+
+/// Rows and columns available to content around `(row, col)`: the interior of the
+/// innermost frame, or the whole screen.
+struct Bounds {
+    top: usize,
+    bottom: usize,
+    left: usize,
+    right: usize,
+}
+
+fn bounds(grid: &[Vec<char>], frames: &[Frame], row: usize, col: usize) -> Bounds {
+    let (r, c) = (to_u16(row), to_u16(col));
+    frames
+        .iter()
+        .filter(|frame| frame.interior_contains(r, c))
+        .min_by_key(|frame| u32::from(frame.width) * u32::from(frame.height))
+        .map_or_else(
+            || Bounds {
+                top: 0,
+                bottom: grid.len(),
+                left: 0,
+                right: grid.get(row).map_or(0, Vec::len),
+            },
+            |frame| Bounds {
+                top: usize::from(frame.row) + 1,
+                bottom: usize::from(frame.bottom()),
+                left: usize::from(frame.col) + 1,
+                right: usize::from(frame.right()),
+            },
+        )
+}
+
+/// The focused text field under a visible cursor, labelled by the nearest prompt
+/// separator to its left (`Name: `, `? Project name `, `$ `).
+fn cursor_input(
+    grid: &[Vec<char>],
+    frames: &[Frame],
+    cursor: CursorPosition,
+    widgets: &[Element],
+) -> Option<Element> {
+    let (row, col) = (usize::from(cursor.row), usize::from(cursor.col));
+    if !cursor.visible || widgets.iter().any(|widget| widget.overlaps(row, col, 1)) {
+        return None;
+    }
+    let chars = grid.get(row)?;
+    let area = bounds(grid, frames, row, col);
+    let col = col.min(area.right);
+
+    let separator = (area.left..col).rev().find(|&idx| {
+        PROMPT_SEPARATORS.contains(&chars[idx]) && (idx + 1 == col || chars[idx + 1] == ' ')
+    })?;
+    let label = label_before(chars, separator + 1);
+    let start = (separator + 2).min(col);
+    let value_end = (start..area.right)
+        .take_while(|&idx| !is_box_drawing(chars[idx]))
+        .filter(|&idx| chars[idx] != ' ')
+        .last()
+        .map_or(start, |idx| idx + 1);
+    let end = value_end.max(col + 1);
+
+    let label = if label.is_empty() {
+        chars[separator].to_string()
+    } else {
+        label
+    };
+    let mut element = Element::new(ElementKind::Input, row, start, end - start, 1, label);
+    element.value = Some(chars[start..value_end].iter().collect());
+    element.focused = true;
+    Some(element)
+}
+
+fn detect_lists(
+    grid: &[Vec<char>],
+    frames: &[Frame],
+    snapshot: &ScreenSnapshot,
+    widgets: &[Element],
+) -> Vec<Element> {
+    let mut lists: Vec<Element> = Vec::new();
+    let covered = |lists: &[Element], row: usize, col: usize| {
+        lists
+            .iter()
+            .any(|list| list.kind == ElementKind::List && list.overlaps(row, col, 1))
+    };
+
+    for span in snapshot.inverse_spans() {
+        let (row, col, len) = (
+            usize::from(span.row),
+            usize::from(span.col),
+            usize::from(span.len),
+        );
+        let is_control = widgets.iter().any(|widget| {
+            matches!(widget.kind, ElementKind::Button | ElementKind::Input)
+                && widget.overlaps(row, col, len)
+        });
+        let chars = &grid[row];
+        let Some(mut text_col) = (col..col + len).find(|&idx| {
+            chars
+                .get(idx)
+                .is_some_and(|ch| *ch != ' ' && !is_box_drawing(*ch))
+        }) else {
+            continue;
+        };
+        if POINTERS.contains(&chars[text_col]) && chars.get(text_col + 1) == Some(&' ') {
+            text_col = (text_col + 1..chars.len())
+                .find(|&idx| chars[idx] != ' ')
+                .unwrap_or(text_col);
+        }
+        if is_control || covered(&lists, row, text_col) {
+            continue;
+        }
+        let full_width = len + 1 >= usize::from(snapshot.cols);
+        let min_items = if full_width { 2 } else { 1 };
+        lists.extend(list_block(grid, frames, row, text_col, 1, min_items));
+    }
+
+    for (row, chars) in grid.iter().enumerate() {
+        for col in 0..chars.len().saturating_sub(2) {
+            let is_pointer = POINTERS.contains(&chars[col])
+                && chars[col + 1] == ' '
+                && chars[col + 2] != ' '
+                && (col == 0 || chars[col - 1] == ' ' || is_box_drawing(chars[col - 1]));
+            if is_pointer && !covered(&lists, row, col) {
+                lists.extend(list_block(grid, frames, row, col + 2, 0, 2));
+            }
+        }
+    }
+    lists
+}
+
+/// Grows a list from the selected row over neighbouring rows whose text starts
+/// within `tolerance` columns of `text_col`. Returns the list followed by its items.
+fn list_block(
+    grid: &[Vec<char>],
+    frames: &[Frame],
+    selected: usize,
+    text_col: usize,
+    tolerance: usize,
+    min_items: usize,
+) -> Vec<Element> {
+    let area = bounds(grid, frames, selected, text_col);
+    let item_start = |row: usize| {
+        let chars = grid.get(row)?;
+        let right = area.right.min(chars.len());
+        (area.left..right).find(|&idx| chars[idx] != ' ' && !is_box_drawing(chars[idx]))
+    };
+    let aligned =
+        |row: usize| item_start(row).is_some_and(|col| col.abs_diff(text_col) <= tolerance);
+
+    let mut top = selected;
+    while top > area.top && aligned(top - 1) {
+        top -= 1;
+    }
+    let mut bottom = selected;
+    while bottom + 1 < area.bottom && aligned(bottom + 1) {
+        bottom += 1;
+    }
+    if bottom + 1 - top < min_items {
+        return Vec::new();
+    }
+
+    let mut items: Vec<Element> = (top..=bottom)
+        .filter_map(|row| {
+            let start = if row == selected {
+                text_col
+            } else {
+                item_start(row)?
+            };
+            let right = area.right.min(grid[row].len());
+            let text: String = grid[row][start..right]
+                .iter()
+                .take_while(|ch| !is_box_drawing(**ch))
+                .collect();
+            let label = text.trim_end().to_string();
+            let width = label.chars().count();
+            let mut item = Element::new(ElementKind::Item, row, start, width, 1, label);
+            item.selected = row == selected;
+            Some(item)
+        })
+        .collect();
+
+    let left = items
+        .iter()
+        .map(|item| usize::from(item.col))
+        .min()
+        .unwrap_or(text_col);
+    let right = items.iter().map(Element::right).max().unwrap_or(text_col);
+    let list = Element::new(
+        ElementKind::List,
+        top,
+        left,
+        right - left,
+        bottom + 1 - top,
+        String::new(),
+    );
+    items.insert(0, list);
+    items
+}
+
+fn build_tree(mut elements: Vec<Element>) -> Vec<Element> {
+    elements.sort_by_key(|element| {
+        (
+            Reverse(element.area()),
+            element.kind,
+            element.row,
+            element.col,
+        )
+    });
+    let mut roots = Vec::new();
+    for element in elements {
+        insert(&mut roots, element);
+    }
+    let mut next = 1;
+    assign_refs(&mut roots, &mut next);
+    roots
+}
+
+fn insert(nodes: &mut Vec<Element>, element: Element) {
+    match nodes
+        .iter_mut()
+        .rev()
+        .find(|node| node.kind.is_container() && node.contains(&element))
+    {
+        Some(parent) => insert(&mut parent.children, element),
+        None => nodes.push(element),
+    }
+}
+
+fn assign_refs(nodes: &mut [Element], next: &mut usize) {
+    nodes.sort_by_key(|node| (node.row, node.col, node.kind));
+    for node in nodes {
+        node.ref_id = format!("e{next}");
+        *next += 1;
+        assign_refs(&mut node.children, next);
+    }
+}
+
+fn to_u16(value: usize) -> u16 {
+    u16::try_from(value).unwrap_or(u16::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(text: &str, cursor: Option<(u16, u16)>) -> ScreenSnapshot {
+        let cols = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let rows = text.lines().count();
+        let (row, col) = cursor.unwrap_or_default();
+        ScreenSnapshot::from_text(
+            text,
+            u16::try_from(cols).unwrap() + 4,
+            u16::try_from(rows).unwrap(),
+            CursorPosition {
+                row,
+                col,
+                visible: cursor.is_some(),
+            },
+        )
+    }
+
+    fn invert(snapshot: &mut ScreenSnapshot, row: usize, cols: std::ops::Range<usize>) {
+        for cell in &mut snapshot.cells[row][cols] {
+            cell.style.inverse = true;
+        }
+    }
+
+    fn flatten(elements: &[Element]) -> Vec<&Element> {
+        elements.iter().flat_map(Element::walk).collect()
+    }
+
+    #[test]
+    fn test_detects_buttons_and_toggles_inside_box() {
+        let screen = snapshot(
+            "┌─ Settings ──────────────┐\n\
+             │ [x] Enable logging      │\n\
+             │ ( ) Small  (*) Large    │\n\
+             │      [ OK ]  <Cancel>   │\n\
+             └─────────────────────────┘",
+            None,
+        );
+
+        let elements = detect_elements(&screen);
+        assert_eq!(elements.len(), 1);
+        let root = &elements[0];
+        assert_eq!(
+            (root.kind, root.label.as_str()),
+            (ElementKind::Box, "Settings")
+        );
+        assert_eq!(root.ref_id, "e1");
+
+        let kinds: Vec<(ElementKind, &str, Option<bool>)> = root
+            .children
+            .iter()
+            .map(|e| (e.kind, e.label.as_str(), e.checked))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (ElementKind::Checkbox, "Enable logging", Some(true)),
+                (ElementKind::Radio, "Small", Some(false)),
+                (ElementKind::Radio, "Large", Some(true)),
+                (ElementKind::Button, "OK", None),
+                (ElementKind::Button, "Cancel", None),
+            ]
+        );
+        assert_eq!(root.children[3].ref_id, "e5");
+    }
+
+    #[test]
+    fn test_ignores_hotkey_brackets_and_border_titles() {
+        let screen = snapshot("╭─[1]─Status─╮\n│ [q]uit     │\n╰────────────╯", None);
+        let elements = detect_elements(&screen);
+        assert_eq!(elements[0].label, "[1] Status");
+        assert!(elements[0].children.is_empty());
+    }
+
+    #[test]
+    fn test_inverse_row_becomes_selected_list_item() {
+        let mut screen = snapshot("Pick one:\n  Apple\n  Banana\n  Cherry\n\n[ Done ]", None);
+        invert(&mut screen, 2, 0..10);
+        invert(&mut screen, 5, 0..8);
+
+        let elements = detect_elements(&screen);
+        let list = elements
+            .iter()
+            .find(|e| e.kind == ElementKind::List)
+            .unwrap();
+        assert_eq!(list.children.len(), 3);
+        assert!(list.children[1].selected);
+        assert_eq!(list.children[1].label, "Banana");
+
+        let button = elements
+            .iter()
+            .find(|e| e.kind == ElementKind::Button)
+            .unwrap();
+        assert!(button.focused);
+    }
+
+    #[test]
+    fn test_pointer_marker_list_needs_aligned_items() {
+        let screen = snapshot("? Framework\n❯ React\n  Vue\n  Svelte", None);
+        let elements = detect_elements(&screen);
+        let list = elements
+            .iter()
+            .find(|e| e.kind == ElementKind::List)
+            .unwrap();
+        let labels: Vec<&str> = list.children.iter().map(|e| e.label.as_str()).collect();
+        assert_eq!(labels, vec!["React", "Vue", "Svelte"]);
+        assert!(list.children[0].selected);
+
+        let shell = snapshot("> ls\nfile1\nfile2", None);
+        assert!(detect_elements(&shell).is_empty());
+    }
+
+    #[test]
+    fn test_cursor_input_takes_label_and_value() {
+        let screen = snapshot("Name: Ada Lovelace\nEmail: ________", Some((0, 18)));
+        let elements = detect_elements(&screen);
+        let inputs: Vec<&Element> = flatten(&elements)
+            .into_iter()
+            .filter(|e| e.kind == ElementKind::Input)
+            .collect();
+
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(inputs[0].label, "Name");
+        assert_eq!(inputs[0].value.as_deref(), Some("Ada Lovelace"));
+        assert!(inputs[0].focused);
+        assert_eq!(inputs[1].label, "Email");
+        assert!(!inputs[1].focused);
+    }
+
+    #[test]
+    fn test_refs_are_stable_for_identical_screens() {
+        let text = "┌──────┐\n│[ OK ]│\n└──────┘\n[Quit]";
+        let first = detect_elements(&snapshot(text, None));
+        let second = detect_elements(&snapshot(text, None));
+        assert_eq!(first, second);
+        let refs: Vec<&str> = flatten(&first).iter().map(|e| e.ref_id.as_str()).collect();
+        assert_eq!(refs, vec!["e1", "e2", "e3"]);
+    }
+}
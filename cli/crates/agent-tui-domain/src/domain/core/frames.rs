@@ -0,0 +1,184 @@
+//! Rectangular frames drawn with box-drawing characters.
+
+use super::ScreenGrid;
+
+// Junctions count as corners so panes that share a border (`┬`, `├`, `┼`) split apart.
+const TOP_LEFT: &[char] = &['┌', '╭', '╔', '┏', '├', '┬', '┼', '+'];
+const TOP_RIGHT: &[char] = &['┐', '╮', '╗', '┓', '┤', '┬', '┼', '+'];
+const BOTTOM_LEFT: &[char] = &['└', '╰', '╚', '┗', '├', '┴', '┼', '+'];
+const BOTTOM_RIGHT: &[char] = &['┘', '╯', '╝', '┛', '┤', '┴', '┼', '+'];
+const HORIZONTAL: &[char] = &['─', '━', '═', '┬', '┴', '┼', '╤', '╧', '┯', '┷', '-'];
+const VERTICAL: &[char] = &[
+    '│', '┃', '║', '├', '┤', '┼', '╟', '╢', '╠', '╣', '┠', '┨', '┣', '┫', '|',
+];
+
+/// A closed rectangle; `row`/`col` is the top-left corner and the size includes the border.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub row: u16,
+    pub col: u16,
+    pub width: u16,
+    pub height: u16,
+    /// Text embedded in the top border, e.g. `Files` in `┌─ Files ──┐`.
+    pub title: Option<String>,
+}
+
+impl Frame {
+    pub fn bottom(&self) -> u16 {
+        self.row + self.height.saturating_sub(1)
+    }
+
+    pub fn right(&self) -> u16 {
+        self.col + self.width.saturating_sub(1)
+    }
+
+    /// True when `(row, col)` lies strictly inside the border.
+    pub fn interior_contains(&self, row: u16, col: u16) -> bool {
+        row > self.row && row < self.bottom() && col > self.col && col < self.right()
+    }
+}
+
+pub fn is_box_drawing(ch: char) -> bool {
+    ('\u{2500}'..='\u{257f}').contains(&ch)
+}
+
+/// Finds every closed frame on the grid, ordered by top-left corner. Both Unicode
+/// box-drawing and ASCII `+-|` borders are recognized.
+pub fn find_frames(grid: &impl ScreenGrid) -> Vec<Frame> {
+    let mut frames = Vec::new();
+    for row in 0..grid.rows() {
+        for col in 0..grid.cols() {
+            if TOP_LEFT.contains(&char_at(grid, row, col))
+                && let Some(frame) = trace_frame(grid, row, col)
+            {
+                frames.push(frame);
+            }
+        }
+    }
+    frames
+}
+
+fn trace_frame(grid: &impl ScreenGrid, top: usize, left: usize) -> Option<Frame> {
+    if !HORIZONTAL.contains(&char_at(grid, top, left + 1)) {
+        return None;
+    }
+    let (right, bottom) = (left + 2..grid.cols())
+        .filter(|&col| TOP_RIGHT.contains(&char_at(grid, top, col)))
+        .find_map(|right| trace_bottom(grid, top, left, right).map(|bottom| (right, bottom)))?;
+
+    let border: String = (left + 1..right)
+        .map(|col| char_at(grid, top, col))
+        .map(|ch| if is_box_drawing(ch) { ' ' } else { ch })
+        .collect();
+    let title = border
+        .split_whitespace()
+        .map(|word| word.trim_matches('-'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    Some(Frame {
+        row: to_u16(top),
+        col: to_u16(left),
+        width: to_u16(right - left + 1),
+        height: to_u16(bottom - top + 1),
+        title: (!title.is_empty()).then_some(title),
+    })
+}
+
+/// Follows both sides down from the top border to the first row closing the frame.
+fn trace_bottom(grid: &impl ScreenGrid, top: usize, left: usize, right: usize) -> Option<usize> {
+    for row in top + 1..grid.rows() {
+        let (ch, right_ch) = (char_at(grid, row, left), char_at(grid, row, right));
+        if BOTTOM_LEFT.contains(&ch) && BOTTOM_RIGHT.contains(&right_ch) {
+            return Some(row);
+        }
+        if !VERTICAL.contains(&ch) || !VERTICAL.contains(&right_ch) {
+            return None;
+        }
+    }
+    None
+}
+
+fn char_at(grid: &impl ScreenGrid, row: usize, col: usize) -> char {
+    grid.cell(row, col).map_or(' ', |(ch, _)| ch)
+}
+
+fn to_u16(value: usize) -> u16 {
+    u16::try_from(value).unwrap_or(u16::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::CursorPosition;
+    use crate::domain::core::ScreenSnapshot;
+
+    fn snapshot(text: &str) -> ScreenSnapshot {
+        let cols = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let rows = text.lines().count();
+        ScreenSnapshot::from_text(
+            text,
+            u16::try_from(cols).unwrap(),
+            u16::try_from(rows).unwrap(),
+            CursorPosition {
+                row: 0,
+                col: 0,
+                visible: false,
+            },
+        )
+    }
+
+    #[test]
+    fn test_find_frames_reads_titles_and_nesting() {
+        let screen = snapshot(
+            "┌─ Files ──────┐\n\
+             │ ╭─ [1] ─╮    │\n\
+             │ ╰───────╯    │\n\
+             └──────────────┘",
+        );
+
+        let frames = find_frames(&screen);
+        assert_eq!(frames.len(), 2);
+        assert_eq!(frames[0].title.as_deref(), Some("Files"));
+        assert_eq!((frames[0].width, frames[0].height), (16, 4));
+        assert_eq!((frames[1].row, frames[1].col), (1, 2));
+        assert_eq!(frames[1].title.as_deref(), Some("[1]"));
+        assert!(frames[0].interior_contains(1, 2));
+    }
+
+    #[test]
+    fn test_find_frames_splits_shared_borders() {
+        let screen = snapshot(
+            "┌──┬───┐\n\
+             │a │ b │\n\
+             ├──┤   │\n\
+             │c │   │\n\
+             └──┴───┘",
+        );
+
+        let frames: Vec<(u16, u16, u16, u16)> = find_frames(&screen)
+            .iter()
+            .map(|f| (f.row, f.col, f.width, f.height))
+            .collect();
+        assert_eq!(frames, vec![(0, 0, 4, 3), (0, 3, 5, 5), (2, 0, 4, 3)]);
+    }
+
+    #[test]
+    fn test_find_frames_reads_ascii_borders() {
+        let screen = snapshot("+-- Log --+\n|  ok     |\n+---------+");
+        let frames = find_frames(&screen);
+        assert_eq!(frames.len(), 1);
+        assert_eq!(frames[0].title.as_deref(), Some("Log"));
+    }
+
+    #[test]
+    fn test_find_frames_ignores_open_shapes() {
+        let screen = snapshot("┌────┐\n│    │\n│     \n└────┘");
+        assert!(find_frames(&screen).is_empty());
+    }
+}
@@ -0,0 +1,569 @@
+//! Golden screen files: a stored screen compared against the live one, with
+//! masks for areas that change between runs (clocks, PIDs, spinners).
+//!
+//! A golden file is the screen text, one row per line. It may start with a
+//! header of `%` directives closed by a `%%` line:
+//!
+//! ```text
+//! %mask regex \d\d:\d\d:\d\d
+//! %mask rect 0,60 20x1
+//! %style 2 0+12 bold fg=4
+//! %%
+//! <screen rows>
+//! ```
+//!
+//! `%mask rect ROW,COL WIDTHxHEIGHT` blanks a region of cells and
+//! `%mask regex PATTERN` collapses each match on a row to a single `*`; both
+//! sides are masked before comparing. `%style ROW RUNS` records the styled
+//! runs of a row (`COL+LEN attrs`, separated by `; `) and is only compared when
+//! styles are requested.
+
+use regex::Regex;
+use thiserror::Error;
+
+use super::CellStyle;
+use super::Color;
+use super::ScreenSnapshot;
+
+/// What masked text and cells are replaced with on both sides.
+pub const GOLDEN_MASK_FILL: char = '*';
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message}")]
+pub struct GoldenParseError {
+    pub message: String,
+}
+
+impl GoldenParseError {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum GoldenMask {
+    Regex(Regex),
+    Rect {
+        row: u16,
+        col: u16,
+        width: u16,
+        height: u16,
+    },
+}
+
+impl GoldenMask {
+    /// Parse `regex PATTERN` or `rect ROW,COL WIDTHxHEIGHT`.
+    pub fn parse(spec: &str) -> Result<Self, GoldenParseError> {
+        let invalid =
+            |reason: &str| GoldenParseError::new(format!("invalid mask '{spec}': {reason}"));
+        let (kind, rest) = spec.trim().split_once(' ').unwrap_or((spec.trim(), ""));
+        let rest = rest.trim();
+        match kind {
+            "regex" => {
+                if rest.is_empty() {
+                    return Err(invalid("missing pattern"));
+                }
+                Regex::new(rest)
+                    .map(Self::Regex)
+                    .map_err(|err| invalid(&err.to_string()))
+            }
+            "rect" => {
+                let expected = "expected 'rect ROW,COL WIDTHxHEIGHT'";
+                let (origin, size) = rest.split_once(' ').ok_or_else(|| invalid(expected))?;
+                let (row, col) = origin.split_once(',').ok_or_else(|| invalid(expected))?;
+                let (width, height) = size
+                    .trim()
+                    .split_once('x')
+                    .ok_or_else(|| invalid(expected))?;
+                let number =
+                    |value: &str| value.trim().parse::<u16>().map_err(|_| invalid(expected));
+                let (width, height) = (number(width)?, number(height)?);
+                if width == 0 || height == 0 {
+                    return Err(invalid("width and height must be at least 1"));
+                }
+                Ok(Self::Rect {
+                    row: number(row)?,
+                    col: number(col)?,
+                    width,
+                    height,
+                })
+            }
+            _ => Err(invalid(
+                "expected 'regex PATTERN' or 'rect ROW,COL WIDTHxHEIGHT'",
+            )),
+        }
+    }
+
+    pub fn spec(&self) -> String {
+        match self {
+            Self::Regex(regex) => format!("regex {}", regex.as_str()),
+            Self::Rect {
+                row,
+                col,
+                width,
+                height,
+            } => format!("rect {row},{col} {width}x{height}"),
+        }
+    }
+
+    fn covers(&self, row: usize, col: usize) -> bool {
+        match self {
+            Self::Regex(_) => false,
+            Self::Rect {
+                row: top,
+                col: left,
+                width,
+                height,
+            } => {
+                let (top, left) = (usize::from(*top), usize::from(*left));
+                (top..top + usize::from(*height)).contains(&row)
+                    && (left..left + usize::from(*width)).contains(&col)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoldenDiffKind {
+    Text,
+    Style,
+}
+
+impl GoldenDiffKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Text => "text",
+            Self::Style => "style",
+        }
+    }
+}
+
+/// A row that differs after masking; `expected` comes from the golden file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoldenDiff {
+    pub row: u16,
+    pub kind: GoldenDiffKind,
+    pub expected: String,
+    pub actual: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Golden {
+    pub masks: Vec<GoldenMask>,
+    /// Screen rows with trailing whitespace and trailing blank rows removed.
+    pub rows: Vec<String>,
+    /// Styled runs per row, for rows that have any.
+    pub styles: Vec<(u16, String)>,
+}
+
+impl Golden {
+    pub fn parse(text: &str) -> Result<Self, GoldenParseError> {
+        let mut golden = Self::default();
+        let mut lines = text.lines().enumerate().peekable();
+        if lines.peek().is_some_and(|(_, line)| line.starts_with('%')) {
+            loop {
+                let Some((index, line)) = lines.next() else {
+                    return Err(GoldenParseError::new(
+                        "header is not closed with a '%%' line",
+                    ));
+                };
+                let line_error = |message: String| {
+                    GoldenParseError::new(format!("line {}: {message}", index + 1))
+                };
+                if line == "%%" {
+                    break;
+                } else if let Some(spec) = line.strip_prefix("%mask ") {
+                    golden
+                        .masks
+                        .push(GoldenMask::parse(spec).map_err(|err| line_error(err.message))?);
+                } else if let Some(style) = line.strip_prefix("%style ") {
+                    let (row, runs) = style.split_once(' ').unwrap_or((style, ""));
+                    let row = row
+                        .parse::<u16>()
+                        .map_err(|_| line_error(format!("invalid style row '{row}'")))?;
+                    golden.styles.push((row, runs.trim().to_string()));
+                } else {
+                    return Err(line_error(format!(
+                        "unknown header directive '{line}' (expected %mask, %style or %%)"
+                    )));
+                }
+            }
+        }
+        golden.rows = trim_rows(lines.map(|(_, line)| line.to_string()).collect());
+        Ok(golden)
+    }
+
+    /// Capture `snapshot` as a golden; style runs are kept only when `styles` is set.
+    pub fn from_snapshot(snapshot: &ScreenSnapshot, masks: Vec<GoldenMask>, styles: bool) -> Self {
+        let rows = (0..snapshot.cells.len())
+            .map(|row| snapshot.row_text(row))
+            .collect();
+        let styles = if styles {
+            snapshot
+                .cells
+                .iter()
+                .enumerate()
+                .filter_map(|(row, cells)| {
+                    let runs = style_runs(cells.iter().map(|cell| cell.style));
+                    (!runs.is_empty()).then(|| (u16::try_from(row).unwrap_or(u16::MAX), runs))
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        Self {
+            masks,
+            rows: trim_rows(rows),
+            styles,
+        }
+    }
+
+    /// Add masks whose spec is not already present.
+    pub fn merge_masks(&mut self, masks: Vec<GoldenMask>) {
+        for mask in masks {
+            if !self
+                .masks
+                .iter()
+                .any(|existing| existing.spec() == mask.spec())
+            {
+                self.masks.push(mask);
+            }
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let needs_header = !self.masks.is_empty()
+            || !self.styles.is_empty()
+            || self.rows.first().is_some_and(|row| row.starts_with('%'));
+        if needs_header {
+            for mask in &self.masks {
+                out.push_str(&format!("%mask {}\n", mask.spec()));
+            }
+            for (row, runs) in &self.styles {
+                out.push_str(&format!("%style {row} {runs}\n"));
+            }
+            out.push_str("%%\n");
+        }
+        for row in &self.rows {
+            out.push_str(row);
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Rows where `actual` differs from this golden once this golden's masks
+    /// are applied to both. Style rows are compared only when `styles` is set.
+    pub fn compare(&self, actual: &Golden, styles: bool) -> Vec<GoldenDiff> {
+        let expected_rows = self.masked_rows(&self.rows);
+        let actual_rows = self.masked_rows(&actual.rows);
+        let mut diffs = Vec::new();
+        for row in 0..expected_rows.len().max(actual_rows.len()) {
+            let expected = expected_rows
+                .get(row)
+                .map(String::as_str)
+                .unwrap_or_default();
+            let actual = actual_rows.get(row).map(String::as_str).unwrap_or_default();
+            if expected != actual {
+                diffs.push(GoldenDiff {
+                    row: u16::try_from(row).unwrap_or(u16::MAX),
+                    kind: GoldenDiffKind::Text,
+                    expected: expected.to_string(),
+                    actual: actual.to_string(),
+                });
+            }
+        }
+        if styles {
+            let expected_styles = self.masked_styles(&self.styles);
+            let actual_styles = self.masked_styles(&actual.styles);
+            let mut style_rows: Vec<u16> = expected_styles
+                .iter()
+                .chain(&actual_styles)
+                .map(|(row, _)| *row)
+                .collect();
+            style_rows.sort_unstable();
+            style_rows.dedup();
+            let runs_at = |styles: &[(u16, String)], row: u16| {
+                styles
+                    .iter()
+                    .find(|(candidate, _)| *candidate == row)
+                    .map(|(_, runs)| runs.clone())
+                    .unwrap_or_default()
+            };
+            for row in style_rows {
+                let expected = runs_at(&expected_styles, row);
+                let actual = runs_at(&actual_styles, row);
+                if expected != actual {
+                    diffs.push(GoldenDiff {
+                        row,
+                        kind: GoldenDiffKind::Style,
+                        expected,
+                        actual,
+                    });
+                }
+            }
+            diffs.sort_by_key(|diff| (diff.row, diff.kind == GoldenDiffKind::Style));
+        }
+        diffs
+    }
+
+    fn masked_rows(&self, rows: &[String]) -> Vec<String> {
+        let rows = rows
+            .iter()
+            .enumerate()
+            .map(|(row, text)| {
+                let mut chars: Vec<char> = text.chars().collect();
+                for mask in &self.masks {
+                    let GoldenMask::Rect {
+                        row: top,
+                        col,
+                        width,
+                        height,
+                    } = mask
+                    else {
+                        continue;
+                    };
+                    if !(usize::from(*top)..usize::from(*top) + usize::from(*height)).contains(&row)
+                    {
+                        continue;
+                    }
+                    let end = usize::from(*col) + usize::from(*width);
+                    if chars.len() < end {
+                        chars.resize(end, ' ');
+                    }
+                    chars[usize::from(*col)..end].fill(GOLDEN_MASK_FILL);
+                }
+                let mut text: String = chars.into_iter().collect();
+                for mask in &self.masks {
+                    if let GoldenMask::Regex(regex) = mask {
+                        let fill = GOLDEN_MASK_FILL.to_string();
+                        text = regex.replace_all(&text, fill.as_str()).into_owned();
+                    }
+                }
+                text.trim_end().to_string()
+            })
+            .collect();
+        trim_rows(rows)
+    }
+
+    /// Drop the parts of style runs that fall inside rect masks.
+    fn masked_styles(&self, styles: &[(u16, String)]) -> Vec<(u16, String)> {
+        if !self
+            .masks
+            .iter()
+            .any(|mask| matches!(mask, GoldenMask::Rect { .. }))
+        {
+            return styles.to_vec();
+        }
+        styles
+            .iter()
+            .filter_map(|(row, runs)| {
+                let mut cells = expand_runs(runs);
+                for (col, cell) in cells.iter_mut().enumerate() {
+                    if self
+                        .masks
+                        .iter()
+                        .any(|mask| mask.covers(usize::from(*row), col))
+                    {
+                        *cell = None;
+                    }
+                }
+                let runs = style_runs_from_labels(&cells);
+                (!runs.is_empty()).then_some((*row, runs))
+            })
+            .collect()
+    }
+}
+
+fn trim_rows(mut rows: Vec<String>) -> Vec<String> {
+    for row in &mut rows {
+        row.truncate(row.trim_end().len());
+    }
+    while rows.last().is_some_and(String::is_empty) {
+        rows.pop();
+    }
+    rows
+}
+
+/// `COL+LEN attrs` runs of non-default style, separated by `; `.
+fn style_runs(styles: impl Iterator<Item = CellStyle>) -> String {
+    let labels: Vec<Option<String>> = styles.map(|style| style_label(&style)).collect();
+    style_runs_from_labels(&labels)
+}
+
+fn style_runs_from_labels(labels: &[Option<String>]) -> String {
+    let mut runs = Vec::new();
+    let mut col = 0;
+    while col < labels.len() {
+        let Some(label) = labels[col].as_ref() else {
+            col += 1;
+            continue;
+        };
+        let start = col;
+        while labels.get(col).and_then(Option::as_ref) == Some(label) {
+            col += 1;
+        }
+        runs.push(format!("{start}+{} {label}", col - start));
+    }
+    runs.join("; ")
+}
+
+/// Per-cell labels from a rendered run list; cells outside any run are `None`.
+fn expand_runs(runs: &str) -> Vec<Option<String>> {
+    let mut cells = Vec::new();
+    for run in runs.split("; ").filter(|run| !run.is_empty()) {
+        let (span, label) = run.split_once(' ').unwrap_or((run, ""));
+        let Some((start, len)) = span.split_once('+') else {
+            continue;
+        };
+        let (Ok(start), Ok(len)) = (start.parse::<usize>(), len.parse::<usize>()) else {
+            continue;
+        };
+        if cells.len() < start + len {
+            cells.resize(start + len, None);
+        }
+        cells[start..start + len].fill(Some(label.to_string()));
+    }
+    cells
+}
+
+fn style_label(style: &CellStyle) -> Option<String> {
+    let mut parts = Vec::new();
+    if style.bold {
+        parts.push("bold".to_string());
+    }
+    if style.underline {
+        parts.push("underline".to_string());
+    }
+    if style.inverse {
+        parts.push("inverse".to_string());
+    }
+    if let Some(color) = style.fg_color.and_then(color_label) {
+        parts.push(format!("fg={color}"));
+    }
+    if let Some(color) = style.bg_color.and_then(color_label) {
+        parts.push(format!("bg={color}"));
+    }
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+fn color_label(color: Color) -> Option<String> {
+    match color {
+        Color::Default => None,
+        Color::Indexed(index) => Some(index.to_string()),
+        Color::Rgb(r, g, b) => Some(format!("#{r:02x}{g:02x}{b:02x}")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::CursorPosition;
+
+    fn snapshot(text: &str) -> ScreenSnapshot {
+        let cursor = CursorPosition {
+            row: 0,
+            col: 0,
+            visible: true,
+        };
+        ScreenSnapshot::from_text(text, 20, 4, cursor)
+    }
+
+    #[test]
+    fn test_parse_reads_header_and_trims_rows() {
+        let golden = Golden::parse(
+            "%mask regex \\d+\n%mask rect 0,10 5x1\n%style 1 0+3 bold\n%%\nA  \nB\n\n",
+        )
+        .unwrap();
+
+        assert_eq!(golden.masks.len(), 2);
+        assert_eq!(golden.masks[1].spec(), "rect 0,10 5x1");
+        assert_eq!(golden.styles, vec![(1, "0+3 bold".to_string())]);
+        assert_eq!(golden.rows, vec!["A", "B"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_unclosed_header_and_bad_masks() {
+        assert!(Golden::parse("%mask regex x\nscreen").is_err());
+        let err = Golden::parse("%mask rect 1 2\n%%\n").unwrap_err();
+        assert!(err.message.starts_with("line 1: invalid mask"), "{err}");
+        assert!(GoldenMask::parse("regex (").is_err());
+        assert!(GoldenMask::parse("circle 1").is_err());
+    }
+
+    #[test]
+    fn test_compare_reports_changed_rows() {
+        let golden = Golden::parse("Title\nready\n").unwrap();
+        let actual = Golden::from_snapshot(&snapshot("Title\nbusy\nextra"), Vec::new(), false);
+
+        let diffs = golden.compare(&actual, false);
+
+        assert_eq!(
+            diffs,
+            vec![
+                GoldenDiff {
+                    row: 1,
+                    kind: GoldenDiffKind::Text,
+                    expected: "ready".to_string(),
+                    actual: "busy".to_string(),
+                },
+                GoldenDiff {
+                    row: 2,
+                    kind: GoldenDiffKind::Text,
+                    expected: String::new(),
+                    actual: "extra".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_masks_hide_volatile_text() {
+        let golden = Golden::parse(
+            "%mask regex \\d\\d:\\d\\d\n%mask rect 1,4 6x1\n%%\nat 09:15 ok\npid 123\n",
+        )
+        .unwrap();
+        let actual = Golden::from_snapshot(&snapshot("at 17:42 ok\npid 98765"), Vec::new(), false);
+
+        assert!(golden.compare(&actual, false).is_empty());
+    }
+
+    #[test]
+    fn test_styles_round_trip_and_compare() {
+        let mut screen = snapshot("> One\n  Two");
+        for cell in &mut screen.cells[0][0..5] {
+            cell.style.inverse = true;
+            cell.style.fg_color = Some(Color::Indexed(4));
+        }
+        let golden = Golden::from_snapshot(&screen, Vec::new(), true);
+        assert_eq!(golden.styles, vec![(0, "0+5 inverse fg=4".to_string())]);
+        let reparsed = Golden::parse(&golden.render()).unwrap();
+        assert!(reparsed.compare(&golden, true).is_empty());
+
+        let plain = Golden::from_snapshot(&snapshot("> One\n  Two"), Vec::new(), true);
+        let diffs = reparsed.compare(&plain, true);
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].kind, GoldenDiffKind::Style);
+        assert!(reparsed.compare(&plain, false).is_empty());
+    }
+
+    #[test]
+    fn test_rect_masks_hide_styles() {
+        let golden = Golden::parse("%mask rect 0,0 2x1\n%style 0 0+2 bold\n%%\nok\n").unwrap();
+        let actual = Golden::from_snapshot(&snapshot("ok"), Vec::new(), true);
+
+        assert!(golden.compare(&actual, true).is_empty());
+    }
+
+    #[test]
+    fn test_render_adds_header_only_when_needed() {
+        let golden = Golden::from_snapshot(&snapshot("plain"), Vec::new(), false);
+        assert_eq!(golden.render(), "plain\n");
+
+        let escaped = Golden::from_snapshot(&snapshot("%100"), Vec::new(), false);
+        assert_eq!(escaped.render(), "%%\n%100\n");
+        assert_eq!(Golden::parse(&escaped.render()).unwrap().rows, vec!["%100"]);
+    }
+}
@@ -0,0 +1,214 @@
+//! Logical (unwrapped) screen lines.
+
+/// One logical line assembled from physical rows joined by soft wraps.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalLine {
+    pub text: String,
+    pub start_row: u16,
+    pub rows: u16,
+}
+
+/// Physical start and end (inclusive) cell of a text occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextSpan {
+    pub row: u16,
+    pub col: u16,
+    pub end_row: u16,
+    pub end_col: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogicalScreen {
+    pub cols: u16,
+    /// Screen column of the first cell; non-zero when built from a region.
+    pub left: u16,
+    pub lines: Vec<LogicalLine>,
+}
+
+impl LogicalScreen {
+    /// Builds logical lines from full-width physical rows and their wrap flags.
+    /// A row flagged as wrapped continues on the next row.
+    pub fn from_rows<I>(cols: u16, rows: I) -> Self
+    where
+        I: IntoIterator<Item = (String, bool)>,
+    {
+        let mut lines = Vec::new();
+        let mut current: Option<LogicalLine> = None;
+
+        for (row_idx, (text, wrapped)) in rows.into_iter().enumerate() {
+            let row = u16::try_from(row_idx).unwrap_or(u16::MAX);
+            let line = current.get_or_insert_with(|| LogicalLine {
+                text: String::new(),
+                start_row: row,
+                rows: 0,
+            });
+            line.rows = line.rows.saturating_add(1);
+            if wrapped {
+                line.text.push_str(&text);
+            } else {
+                line.text.push_str(text.trim_end());
+                lines.extend(current.take());
+            }
+        }
+        if let Some(mut line) = current {
+            line.text.truncate(line.text.trim_end().len());
+            lines.push(line);
+        }
+
+        while lines.last().is_some_and(|line| line.text.is_empty()) {
+            lines.pop();
+        }
+
+        Self {
+            cols,
+            left: 0,
+            lines,
+        }
+    }
+
+    /// Moves the lines so positions are reported relative to the full screen,
+    /// for a screen built from a region whose first cell is at `(top, left)`.
+    pub fn offset(mut self, top: u16, left: u16) -> Self {
+        for line in &mut self.lines {
+            line.start_row = line.start_row.saturating_add(top);
+        }
+        self.left = left;
+        self
+    }
+
+    /// Treats each `\n`-separated line as exactly one physical row.
+    pub fn from_text(text: &str, cols: u16) -> Self {
+        Self::from_rows(cols, text.split('\n').map(|line| (line.to_string(), false)))
+    }
+
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.text.as_str())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Maps a char offset within a logical line to a physical `(row, col)`.
+    pub fn physical_position(&self, line: usize, offset: usize) -> Option<(u16, u16)> {
+        let line = self.lines.get(line)?;
+        let cols = usize::from(self.cols.max(1));
+        let row = usize::from(line.start_row) + offset / cols;
+        let col = usize::from(self.left) + offset % cols;
+        Some((
+            u16::try_from(row).unwrap_or(u16::MAX),
+            u16::try_from(col).unwrap_or(u16::MAX),
+        ))
+    }
+
+    pub fn find(&self, needle: &str) -> Vec<TextSpan> {
+        let needle_chars = needle.chars().count();
+        if needle_chars == 0 {
+            return Vec::new();
+        }
+
+        let mut spans = Vec::new();
+        for (line_idx, line) in self.lines.iter().enumerate() {
+            for (byte_idx, _) in line.text.match_indices(needle) {
+                let offset = line.text[..byte_idx].chars().count();
+                let start = self.physical_position(line_idx, offset);
+                let end = self.physical_position(line_idx, offset + needle_chars - 1);
+                if let (Some((row, col)), Some((end_row, end_col))) = (start, end) {
+                    spans.push(TextSpan {
+                        row,
+                        col,
+                        end_row,
+                        end_col,
+                    });
+                }
+            }
+        }
+        spans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(text: &str, cols: usize) -> String {
+        format!("{text:<cols$}")
+    }
+
+    #[test]
+    fn test_from_rows_joins_wrapped_rows() {
+        let screen = LogicalScreen::from_rows(
+            10,
+            vec![
+                (row("/usr/local", 10), true),
+                (row("/bin/tool", 10), false),
+                (row("next", 10), false),
+            ],
+        );
+
+        assert_eq!(screen.lines.len(), 2);
+        assert_eq!(screen.lines[0].text, "/usr/local/bin/tool");
+        assert_eq!((screen.lines[0].start_row, screen.lines[0].rows), (0, 2));
+        assert_eq!((screen.lines[1].start_row, screen.lines[1].rows), (2, 1));
+        assert_eq!(screen.text(), "/usr/local/bin/tool\nnext");
+    }
+
+    #[test]
+    fn test_from_rows_keeps_spaces_at_wrap_boundary() {
+        let screen =
+            LogicalScreen::from_rows(6, vec![(row("hello ", 6), true), (row("world", 6), false)]);
+        assert_eq!(screen.text(), "hello world");
+    }
+
+    #[test]
+    fn test_from_rows_drops_trailing_empty_lines() {
+        let screen = LogicalScreen::from_rows(
+            4,
+            vec![
+                (row("ok", 4), false),
+                (row("", 4), false),
+                (row("", 4), false),
+            ],
+        );
+        assert_eq!(screen.lines.len(), 1);
+    }
+
+    #[test]
+    fn test_find_maps_wrapped_match_to_physical_rows() {
+        let screen = LogicalScreen::from_rows(
+            10,
+            vec![
+                (row("$ ls", 10), false),
+                (row("/usr/local", 10), true),
+                (row("/bin/tool", 10), false),
+            ],
+        );
+
+        let spans = screen.find("local/bin");
+        assert_eq!(
+            spans,
+            vec![TextSpan {
+                row: 1,
+                col: 5,
+                end_row: 2,
+                end_col: 3,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_offset_maps_region_positions_to_screen() {
+        let screen = LogicalScreen::from_text("ok\nready", 10).offset(4, 20);
+        assert_eq!(screen.find("ready")[0].row, 5);
+        assert_eq!(screen.find("ready")[0].col, 20);
+        assert_eq!(screen.physical_position(0, 1), Some((4, 21)));
+    }
+
+    #[test]
+    fn test_from_text_is_identity_mapping() {
+        let screen = LogicalScreen::from_text("first\nsecond", 80);
+        assert_eq!(screen.physical_position(1, 3), Some((1, 3)));
+        assert_eq!(screen.text(), "first\nsecond");
+        assert_eq!(screen.find("cond")[0].row, 1);
+    }
+}
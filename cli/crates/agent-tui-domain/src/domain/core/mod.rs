@@ -2,14 +2,49 @@
 
 //! Core domain model for screen and terminal semantics.
 
+pub mod diff;
+pub mod elements;
+pub mod frames;
+pub mod golden;
+pub mod lines;
+pub mod panes;
 pub mod screen;
+pub mod selection;
 pub mod style;
+pub mod tables;
 
+pub use diff::RowChange;
+pub use diff::ScreenDiff;
+pub use diff::ScreenState;
+pub use elements::Element;
+pub use elements::ElementKind;
+pub use elements::detect_elements;
+pub use frames::Frame;
+pub use frames::find_frames;
+pub use golden::Golden;
+pub use golden::GoldenDiff;
+pub use golden::GoldenDiffKind;
+pub use golden::GoldenMask;
+pub use golden::GoldenParseError;
+pub use lines::LogicalLine;
+pub use lines::LogicalScreen;
+pub use lines::TextSpan;
+pub use panes::Pane;
+pub use panes::find_panes;
+pub use panes::region_names;
+pub use panes::resolve_region;
+pub use screen::CellSpan;
 pub use screen::ScreenCell;
 pub use screen::ScreenGrid;
 pub use screen::ScreenSnapshot;
+pub use selection::Highlight;
+pub use selection::find_highlight;
 pub use style::CellStyle;
 pub use style::Color;
+pub use tables::Table;
+pub use tables::TableColumn;
+pub use tables::TableRow;
+pub use tables::extract_table;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct CursorPosition {
@@ -0,0 +1,280 @@
+//! Pane segmentation: frames treated as named screen regions.
+
+use super::CellStyle;
+use super::Color;
+use super::LogicalScreen;
+use super::ScreenGrid;
+use super::frames::Frame;
+use super::frames::find_frames;
+use super::frames::is_box_drawing;
+
+/// Region names that select a pane by position or focus instead of by title.
+pub const REGION_KEYWORDS: &[&str] = &["focused", "left", "right", "top", "bottom"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pane {
+    /// Reading-order id (`p1`, `p2`, ...).
+    pub id: String,
+    pub title: Option<String>,
+    pub row: u16,
+    pub col: u16,
+    pub width: u16,
+    pub height: u16,
+    /// Id of the innermost pane enclosing this one.
+    pub parent: Option<String>,
+    pub focused: bool,
+}
+
+impl Pane {
+    fn bottom(&self) -> u16 {
+        self.row + self.height.saturating_sub(1)
+    }
+
+    fn right(&self) -> u16 {
+        self.col + self.width.saturating_sub(1)
+    }
+
+    /// Text inside the border as lines positioned on the full screen.
+    pub fn lines(&self, grid: &impl ScreenGrid) -> LogicalScreen {
+        let (top, left) = (usize::from(self.row) + 1, usize::from(self.col) + 1);
+        let (bottom, right) = (usize::from(self.bottom()), usize::from(self.right()));
+        let rows = (top..bottom).map(|row| {
+            let text: String = (left..right)
+                .map(|col| grid.cell(row, col).map_or(' ', |(ch, _)| ch))
+                .collect();
+            (text, false)
+        });
+        LogicalScreen::from_rows(self.width.saturating_sub(2), rows)
+            .offset(self.row + 1, self.col + 1)
+    }
+
+    fn encloses(&self, other: &Pane) -> bool {
+        self.id != other.id
+            && self.row <= other.row
+            && self.col <= other.col
+            && other.bottom() <= self.bottom()
+            && other.right() <= self.right()
+    }
+}
+
+/// Segments the grid into panes. A pane is focused when its border stands out from
+/// the rest: the only emphasized border (bold, colored, heavy/double, inverse
+/// title), or the only border whose glyph and style differ from the others.
+pub fn find_panes(grid: &impl ScreenGrid) -> Vec<Pane> {
+    let frames = find_frames(grid);
+    let mut panes: Vec<Pane> = frames
+        .iter()
+        .enumerate()
+        .map(|(idx, frame)| Pane {
+            id: format!("p{}", idx + 1),
+            title: frame.title.clone(),
+            row: frame.row,
+            col: frame.col,
+            width: frame.width,
+            height: frame.height,
+            parent: None,
+            focused: false,
+        })
+        .collect();
+
+    let parents: Vec<Option<String>> = panes
+        .iter()
+        .map(|pane| {
+            panes
+                .iter()
+                .filter(|other| other.encloses(pane))
+                .min_by_key(|other| u32::from(other.width) * u32::from(other.height))
+                .map(|other| other.id.clone())
+        })
+        .collect();
+    for (pane, parent) in panes.iter_mut().zip(parents) {
+        pane.parent = parent;
+    }
+
+    if let Some(idx) = focused_index(grid, &frames) {
+        panes[idx].focused = true;
+    }
+    panes
+}
+
+fn focused_index(grid: &impl ScreenGrid, frames: &[Frame]) -> Option<usize> {
+    if frames.len() < 2 {
+        return None;
+    }
+    let borders: Vec<(char, CellStyle)> = frames
+        .iter()
+        .map(|frame| {
+            grid.cell(usize::from(frame.row), usize::from(frame.col) + 1)
+                .unwrap_or((' ', CellStyle::default()))
+        })
+        .collect();
+
+    let emphasized: Vec<usize> = frames
+        .iter()
+        .zip(&borders)
+        .enumerate()
+        .filter(|(_, (frame, border))| is_emphasized(**border) || title_inverse(grid, frame))
+        .map(|(idx, _)| idx)
+        .collect();
+    if let [idx] = emphasized.as_slice() {
+        return Some(*idx);
+    }
+
+    let unique: Vec<usize> = (0..borders.len())
+        .filter(|&idx| borders.iter().filter(|b| **b == borders[idx]).count() == 1)
+        .collect();
+    match unique.as_slice() {
+        [idx] if borders.len() > 2 => Some(*idx),
+        _ => None,
+    }
+}
+
+fn is_emphasized((ch, style): (char, CellStyle)) -> bool {
+    let colored = |color: Option<Color>| color.is_some_and(|c| c != Color::Default);
+    style.bold
+        || style.inverse
+        || colored(style.fg_color)
+        || colored(style.bg_color)
+        || matches!(ch, '━' | '═')
+}
+
+fn title_inverse(grid: &impl ScreenGrid, frame: &Frame) -> bool {
+    let row = usize::from(frame.row);
+    (usize::from(frame.col) + 1..usize::from(frame.right())).any(|col| {
+        grid.cell(row, col)
+            .is_some_and(|(ch, style)| style.inverse && !is_box_drawing(ch) && ch != '-')
+    })
+}
+
+/// Resolves a region name: a pane id, a title (exact, then substring, ignoring
+/// case), or one of [`REGION_KEYWORDS`]. Positional keywords consider only
+/// top-level panes.
+pub fn resolve_region<'a>(panes: &'a [Pane], name: &str) -> Option<&'a Pane> {
+    let wanted = name.trim().to_lowercase();
+    let title = |pane: &Pane| pane.title.as_deref().unwrap_or_default().to_lowercase();
+
+    if let Some(pane) = panes
+        .iter()
+        .find(|pane| pane.id == wanted || title(pane) == wanted)
+    {
+        return Some(pane);
+    }
+
+    let top_level = || panes.iter().filter(|pane| pane.parent.is_none());
+    let by_keyword = match wanted.as_str() {
+        "focused" => panes.iter().find(|pane| pane.focused),
+        "left" => top_level().min_by_key(|pane| (pane.col, pane.row)),
+        "right" => top_level().max_by_key(|pane| (pane.right(), u16::MAX - pane.row)),
+        "top" => top_level().min_by_key(|pane| (pane.row, pane.col)),
+        "bottom" => top_level().max_by_key(|pane| (pane.bottom(), u16::MAX - pane.col)),
+        _ => None,
+    };
+    by_keyword.or_else(|| {
+        panes
+            .iter()
+            .find(|pane| !wanted.is_empty() && title(pane).contains(&wanted))
+    })
+}
+
+/// Names accepted by [`resolve_region`] for these panes, for error messages.
+pub fn region_names(panes: &[Pane]) -> Vec<String> {
+    let mut names: Vec<String> = panes
+        .iter()
+        .map(|pane| pane.title.clone().unwrap_or_else(|| pane.id.clone()))
+        .collect();
+    if !panes.is_empty() {
+        names.extend(REGION_KEYWORDS.iter().map(|keyword| keyword.to_string()));
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::CursorPosition;
+    use crate::domain::core::ScreenSnapshot;
+
+    const LAYOUT: &str = "\
+┌─ Files ──┐┌─ Log ─────┐
+│ a.txt    ││ started   │
+│ ┌─ Sub ┐ ││ ready     │
+│ │ x    │ ││           │
+│ └──────┘ ││           │
+└──────────┘└───────────┘";
+
+    fn snapshot(text: &str) -> ScreenSnapshot {
+        let cols = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        ScreenSnapshot::from_text(
+            text,
+            u16::try_from(cols).unwrap(),
+            u16::try_from(text.lines().count()).unwrap(),
+            CursorPosition {
+                row: 0,
+                col: 0,
+                visible: false,
+            },
+        )
+    }
+
+    fn style_border(snapshot: &mut ScreenSnapshot, row: usize, cols: std::ops::Range<usize>) {
+        for cell in &mut snapshot.cells[row][cols] {
+            cell.style.fg_color = Some(Color::Indexed(2));
+        }
+    }
+
+    #[test]
+    fn test_find_panes_reports_titles_and_nesting() {
+        let panes = find_panes(&snapshot(LAYOUT));
+        let summary: Vec<(&str, Option<&str>, Option<&str>)> = panes
+            .iter()
+            .map(|p| (p.id.as_str(), p.title.as_deref(), p.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("p1", Some("Files"), None),
+                ("p2", Some("Log"), None),
+                ("p3", Some("Sub"), Some("p1")),
+            ]
+        );
+        assert!(panes.iter().all(|pane| !pane.focused));
+    }
+
+    #[test]
+    fn test_find_panes_marks_colored_border_as_focused() {
+        let mut screen = snapshot(LAYOUT);
+        style_border(&mut screen, 0, 12..25);
+        let panes = find_panes(&screen);
+        assert!(panes[1].focused);
+        assert_eq!(
+            resolve_region(&panes, "focused").map(|p| p.id.as_str()),
+            Some("p2")
+        );
+    }
+
+    #[test]
+    fn test_resolve_region_by_title_id_and_position() {
+        let panes = find_panes(&snapshot(LAYOUT));
+        let id = |name: &str| resolve_region(&panes, name).map(|p| p.id.as_str());
+        assert_eq!(id("files"), Some("p1"));
+        assert_eq!(id("p3"), Some("p3"));
+        assert_eq!(id("right"), Some("p2"));
+        assert_eq!(id("left"), Some("p1"));
+        assert_eq!(id("Lo"), Some("p2"));
+        assert_eq!(id("missing"), None);
+    }
+
+    #[test]
+    fn test_pane_lines_are_positioned_on_screen() {
+        let screen = snapshot(LAYOUT);
+        let panes = find_panes(&screen);
+        let log = panes[1].lines(&screen);
+        assert_eq!(log.text(), " started\n ready");
+        let span = log.find("ready")[0];
+        assert_eq!((span.row, span.col), (2, 14));
+    }
+}
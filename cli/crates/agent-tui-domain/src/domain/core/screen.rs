@@ -7,6 +7,11 @@ pub trait ScreenGrid {
     fn rows(&self) -> usize;
     fn cols(&self) -> usize;
     fn cell(&self, row: usize, col: usize) -> Option<(char, CellStyle)>;
+    /// Whether the cell holds a double-width character; the next column is
+    /// then only padding.
+    fn is_wide(&self, _row: usize, _col: usize) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -22,3 +27,132 @@ pub struct ScreenSnapshot {
     pub cells: Vec<Vec<ScreenCell>>,
     pub cursor: CursorPosition,
 }
+
+/// A run of adjacent cells on one row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellSpan {
+    pub row: u16,
+    pub col: u16,
+    pub len: u16,
+}
+
+impl ScreenSnapshot {
+    /// Unstyled snapshot with each `\n`-separated line padded to `cols`.
+    pub fn from_text(text: &str, cols: u16, rows: u16, cursor: CursorPosition) -> Self {
+        let mut lines = text.split('\n');
+        let cells = (0..rows)
+            .map(|_| {
+                let mut chars = lines.next().unwrap_or_default().chars();
+                (0..cols)
+                    .map(|_| ScreenCell {
+                        ch: chars.next().unwrap_or(' '),
+                        style: CellStyle::default(),
+                    })
+                    .collect()
+            })
+            .collect();
+        Self {
+            cols,
+            rows,
+            cells,
+            cursor,
+        }
+    }
+
+    pub fn row_text(&self, row: usize) -> String {
+        self.cells
+            .get(row)
+            .map(|cells| cells.iter().map(|cell| cell.ch).collect::<String>())
+            .unwrap_or_default()
+    }
+
+    /// Runs of cells drawn in inverse video, the usual way TUIs mark a selection.
+    pub fn inverse_spans(&self) -> Vec<CellSpan> {
+        self.style_spans(|style| style.inverse)
+    }
+
+    pub fn style_spans(&self, mut predicate: impl FnMut(&CellStyle) -> bool) -> Vec<CellSpan> {
+        let mut spans = Vec::new();
+        for (row_idx, row) in self.cells.iter().enumerate() {
+            let mut start: Option<usize> = None;
+            for col in 0..=row.len() {
+                let matched = row.get(col).is_some_and(|cell| predicate(&cell.style));
+                match (matched, start) {
+                    (true, None) => start = Some(col),
+                    (false, Some(begin)) => {
+                        spans.push(CellSpan {
+                            row: u16::try_from(row_idx).unwrap_or(u16::MAX),
+                            col: u16::try_from(begin).unwrap_or(u16::MAX),
+                            len: u16::try_from(col - begin).unwrap_or(u16::MAX),
+                        });
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        spans
+    }
+}
+
+impl ScreenGrid for ScreenSnapshot {
+    fn rows(&self) -> usize {
+        self.cells.len()
+    }
+
+    fn cols(&self) -> usize {
+        usize::from(self.cols)
+    }
+
+    fn cell(&self, row: usize, col: usize) -> Option<(char, CellStyle)> {
+        let cell = self.cells.get(row)?.get(col)?;
+        Some((cell.ch, cell.style))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cursor() -> CursorPosition {
+        CursorPosition {
+            row: 0,
+            col: 0,
+            visible: true,
+        }
+    }
+
+    #[test]
+    fn test_from_text_pads_rows_and_cols() {
+        let snapshot = ScreenSnapshot::from_text("ab\nc", 4, 3, cursor());
+        assert_eq!(snapshot.cells.len(), 3);
+        assert_eq!(snapshot.row_text(0), "ab  ");
+        assert_eq!(snapshot.row_text(2), "    ");
+    }
+
+    #[test]
+    fn test_inverse_spans_groups_adjacent_cells() {
+        let mut snapshot = ScreenSnapshot::from_text("  One\n> Two", 8, 2, cursor());
+        for cell in &mut snapshot.cells[1][2..5] {
+            cell.style.inverse = true;
+        }
+        snapshot.cells[1][7].style.inverse = true;
+
+        let spans = snapshot.inverse_spans();
+        assert_eq!(
+            spans,
+            vec![
+                CellSpan {
+                    row: 1,
+                    col: 2,
+                    len: 3
+                },
+                CellSpan {
+                    row: 1,
+                    col: 7,
+                    len: 1
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! The highlighted line of a menu or list.
+
+use super::CellStyle;
+use super::Color;
+use super::Pane;
+use super::ScreenGrid;
+use super::elements::POINTERS;
+use super::frames::is_box_drawing;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    pub row: u16,
+    /// Visible text of the row without borders or a leading pointer.
+    pub text: String,
+}
+
+struct Row {
+    row: usize,
+    cells: Vec<(char, CellStyle)>,
+}
+
+impl Row {
+    fn visible(&self) -> impl Iterator<Item = &(char, CellStyle)> {
+        self.cells
+            .iter()
+            .filter(|(ch, _)| *ch != ' ' && !is_box_drawing(*ch))
+    }
+
+    /// Style key of the row when most of its text is drawn with `pick`.
+    fn styled<K>(&self, pick: impl Fn(&CellStyle) -> Option<K>) -> Option<K> {
+        let visible: Vec<&CellStyle> = self.visible().map(|(_, style)| style).collect();
+        let keys: Vec<K> = visible.iter().filter_map(|style| pick(style)).collect();
+        if visible.is_empty() || keys.len() * 2 <= visible.len() {
+            return None;
+        }
+        keys.into_iter().next()
+    }
+
+    fn styled_width(&self, pick: impl Fn(&CellStyle) -> bool) -> usize {
+        self.cells.iter().filter(|(_, style)| pick(style)).count()
+    }
+
+    fn pointer(&self) -> bool {
+        let mut chars = self
+            .cells
+            .iter()
+            .map(|(ch, _)| *ch)
+            .skip_while(|ch| *ch == ' ' || is_box_drawing(*ch));
+        chars.next().is_some_and(|ch| POINTERS.contains(&ch)) && chars.next() == Some(' ')
+    }
+
+    fn text(&self) -> String {
+        let text: String = self.cells.iter().map(|(ch, _)| *ch).collect();
+        let text = text.trim_matches(|ch: char| ch == ' ' || is_box_drawing(ch));
+        let text = match text.chars().next() {
+            Some(ch) if POINTERS.contains(&ch) && text[ch.len_utf8()..].starts_with(' ') => {
+                &text[ch.len_utf8()..]
+            }
+            _ => text,
+        };
+        text.trim().to_string()
+    }
+}
+
+/// Finds the row drawn as the current selection, inside `within` or on the whole
+/// grid: the row in inverse video, else the row with a background color, else the
+/// row marked with a pointer (`❯`, `>`, ...). When several rows share a style, the
+/// one whose color differs from the others wins. Full-width bars on the first or
+/// last screen row (title and status lines) are ignored.
+pub fn find_highlight(grid: &impl ScreenGrid, within: Option<&Pane>) -> Option<Highlight> {
+    let (top, left, bottom, right) = match within {
+        Some(pane) => (
+            usize::from(pane.row) + 1,
+            usize::from(pane.col) + 1,
+            usize::from(pane.row + pane.height.saturating_sub(1)),
+            usize::from(pane.col + pane.width.saturating_sub(1)),
+        ),
+        None => (0, 0, grid.rows(), grid.cols()),
+    };
+    let width = right.saturating_sub(left);
+    let rows: Vec<Row> = (top..bottom)
+        .map(|row| Row {
+            row,
+            cells: (left..right)
+                .map(|col| grid.cell(row, col).unwrap_or((' ', CellStyle::default())))
+                .collect(),
+        })
+        .collect();
+    let is_bar = |row: &Row, pick: &dyn Fn(&CellStyle) -> bool| {
+        (row.row == 0 || row.row + 1 == grid.rows()) && row.styled_width(pick) * 10 >= width * 9
+    };
+
+    let colored = |color: Option<Color>| color.filter(|c| *c != Color::Default);
+    let inverse: Vec<(&Row, Option<Color>)> = rows
+        .iter()
+        .filter(|row| !is_bar(row, &|style| style.inverse))
+        .filter_map(|row| {
+            row.styled(|style| style.inverse.then_some(style.fg_color))
+                .map(|key| (row, key))
+        })
+        .collect();
+    let background: Vec<(&Row, Color)> = rows
+        .iter()
+        .filter(|row| !is_bar(row, &|style| colored(style.bg_color).is_some()))
+        .filter_map(|row| {
+            row.styled(|style| colored(style.bg_color))
+                .map(|key| (row, key))
+        })
+        .collect();
+    let pointers: Vec<(&Row, ())> = rows
+        .iter()
+        .filter(|row| row.pointer())
+        .map(|row| (row, ()))
+        .collect();
+
+    let row = odd_one_out(&inverse)
+        .or_else(|| odd_one_out(&background))
+        .or_else(|| odd_one_out(&pointers))?;
+    Some(Highlight {
+        row: u16::try_from(row.row).unwrap_or(u16::MAX),
+        text: row.text(),
+    })
+}
+
+/// The only candidate, or the only one whose style key no other candidate shares.
+fn odd_one_out<'a, K: PartialEq>(candidates: &[(&'a Row, K)]) -> Option<&'a Row> {
+    if let [(row, _)] = candidates {
+        return Some(row);
+    }
+    let unique: Vec<&Row> = candidates
+        .iter()
+        .filter(|(_, key)| candidates.iter().filter(|(_, other)| other == key).count() == 1)
+        .map(|(row, _)| *row)
+        .collect();
+    match unique.as_slice() {
+        [row] => Some(row),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::CursorPosition;
+    use crate::domain::core::ScreenSnapshot;
+    use crate::domain::core::find_panes;
+
+    fn snapshot(text: &str) -> ScreenSnapshot {
+        let cols = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        ScreenSnapshot::from_text(
+            text,
+            u16::try_from(cols).unwrap(),
+            u16::try_from(text.lines().count()).unwrap(),
+            CursorPosition {
+                row: 0,
+                col: 0,
+                visible: false,
+            },
+        )
+    }
+
+    fn style_row(screen: &mut ScreenSnapshot, row: usize, apply: impl Fn(&mut CellStyle)) {
+        for cell in &mut screen.cells[row] {
+            apply(&mut cell.style);
+        }
+    }
+
+    #[test]
+    fn test_find_highlight_prefers_inverse_and_skips_status_bar() {
+        let mut screen =
+            snapshot(" Editor          \n  Open file\n  Save file\n  Quit\n F1 Help  F2 Menu");
+        style_row(&mut screen, 0, |style| style.inverse = true);
+        style_row(&mut screen, 2, |style| style.inverse = true);
+        style_row(&mut screen, 4, |style| style.inverse = true);
+
+        let highlight = find_highlight(&screen, None).unwrap();
+
+        assert_eq!(highlight.row, 2);
+        assert_eq!(highlight.text, "Save file");
+    }
+
+    #[test]
+    fn test_find_highlight_picks_row_with_distinct_background() {
+        let mut screen = snapshot("  PID  CMD\n  1    init\n  42   vim\n  77   htop");
+        style_row(&mut screen, 0, |style| {
+            style.bg_color = Some(Color::Indexed(2))
+        });
+        style_row(&mut screen, 2, |style| {
+            style.bg_color = Some(Color::Indexed(6))
+        });
+
+        assert_eq!(find_highlight(&screen, None).map(|h| h.row), Some(2));
+    }
+
+    #[test]
+    fn test_find_highlight_falls_back_to_pointer_inside_pane() {
+        let screen = snapshot(
+            "┌─ Pick ──────┐\n\
+             │   apple     │\n\
+             │ ❯ banana    │\n\
+             │   cherry    │\n\
+             └─────────────┘",
+        );
+        let panes = find_panes(&screen);
+
+        let highlight = find_highlight(&screen, panes.first()).unwrap();
+
+        assert_eq!(highlight.row, 2);
+        assert_eq!(highlight.text, "banana");
+    }
+
+    #[test]
+    fn test_find_highlight_none_without_selection() {
+        assert!(find_highlight(&snapshot("one\ntwo\nthree"), None).is_none());
+    }
+}
@@ -0,0 +1,354 @@
+//! Column inference for fixed-width tables (process lists, `docker ps`, file managers).
+
+use super::CellStyle;
+use super::Color;
+use super::Pane;
+use super::ScreenGrid;
+use super::frames::is_box_drawing;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableColumn {
+    /// Header text; repeated headers get a `_2`, `_3`, ... suffix.
+    pub name: String,
+    /// Screen column where the column's cells start.
+    pub col: u16,
+    /// Cells run up to the next column; the last column takes the rest of the row.
+    pub width: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableRow {
+    pub row: u16,
+    /// One trimmed cell per column.
+    pub cells: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub header_row: u16,
+    pub columns: Vec<TableColumn>,
+    pub rows: Vec<TableRow>,
+    /// Index into `rows` of the row drawn as the selection (inverse, else background color).
+    pub highlighted: Option<usize>,
+}
+
+struct Line {
+    row: u16,
+    cells: Vec<(char, CellStyle)>,
+}
+
+impl Line {
+    fn is_gap(&self, col: usize) -> bool {
+        self.cells.get(col).is_none_or(|&(ch, _)| is_separator(ch))
+    }
+
+    fn is_blank(&self) -> bool {
+        self.cells.iter().all(|&(ch, _)| ch == ' ')
+    }
+
+    /// A horizontal rule such as `───┼───` or `----+----`.
+    fn is_rule(&self) -> bool {
+        !self.is_blank()
+            && self
+                .cells
+                .iter()
+                .all(|&(ch, _)| is_separator(ch) || matches!(ch, '-' | '=' | '+' | ':'))
+    }
+
+    fn text(&self, start: usize, end: usize) -> String {
+        let end = end.min(self.cells.len());
+        let text: String = self
+            .cells
+            .get(start..end)
+            .unwrap_or_default()
+            .iter()
+            .map(|&(ch, _)| ch)
+            .collect();
+        text.trim_matches(is_separator).to_string()
+    }
+
+    /// Share of the row's visible cells drawn with `pick`.
+    fn mostly(&self, pick: impl Fn(&CellStyle) -> bool) -> bool {
+        let visible: Vec<&CellStyle> = self
+            .cells
+            .iter()
+            .filter(|(ch, _)| !is_separator(*ch))
+            .map(|(_, style)| style)
+            .collect();
+        !visible.is_empty()
+            && visible.iter().filter(|style| pick(style)).count() * 2 > visible.len()
+    }
+}
+
+fn is_separator(ch: char) -> bool {
+    ch == ' ' || ch == '|' || is_box_drawing(ch)
+}
+
+/// Infers a table from header text and whitespace alignment, inside `within` or
+/// on the whole grid. Column boundaries are columns blank in the header and in
+/// every row below it, up to the next blank line. When several blocks qualify,
+/// the one with the most rows wins.
+pub fn extract_table(grid: &impl ScreenGrid, within: Option<&Pane>) -> Option<Table> {
+    let (top, left, bottom, right) = match within {
+        Some(pane) => (
+            usize::from(pane.row) + 1,
+            usize::from(pane.col) + 1,
+            usize::from(pane.row + pane.height.saturating_sub(1)),
+            usize::from(pane.col + pane.width.saturating_sub(1)),
+        ),
+        None => (0, 0, grid.rows(), grid.cols()),
+    };
+    let lines: Vec<Line> = (top..bottom)
+        .map(|row| Line {
+            row: to_u16(row),
+            cells: (left..right)
+                .map(|col| grid.cell(row, col).unwrap_or((' ', CellStyle::default())))
+                .collect(),
+        })
+        .collect();
+
+    let mut best: Option<Table> = None;
+    for idx in 0..lines.len() {
+        if lines[idx].is_blank() || lines[idx].is_rule() {
+            continue;
+        }
+        let Some(table) = table_at(&lines, idx, left) else {
+            continue;
+        };
+        let better = best.as_ref().is_none_or(|best| {
+            (table.rows.len(), table.columns.len()) > (best.rows.len(), best.columns.len())
+        });
+        if better {
+            best = Some(table);
+        }
+    }
+    best
+}
+
+fn table_at(lines: &[Line], header_idx: usize, left: usize) -> Option<Table> {
+    let header = &lines[header_idx];
+    let body: Vec<&Line> = lines[header_idx + 1..]
+        .iter()
+        .skip_while(|line| line.is_rule())
+        .take_while(|line| !line.is_blank())
+        .filter(|line| !line.is_rule())
+        .collect();
+    if body.is_empty() {
+        return None;
+    }
+
+    let width = body
+        .iter()
+        .chain([&header])
+        .map(|line| line.cells.len())
+        .max()
+        .unwrap_or(0);
+    let is_gap = |col: usize| header.is_gap(col) && body.iter().all(|line| line.is_gap(col));
+
+    let mut starts: Vec<(usize, String)> = Vec::new();
+    let mut pending: Option<usize> = None;
+    let mut col = 0;
+    while col < width {
+        if is_gap(col) {
+            col += 1;
+            continue;
+        }
+        let start = col;
+        while col < width && !is_gap(col) {
+            col += 1;
+        }
+        let name = header.text(start, col);
+        if name.is_empty() {
+            // Cells without a header belong to the column on their left.
+            if starts.is_empty() {
+                pending.get_or_insert(start);
+            }
+            continue;
+        }
+        starts.push((pending.take().unwrap_or(start), name));
+    }
+    if starts.len() < 2 {
+        return None;
+    }
+
+    let ends: Vec<usize> = starts
+        .iter()
+        .skip(1)
+        .map(|(start, _)| *start)
+        .chain([width])
+        .collect();
+    let names = unique_names(starts.iter().map(|(_, name)| name.as_str()));
+    let columns = starts
+        .iter()
+        .zip(&ends)
+        .zip(names)
+        .map(|(((start, _), end), name)| TableColumn {
+            name,
+            col: to_u16(left + start),
+            width: to_u16(end - start),
+        })
+        .collect();
+    let rows: Vec<TableRow> = body
+        .iter()
+        .map(|line| TableRow {
+            row: line.row,
+            cells: starts
+                .iter()
+                .zip(&ends)
+                .map(|((start, _), end)| line.text(*start, *end))
+                .collect(),
+        })
+        .collect();
+    // Prose lines leave a ragged right edge that looks like a gap; a real table
+    // fills at least two columns in most rows.
+    let filled = (0..starts.len())
+        .filter(|&idx| {
+            rows.iter().filter(|row| !row.cells[idx].is_empty()).count() * 2 > rows.len()
+        })
+        .count();
+    if filled < 2 {
+        return None;
+    }
+
+    Some(Table {
+        header_row: header.row,
+        columns,
+        highlighted: highlighted_row(&body),
+        rows,
+    })
+}
+
+fn highlighted_row(body: &[&Line]) -> Option<usize> {
+    let colored = |color: Option<Color>| color.is_some_and(|c| c != Color::Default);
+    let only = |pick: &dyn Fn(&CellStyle) -> bool| {
+        let matching: Vec<usize> = (0..body.len())
+            .filter(|&idx| body[idx].mostly(pick))
+            .collect();
+        match matching.as_slice() {
+            [idx] => Some(*idx),
+            _ => None,
+        }
+    };
+    only(&|style| style.inverse).or_else(|| only(&|style| colored(style.bg_color)))
+}
+
+fn unique_names<'a>(names: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut seen: Vec<String> = Vec::new();
+    for name in names {
+        let mut unique = name.to_string();
+        let mut suffix = 2;
+        while seen.contains(&unique) {
+            unique = format!("{}_{}", name, suffix);
+            suffix += 1;
+        }
+        seen.push(unique);
+    }
+    seen
+}
+
+fn to_u16(value: usize) -> u16 {
+    u16::try_from(value).unwrap_or(u16::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::CursorPosition;
+    use crate::domain::core::ScreenSnapshot;
+    use crate::domain::core::find_panes;
+
+    fn snapshot(text: &str) -> ScreenSnapshot {
+        let cols = text
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        ScreenSnapshot::from_text(
+            text,
+            u16::try_from(cols).unwrap(),
+            u16::try_from(text.lines().count()).unwrap(),
+            CursorPosition {
+                row: 0,
+                col: 0,
+                visible: false,
+            },
+        )
+    }
+
+    fn names(table: &Table) -> Vec<&str> {
+        table.columns.iter().map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_extract_table_keys_cells_by_header() {
+        let screen = snapshot(
+            "Containers\n\
+             \n\
+             CONTAINER ID   IMAGE   STATUS\n\
+             3f1c2a9d0b11   nginx   Up 2 hours\n\
+             91ab77e0c4d2   redis   Exited (0)",
+        );
+
+        let table = extract_table(&screen, None).unwrap();
+
+        assert_eq!(table.header_row, 2);
+        assert_eq!(names(&table), vec!["CONTAINER ID", "IMAGE", "STATUS"]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].row, 3);
+        assert_eq!(
+            table.rows[0].cells,
+            vec!["3f1c2a9d0b11", "nginx", "Up 2 hours"]
+        );
+        assert_eq!(table.rows[1].cells[2], "Exited (0)");
+        assert_eq!(table.highlighted, None);
+    }
+
+    #[test]
+    fn test_extract_table_right_aligned_numbers_and_highlight() {
+        let mut screen = snapshot(
+            "  PID USER     CPU% Command\n\
+             \x20   1 root      0.0 /sbin/init\n\
+             12345 alice    12.5 vim notes.txt\n\
+             \x20 802 bob       3.1 htop",
+        );
+        for cell in &mut screen.cells[2] {
+            cell.style.inverse = true;
+        }
+
+        let table = extract_table(&screen, None).unwrap();
+
+        assert_eq!(names(&table), vec!["PID", "USER", "CPU%", "Command"]);
+        assert_eq!(
+            table.rows[1].cells,
+            vec!["12345", "alice", "12.5", "vim notes.txt"]
+        );
+        assert_eq!(table.columns[0].col, 0);
+        assert_eq!(table.highlighted, Some(1));
+    }
+
+    #[test]
+    fn test_extract_table_inside_pane_skips_rules() {
+        let screen = snapshot(
+            "┌─ Pods ──────────────┐\n\
+             │ NAME     READY      │\n\
+             │ ──────── ─────      │\n\
+             │ api-0    1/1        │\n\
+             │ web-1    0/1        │\n\
+             └─────────────────────┘",
+        );
+        let panes = find_panes(&screen);
+
+        let table = extract_table(&screen, panes.first()).unwrap();
+
+        assert_eq!(names(&table), vec!["NAME", "READY"]);
+        assert_eq!(table.columns[0].col, 2);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1].cells, vec!["web-1", "0/1"]);
+    }
+
+    #[test]
+    fn test_extract_table_needs_columns_and_rows() {
+        assert!(extract_table(&snapshot("Just some prose here\nand more prose"), None).is_none());
+        assert!(extract_table(&snapshot("NAME   SIZE"), None).is_none());
+    }
+}
@@ -146,6 +146,8 @@ pub struct SessionInfo {
     pub command: String,
     pub pid: u32,
     pub running: bool,
+    /// Exit code once the process has exited; `None` while running or unknown.
+    pub exit_code: Option<i32>,
     pub created_at: String,
     pub size: TerminalSize,
 }
@@ -172,6 +174,25 @@ impl SessionInfo {
     }
 }
 
+/// Line discipline flags read from the PTY's termios.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TtyState {
+    pub echo: bool,
+    pub icanon: bool,
+    pub isig: bool,
+}
+
+impl TtyState {
+    /// Canonical mode with echo disabled, as used by password prompts.
+    pub fn is_password_prompt(&self) -> bool {
+        self.icanon && !self.echo
+    }
+
+    pub fn input_mode(&self) -> &'static str {
+        if self.icanon { "canonical" } else { "raw" }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,6 +235,7 @@ mod tests {
             command: "bash".to_string(),
             pid: 1234,
             running: true,
+            exit_code: None,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             size: TerminalSize::default(),
         };
@@ -230,6 +252,7 @@ mod tests {
             command: "bash".to_string(),
             pid: 1234,
             running: true,
+            exit_code: None,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             size: TerminalSize::default(),
         };
@@ -240,6 +263,7 @@ mod tests {
             command: "bash".to_string(),
             pid: 1235,
             running: false,
+            exit_code: None,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             size: TerminalSize::default(),
         };
@@ -253,6 +277,7 @@ mod tests {
             command: "bash".to_string(),
             pid: 1234,
             running: true,
+            exit_code: None,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             size: TerminalSize::try_new(120, 40).unwrap(),
         };
@@ -268,12 +293,32 @@ mod tests {
             command: "bash".to_string(),
             pid: 1234,
             running: true,
+            exit_code: None,
             created_at: "2024-01-01T12:30:45Z".to_string(),
             size: TerminalSize::default(),
         };
         assert_eq!(info.created_at(), "2024-01-01T12:30:45Z");
     }
 
+    #[test]
+    fn test_tty_state_password_prompt() {
+        let password = TtyState {
+            echo: false,
+            icanon: true,
+            isig: true,
+        };
+        assert!(password.is_password_prompt());
+        assert_eq!(password.input_mode(), "canonical");
+
+        let raw = TtyState {
+            echo: false,
+            icanon: false,
+            isig: false,
+        };
+        assert!(!raw.is_password_prompt());
+        assert_eq!(raw.input_mode(), "raw");
+    }
+
     mod session_id_validation_tests {
         use super::*;
 
@@ -4,14 +4,28 @@ use std::collections::HashMap;
 use std::fmt;
 use std::str::FromStr;
 
+use super::core::CellSpan;
 use super::core::CursorPosition;
+use super::core::Element;
+use super::core::Golden;
+use super::core::GoldenDiff;
+use super::core::GoldenMask;
+use super::core::Highlight;
+use super::core::LogicalScreen;
+use super::core::Pane;
+use super::core::ScreenDiff;
+use super::core::Table;
+use super::core::TextSpan;
 use super::session_types::SessionId;
 use super::session_types::SessionInfo;
+use super::session_types::TtyState;
 
 use thiserror::Error;
 
 #[derive(Debug, Clone, PartialEq, Eq, Error)]
-#[error("Invalid wait condition type '{invalid_value}'. Must be one of: text, stable, text_gone")]
+#[error(
+    "Invalid wait condition type '{invalid_value}'. Must be one of: text, stable, text_gone, echo_off"
+)]
 pub struct WaitConditionTypeError {
     pub invalid_value: String,
 }
@@ -21,6 +35,7 @@ pub enum WaitConditionType {
     Text,
     Stable,
     TextGone,
+    EchoOff,
 }
 
 impl WaitConditionType {
@@ -29,6 +44,7 @@ impl WaitConditionType {
             "text" => Ok(Self::Text),
             "stable" => Ok(Self::Stable),
             "text_gone" => Ok(Self::TextGone),
+            "echo_off" => Ok(Self::EchoOff),
             _ => Err(WaitConditionTypeError {
                 invalid_value: s.to_string(),
             }),
@@ -40,6 +56,7 @@ impl WaitConditionType {
             Self::Text => "text",
             Self::Stable => "stable",
             Self::TextGone => "text_gone",
+            Self::EchoOff => "echo_off",
         }
     }
 
@@ -87,6 +104,41 @@ pub struct RestartOutput {
     pub pid: u32,
 }
 
+/// Encodings a screenshot can be rendered to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    /// Self-contained HTML fragment with inline styles.
+    Html,
+}
+
+impl ImageFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+            Self::Html => "html",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            "html" => Some(Self::Html),
+            _ => None,
+        }
+    }
+}
+
+/// The whole screen rendered with the session's palette, styles and cursor.
+#[derive(Debug, Clone)]
+pub struct ScreenImage {
+    pub format: ImageFormat,
+    pub data: Vec<u8>,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct SnapshotInput {
     pub session_id: Option<SessionId>,
@@ -94,6 +146,9 @@ pub struct SnapshotInput {
     pub strip_ansi: bool,
     pub include_cursor: bool,
     pub include_render: bool,
+    pub unwrap: bool,
+    pub include_highlights: bool,
+    pub image: Option<ImageFormat>,
 }
 
 #[derive(Debug, Clone)]
@@ -102,6 +157,87 @@ pub struct SnapshotOutput {
     pub screenshot: String,
     pub cursor: Option<CursorPosition>,
     pub rendered: Option<String>,
+    pub logical_screen: Option<LogicalScreen>,
+    /// Pane the screenshot was limited to.
+    pub region: Option<Pane>,
+    pub screen_version: u64,
+    pub highlights: Option<Vec<CellSpan>>,
+    pub image: Option<ScreenImage>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotDiffInput {
+    pub session_id: Option<SessionId>,
+    pub since: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SnapshotDiffOutput {
+    pub session_id: SessionId,
+    pub diff: ScreenDiff,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssertScreenInput {
+    pub session_id: Option<SessionId>,
+    pub golden: Golden,
+    /// Masks added on top of the golden file's header.
+    pub masks: Vec<GoldenMask>,
+    /// Compare cell styles too (always on when the golden records styles).
+    pub styles: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AssertScreenOutput {
+    pub session_id: SessionId,
+    pub matched: bool,
+    pub styles: bool,
+    pub diffs: Vec<GoldenDiff>,
+    /// The current screen as a golden file, with the merged masks.
+    pub golden: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElementsOutput {
+    pub session_id: SessionId,
+    pub screen_version: u64,
+    pub elements: Vec<Element>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PanesOutput {
+    pub session_id: SessionId,
+    pub screen_version: u64,
+    pub panes: Vec<Pane>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableInput {
+    pub session_id: Option<SessionId>,
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableOutput {
+    pub session_id: SessionId,
+    pub screen_version: u64,
+    /// Pane the search was limited to.
+    pub region: Option<Pane>,
+    pub table: Option<Table>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FindInput {
+    pub session_id: Option<SessionId>,
+    pub text: String,
+    pub unwrap: bool,
+    pub region: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FindOutput {
+    pub session_id: SessionId,
+    pub matches: Vec<TextSpan>,
 }
 
 #[derive(Debug, Clone)]
@@ -115,6 +251,88 @@ pub struct KeystrokeOutput {
     pub success: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct SelectInput {
+    pub session_id: Option<SessionId>,
+    /// Text the highlighted row must contain.
+    pub item: String,
+    pub up_key: String,
+    pub down_key: String,
+    /// Press Enter once the item is highlighted.
+    pub submit: bool,
+    pub region: Option<String>,
+    pub max_steps: u32,
+    /// How long to wait for the screen to react to each key.
+    pub settle_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct SelectOutput {
+    pub found: bool,
+    /// The highlighted row when the search stopped.
+    pub highlight: Option<Highlight>,
+    pub steps: u32,
+    pub submitted: bool,
+}
+
+/// What to do once a prompt shows up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FillAction {
+    /// Type the text and press Enter; empty text just accepts the default.
+    Answer(String),
+    /// Press the keys as given, with no implicit Enter.
+    Keys(Vec<String>),
+    /// Move the list highlight onto the option and press Enter.
+    Select(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct FillField {
+    /// Text the prompt line must contain, matched case-insensitively.
+    pub label: String,
+    pub action: FillAction,
+}
+
+#[derive(Debug, Clone)]
+pub struct FillInput {
+    pub session_id: Option<SessionId>,
+    pub fields: Vec<FillField>,
+    /// Budget for the whole form.
+    pub timeout_ms: u64,
+    /// How long a quiet screen may sit on a prompt no field matches before the
+    /// run stops and reports it as unexpected.
+    pub prompt_timeout_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct FillStep {
+    pub label: String,
+    /// The screen line the label matched.
+    pub prompt: String,
+    pub row: u16,
+    /// False when a `Select` option could not be highlighted.
+    pub answered: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct FillOutput {
+    pub steps: Vec<FillStep>,
+    /// Prompts the screen stopped on that no field matched.
+    pub unexpected: Vec<String>,
+    /// Labels that never appeared.
+    pub missing: Vec<String>,
+    pub elapsed_ms: u64,
+}
+
+impl FillOutput {
+    pub fn completed(&self) -> bool {
+        self.unexpected.is_empty()
+            && self.missing.is_empty()
+            && self.steps.iter().all(|step| step.answered)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct TypeInput {
     pub session_id: Option<SessionId>,
@@ -148,18 +366,41 @@ pub struct KeyupOutput {
     pub success: bool,
 }
 
+/// A fuzzy occurrence of expected text on screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextMatch {
+    pub text: String,
+    pub row: u16,
+    pub col: u16,
+    pub distance: usize,
+}
+
+/// Context returned when a wait times out or an assertion fails.
+#[derive(Debug, Clone)]
+pub struct MatchDiagnostics {
+    pub screen: String,
+    pub nearest_matches: Vec<TextMatch>,
+    /// For text that should be gone, where it is still on screen; `nearest_matches`
+    /// is then empty.
+    pub still_visible: Vec<TextMatch>,
+    pub screen_idle_ms: u64,
+}
+
 #[derive(Debug, Clone)]
 pub struct WaitInput {
     pub session_id: Option<SessionId>,
     pub text: Option<String>,
     pub timeout_ms: u64,
     pub condition: Option<WaitConditionType>,
+    pub unwrap: bool,
+    pub region: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct WaitOutput {
     pub found: bool,
     pub elapsed_ms: u64,
+    pub diagnostics: Option<MatchDiagnostics>,
 }
 
 #[derive(Debug, Clone)]
@@ -177,12 +418,186 @@ pub struct ResizeOutput {
     pub rows: u16,
 }
 
+/// Signals a session's process group can be sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessSignal {
+    Int,
+    Quit,
+    Tstp,
+    Term,
+    Hup,
+    Kill,
+}
+
+impl ProcessSignal {
+    pub const NAMES: [&'static str; 6] = ["INT", "QUIT", "TSTP", "TERM", "HUP", "KILL"];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Int => "INT",
+            Self::Quit => "QUIT",
+            Self::Tstp => "TSTP",
+            Self::Term => "TERM",
+            Self::Hup => "HUP",
+            Self::Kill => "KILL",
+        }
+    }
+
+    /// Accepts `INT` or `SIGINT`, in any case.
+    pub fn parse(name: &str) -> Option<Self> {
+        let upper = name.to_ascii_uppercase();
+        match upper.strip_prefix("SIG").unwrap_or(&upper) {
+            "INT" => Some(Self::Int),
+            "QUIT" => Some(Self::Quit),
+            "TSTP" => Some(Self::Tstp),
+            "TERM" => Some(Self::Term),
+            "HUP" => Some(Self::Hup),
+            "KILL" => Some(Self::Kill),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SignalInput {
+    pub session_id: Option<SessionId>,
+    pub signal: ProcessSignal,
+}
+
+#[derive(Debug, Clone)]
+pub struct SignalOutput {
+    pub session_id: SessionId,
+    pub success: bool,
+    pub signal: ProcessSignal,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordStartInput {
+    pub session_id: Option<SessionId>,
+    /// Absolute path of the `.cast` file to create.
+    pub path: String,
+    /// Also record what was typed or pressed as `i` events.
+    pub include_input: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordStartOutput {
+    pub session_id: SessionId,
+    pub path: String,
+}
+
+/// A finished asciinema recording.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recording {
+    pub path: String,
+    pub events: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordStopOutput {
+    pub session_id: SessionId,
+    /// `None` when the session was not being recorded.
+    pub recording: Option<Recording>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceStartInput {
+    pub session_id: Option<SessionId>,
+    /// Absolute path of the `.zip` bundle written by `trace stop`.
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceStartOutput {
+    pub session_id: SessionId,
+    pub path: String,
+}
+
+/// A trace bundle written to disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceSummary {
+    pub path: String,
+    pub steps: u64,
+    pub output_bytes: u64,
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct TraceStopOutput {
+    pub session_id: SessionId,
+    /// `None` when the session was not being traced.
+    pub trace: Option<TraceSummary>,
+}
+
+/// How a replayed recording advances.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ReplayPacing {
+    /// Follow the recorded timeline, scaled by the factor (2.0 plays twice as fast).
+    Speed(f64),
+    /// Advance only when input is sent to the session.
+    Step,
+}
+
+/// Source format of a replayed recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayFormat {
+    /// Asciinema v2 `.cast` file.
+    Cast,
+    /// Raw terminal output with no timing.
+    Raw,
+}
+
+impl ReplayFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReplayFormat::Cast => "cast",
+            ReplayFormat::Raw => "raw",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayInput {
+    /// Absolute path of the `.cast` file or raw byte log.
+    pub path: String,
+    pub pacing: ReplayPacing,
+    pub session_id: Option<SessionId>,
+    /// Terminal size for raw logs; casts use the size in their header.
+    pub cols: u16,
+    pub rows: u16,
+}
+
+/// What a replay session is going to play.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplaySummary {
+    pub format: ReplayFormat,
+    pub cols: u16,
+    pub rows: u16,
+    /// Output events (one per line for raw logs).
+    pub events: u64,
+    /// Recorded duration before any speed factor.
+    pub duration_ms: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReplayOutput {
+    pub session_id: SessionId,
+    pub replay: ReplaySummary,
+}
+
 #[derive(Debug, Clone)]
 pub struct SessionsOutput {
     pub sessions: Vec<SessionInfo>,
     pub active_session: Option<SessionId>,
 }
 
+#[derive(Debug, Clone)]
+pub struct TtyStateOutput {
+    pub session_id: SessionId,
+    pub tty_state: Option<TtyState>,
+}
+
 #[derive(Debug, Clone)]
 pub struct KillOutput {
     pub session_id: SessionId,
@@ -271,12 +686,14 @@ pub struct AssertInput {
     pub session_id: Option<SessionId>,
     pub condition_type: AssertConditionType,
     pub value: String,
+    pub unwrap: bool,
 }
 
 #[derive(Debug, Clone)]
 pub struct AssertOutput {
     pub passed: bool,
     pub condition: String,
+    pub diagnostics: Option<MatchDiagnostics>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -291,6 +708,20 @@ pub struct ShutdownOutput {
 mod tests {
     use super::*;
 
+    #[test]
+    fn process_signal_parses_short_and_sig_prefixed_names() {
+        assert_eq!(ProcessSignal::parse("INT"), Some(ProcessSignal::Int));
+        assert_eq!(ProcessSignal::parse("sigterm"), Some(ProcessSignal::Term));
+        assert_eq!(ProcessSignal::parse("Hup"), Some(ProcessSignal::Hup));
+        assert_eq!(ProcessSignal::parse("USR1"), None);
+        for name in ProcessSignal::NAMES {
+            assert_eq!(
+                ProcessSignal::parse(name).map(ProcessSignal::as_str),
+                Some(name)
+            );
+        }
+    }
+
     mod wait_condition_type_tests {
         use super::*;
 
@@ -312,6 +743,13 @@ mod tests {
             assert_eq!(cond, WaitConditionType::TextGone);
         }
 
+        #[test]
+        fn test_wait_condition_type_from_str_echo_off() {
+            let cond = WaitConditionType::parse("echo_off").expect("Should parse 'echo_off'");
+            assert_eq!(cond, WaitConditionType::EchoOff);
+            assert!(!cond.requires_text());
+        }
+
         #[test]
         fn test_wait_condition_type_from_str_invalid() {
             let result = WaitConditionType::parse("invalid");
@@ -341,6 +779,7 @@ mod tests {
             assert_eq!(WaitConditionType::Text.as_str(), "text");
             assert_eq!(WaitConditionType::Stable.as_str(), "stable");
             assert_eq!(WaitConditionType::TextGone.as_str(), "text_gone");
+            assert_eq!(WaitConditionType::EchoOff.as_str(), "echo_off");
         }
 
         #[test]
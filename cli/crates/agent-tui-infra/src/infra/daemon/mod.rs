@@ -5,11 +5,13 @@ mod config;
 mod file_lock;
 mod lock_helpers;
 mod pty_session;
+mod recording;
 mod repository;
 mod session;
 mod signal_handler;
 mod system_clock;
 mod terminal_state;
+mod trace;
 
 pub use crate::usecases::ports::SessionError;
 pub use config::DaemonConfig;
@@ -19,3 +21,8 @@ pub use session::SessionManager;
 pub use signal_handler::SignalHandler;
 pub use system_clock::SystemClock;
 pub use terminal_state::TerminalState;
+pub use trace::TraceBundle;
+pub use trace::TraceManifest;
+pub use trace::TraceStep;
+pub use trace::TraceWait;
+pub use trace::read_trace_bundle;
@@ -1,59 +1,148 @@
 //! PTY session management.
 
+use crate::domain::ProcessSignal;
+use crate::domain::session_types::TtyState;
 use crate::infra::terminal::PtyHandle;
 use crate::infra::terminal::ReadEvent;
+use crate::infra::terminal::ReplayHandle;
 use crossbeam_channel::Receiver;
 
 use crate::infra::daemon::SessionError;
+use crate::usecases::ports::TerminalError as PortTerminalError;
+
+/// Where a session's output comes from: a child process on a PTY, or a
+/// recording played back with no process behind it.
+enum Backend {
+    Pty(PtyHandle),
+    Replay(ReplayHandle),
+}
 
 pub struct PtySession {
-    handle: PtyHandle,
+    backend: Backend,
 }
 
 impl PtySession {
     pub fn new(handle: PtyHandle) -> Self {
-        Self { handle }
+        Self {
+            backend: Backend::Pty(handle),
+        }
+    }
+
+    pub(crate) fn replay(handle: ReplayHandle) -> Self {
+        Self {
+            backend: Backend::Replay(handle),
+        }
     }
 
     pub fn pid(&self) -> Option<u32> {
-        self.handle.pid()
+        match &self.backend {
+            Backend::Pty(handle) => handle.pid(),
+            Backend::Replay(_) => None,
+        }
     }
 
     pub fn is_running(&mut self) -> bool {
-        self.handle.is_running()
+        match &mut self.backend {
+            Backend::Pty(handle) => handle.is_running(),
+            Backend::Replay(handle) => handle.is_running(),
+        }
+    }
+
+    pub fn exit_code(&mut self) -> Option<i32> {
+        match &mut self.backend {
+            Backend::Pty(handle) => handle.exit_code(),
+            Backend::Replay(_) => None,
+        }
     }
 
     pub fn write(&self, data: &[u8]) -> Result<(), SessionError> {
-        self.handle
-            .write(data)
-            .map_err(|err| SessionError::Terminal(err.into_port_error()))
+        match &self.backend {
+            Backend::Pty(handle) => handle
+                .write(data)
+                .map_err(|err| SessionError::Terminal(err.into_port_error())),
+            Backend::Replay(handle) => {
+                handle.input(data);
+                Ok(())
+            }
+        }
     }
 
     pub fn write_str(&self, s: &str) -> Result<(), SessionError> {
-        self.handle
-            .write_str(s)
-            .map_err(|err| SessionError::Terminal(err.into_port_error()))
+        match &self.backend {
+            Backend::Pty(handle) => handle
+                .write_str(s)
+                .map_err(|err| SessionError::Terminal(err.into_port_error())),
+            Backend::Replay(handle) => {
+                handle.input(s.as_bytes());
+                Ok(())
+            }
+        }
+    }
+
+    pub fn tty_state(&self) -> Option<TtyState> {
+        match &self.backend {
+            Backend::Pty(handle) => handle.tty_state(),
+            Backend::Replay(_) => None,
+        }
     }
 
     pub fn try_read(&mut self, buf: &mut [u8], timeout_ms: i32) -> Result<usize, SessionError> {
-        self.handle
-            .try_read(buf, timeout_ms)
-            .map_err(|err| SessionError::Terminal(err.into_port_error()))
+        match &mut self.backend {
+            Backend::Pty(handle) => handle
+                .try_read(buf, timeout_ms)
+                .map_err(|err| SessionError::Terminal(err.into_port_error())),
+            Backend::Replay(_) => Ok(0),
+        }
     }
 
     pub(crate) fn take_read_rx(&mut self) -> Option<Receiver<ReadEvent>> {
-        self.handle.take_read_rx()
+        match &mut self.backend {
+            Backend::Pty(handle) => handle.take_read_rx(),
+            Backend::Replay(handle) => handle.take_read_rx(),
+        }
     }
 
     pub fn resize(&mut self, cols: u16, rows: u16) -> Result<(), SessionError> {
-        self.handle
-            .resize(cols, rows)
-            .map_err(|err| SessionError::Terminal(err.into_port_error()))
+        match &mut self.backend {
+            Backend::Pty(handle) => handle
+                .resize(cols, rows)
+                .map_err(|err| SessionError::Terminal(err.into_port_error())),
+            Backend::Replay(_) => Ok(()),
+        }
+    }
+
+    pub fn signal(&mut self, signal: ProcessSignal) -> Result<(), SessionError> {
+        match &mut self.backend {
+            Backend::Pty(handle) => handle
+                .signal(signal_number(signal))
+                .map_err(|err| SessionError::Terminal(err.into_port_error())),
+            Backend::Replay(_) => Err(SessionError::Terminal(PortTerminalError::Signal {
+                reason: "replay sessions have no process to signal".to_string(),
+                source: None,
+            })),
+        }
     }
 
     pub fn kill(&mut self) -> Result<(), SessionError> {
-        self.handle
-            .kill()
-            .map_err(|err| SessionError::Terminal(err.into_port_error()))
+        match &mut self.backend {
+            Backend::Pty(handle) => handle
+                .kill()
+                .map_err(|err| SessionError::Terminal(err.into_port_error())),
+            Backend::Replay(handle) => {
+                handle.stop();
+                Ok(())
+            }
+        }
+    }
+}
+
+fn signal_number(signal: ProcessSignal) -> libc::c_int {
+    match signal {
+        ProcessSignal::Int => libc::SIGINT,
+        ProcessSignal::Quit => libc::SIGQUIT,
+        ProcessSignal::Tstp => libc::SIGTSTP,
+        ProcessSignal::Term => libc::SIGTERM,
+        ProcessSignal::Hup => libc::SIGHUP,
+        ProcessSignal::Kill => libc::SIGKILL,
     }
 }
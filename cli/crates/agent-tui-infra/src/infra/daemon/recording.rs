@@ -0,0 +1,261 @@
+//! Asciinema v2 recordings of session output.
+//!
+//! A `.cast` file is a JSON header line followed by one `[time, code, data]`
+//! line per event: `o` for output, `i` for input and `r` for resizes. Lines are
+//! flushed as they are written so a recording survives a daemon crash.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::LineWriter;
+use std::io::Write;
+use std::time::Instant;
+
+use chrono::Utc;
+use serde_json::json;
+use tracing::warn;
+
+use crate::domain::Recording;
+use crate::infra::terminal::PTY_TERM;
+
+/// `TERM` and `SHELL` as a session's process sees them, for the `.cast` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CastEnv {
+    pub(crate) term: String,
+    pub(crate) shell: Option<String>,
+}
+
+impl CastEnv {
+    /// The PTY's `TERM`, and `SHELL` from the spawn `env` merged over the
+    /// environment the process inherits from the daemon.
+    pub(crate) fn for_spawn(env: Option<&HashMap<String, String>>) -> Self {
+        let shell = env
+            .and_then(|env| env.get("SHELL").cloned())
+            .or_else(|| std::env::var("SHELL").ok());
+        Self {
+            term: PTY_TERM.to_string(),
+            shell,
+        }
+    }
+}
+
+impl Default for CastEnv {
+    /// A session without a process of its own, such as a replay.
+    fn default() -> Self {
+        Self {
+            term: PTY_TERM.to_string(),
+            shell: None,
+        }
+    }
+}
+
+pub(crate) struct CastRecorder {
+    path: String,
+    writer: Option<LineWriter<File>>,
+    started: Instant,
+    include_input: bool,
+    events: u64,
+    /// Trailing bytes of an output chunk that ended inside a UTF-8 sequence.
+    pending: Vec<u8>,
+}
+
+impl CastRecorder {
+    /// Create `path` and write the header plus `initial` (the current screen)
+    /// as the first output event.
+    pub(crate) fn start(
+        path: &str,
+        cols: u16,
+        rows: u16,
+        command: &str,
+        env: &CastEnv,
+        include_input: bool,
+        initial: &str,
+    ) -> std::io::Result<Self> {
+        let mut writer = LineWriter::new(File::create(path)?);
+        let mut header_env = serde_json::Map::new();
+        header_env.insert("TERM".to_string(), json!(env.term));
+        if let Some(shell) = &env.shell {
+            header_env.insert("SHELL".to_string(), json!(shell));
+        }
+        let header = json!({
+            "version": 2,
+            "width": cols,
+            "height": rows,
+            "timestamp": Utc::now().timestamp(),
+            "command": command,
+            "env": header_env
+        });
+        writeln!(writer, "{header}")?;
+        let mut recorder = Self {
+            path: path.to_string(),
+            writer: Some(writer),
+            started: Instant::now(),
+            include_input,
+            events: 0,
+            pending: Vec::new(),
+        };
+        recorder.event("o", initial);
+        Ok(recorder)
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub(crate) fn output(&mut self, data: &[u8]) {
+        self.pending.extend_from_slice(data);
+        let complete = match std::str::from_utf8(&self.pending) {
+            Ok(_) => self.pending.len(),
+            Err(err) if err.error_len().is_none() => err.valid_up_to(),
+            Err(_) => self.pending.len(),
+        };
+        let rest = self.pending.split_off(complete);
+        let text = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending = rest;
+        self.event("o", &text);
+    }
+
+    pub(crate) fn input(&mut self, data: &[u8]) {
+        if self.include_input {
+            self.event("i", &String::from_utf8_lossy(data));
+        }
+    }
+
+    pub(crate) fn resize(&mut self, cols: u16, rows: u16) {
+        self.event("r", &format!("{cols}x{rows}"));
+    }
+
+    pub(crate) fn finish(mut self) -> Recording {
+        if !self.pending.is_empty() {
+            let text = String::from_utf8_lossy(&std::mem::take(&mut self.pending)).into_owned();
+            self.event("o", &text);
+        }
+        if let Some(mut writer) = self.writer.take()
+            && let Err(err) = writer.flush()
+        {
+            warn!(path = %self.path, error = %err, "Failed to flush recording");
+        }
+        Recording {
+            path: self.path,
+            events: self.events,
+            duration_ms: u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX),
+        }
+    }
+
+    fn event(&mut self, code: &str, data: &str) {
+        if data.is_empty() {
+            return;
+        }
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+        let time = self.started.elapsed().as_micros() as f64 / 1_000_000.0;
+        if let Err(err) = writeln!(writer, "{}", json!([time, code, data])) {
+            warn!(path = %self.path, error = %err, "Failed to write recording; stopping it");
+            self.writer = None;
+            return;
+        }
+        self.events += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(path: &std::path::Path) -> Vec<serde_json::Value> {
+        std::fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_cast_has_header_and_ordered_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.cast");
+        let env = CastEnv {
+            term: PTY_TERM.to_string(),
+            shell: Some("/usr/bin/fish".to_string()),
+        };
+        let mut recorder =
+            CastRecorder::start(path.to_str().unwrap(), 80, 24, "bash", &env, true, "$ ").unwrap();
+
+        recorder.output("caf\u{e9}".as_bytes().split_last().unwrap().1);
+        recorder.output(&[0xA9, b'\n']);
+        recorder.input(b"ls\r");
+        recorder.resize(100, 30);
+        let recording = recorder.finish();
+
+        let lines = lines(&path);
+        assert_eq!(lines[0]["version"], 2);
+        assert_eq!(
+            (lines[0]["width"].as_u64(), lines[0]["height"].as_u64()),
+            (Some(80), Some(24))
+        );
+        assert_eq!(lines[0]["command"], "bash");
+        assert_eq!(
+            lines[0]["env"],
+            json!({ "TERM": "xterm-256color", "SHELL": "/usr/bin/fish" })
+        );
+        let events: Vec<(&str, &str)> = lines[1..]
+            .iter()
+            .map(|event| (event[1].as_str().unwrap(), event[2].as_str().unwrap()))
+            .collect();
+        assert_eq!(
+            events,
+            vec![
+                ("o", "$ "),
+                ("o", "caf"),
+                ("o", "\u{e9}\n"),
+                ("i", "ls\r"),
+                ("r", "100x30")
+            ]
+        );
+        assert!(
+            lines[1..]
+                .windows(2)
+                .all(|w| w[0][0].as_f64() <= w[1][0].as_f64())
+        );
+        assert_eq!(recording.events, 5);
+    }
+
+    #[test]
+    fn test_input_events_are_opt_in() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.cast");
+        let mut recorder = CastRecorder::start(
+            path.to_str().unwrap(),
+            80,
+            24,
+            "bash",
+            &CastEnv::default(),
+            false,
+            "",
+        )
+        .unwrap();
+
+        recorder.input(b"secret\r");
+        let recording = recorder.finish();
+
+        assert_eq!(recording.events, 0);
+        assert_eq!(lines(&path).len(), 1);
+    }
+
+    #[test]
+    fn test_cast_env_prefers_spawn_env_shell() {
+        let env = HashMap::from([
+            ("SHELL".to_string(), "/bin/zsh".to_string()),
+            ("TERM".to_string(), "dumb".to_string()),
+        ]);
+        // The PTY always sets its own TERM, so a spawn override does not apply.
+        assert_eq!(
+            CastEnv::for_spawn(Some(&env)),
+            CastEnv {
+                term: "xterm-256color".to_string(),
+                shell: Some("/bin/zsh".to_string()),
+            }
+        );
+        assert_eq!(CastEnv::for_spawn(None).shell, std::env::var("SHELL").ok());
+    }
+}
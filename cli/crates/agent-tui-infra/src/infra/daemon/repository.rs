@@ -5,7 +5,17 @@ use std::sync::Arc;
 use std::sync::Mutex;
 
 use crate::common::mutex_lock_or_recover;
+use crate::domain::ImageFormat;
+use crate::domain::ProcessSignal;
+use crate::domain::Recording;
+use crate::domain::ReplayPacing;
+use crate::domain::ReplaySummary;
+use crate::domain::TraceSummary;
 use crate::domain::core::CursorPosition;
+use crate::domain::core::LogicalScreen;
+use crate::domain::core::ScreenSnapshot;
+use crate::domain::core::ScreenState;
+use crate::domain::session_types::TtyState;
 use crate::usecases::ports::LivePreviewSnapshot;
 use crate::usecases::ports::SessionError;
 use crate::usecases::ports::SessionHandle;
@@ -62,11 +72,26 @@ impl SessionOps for SessionHandleImpl {
         session_guard.screen_text()
     }
 
+    fn logical_screen(&self) -> LogicalScreen {
+        let session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.logical_screen()
+    }
+
     fn screen_render(&self) -> String {
         let session_guard = mutex_lock_or_recover(&self.inner);
         session_guard.screen_render()
     }
 
+    fn screen_image(&self, format: ImageFormat) -> Vec<u8> {
+        let session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.screen_image(format)
+    }
+
+    fn screen_snapshot(&self) -> ScreenSnapshot {
+        let session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.screen_snapshot()
+    }
+
     fn terminal_write(&self, data: &[u8]) -> Result<(), SessionError> {
         let mut session_guard = mutex_lock_or_recover(&self.inner);
         session_guard.pty_write(data)
@@ -123,6 +148,11 @@ impl SessionOps for SessionHandleImpl {
         session_guard.resize(cols, rows)
     }
 
+    fn signal(&self, signal: ProcessSignal) -> Result<(), SessionError> {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.signal(signal)
+    }
+
     fn cursor(&self) -> CursorPosition {
         let session_guard = mutex_lock_or_recover(&self.inner);
         session_guard.cursor()
@@ -147,6 +177,56 @@ impl SessionOps for SessionHandleImpl {
         let session_guard = mutex_lock_or_recover(&self.inner);
         session_guard.live_preview_snapshot()
     }
+
+    fn tty_state(&self) -> Option<TtyState> {
+        let session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.tty_state()
+    }
+
+    fn screen_idle_ms(&self) -> u64 {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.screen_idle_ms()
+    }
+
+    fn screen_version(&self) -> u64 {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.screen_version()
+    }
+
+    fn screen_state(&self) -> ScreenState {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.screen_state()
+    }
+
+    fn screen_state_at(&self, version: u64) -> Option<ScreenState> {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.screen_state_at(version)
+    }
+
+    fn record_start(&self, path: &str, include_input: bool) -> Result<(), SessionError> {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.record_start(path, include_input)
+    }
+
+    fn record_stop(&self) -> Option<Recording> {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.record_stop()
+    }
+
+    fn trace_start(&self, path: &str) -> Result<(), SessionError> {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.trace_start(path)
+    }
+
+    fn trace_stop(&self) -> Result<Option<TraceSummary>, SessionError> {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.trace_stop()
+    }
+
+    fn record_wait(&self, condition: &str, found: bool, elapsed_ms: u64, before: &str) {
+        let mut session_guard = mutex_lock_or_recover(&self.inner);
+        session_guard.record_wait(condition, found, elapsed_ms, before);
+    }
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -164,6 +244,17 @@ impl SessionRepository for SessionManager {
         SessionManager::spawn(self, command, args, cwd, env, session_id, cols, rows)
     }
 
+    fn spawn_replay(
+        &self,
+        path: &str,
+        pacing: ReplayPacing,
+        session_id: Option<String>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(SessionId, ReplaySummary), SessionError> {
+        SessionManager::spawn_replay(self, path, pacing, session_id, cols, rows)
+    }
+
     fn get(&self, session_id: &SessionId) -> Result<SessionHandle, SessionError> {
         let session = SessionManager::get(self, session_id.as_str())?;
         Ok(SessionHandleImpl::new_handle(session))
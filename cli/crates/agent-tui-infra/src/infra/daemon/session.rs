@@ -10,6 +10,7 @@ use std::io::BufReader;
 use std::io::BufWriter;
 use std::io::Write;
 use std::os::unix::io::AsRawFd;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Condvar;
@@ -47,7 +48,10 @@ use crate::common::rwlock_write_or_recover;
 use crate::infra::terminal::CursorPosition;
 use crate::infra::terminal::PtyHandle;
 use crate::infra::terminal::ReadEvent;
+use crate::infra::terminal::Replay;
+use crate::infra::terminal::ReplayHandle;
 use crate::infra::terminal::key_to_escape_sequence;
+use crate::infra::terminal::render_image;
 use crate::infra::terminal::render_screen;
 use crate::usecases::ports::LivePreviewSnapshot;
 use crate::usecases::ports::StreamCursor;
@@ -56,11 +60,28 @@ use crate::usecases::ports::StreamWaiter;
 use crate::usecases::ports::StreamWaiterHandle;
 
 use super::pty_session::PtySession;
+use super::recording::CastEnv;
+use super::recording::CastRecorder;
+use super::terminal_state::SCREEN_SETTLE;
+use super::trace::TRACE_SETTLE;
+use super::trace::TraceMeta;
+use super::trace::TraceRecorder;
+use super::trace::TraceWait;
 use crate::infra::daemon::TerminalState;
 
+use crate::domain::ImageFormat;
+use crate::domain::ProcessSignal;
+use crate::domain::Recording;
+use crate::domain::ReplayPacing;
+use crate::domain::ReplaySummary;
+use crate::domain::TraceSummary;
+use crate::domain::core::LogicalScreen;
+use crate::domain::core::ScreenSnapshot;
+use crate::domain::core::ScreenState;
 pub use crate::domain::session_types::SessionId;
 pub use crate::domain::session_types::SessionInfo;
 use crate::domain::session_types::TerminalSize;
+use crate::domain::session_types::TtyState;
 pub use crate::infra::daemon::SessionError;
 
 const STREAM_MAX_BUFFER_BYTES: usize = 8 * 1024 * 1024;
@@ -429,7 +450,17 @@ fn pump_loop(
     rx: channel::Receiver<PumpCommand>,
 ) {
     loop {
+        let idle = match session.lock() {
+            Ok(sess) => sess.idle_timeout(),
+            Err(_) => None,
+        };
+        let idle_tick = idle.map_or_else(channel::never, channel::after);
         channel::select! {
+            recv(idle_tick) -> _ => {
+                if let Ok(mut sess) = session.lock() {
+                    sess.on_idle();
+                }
+            },
             recv(rx) -> cmd => match cmd {
                 Ok(PumpCommand::Flush(ack)) => {
                     let mut should_continue = true;
@@ -593,12 +624,22 @@ pub struct Session {
     pty_cursor: Arc<Mutex<StreamCursor>>,
     pump_tx: Option<channel::Sender<PumpCommand>>,
     pump_join: Option<thread::JoinHandle<()>>,
+    /// Environment the `.cast` header reports for this session.
+    cast_env: CastEnv,
+    recorder: Option<CastRecorder>,
+    tracer: Option<TraceRecorder>,
 }
 
 impl Session {
-    fn new(id: SessionId, command: String, pty: PtyHandle, cols: u16, rows: u16) -> Self {
+    fn new(
+        id: SessionId,
+        command: String,
+        cast_env: CastEnv,
+        mut pty: PtySession,
+        cols: u16,
+        rows: u16,
+    ) -> Self {
         let stream = Arc::new(StreamBuffer::new(STREAM_MAX_BUFFER_BYTES));
-        let mut pty = PtySession::new(pty);
         let pty_rx = pty.take_read_rx();
         Self {
             id,
@@ -613,10 +654,16 @@ impl Session {
             pty_cursor: Arc::new(Mutex::new(StreamCursor::default())),
             pump_tx: None,
             pump_join: None,
+            cast_env,
+            recorder: None,
+            tracer: None,
         }
     }
 
     fn record_command_timeline_entry(&mut self, kind: &str, value: String) {
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.step(kind, &value, None, &self.terminal.screen_text());
+        }
         self.command_timeline.push(kind, value);
         self.stream.notify();
     }
@@ -629,6 +676,10 @@ impl Session {
         self.pty.is_running()
     }
 
+    pub fn exit_code(&mut self) -> Option<i32> {
+        self.pty.exit_code()
+    }
+
     pub fn size(&self) -> (u16, u16) {
         self.terminal.size()
     }
@@ -647,19 +698,58 @@ impl Session {
         self.terminal.screen_text()
     }
 
+    pub fn logical_screen(&self) -> LogicalScreen {
+        self.terminal.logical_screen()
+    }
+
     pub fn screen_render(&self) -> String {
         let buffer = self.terminal.screen_buffer();
         render_screen(&buffer)
     }
 
+    pub fn screen_image(&self, format: ImageFormat) -> Vec<u8> {
+        let buffer = self.terminal.screen_buffer();
+        render_image(
+            &buffer,
+            self.terminal.cursor(),
+            &self.terminal.palette(),
+            format,
+        )
+    }
+
+    pub fn screen_snapshot(&self) -> ScreenSnapshot {
+        self.terminal.snapshot()
+    }
+
     pub fn cursor(&self) -> CursorPosition {
         self.terminal.cursor()
     }
 
+    pub fn tty_state(&self) -> Option<TtyState> {
+        self.pty.tty_state()
+    }
+
+    pub fn screen_idle_ms(&mut self) -> u64 {
+        u64::try_from(self.terminal.screen_idle().as_millis()).unwrap_or(u64::MAX)
+    }
+
+    pub fn screen_version(&mut self) -> u64 {
+        self.terminal.screen_version()
+    }
+
+    pub fn screen_state(&mut self) -> ScreenState {
+        self.terminal.screen_state()
+    }
+
+    pub fn screen_state_at(&mut self, version: u64) -> Option<ScreenState> {
+        self.terminal.screen_state_at(version)
+    }
+
     pub fn keystroke(&mut self, key: &str) -> Result<(), SessionError> {
         let seq =
             key_to_escape_sequence(key).ok_or_else(|| SessionError::InvalidKey(key.to_string()))?;
         self.pty.write(&seq)?;
+        self.record_input(&seq);
         self.record_command_timeline_entry("press", key.to_string());
         Ok(())
     }
@@ -690,6 +780,7 @@ impl Session {
 
     pub fn type_text(&mut self, text: &str) -> Result<(), SessionError> {
         self.pty.write_str(text)?;
+        self.record_input(text.as_bytes());
         self.record_command_timeline_entry("type", sanitize_command_timeline_value(text));
         Ok(())
     }
@@ -697,11 +788,20 @@ impl Session {
     pub fn resize(&mut self, cols: u16, rows: u16) -> Result<(), SessionError> {
         self.pty.resize(cols, rows)?;
         self.terminal.resize(cols, rows);
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.resize(cols, rows);
+        }
         self.record_command_timeline_entry("resize", format!("{cols}x{rows}"));
         self.stream.notify();
         Ok(())
     }
 
+    pub fn signal(&mut self, signal: ProcessSignal) -> Result<(), SessionError> {
+        self.pty.signal(signal)?;
+        self.record_command_timeline_entry("signal", signal.as_str().to_string());
+        Ok(())
+    }
+
     pub fn kill(&mut self) -> Result<(), SessionError> {
         self.pty.kill()?;
         Ok(())
@@ -709,10 +809,102 @@ impl Session {
 
     pub fn pty_write(&mut self, data: &[u8]) -> Result<(), SessionError> {
         self.pty.write(data)?;
+        self.record_input(data);
         self.record_command_timeline_entry("write", format!("{} bytes", data.len()));
         Ok(())
     }
 
+    pub fn record_start(&mut self, path: &str, include_input: bool) -> Result<(), SessionError> {
+        let recording_error = |reason: String| SessionError::Recording {
+            path: path.to_string(),
+            reason,
+        };
+        if let Some(current) = self.recorder.as_ref() {
+            return Err(recording_error(format!(
+                "already recording to {}",
+                current.path()
+            )));
+        }
+        let (cols, rows) = self.terminal.size();
+        let initial =
+            render_live_preview_init(&self.terminal.screen_buffer(), &self.terminal.cursor());
+        let recorder = CastRecorder::start(
+            path,
+            cols,
+            rows,
+            &self.command,
+            &self.cast_env,
+            include_input,
+            &initial,
+        )
+        .map_err(|err| recording_error(err.to_string()))?;
+        self.recorder = Some(recorder);
+        Ok(())
+    }
+
+    pub fn record_stop(&mut self) -> Option<Recording> {
+        self.recorder.take().map(CastRecorder::finish)
+    }
+
+    pub fn trace_start(&mut self, path: &str) -> Result<(), SessionError> {
+        let trace_error = |reason: String| SessionError::Recording {
+            path: path.to_string(),
+            reason,
+        };
+        if let Some(current) = self.tracer.as_ref() {
+            return Err(trace_error(format!(
+                "already tracing to {}",
+                current.path()
+            )));
+        }
+        let tracer = TraceRecorder::start(path).map_err(|err| trace_error(err.to_string()))?;
+        self.tracer = Some(tracer);
+        Ok(())
+    }
+
+    pub fn trace_stop(&mut self) -> Result<Option<TraceSummary>, SessionError> {
+        let Some(tracer) = self.tracer.take() else {
+            return Ok(None);
+        };
+        let path = tracer.path().to_string();
+        let (cols, rows) = self.terminal.size();
+        let meta = TraceMeta {
+            session_id: self.id.as_str(),
+            command: &self.command,
+            cols,
+            rows,
+        };
+        tracer
+            .finish(&self.terminal.screen_text(), meta)
+            .map(Some)
+            .map_err(|err| SessionError::Recording {
+                path,
+                reason: err.to_string(),
+            })
+    }
+
+    /// Note the outcome of a wait in the command timeline and any active trace;
+    /// `before` is the screen when the wait started.
+    pub fn record_wait(&mut self, condition: &str, found: bool, elapsed_ms: u64, before: &str) {
+        let value = if found {
+            format!("{condition} found in {elapsed_ms}ms")
+        } else {
+            format!("{condition} timed out after {elapsed_ms}ms")
+        };
+        if let Some(tracer) = self.tracer.as_mut() {
+            let wait = TraceWait { found, elapsed_ms };
+            tracer.wait(&value, wait, before, &self.terminal.screen_text());
+        }
+        self.command_timeline.push("wait", value);
+        self.stream.notify();
+    }
+
+    fn record_input(&mut self, data: &[u8]) {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.input(data);
+        }
+    }
+
     pub fn stream_read(
         &self,
         cursor: &mut StreamCursor,
@@ -749,10 +941,25 @@ impl Session {
     fn handle_read_event(&mut self, event: ReadEvent) -> bool {
         match event {
             ReadEvent::Data(data) => {
-                self.terminal.process(&data);
+                let stream_seq = self.stream.latest_seq().saturating_add(data.len() as u64);
+                self.terminal.process(&data, stream_seq);
+                if let Some(recorder) = self.recorder.as_mut() {
+                    recorder.output(&data);
+                }
+                if let Some(tracer) = self.tracer.as_mut() {
+                    tracer.output(&data);
+                }
                 self.stream.push_bytes(Bytes::from(data));
                 true
             }
+            ReadEvent::Resize(cols, rows) => {
+                self.terminal.resize(cols, rows);
+                if let Some(recorder) = self.recorder.as_mut() {
+                    recorder.resize(cols, rows);
+                }
+                self.stream.notify();
+                true
+            }
             ReadEvent::Eof => {
                 self.stream.close(None);
                 let _ = self.pty.is_running();
@@ -766,6 +973,28 @@ impl Session {
         }
     }
 
+    /// How long the pump may stay quiet before `on_idle` has work to do.
+    fn idle_timeout(&self) -> Option<Duration> {
+        if self.terminal.is_dirty() {
+            return Some(SCREEN_SETTLE);
+        }
+        self.tracer
+            .as_ref()
+            .filter(|tracer| tracer.wants_settle())
+            .map(|_| TRACE_SETTLE)
+    }
+
+    /// Called by the pump once no output arrived for `idle_timeout`.
+    fn on_idle(&mut self) {
+        if self.terminal.is_dirty() {
+            self.terminal.settle();
+            return;
+        }
+        if let Some(tracer) = self.tracer.as_mut() {
+            tracer.settle(&self.terminal.screen_text());
+        }
+    }
+
     fn pump_drain_events(&mut self, pty_rx: &channel::Receiver<ReadEvent>) -> bool {
         while let Ok(event) = pty_rx.try_recv() {
             if !self.handle_read_event(event) {
@@ -847,6 +1076,74 @@ impl SessionManager {
         cols: u16,
         rows: u16,
     ) -> Result<(SessionId, u32), SessionError> {
+        let id = self.new_session_id(session_id)?;
+
+        let pty = PtyHandle::spawn(command, args, cwd, env, cols, rows)
+            .map_err(|e| SessionError::Terminal(e.into_port_error()))?;
+        let pid = pty.pid().unwrap_or(0);
+
+        let session = Session::new(
+            id.clone(),
+            command.to_string(),
+            CastEnv::for_spawn(env),
+            PtySession::new(pty),
+            cols,
+            rows,
+        );
+
+        let created_at = Utc::now().to_rfc3339();
+        let persisted = PersistedSession {
+            id: id.to_string(),
+            command: command.to_string(),
+            pid,
+            created_at,
+            cols,
+            rows,
+        };
+
+        self.start_session(&id, session);
+
+        if let Err(e) = self.persistence.add_session(persisted) {
+            warn!(error = %e, "Failed to persist session metadata");
+        }
+
+        Ok((id, pid))
+    }
+
+    /// Start a session that plays back a recording. Nothing is persisted: there
+    /// is no process to clean up if the daemon dies.
+    pub fn spawn_replay(
+        &self,
+        path: &str,
+        pacing: ReplayPacing,
+        session_id: Option<String>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(SessionId, ReplaySummary), SessionError> {
+        let id = self.new_session_id(session_id)?;
+
+        let replay =
+            Replay::load(Path::new(path), cols, rows).map_err(|reason| SessionError::Replay {
+                path: path.to_string(),
+                reason,
+            })?;
+        let summary = replay.summary();
+        let pty = PtySession::replay(ReplayHandle::start(replay, pacing));
+        let session = Session::new(
+            id.clone(),
+            format!("replay {path}"),
+            CastEnv::default(),
+            pty,
+            summary.cols,
+            summary.rows,
+        );
+
+        self.start_session(&id, session);
+
+        Ok((id, summary))
+    }
+
+    fn new_session_id(&self, session_id: Option<String>) -> Result<SessionId, SessionError> {
         if let Some(ref requested_id) = session_id {
             let sessions = rwlock_read_or_recover(&self.sessions);
             let id = SessionId::new(requested_id.clone());
@@ -862,27 +1159,15 @@ impl SessionManager {
             }
         }
 
-        let id = session_id
+        Ok(session_id
             .map(SessionId::new)
-            .unwrap_or_else(generate_session_id);
-
-        let pty = PtyHandle::spawn(command, args, cwd, env, cols, rows)
-            .map_err(|e| SessionError::Terminal(e.into_port_error()))?;
-        let pid = pty.pid().unwrap_or(0);
+            .unwrap_or_else(generate_session_id))
+    }
 
-        let session = Session::new(id.clone(), command.to_string(), pty, cols, rows);
+    /// Register `session`, make it active and start its output pump.
+    fn start_session(&self, id: &SessionId, session: Session) {
         let session = Arc::new(Mutex::new(session));
 
-        let created_at = Utc::now().to_rfc3339();
-        let persisted = PersistedSession {
-            id: id.to_string(),
-            command: command.to_string(),
-            pid,
-            created_at,
-            cols,
-            rows,
-        };
-
         {
             let mut sessions = rwlock_write_or_recover(&self.sessions);
             sessions.insert(id.clone(), Arc::clone(&session));
@@ -899,12 +1184,6 @@ impl SessionManager {
             let mut sess = mutex_lock_or_recover(&session);
             sess.attach_pump(pump_tx, pump_join);
         }
-
-        if let Err(e) = self.persistence.add_session(persisted) {
-            warn!(error = %e, "Failed to persist session metadata");
-        }
-
-        Ok((id, pid))
     }
 
     pub fn get(&self, session_id: &str) -> Result<Arc<Mutex<Session>>, SessionError> {
@@ -984,6 +1263,7 @@ impl SessionManager {
                         command: sess.command.clone(),
                         pid: sess.pid().unwrap_or(0),
                         running: sess.is_running(),
+                        exit_code: sess.exit_code(),
                         created_at: sess.created_at.to_rfc3339(),
                         size: TerminalSize::try_new(cols, rows).unwrap_or_default(),
                     }
@@ -993,6 +1273,7 @@ impl SessionManager {
                         command: "(locked)".to_string(),
                         pid: 0,
                         running: false,
+                        exit_code: None,
                         created_at: String::new(),
                         size: TerminalSize::default(),
                     }
@@ -1603,11 +1884,16 @@ mod stream_tests {
 
 #[cfg(test)]
 mod pump_tests {
+    use super::CastEnv;
     use super::PUMP_FLUSH_TIMEOUT;
+    use super::PtySession;
     use super::Session;
     use super::StreamCursor;
     use super::spawn_pump;
+    use crate::domain::ReplayPacing;
     use crate::infra::terminal::PtyHandle;
+    use crate::infra::terminal::Replay;
+    use crate::infra::terminal::ReplayHandle;
     use std::path::Path;
     use std::sync::Arc;
     use std::sync::Mutex;
@@ -1624,7 +1910,14 @@ mod pump_tests {
             "sh"
         };
         let pty = PtyHandle::spawn(shell, &args, Some("/tmp"), None, 80, 24).unwrap();
-        let session = Session::new("test-session".into(), "sh".to_string(), pty, 80, 24);
+        let session = Session::new(
+            "test-session".into(),
+            "sh".to_string(),
+            CastEnv::default(),
+            PtySession::new(pty),
+            80,
+            24,
+        );
         let session = Arc::new(Mutex::new(session));
 
         let (tx, join) = spawn_pump(Arc::clone(&session), "test-pump".to_string());
@@ -1682,6 +1975,64 @@ mod pump_tests {
             run_pump_stream_output_case();
         }
     }
+
+    #[test]
+    fn session_pump_plays_replay_into_terminal() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.cast");
+        std::fs::write(
+            &path,
+            concat!(
+                r#"{"version": 2, "width": 40, "height": 10}"#,
+                "\n",
+                r#"[0.0, "o", "hello replay"]"#,
+                "\n",
+                r#"[0.01, "r", "50x12"]"#,
+                "\n"
+            ),
+        )
+        .unwrap();
+        let replay = Replay::load(&path, 80, 24).unwrap();
+        let pty = PtySession::replay(ReplayHandle::start(replay, ReplayPacing::Speed(1.0)));
+        let session = Session::new(
+            "replay".into(),
+            "replay".to_string(),
+            CastEnv::default(),
+            pty,
+            40,
+            10,
+        );
+        let session = Arc::new(Mutex::new(session));
+
+        let (tx, join) = spawn_pump(Arc::clone(&session), "test-pump".to_string());
+        {
+            let mut guard = session.lock().unwrap();
+            guard.attach_pump(tx, join);
+        }
+
+        let deadline = Instant::now() + Duration::from_millis(1500);
+        while Instant::now() < deadline {
+            let ack = { session.lock().unwrap().request_flush() };
+            if let Some(ack) = ack {
+                let _ = ack.recv_timeout(PUMP_FLUSH_TIMEOUT);
+            }
+            let guard = session.lock().unwrap();
+            if guard.size() == (50, 12) {
+                break;
+            }
+        }
+
+        let mut guard = session.lock().unwrap();
+        assert!(guard.screen_text().contains("hello replay"));
+        assert_eq!(guard.size(), (50, 12));
+        assert!(guard.is_running());
+        assert_eq!(guard.pid(), None);
+        let join = guard.shutdown_pump();
+        drop(guard);
+        if let Some(join) = join {
+            let _ = join.join();
+        }
+    }
 }
 
 impl Default for SessionPersistence {
@@ -1,41 +1,258 @@
 //! Terminal state management.
 
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::domain::core::LogicalScreen;
+use crate::domain::core::ScreenSnapshot;
+use crate::domain::core::ScreenState;
 use crate::infra::terminal::CursorPosition;
+use crate::infra::terminal::Palette;
 use crate::infra::terminal::ScreenBuffer;
 use crate::infra::terminal::VirtualTerminal;
+use crate::usecases::ports::TerminalEngine;
+
+/// Screen states kept for `snapshot_diff`; older versions fall back to a full diff.
+const SCREEN_HISTORY_LIMIT: usize = 64;
+/// Output quiet time after which the session pump settles a dirty screen.
+pub(crate) const SCREEN_SETTLE: Duration = Duration::from_millis(10);
+
+/// Output fed since the screen was last compared with its previous state.
+#[derive(Clone, Copy)]
+struct PendingOutput {
+    stream_seq: u64,
+    at: Instant,
+}
 
 pub struct TerminalState {
     terminal: VirtualTerminal,
+    screen_hash: u64,
+    screen_changed_at: Instant,
+    screen_version: u64,
+    history: VecDeque<ScreenState>,
+    pending: Option<PendingOutput>,
 }
 
 impl TerminalState {
     pub fn new(cols: u16, rows: u16) -> Self {
+        let terminal = VirtualTerminal::new(cols, rows);
+        let buffer = terminal.screen_buffer();
+        let screen_hash = hash_screen(&buffer, &terminal.cursor());
+        let initial = screen_state(0, &buffer, terminal.cursor());
         Self {
-            terminal: VirtualTerminal::new(cols, rows),
+            terminal,
+            screen_hash,
+            screen_changed_at: Instant::now(),
+            screen_version: 0,
+            history: VecDeque::from([initial]),
+            pending: None,
         }
     }
 
-    pub fn process(&mut self, data: &[u8]) {
+    /// Feeds PTY output; `stream_seq` is the output stream position after `data`
+    /// and becomes the screen version if the screen changed.
+    ///
+    /// The screen is not hashed here: output often arrives in many small
+    /// chunks, so the comparison waits until [`Self::settle`] runs, either
+    /// when the read loop goes quiet or when a reader needs the version.
+    pub fn process(&mut self, data: &[u8], stream_seq: u64) {
         self.terminal.process(data);
+        self.pending = Some(PendingOutput {
+            stream_seq,
+            at: Instant::now(),
+        });
+    }
+
+    /// Whether output arrived since the screen was last compared.
+    pub fn is_dirty(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Compare the screen with its last known state, bumping the version and
+    /// recording history if it changed.
+    pub fn settle(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            self.track_screen_change(pending.stream_seq, pending.at);
+        }
     }
 
     pub fn screen_text(&self) -> String {
         self.terminal.screen_text()
     }
 
+    pub fn logical_screen(&self) -> LogicalScreen {
+        self.terminal.logical_screen()
+    }
+
     pub fn screen_buffer(&self) -> ScreenBuffer {
         self.terminal.screen_buffer()
     }
 
+    pub fn snapshot(&self) -> ScreenSnapshot {
+        TerminalEngine::snapshot(&self.terminal)
+    }
+
     pub fn cursor(&self) -> CursorPosition {
         self.terminal.cursor()
     }
 
+    pub fn palette(&self) -> Palette {
+        self.terminal.palette()
+    }
+
     pub fn size(&self) -> (u16, u16) {
         self.terminal.size()
     }
 
     pub fn resize(&mut self, cols: u16, rows: u16) {
+        self.settle();
         self.terminal.resize(cols, rows);
+        self.track_screen_change(self.screen_version, Instant::now());
+    }
+
+    pub fn screen_version(&mut self) -> u64 {
+        self.settle();
+        self.screen_version
+    }
+
+    pub fn screen_state(&mut self) -> ScreenState {
+        self.settle();
+        screen_state(
+            self.screen_version,
+            &self.terminal.screen_buffer(),
+            self.terminal.cursor(),
+        )
+    }
+
+    pub fn screen_state_at(&mut self, version: u64) -> Option<ScreenState> {
+        self.settle();
+        if version > self.screen_version {
+            return None;
+        }
+        self.history
+            .iter()
+            .rev()
+            .find(|state| state.version <= version)
+            .cloned()
+    }
+
+    /// Time since the visible cells (text or style) last changed.
+    pub fn screen_idle(&mut self) -> Duration {
+        self.settle();
+        self.screen_changed_at.elapsed()
+    }
+
+    /// `changed_at` is when the output behind the change arrived.
+    fn track_screen_change(&mut self, stream_seq: u64, changed_at: Instant) {
+        let buffer = self.terminal.screen_buffer();
+        let cursor = self.terminal.cursor();
+        let hash = hash_screen(&buffer, &cursor);
+        if hash == self.screen_hash {
+            return;
+        }
+        self.screen_hash = hash;
+        self.screen_changed_at = changed_at;
+        // Resizes change the screen without new output, so always move forward.
+        self.screen_version = stream_seq.max(self.screen_version + 1);
+        if self.history.len() == SCREEN_HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history
+            .push_back(screen_state(self.screen_version, &buffer, cursor));
+    }
+}
+
+fn screen_state(version: u64, buffer: &ScreenBuffer, cursor: CursorPosition) -> ScreenState {
+    let rows = buffer
+        .cells
+        .iter()
+        .map(|row| {
+            let text: String = row.iter().map(|cell| cell.char).collect();
+            text.trim_end().to_string()
+        })
+        .collect();
+    ScreenState {
+        version,
+        rows,
+        cursor,
+    }
+}
+
+fn hash_screen(buffer: &ScreenBuffer, cursor: &CursorPosition) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (cursor.row, cursor.col).hash(&mut hasher);
+    for row in &buffer.cells {
+        for cell in row {
+            cell.char.hash(&mut hasher);
+            cell.style.hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_screen_change_ignores_non_visual_output() {
+        let mut state = TerminalState::new(80, 24);
+        state.process(b"hello", 5);
+        state.settle();
+        let hash = state.screen_hash;
+        let changed_at = state.screen_changed_at;
+
+        state.process(b"\x1b[?25l", 11);
+        state.settle();
+        assert_eq!(state.screen_hash, hash);
+        assert_eq!(state.screen_changed_at, changed_at);
+        assert_eq!(state.screen_version(), 5);
+
+        state.process(b" world", 17);
+        assert_eq!(state.screen_version(), 17);
+        assert_ne!(state.screen_hash, hash);
+    }
+
+    #[test]
+    fn test_screen_state_at_returns_screen_current_at_version() {
+        let mut state = TerminalState::new(20, 4);
+        state.process(b"one", 3);
+        state.settle();
+        state.process(b"\r\ntwo", 8);
+
+        let at_five = state.screen_state_at(5).unwrap();
+        assert_eq!(at_five.version, 3);
+        assert_eq!(at_five.rows[0], "one");
+        assert_eq!(at_five.rows[1], "");
+        assert_eq!(state.screen_state().rows[1], "two");
+        assert!(state.screen_state_at(9).is_none());
+    }
+
+    #[test]
+    fn test_resize_advances_screen_version() {
+        let mut state = TerminalState::new(20, 4);
+        state.process(b"abc", 3);
+        state.resize(10, 4);
+        assert!(state.screen_version() > 3);
+    }
+
+    #[test]
+    fn test_screen_is_compared_once_per_settle() {
+        let mut state = TerminalState::new(20, 4);
+        let hash = state.screen_hash;
+        state.process(b"one", 3);
+        state.process(b" two", 7);
+        assert!(state.is_dirty());
+        assert_eq!(state.screen_hash, hash);
+        assert_eq!(state.history.len(), 1);
+
+        assert_eq!(state.screen_version(), 7);
+        assert!(!state.is_dirty());
+        assert_eq!(state.history.len(), 2);
+        assert_eq!(state.screen_state().rows[0], "one two");
     }
 }
@@ -0,0 +1,395 @@
+//! Trace bundles: a zip archive of everything that happened to a session
+//! between `trace start` and `trace stop`.
+//!
+//! The archive holds `trace.json` (the manifest and step list), one
+//! `screens/NNNN.txt` file per distinct screen, and `output.bin` with the raw
+//! output stream. Each step points at the screen seen when the action was
+//! applied (`before`) and the screen once the output it caused went quiet for
+//! [`TRACE_SETTLE`] (`after`). A step that produced no output, or whose output
+//! was still arriving, ends with the screen seen when the next step started or
+//! the trace stopped. Identical screens are stored once.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::io::Write;
+use std::time::Duration;
+use std::time::Instant;
+
+use chrono::Utc;
+use serde::Deserialize;
+use serde::Serialize;
+use zip::CompressionMethod;
+use zip::ZipArchive;
+use zip::ZipWriter;
+use zip::write::SimpleFileOptions;
+
+use crate::domain::TraceSummary;
+
+pub const TRACE_VERSION: u32 = 1;
+const TRACE_MANIFEST: &str = "trace.json";
+const TRACE_OUTPUT: &str = "output.bin";
+/// Raw output kept per trace; later output is dropped and flagged in the manifest.
+const TRACE_MAX_OUTPUT_BYTES: usize = 32 * 1024 * 1024;
+/// Quiet time after which a step's output counts as settled.
+pub(crate) const TRACE_SETTLE: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceWait {
+    pub found: bool,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub index: u64,
+    pub at_ms: u64,
+    pub kind: String,
+    pub value: String,
+    pub wait: Option<TraceWait>,
+    /// Archive path of the screen before the step.
+    pub before: String,
+    /// Archive path of the screen once the step's output settled.
+    pub after: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceManifest {
+    pub version: u32,
+    pub session_id: String,
+    pub command: String,
+    pub cols: u16,
+    pub rows: u16,
+    pub started_at: String,
+    pub duration_ms: u64,
+    pub output_bytes: u64,
+    pub output_truncated: bool,
+    pub steps: Vec<TraceStep>,
+}
+
+/// A trace bundle read back from disk.
+#[derive(Debug, Clone)]
+pub struct TraceBundle {
+    pub manifest: TraceManifest,
+    /// Screen text keyed by archive path.
+    pub screens: HashMap<String, String>,
+}
+
+impl TraceBundle {
+    pub fn screen(&self, name: &str) -> &str {
+        self.screens.get(name).map(String::as_str).unwrap_or("")
+    }
+}
+
+/// Session metadata written into the manifest when a trace is finished.
+pub(crate) struct TraceMeta<'a> {
+    pub session_id: &'a str,
+    pub command: &'a str,
+    pub cols: u16,
+    pub rows: u16,
+}
+
+pub(crate) struct TraceRecorder {
+    path: String,
+    started: Instant,
+    started_at: String,
+    steps: Vec<TraceStep>,
+    screens: Vec<String>,
+    screen_index: HashMap<String, usize>,
+    output: Vec<u8>,
+    output_truncated: bool,
+    /// The last step is still waiting for its output to settle.
+    step_open: bool,
+    /// Output arrived since the last step was recorded.
+    output_since_step: bool,
+}
+
+impl TraceRecorder {
+    /// Begin a trace that will be written to `path`. The file is created now so
+    /// an unwritable path fails at start rather than at stop.
+    pub(crate) fn start(path: &str) -> std::io::Result<Self> {
+        File::create(path)?;
+        Ok(Self {
+            path: path.to_string(),
+            started: Instant::now(),
+            started_at: Utc::now().to_rfc3339(),
+            steps: Vec::new(),
+            screens: Vec::new(),
+            screen_index: HashMap::new(),
+            output: Vec::new(),
+            output_truncated: false,
+            step_open: false,
+            output_since_step: false,
+        })
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub(crate) fn step(&mut self, kind: &str, value: &str, wait: Option<TraceWait>, screen: &str) {
+        let screen = self.intern_screen(screen);
+        if self.step_open
+            && let Some(previous) = self.steps.last_mut()
+        {
+            previous.after.clone_from(&screen);
+        }
+        self.step_open = true;
+        self.output_since_step = false;
+        self.steps.push(TraceStep {
+            index: self.steps.len() as u64 + 1,
+            at_ms: self.elapsed_ms(),
+            kind: kind.to_string(),
+            value: value.to_string(),
+            wait,
+            before: screen.clone(),
+            after: screen,
+        });
+    }
+
+    /// Record a finished wait, from the screen when it started to the one it
+    /// ended on. Output after the wait is not attributed to it.
+    pub(crate) fn wait(&mut self, value: &str, wait: TraceWait, before: &str, after: &str) {
+        self.step("wait", value, Some(wait), after);
+        let before = self.intern_screen(before);
+        if let Some(last) = self.steps.last_mut() {
+            last.before = before;
+        }
+        self.step_open = false;
+    }
+
+    pub(crate) fn output(&mut self, data: &[u8]) {
+        let room = TRACE_MAX_OUTPUT_BYTES.saturating_sub(self.output.len());
+        if data.len() > room {
+            self.output_truncated = true;
+        }
+        self.output.extend_from_slice(&data[..data.len().min(room)]);
+        self.output_since_step = true;
+    }
+
+    /// Whether the session should call [`Self::settle`] once output goes quiet.
+    pub(crate) fn wants_settle(&self) -> bool {
+        self.step_open && self.output_since_step
+    }
+
+    /// Record `screen` as the last step's final state once its output stopped.
+    pub(crate) fn settle(&mut self, screen: &str) {
+        if !self.wants_settle() {
+            return;
+        }
+        let screen = self.intern_screen(screen);
+        if let Some(last) = self.steps.last_mut() {
+            last.after = screen;
+        }
+        self.step_open = false;
+    }
+
+    /// Write the bundle, using `screen` as the final state of the last step.
+    pub(crate) fn finish(
+        mut self,
+        screen: &str,
+        meta: TraceMeta<'_>,
+    ) -> std::io::Result<TraceSummary> {
+        if self.step_open {
+            let last = self.intern_screen(screen);
+            if let Some(previous) = self.steps.last_mut() {
+                previous.after = last;
+            }
+        }
+        let duration_ms = self.elapsed_ms();
+        let manifest = TraceManifest {
+            version: TRACE_VERSION,
+            session_id: meta.session_id.to_string(),
+            command: meta.command.to_string(),
+            cols: meta.cols,
+            rows: meta.rows,
+            started_at: self.started_at,
+            duration_ms,
+            output_bytes: self.output.len() as u64,
+            output_truncated: self.output_truncated,
+            steps: self.steps,
+        };
+
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Deflated);
+        let mut zip = ZipWriter::new(File::create(&self.path)?);
+        zip.start_file(TRACE_MANIFEST, options)
+            .map_err(std::io::Error::other)?;
+        serde_json::to_writer_pretty(&mut zip, &manifest)?;
+        for (index, text) in self.screens.iter().enumerate() {
+            zip.start_file(screen_name(index), options)
+                .map_err(std::io::Error::other)?;
+            zip.write_all(text.as_bytes())?;
+        }
+        zip.start_file(TRACE_OUTPUT, options)
+            .map_err(std::io::Error::other)?;
+        zip.write_all(&self.output)?;
+        zip.finish().map_err(std::io::Error::other)?;
+
+        Ok(TraceSummary {
+            path: self.path,
+            steps: manifest.steps.len() as u64,
+            output_bytes: manifest.output_bytes,
+            duration_ms,
+        })
+    }
+
+    fn intern_screen(&mut self, screen: &str) -> String {
+        let index = match self.screen_index.get(screen) {
+            Some(index) => *index,
+            None => {
+                let index = self.screens.len();
+                self.screens.push(screen.to_string());
+                self.screen_index.insert(screen.to_string(), index);
+                index
+            }
+        };
+        screen_name(index)
+    }
+
+    fn elapsed_ms(&self) -> u64 {
+        u64::try_from(self.started.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+}
+
+fn screen_name(index: usize) -> String {
+    format!("screens/{:04}.txt", index + 1)
+}
+
+/// Read a bundle written by `trace stop`. Errors are human-readable strings.
+pub fn read_trace_bundle(path: &str) -> Result<TraceBundle, String> {
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut archive = ZipArchive::new(file).map_err(|err| format!("not a trace bundle: {err}"))?;
+    let manifest: TraceManifest = {
+        let entry = archive
+            .by_name(TRACE_MANIFEST)
+            .map_err(|_| format!("not a trace bundle: missing {TRACE_MANIFEST}"))?;
+        serde_json::from_reader(entry).map_err(|err| format!("invalid {TRACE_MANIFEST}: {err}"))?
+    };
+    if manifest.version != TRACE_VERSION {
+        return Err(format!(
+            "unsupported trace version {} (expected {TRACE_VERSION})",
+            manifest.version
+        ));
+    }
+    let mut screens = HashMap::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|err| err.to_string())?;
+        if !entry.name().starts_with("screens/") {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut text = String::new();
+        entry
+            .read_to_string(&mut text)
+            .map_err(|err| format!("invalid {name}: {err}"))?;
+        screens.insert(name, text);
+    }
+    Ok(TraceBundle { manifest, screens })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn meta() -> TraceMeta<'static> {
+        TraceMeta {
+            session_id: "abc123",
+            command: "bash",
+            cols: 80,
+            rows: 24,
+        }
+    }
+
+    #[test]
+    fn test_steps_link_before_and_after_screens() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.zip");
+        let path = path.to_str().unwrap();
+        let mut recorder = TraceRecorder::start(path).unwrap();
+
+        recorder.step("type", "ls", None, "$ ");
+        recorder.output(b"ls\r\nfile\r\n$ ");
+        // The wait started before the output reached the screen.
+        recorder.wait(
+            "text \"file\" found in 3ms",
+            TraceWait {
+                found: true,
+                elapsed_ms: 3,
+            },
+            "$ ",
+            "$ ls\nfile\n$ ",
+        );
+        recorder.output(b"clock tick");
+        let summary = recorder.finish("$ ls\nfile\n$ 12:01", meta()).unwrap();
+
+        assert_eq!(summary.steps, 2);
+        assert_eq!(summary.output_bytes, 22);
+        let bundle = read_trace_bundle(path).unwrap();
+        let steps = &bundle.manifest.steps;
+        assert_eq!(bundle.manifest.session_id, "abc123");
+        assert_eq!(steps[0].kind, "type");
+        assert_eq!(bundle.screen(&steps[0].before), "$ ");
+        assert_eq!(bundle.screen(&steps[0].after), "$ ls\nfile\n$ ");
+        assert_ne!(steps[1].before, steps[1].after);
+        assert_eq!(bundle.screen(&steps[1].before), "$ ");
+        assert_eq!(bundle.screen(&steps[1].after), "$ ls\nfile\n$ ");
+        assert_eq!(
+            steps[1].wait,
+            Some(TraceWait {
+                found: true,
+                elapsed_ms: 3
+            })
+        );
+        assert_eq!(bundle.screens.len(), 2);
+    }
+
+    #[test]
+    fn test_after_is_screen_once_output_settled() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.zip");
+        let path = path.to_str().unwrap();
+        let mut recorder = TraceRecorder::start(path).unwrap();
+
+        recorder.step("press", "Enter", None, "menu");
+        assert!(!recorder.wants_settle(), "no output yet");
+        recorder.output(b"opened");
+        assert!(recorder.wants_settle());
+        recorder.settle("opened");
+        assert!(!recorder.wants_settle());
+        // Output after the step settled is not attributed to it.
+        recorder.output(b"clock tick");
+        recorder.step("type", "x", None, "opened 12:01");
+        recorder.finish("opened 12:01 x", meta()).unwrap();
+
+        let bundle = read_trace_bundle(path).unwrap();
+        let steps = &bundle.manifest.steps;
+        assert_eq!(bundle.screen(&steps[0].before), "menu");
+        assert_eq!(bundle.screen(&steps[0].after), "opened");
+        assert_eq!(bundle.screen(&steps[1].before), "opened 12:01");
+        assert_eq!(bundle.screen(&steps[1].after), "opened 12:01 x");
+    }
+
+    #[test]
+    fn test_output_is_capped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.zip");
+        let mut recorder = TraceRecorder::start(path.to_str().unwrap()).unwrap();
+
+        recorder.output(&vec![b'x'; TRACE_MAX_OUTPUT_BYTES - 1]);
+        recorder.output(b"yz");
+
+        assert_eq!(recorder.output.len(), TRACE_MAX_OUTPUT_BYTES);
+        assert!(recorder.output_truncated);
+    }
+
+    #[test]
+    fn test_read_rejects_non_bundles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("trace.zip");
+        std::fs::write(&path, "not a zip").unwrap();
+
+        let err = read_trace_bundle(path.to_str().unwrap()).unwrap_err();
+
+        assert!(err.starts_with("not a trace bundle"), "{err}");
+    }
+}
@@ -129,6 +129,7 @@ pub trait DaemonClient: Send + Sync {
     }
 }
 
+#[derive(Clone)]
 pub struct UnixSocketClient {
     transport: std::sync::Arc<dyn IpcTransport>,
 }
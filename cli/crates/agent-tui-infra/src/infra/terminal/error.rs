@@ -38,6 +38,12 @@ pub enum PtyError {
         #[source]
         source: Option<io::Error>,
     },
+    #[error("Failed to signal PTY child: {reason}")]
+    Signal {
+        reason: String,
+        #[source]
+        source: Option<io::Error>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -90,6 +96,10 @@ impl PtyError {
             PtyError::Resize { .. } => {
                 "Failed to resize terminal. Try again or restart the session.".to_string()
             }
+            PtyError::Signal { .. } => {
+                "Failed to signal the process. The session may have ended. Run 'sessions' to check status."
+                    .to_string()
+            }
         }
     }
 
@@ -104,6 +114,7 @@ impl PtyError {
             PtyError::Write { .. } => "write",
             PtyError::Read { .. } => "read",
             PtyError::Resize { .. } => "resize",
+            PtyError::Signal { .. } => "signal",
         }
     }
 
@@ -112,7 +123,8 @@ impl PtyError {
             PtyError::Open { reason, .. }
             | PtyError::Write { reason, .. }
             | PtyError::Read { reason, .. }
-            | PtyError::Resize { reason, .. } => reason,
+            | PtyError::Resize { reason, .. }
+            | PtyError::Signal { reason, .. } => reason,
             PtyError::Spawn { reason, .. } => reason,
         }
     }
@@ -140,6 +152,10 @@ impl PtyError {
                 reason,
                 source: source.map(|err| Box::new(err) as _),
             },
+            PtyError::Signal { reason, source } => PortTerminalError::Signal {
+                reason,
+                source: source.map(|err| Box::new(err) as _),
+            },
         }
     }
 }
@@ -0,0 +1,74 @@
+//! Embedded 8x8 bitmap font for rendering screen images.
+//!
+//! Glyphs come from the public domain font8x8 tables. Each glyph is eight rows,
+//! one byte per row, with bit 0 as the leftmost pixel.
+
+use font8x8::legacy::BASIC_LEGACY;
+use font8x8::legacy::BLOCK_LEGACY;
+use font8x8::legacy::BOX_LEGACY;
+use font8x8::legacy::GREEK_LEGACY;
+use font8x8::legacy::LATIN_LEGACY;
+
+pub const GLYPH_WIDTH: usize = 8;
+/// Cell height: glyph rows plus one padding row above and below.
+pub const CELL_HEIGHT: usize = 10;
+
+/// Shown for characters the font does not cover.
+const MISSING: [u8; 8] = [0x00, 0x7E, 0x42, 0x42, 0x42, 0x42, 0x7E, 0x00];
+const CHECK: [u8; 8] = [0x00, 0x80, 0xC0, 0x61, 0x33, 0x1E, 0x0C, 0x00];
+const BULLET: [u8; 8] = [0x00, 0x00, 0x3C, 0x7E, 0x7E, 0x3C, 0x00, 0x00];
+const ELLIPSIS: [u8; 8] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x49, 0x00];
+
+/// Pixel rows of `ch` stretched to the cell height. Box-drawing and block glyphs
+/// repeat their edge rows so lines join the cells above and below; text glyphs
+/// get blank padding instead. `None` for blanks.
+pub fn glyph_rows(ch: char) -> Option<[u8; CELL_HEIGHT]> {
+    if ch == ' ' || ch == '\u{a0}' {
+        return None;
+    }
+    let (bitmap, connects) = bitmap(ch);
+    let mut rows = [0; CELL_HEIGHT];
+    rows[1..9].copy_from_slice(&bitmap);
+    if connects {
+        rows[0] = bitmap[0];
+        rows[9] = bitmap[7];
+    }
+    Some(rows)
+}
+
+fn bitmap(ch: char) -> ([u8; 8], bool) {
+    let code = ch as usize;
+    let table = |table: &[[u8; 8]], first: usize| table.get(code.wrapping_sub(first)).copied();
+    let glyph = match code {
+        0x20..=0x7E => table(&BASIC_LEGACY, 0),
+        0xA0..=0xFF => table(&LATIN_LEGACY, 0xA0),
+        0x390..=0x3C9 => table(&GREEK_LEGACY, 0x390),
+        0x2500..=0x257F => return (table(&BOX_LEGACY, 0x2500).unwrap_or(MISSING), true),
+        0x2580..=0x259F => return (table(&BLOCK_LEGACY, 0x2580).unwrap_or(MISSING), true),
+        _ => substitute(ch),
+    };
+    (
+        glyph
+            .filter(|rows| rows.iter().any(|row| *row != 0))
+            .unwrap_or(MISSING),
+        false,
+    )
+}
+
+/// Common TUI symbols outside the font's ranges.
+fn substitute(ch: char) -> Option<[u8; 8]> {
+    let ascii = |ch: u8| BASIC_LEGACY.get(usize::from(ch)).copied();
+    match ch {
+        '❯' | '›' | '▶' | '▸' | '➜' | '→' => ascii(b'>'),
+        '❮' | '‹' | '◀' | '◂' | '←' => ascii(b'<'),
+        '✔' | '✓' => Some(CHECK),
+        '✗' | '✘' => ascii(b'x'),
+        '•' | '●' | '◉' | '◆' => Some(BULLET),
+        '○' | '◯' | '◇' => ascii(b'o'),
+        '…' => Some(ELLIPSIS),
+        '‘' | '’' => ascii(b'\''),
+        '“' | '”' => ascii(b'"'),
+        '–' | '—' => ascii(b'-'),
+        _ => None,
+    }
+}
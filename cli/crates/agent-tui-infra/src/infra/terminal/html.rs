@@ -0,0 +1,292 @@
+//! Screen HTML: the screen buffer as a self-contained `<pre>` fragment.
+//!
+//! Styles are inline so the fragment survives being pasted into reports that
+//! strip `<style>` blocks; each run of identically styled cells is one `<span>`.
+//! The padding column after a double-width character is left out, since the
+//! browser already draws the glyph two columns wide.
+
+use std::fmt::Write;
+
+use super::CellStyle;
+use super::CursorPosition;
+use super::image::Palette;
+use super::image::hex;
+use crate::domain::core::ScreenGrid;
+
+const FONT_STACK: &str = "ui-monospace,SFMono-Regular,Menlo,Consolas,monospace";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Run {
+    fg: [u8; 3],
+    bg: [u8; 3],
+    bold: bool,
+    underline: bool,
+    cursor: bool,
+}
+
+pub(super) fn render_html(
+    grid: &impl ScreenGrid,
+    cursor: CursorPosition,
+    palette: &Palette,
+) -> String {
+    let plain = Run {
+        fg: palette.foreground,
+        bg: palette.background,
+        bold: false,
+        underline: false,
+        cursor: false,
+    };
+    let lines: Vec<Vec<(char, Run)>> = (0..grid.rows())
+        .map(|row| {
+            let mut line: Vec<(char, Run)> = (0..grid.cols())
+                .filter(|&col| col == 0 || !grid.is_wide(row, col - 1))
+                .map(|col| {
+                    let (ch, style) = grid.cell(row, col).unwrap_or((' ', CellStyle::default()));
+                    let at_cursor = cursor.visible
+                        && usize::from(cursor.row) == row
+                        && usize::from(cursor.col) == col;
+                    (ch, run(style, at_cursor, palette))
+                })
+                .collect();
+            while line
+                .last()
+                .is_some_and(|&(ch, run)| ch == ' ' && run == plain)
+            {
+                line.pop();
+            }
+            line
+        })
+        .collect();
+    let height = lines
+        .iter()
+        .rposition(|line| !line.is_empty())
+        .map_or(0, |last| last + 1);
+
+    let mut html = format!(
+        r#"<pre class="agent-tui-screen" style="margin:0;padding:0.5em;background:{};color:{};font-family:{};line-height:1.2">"#,
+        hex(palette.background),
+        hex(palette.foreground),
+        FONT_STACK
+    );
+    for (index, line) in lines[..height].iter().enumerate() {
+        if index > 0 {
+            html.push('\n');
+        }
+        let mut start = 0;
+        while start < line.len() {
+            let style = line[start].1;
+            let end = (start..line.len())
+                .find(|&col| line[col].1 != style)
+                .unwrap_or(line.len());
+            let text: String = line[start..end].iter().map(|&(ch, _)| ch).collect();
+            write_run(&mut html, &text, style, &plain);
+            start = end;
+        }
+    }
+    html.push_str("</pre>\n");
+    html
+}
+
+fn run(style: CellStyle, cursor: bool, palette: &Palette) -> Run {
+    let mut fg = palette.resolve(style.fg_color, palette.foreground);
+    let mut bg = palette.resolve(style.bg_color, palette.background);
+    if style.inverse {
+        std::mem::swap(&mut fg, &mut bg);
+    }
+    if cursor {
+        (fg, bg) = (palette.background, palette.cursor);
+    }
+    Run {
+        fg,
+        bg,
+        bold: style.bold,
+        underline: style.underline,
+        cursor,
+    }
+}
+
+fn write_run(html: &mut String, text: &str, run: Run, plain: &Run) {
+    if run == *plain {
+        escape_into(html, text);
+        return;
+    }
+    let mut css = Vec::new();
+    if run.fg != plain.fg {
+        css.push(format!("color:{}", hex(run.fg)));
+    }
+    if run.bg != plain.bg {
+        css.push(format!("background:{}", hex(run.bg)));
+    }
+    if run.bold {
+        css.push("font-weight:bold".to_string());
+    }
+    if run.underline {
+        css.push("text-decoration:underline".to_string());
+    }
+    let class = if run.cursor { r#" class="cursor""# } else { "" };
+    let _ = write!(html, r#"<span{} style="{}">"#, class, css.join(";"));
+    escape_into(html, text);
+    html.push_str("</span>");
+}
+
+fn escape_into(html: &mut String, text: &str) {
+    for ch in text.chars() {
+        match ch {
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            _ => html.push(ch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::Color;
+    use crate::infra::terminal::vterm::Cell;
+    use crate::infra::terminal::vterm::ScreenBuffer;
+
+    fn buffer(lines: &[&str], style: impl Fn(usize, usize) -> CellStyle) -> ScreenBuffer {
+        ScreenBuffer {
+            cells: lines
+                .iter()
+                .enumerate()
+                .map(|(row, line)| {
+                    line.chars()
+                        .enumerate()
+                        .map(|(col, char)| Cell {
+                            char,
+                            style: style(row, col),
+                            wide: false,
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    const HIDDEN: CursorPosition = CursorPosition {
+        row: 0,
+        col: 0,
+        visible: false,
+    };
+
+    #[test]
+    fn test_html_escapes_markup_characters() {
+        let screen = buffer(&[r#"<b>"Tom" & 'Jerry'</b>"#], |_, _| CellStyle::default());
+
+        let html = render_html(&screen, HIDDEN, &Palette::default());
+
+        assert!(html.contains(r#">&lt;b&gt;&quot;Tom&quot; &amp; 'Jerry'&lt;/b&gt;</pre>"#));
+    }
+
+    #[test]
+    fn test_html_merges_cells_that_resolve_to_the_same_style() {
+        let palette = Palette::default();
+        // Indexed 1 and the same color as RGB render identically, so they
+        // share one span; the bold cell after them starts a new one.
+        let [r, g, b] = palette.colors[1];
+        let screen = buffer(&["abcd"], |_, col| CellStyle {
+            fg_color: Some(if col == 0 {
+                Color::Indexed(1)
+            } else {
+                Color::Rgb(r, g, b)
+            }),
+            bold: col == 3,
+            ..CellStyle::default()
+        });
+
+        let html = render_html(&screen, HIDDEN, &palette);
+
+        let red = hex(palette.colors[1]);
+        assert!(html.contains(&format!(
+            r#"<span style="color:{red}">abc</span><span style="color:{red};font-weight:bold">d</span></pre>"#
+        )));
+        assert_eq!(html.matches("<span").count(), 2);
+    }
+
+    #[test]
+    fn test_html_swaps_colors_for_inverse_and_cursor_cells() {
+        let palette = Palette::default();
+        let screen = buffer(&["ab c"], |_, col| CellStyle {
+            inverse: col < 2,
+            underline: col == 3,
+            ..CellStyle::default()
+        });
+        let cursor = CursorPosition {
+            row: 0,
+            col: 3,
+            visible: true,
+        };
+
+        let html = render_html(&screen, cursor, &palette);
+
+        assert!(html.contains(&format!(
+            r#"<span style="color:{};background:{}">ab</span> "#,
+            hex(palette.background),
+            hex(palette.foreground)
+        )));
+        assert!(html.contains(&format!(
+            r#"<span class="cursor" style="color:{};background:{};text-decoration:underline">c</span>"#,
+            hex(palette.background),
+            hex(palette.cursor)
+        )));
+        let hidden = render_html(&screen, HIDDEN, &palette);
+        assert!(!hidden.contains(r#"class="cursor""#));
+    }
+
+    #[test]
+    fn test_html_drops_padding_after_wide_characters() {
+        let cell = |char, wide| Cell {
+            char,
+            style: CellStyle::default(),
+            wide,
+        };
+        let screen = ScreenBuffer {
+            cells: vec![vec![
+                cell('漢', true),
+                cell(' ', false),
+                cell('字', true),
+                cell(' ', false),
+                cell('a', false),
+            ]],
+        };
+
+        let html = render_html(&screen, HIDDEN, &Palette::default());
+
+        assert!(html.contains(">漢字a</pre>"), "{html}");
+    }
+
+    #[test]
+    fn test_html_groups_styled_runs_and_marks_cursor() {
+        let screen = buffer(&["<a> ok  ", "$       ", "        "], |row, col| {
+            CellStyle {
+                fg_color: (row == 0 && col < 3).then_some(Color::Indexed(1)),
+                bold: row == 0 && col < 3,
+                ..CellStyle::default()
+            }
+        });
+        let palette = Palette::default();
+        let cursor = CursorPosition {
+            row: 1,
+            col: 2,
+            visible: true,
+        };
+
+        let html = render_html(&screen, cursor, &palette);
+
+        let red = hex(palette.colors[1]);
+        assert!(html.starts_with(r#"<pre class="agent-tui-screen""#));
+        assert!(html.contains(&format!(
+            r#"<span style="color:{red};font-weight:bold">&lt;a&gt;</span> ok"#
+        )));
+        assert!(html.contains(&format!(
+            "\n$ <span class=\"cursor\" style=\"color:{};background:{}\"> </span></pre>",
+            hex(palette.background),
+            hex(palette.cursor)
+        )));
+        assert_eq!(html.matches('\n').count(), 2);
+    }
+}
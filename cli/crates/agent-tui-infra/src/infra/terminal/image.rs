@@ -0,0 +1,327 @@
+//! Screen images: the screen buffer drawn as SVG or PNG (or handed to the HTML
+//! renderer).
+//!
+//! Both formats use the embedded bitmap font, so the output depends only on the
+//! cells, cursor and palette and is byte-for-byte stable across machines.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use tracing::debug;
+
+use super::CellStyle;
+use super::Color;
+use super::CursorPosition;
+use super::font::CELL_HEIGHT;
+use super::font::GLYPH_WIDTH;
+use super::font::glyph_rows;
+use super::html::render_html;
+use crate::domain::ImageFormat;
+use crate::domain::core::ScreenGrid;
+
+/// Image pixels per font pixel.
+const SCALE: usize = 2;
+
+type Rgb = [u8; 3];
+
+/// Resolved terminal colors: the 256-color table plus the defaults.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    pub colors: [Rgb; 256],
+    pub foreground: Rgb,
+    pub background: Rgb,
+    pub cursor: Rgb,
+}
+
+impl Palette {
+    pub(super) fn resolve(&self, color: Option<Color>, default: Rgb) -> Rgb {
+        match color {
+            None | Some(Color::Default) => default,
+            Some(Color::Indexed(index)) => self.colors[usize::from(index)],
+            Some(Color::Rgb(r, g, b)) => [r, g, b],
+        }
+    }
+}
+
+struct Paint {
+    rows: Option<[u8; CELL_HEIGHT]>,
+    fg: Rgb,
+    bg: Rgb,
+}
+
+pub fn render_image(
+    grid: &impl ScreenGrid,
+    cursor: CursorPosition,
+    palette: &Palette,
+    format: ImageFormat,
+) -> Vec<u8> {
+    match format {
+        ImageFormat::Svg => render_svg(&paint(grid, cursor, palette), palette).into_bytes(),
+        ImageFormat::Png => render_png(&paint(grid, cursor, palette)),
+        ImageFormat::Html => render_html(grid, cursor, palette).into_bytes(),
+    }
+}
+
+fn paint(grid: &impl ScreenGrid, cursor: CursorPosition, palette: &Palette) -> Vec<Vec<Paint>> {
+    (0..grid.rows())
+        .map(|row| {
+            (0..grid.cols())
+                .map(|col| {
+                    let (ch, style) = grid.cell(row, col).unwrap_or((' ', CellStyle::default()));
+                    let mut fg = palette.resolve(style.fg_color, palette.foreground);
+                    let mut bg = palette.resolve(style.bg_color, palette.background);
+                    if style.inverse {
+                        std::mem::swap(&mut fg, &mut bg);
+                    }
+                    if cursor.visible
+                        && usize::from(cursor.row) == row
+                        && usize::from(cursor.col) == col
+                    {
+                        (fg, bg) = (palette.background, palette.cursor);
+                    }
+                    let mut rows = glyph_rows(ch);
+                    if style.bold
+                        && let Some(rows) = rows.as_mut()
+                    {
+                        rows.iter_mut().for_each(|row| *row |= *row << 1);
+                    }
+                    if style.underline {
+                        rows.get_or_insert([0; CELL_HEIGHT])[CELL_HEIGHT - 1] = 0xFF;
+                    }
+                    Paint { rows, fg, bg }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub(super) fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Glyphs are defined once as pixel-run paths and placed with `<use>`; cell
+/// backgrounds other than the default are merged into one rect per run.
+fn render_svg(cells: &[Vec<Paint>], palette: &Palette) -> String {
+    let cols = cells.first().map_or(0, Vec::len);
+    let (width, height) = (cols * GLYPH_WIDTH, cells.len() * CELL_HEIGHT);
+    let mut glyphs: BTreeMap<String, String> = BTreeMap::new();
+    let mut body = String::new();
+
+    for (row, line) in cells.iter().enumerate() {
+        let y = row * CELL_HEIGHT;
+        let mut col = 0;
+        while col < line.len() {
+            let bg = line[col].bg;
+            let end = (col..line.len())
+                .find(|&c| line[c].bg != bg)
+                .unwrap_or(line.len());
+            if bg != palette.background {
+                let _ = writeln!(
+                    body,
+                    r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                    col * GLYPH_WIDTH,
+                    y,
+                    (end - col) * GLYPH_WIDTH,
+                    CELL_HEIGHT,
+                    hex(bg)
+                );
+            }
+            col = end;
+        }
+        for (col, cell) in line.iter().enumerate() {
+            let Some(rows) = cell.rows else {
+                continue;
+            };
+            let id: String = rows.iter().map(|row| format!("{:02x}", row)).collect();
+            glyphs
+                .entry(id.clone())
+                .or_insert_with(|| glyph_path(&rows));
+            let _ = writeln!(
+                body,
+                r##"<use href="#g{}" x="{}" y="{}" fill="{}"/>"##,
+                id,
+                col * GLYPH_WIDTH,
+                y,
+                hex(cell.fg)
+            );
+        }
+    }
+
+    let mut svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}" shape-rendering="crispEdges">"#,
+        width * SCALE,
+        height * SCALE,
+        width,
+        height
+    );
+    svg.push_str("\n<defs>\n");
+    for (id, path) in &glyphs {
+        let _ = writeln!(svg, r#"<path id="g{}" d="{}"/>"#, id, path);
+    }
+    svg.push_str("</defs>\n");
+    let _ = writeln!(
+        svg,
+        r#"<rect width="{}" height="{}" fill="{}"/>"#,
+        width,
+        height,
+        hex(palette.background)
+    );
+    svg.push_str(&body);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn glyph_path(rows: &[u8; CELL_HEIGHT]) -> String {
+    let mut path = String::new();
+    for (y, bits) in rows.iter().enumerate() {
+        let mut x = 0;
+        while x < GLYPH_WIDTH {
+            if bits & (1 << x) == 0 {
+                x += 1;
+                continue;
+            }
+            let start = x;
+            while x < GLYPH_WIDTH && bits & (1 << x) != 0 {
+                x += 1;
+            }
+            let _ = write!(path, "M{} {}h{}v1h-{}z", start, y, x - start, x - start);
+        }
+    }
+    path
+}
+
+fn render_png(cells: &[Vec<Paint>]) -> Vec<u8> {
+    let cols = cells.first().map_or(0, Vec::len);
+    let (width, height) = (
+        cols * GLYPH_WIDTH * SCALE,
+        cells.len() * CELL_HEIGHT * SCALE,
+    );
+    let mut pixels = vec![0u8; width * height * 3];
+
+    for (row, line) in cells.iter().enumerate() {
+        for (col, cell) in line.iter().enumerate() {
+            for gy in 0..CELL_HEIGHT {
+                let bits = cell.rows.map_or(0, |rows| rows[gy]);
+                for gx in 0..GLYPH_WIDTH {
+                    let color = if bits & (1 << gx) != 0 {
+                        cell.fg
+                    } else {
+                        cell.bg
+                    };
+                    for dy in 0..SCALE {
+                        let y = (row * CELL_HEIGHT + gy) * SCALE + dy;
+                        let x = (col * GLYPH_WIDTH + gx) * SCALE;
+                        let start = (y * width + x) * 3;
+                        for pixel in pixels[start..start + SCALE * 3].chunks_exact_mut(3) {
+                            pixel.copy_from_slice(&color);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    let result = (|| {
+        let mut encoder = png::Encoder::new(
+            &mut out,
+            u32::try_from(width).unwrap_or(u32::MAX),
+            u32::try_from(height).unwrap_or(u32::MAX),
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::Balanced);
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(&pixels)?;
+        writer.finish()
+    })();
+    if let Err(err) = result {
+        debug!(error = %err, "Failed to encode screen image as PNG");
+        return Vec::new();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::terminal::vterm::Cell;
+    use crate::infra::terminal::vterm::ScreenBuffer;
+
+    fn buffer(lines: &[&str], style: impl Fn(usize, usize) -> CellStyle) -> ScreenBuffer {
+        ScreenBuffer {
+            cells: lines
+                .iter()
+                .enumerate()
+                .map(|(row, line)| {
+                    line.chars()
+                        .enumerate()
+                        .map(|(col, char)| Cell {
+                            char,
+                            style: style(row, col),
+                            wide: false,
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    fn cursor(row: u16, col: u16) -> CursorPosition {
+        CursorPosition {
+            row,
+            col,
+            visible: true,
+        }
+    }
+
+    #[test]
+    fn test_png_has_scaled_size_and_is_deterministic() {
+        let screen = buffer(&["┌──┐", "│ok│", "└──┘"], |_, _| {
+            CellStyle::default()
+        });
+        let palette = Palette::default();
+
+        let first = render_image(&screen, cursor(1, 1), &palette, ImageFormat::Png);
+        let second = render_image(&screen, cursor(1, 1), &palette, ImageFormat::Png);
+
+        assert_eq!(&first[1..4], b"PNG");
+        let width = u32::from_be_bytes([first[16], first[17], first[18], first[19]]);
+        let height = u32::from_be_bytes([first[20], first[21], first[22], first[23]]);
+        assert_eq!((width, height), (4 * 8 * 2, 3 * 10 * 2));
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_svg_draws_highlight_cursor_and_shares_glyphs() {
+        let screen = buffer(&["aa", "b "], |row, _| CellStyle {
+            inverse: row == 0,
+            ..CellStyle::default()
+        });
+        let palette = Palette::default();
+
+        let svg = String::from_utf8(render_image(
+            &screen,
+            cursor(1, 1),
+            &palette,
+            ImageFormat::Svg,
+        ))
+        .unwrap();
+
+        assert!(
+            svg.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" width="32" height="40""#)
+        );
+        let highlight = format!(
+            r#"<rect x="0" y="0" width="16" height="10" fill="{}"/>"#,
+            hex(palette.foreground)
+        );
+        assert!(svg.contains(&highlight));
+        let cursor = format!(
+            r#"<rect x="8" y="10" width="8" height="10" fill="{}"/>"#,
+            hex(palette.cursor)
+        );
+        assert!(svg.contains(&cursor));
+        assert_eq!(svg.matches("<path ").count(), 2);
+        assert_eq!(svg.matches("<use ").count(), 3);
+    }
+}
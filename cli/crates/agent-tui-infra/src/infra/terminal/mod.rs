@@ -3,14 +3,23 @@
 //! Terminal/PTY infrastructure and rendering.
 
 pub mod error;
+mod font;
+mod html;
+mod image;
 mod pty;
 mod render;
+mod replay;
 mod vterm;
 
+pub use image::Palette;
+pub use image::render_image;
+pub use pty::PTY_TERM;
 pub use pty::PtyHandle;
 pub(crate) use pty::ReadEvent;
 pub use pty::key_to_escape_sequence;
 pub use render::render_screen;
+pub(crate) use replay::Replay;
+pub(crate) use replay::ReplayHandle;
 pub use vterm::CursorPosition;
 pub use vterm::ScreenBuffer;
 pub use vterm::VirtualTerminal;
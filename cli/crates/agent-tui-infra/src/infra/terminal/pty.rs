@@ -26,6 +26,7 @@ use tracing::debug;
 use tracing::warn;
 
 use crate::common::mutex_lock_or_recover;
+use crate::domain::session_types::TtyState;
 use crate::usecases::ports::SpawnErrorKind;
 
 pub use crate::infra::terminal::error::PtyError;
@@ -45,6 +46,9 @@ const TERMINATE_TIMEOUT: Duration = Duration::from_millis(500);
 const KILL_TIMEOUT: Duration = Duration::from_millis(500);
 const KILL_POLL_INTERVAL: Duration = Duration::from_millis(25);
 
+/// `TERM` every spawned process gets, whatever the spawn environment says.
+pub const PTY_TERM: &str = "xterm-256color";
+
 impl Drop for PtyHandle {
     fn drop(&mut self) {
         if self.is_running() {
@@ -89,7 +93,7 @@ impl PtyHandle {
             }
         }
 
-        cmd.env("TERM", "xterm-256color");
+        cmd.env("TERM", PTY_TERM);
 
         let child = pair.slave.spawn_command(cmd).map_err(|e| {
             let kind = if let Some(io_err) = e.downcast_ref::<io::Error>() {
@@ -141,6 +145,15 @@ impl PtyHandle {
             .unwrap_or(false)
     }
 
+    /// Exit code of the child once it has exited.
+    pub fn exit_code(&mut self) -> Option<i32> {
+        self.child
+            .try_wait()
+            .ok()
+            .flatten()
+            .map(|status| i32::try_from(status.exit_code()).unwrap_or(i32::MAX))
+    }
+
     pub fn write(&self, data: &[u8]) -> Result<(), PtyError> {
         if data.is_empty() {
             return Ok(());
@@ -221,6 +234,32 @@ impl PtyHandle {
         }
     }
 
+    pub fn tty_state(&self) -> Option<TtyState> {
+        #[cfg(unix)]
+        {
+            let fd = self.master.as_raw_fd()?;
+            // SAFETY: `termios` is plain old data; an all-zero value is valid and is
+            // fully overwritten by `tcgetattr` on success.
+            let mut termios: libc::termios = unsafe { std::mem::zeroed() };
+            // SAFETY: `fd` is the PTY master owned by `self.master`, and `termios`
+            // is a valid pointer for the duration of the call.
+            let rc = unsafe { libc::tcgetattr(fd, &mut termios) };
+            if rc != 0 {
+                debug!(error = %io::Error::last_os_error(), "tcgetattr on PTY master failed");
+                return None;
+            }
+            Some(TtyState {
+                echo: termios.c_lflag & libc::ECHO != 0,
+                icanon: termios.c_lflag & libc::ICANON != 0,
+                isig: termios.c_lflag & libc::ISIG != 0,
+            })
+        }
+        #[cfg(not(unix))]
+        {
+            None
+        }
+    }
+
     pub fn try_read(&mut self, buf: &mut [u8], timeout_ms: i32) -> Result<usize, PtyError> {
         if buf.is_empty() {
             return Ok(0);
@@ -374,6 +413,31 @@ impl PtyHandle {
         Ok(())
     }
 
+    /// Send `signal` to the child's process group, or to the child alone when
+    /// it does not lead its own group.
+    pub fn signal(&mut self, signal: libc::c_int) -> Result<(), PtyError> {
+        let signal_error = |err: io::Error| PtyError::Signal {
+            reason: err.to_string(),
+            source: Some(err),
+        };
+        let pid = self.child.process_id().ok_or_else(|| PtyError::Signal {
+            reason: "child process has exited".to_string(),
+            source: None,
+        })?;
+        if can_signal_process_group(pid).map_err(signal_error)? {
+            return signal_process_group(pid, signal).map_err(signal_error);
+        }
+        let pid_t: libc::pid_t = pid.try_into().map_err(|_| PtyError::Signal {
+            reason: "invalid pid".to_string(),
+            source: None,
+        })?;
+        // SAFETY: `kill` is safe with a valid pid_t and signal number.
+        if unsafe { libc::kill(pid_t, signal) } == -1 {
+            return Err(signal_error(io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
     pub(crate) fn take_read_rx(&mut self) -> Option<channel::Receiver<ReadEvent>> {
         self.read_rx.take()
     }
@@ -399,6 +463,7 @@ impl PtyHandle {
     fn handle_read_event(&mut self, event: ReadEvent) {
         match event {
             ReadEvent::Data(data) => self.read_buffer.extend(data),
+            ReadEvent::Resize(..) => {}
             ReadEvent::Eof => self.read_closed = true,
             ReadEvent::Error(error) => {
                 self.read_closed = true;
@@ -444,6 +509,8 @@ fn can_signal_process_group(pid: u32) -> io::Result<bool> {
 
 pub(crate) enum ReadEvent {
     Data(Vec<u8>),
+    /// Recorded terminal resize; only replays produce it.
+    Resize(u16, u16),
     Eof,
     Error(String),
 }
@@ -598,6 +665,37 @@ mod tests {
         assert_eq!(key_to_escape_sequence("a"), Some(vec![b'a']));
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn tty_state_reads_master_termios() {
+        let mut handle =
+            PtyHandle::spawn("sleep", &["5".to_string()], None, None, 80, 24).expect("spawn pty");
+        let state = handle.tty_state().expect("termios should be readable");
+        assert!(state.icanon, "fresh PTY should start in canonical mode");
+        assert!(state.echo, "fresh PTY should start with echo enabled");
+        let _ = handle.kill();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn signal_reaches_child_with_isig_disabled() {
+        let mut handle = PtyHandle::spawn(
+            "sh",
+            &["-c".to_string(), "stty -isig; exec sleep 5".to_string()],
+            None,
+            None,
+            80,
+            24,
+        )
+        .expect("spawn pty");
+        std::thread::park_timeout(Duration::from_millis(200));
+        handle.signal(libc::SIGTERM).expect("signal child");
+        assert!(
+            handle.wait_for_exit(Duration::from_secs(2)),
+            "SIGTERM should end the child even when the line discipline ignores ^C"
+        );
+    }
+
     #[cfg(unix)]
     #[test]
     fn can_signal_process_group_is_false_for_non_group_leader() {
@@ -0,0 +1,412 @@
+//! Playback of recorded terminal output.
+//!
+//! A replay stands in for a PTY: a player thread feeds the recorded output into
+//! the same `ReadEvent` channel a live process would, so the session pump, the
+//! virtual terminal and the live preview cannot tell the difference.
+//!
+//! Asciinema v2 casts keep their timing and `r` (resize) events. Raw byte logs
+//! have no timing and are split into lines so `--step` has something to step
+//! through. A replay that has played everything stays open like an idle app,
+//! so its final screen can still be inspected; only `kill` ends it.
+
+use std::path::Path;
+use std::time::Duration;
+use std::time::Instant;
+
+use crossbeam_channel as channel;
+use serde_json::Value;
+use tracing::debug;
+
+use crate::domain::ReplayFormat;
+use crate::domain::ReplayPacing;
+use crate::domain::ReplaySummary;
+use crate::infra::terminal::ReadEvent;
+
+const REPLAY_CHANNEL_CAPACITY: usize = 256;
+/// Steps queued ahead of playback; input beyond this is dropped.
+const REPLAY_CONTROL_CAPACITY: usize = 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+enum ReplayEventKind {
+    Output(Vec<u8>),
+    /// A recorded keystroke; in step mode each one waits for the agent's input.
+    Input,
+    Resize(u16, u16),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ReplayEvent {
+    at: Duration,
+    kind: ReplayEventKind,
+}
+
+/// A parsed recording, ready to play.
+#[derive(Debug)]
+pub(crate) struct Replay {
+    format: ReplayFormat,
+    cols: u16,
+    rows: u16,
+    events: Vec<ReplayEvent>,
+}
+
+impl Replay {
+    /// Read `path` as a cast if its first line is an asciicast header and as a
+    /// raw byte log otherwise; `cols`/`rows` only size raw logs.
+    pub(crate) fn load(path: &Path, cols: u16, rows: u16) -> Result<Self, String> {
+        let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+        Self::parse(&bytes, cols, rows)
+    }
+
+    fn parse(bytes: &[u8], cols: u16, rows: u16) -> Result<Self, String> {
+        let first_line = bytes.split(|&b| b == b'\n').next().unwrap_or_default();
+        let header = serde_json::from_slice::<Value>(first_line)
+            .ok()
+            .filter(|header| header.get("version").is_some());
+        match header {
+            Some(header) => {
+                let text = std::str::from_utf8(bytes)
+                    .map_err(|err| format!("cast is not valid UTF-8: {err}"))?;
+                Self::parse_cast(&header, text)
+            }
+            None => Ok(Self::parse_raw(bytes, cols, rows)),
+        }
+    }
+
+    fn parse_cast(header: &Value, text: &str) -> Result<Self, String> {
+        let version = header.get("version").and_then(Value::as_u64);
+        if version != Some(2) {
+            return Err(format!(
+                "unsupported asciicast version {}; only version 2 can be replayed",
+                header["version"]
+            ));
+        }
+        let dimension = |key: &str| {
+            header
+                .get(key)
+                .and_then(Value::as_u64)
+                .and_then(|value| u16::try_from(value).ok())
+                .filter(|&value| value > 0)
+                .ok_or_else(|| format!("header has no valid {key}"))
+        };
+        let (cols, rows) = (dimension("width")?, dimension("height")?);
+
+        let mut events = Vec::new();
+        for (index, line) in text.lines().enumerate().skip(1) {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let event =
+                parse_cast_event(line).map_err(|reason| format!("line {}: {reason}", index + 1))?;
+            if let Some(event) = event {
+                events.push(event);
+            }
+        }
+        Ok(Self {
+            format: ReplayFormat::Cast,
+            cols,
+            rows,
+            events,
+        })
+    }
+
+    fn parse_raw(bytes: &[u8], cols: u16, rows: u16) -> Self {
+        let events = bytes
+            .split_inclusive(|&b| b == b'\n')
+            .map(|line| ReplayEvent {
+                at: Duration::ZERO,
+                kind: ReplayEventKind::Output(line.to_vec()),
+            })
+            .collect();
+        Self {
+            format: ReplayFormat::Raw,
+            cols,
+            rows,
+            events,
+        }
+    }
+
+    pub(crate) fn summary(&self) -> ReplaySummary {
+        let events = self
+            .events
+            .iter()
+            .filter(|event| matches!(event.kind, ReplayEventKind::Output(_)))
+            .count();
+        let duration = self.events.iter().map(|event| event.at).max();
+        ReplaySummary {
+            format: self.format,
+            cols: self.cols,
+            rows: self.rows,
+            events: events as u64,
+            duration_ms: duration
+                .map(|at| u64::try_from(at.as_millis()).unwrap_or(u64::MAX))
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Parse one `[time, code, data]` line; unknown codes (e.g. `m` markers) are skipped.
+fn parse_cast_event(line: &str) -> Result<Option<ReplayEvent>, String> {
+    let value: Value = serde_json::from_str(line).map_err(|err| err.to_string())?;
+    let (Some(time), Some(code), Some(data)) = (
+        value.get(0).and_then(Value::as_f64),
+        value.get(1).and_then(Value::as_str),
+        value.get(2).and_then(Value::as_str),
+    ) else {
+        return Err("expected [time, code, data]".to_string());
+    };
+    let at = Duration::try_from_secs_f64(time).map_err(|_| format!("invalid time {time}"))?;
+    let kind = match code {
+        "o" => ReplayEventKind::Output(data.as_bytes().to_vec()),
+        "i" => ReplayEventKind::Input,
+        "r" => {
+            let size = data
+                .split_once('x')
+                .and_then(|(cols, rows)| Some((cols.parse().ok()?, rows.parse().ok()?)))
+                .filter(|&(cols, rows)| cols > 0 && rows > 0);
+            let Some((cols, rows)) = size else {
+                return Err(format!("invalid resize {data:?}"));
+            };
+            ReplayEventKind::Resize(cols, rows)
+        }
+        _ => return Ok(None),
+    };
+    Ok(Some(ReplayEvent { at, kind }))
+}
+
+enum ReplayControl {
+    Step,
+    Stop,
+}
+
+/// A running playback; the counterpart of `PtyHandle` for replay sessions.
+pub(crate) struct ReplayHandle {
+    read_rx: Option<channel::Receiver<ReadEvent>>,
+    control: channel::Sender<ReplayControl>,
+    stopped: bool,
+    pacing: ReplayPacing,
+}
+
+impl ReplayHandle {
+    pub(crate) fn start(replay: Replay, pacing: ReplayPacing) -> Self {
+        let (read_tx, read_rx) = channel::bounded(REPLAY_CHANNEL_CAPACITY);
+        let (control_tx, control_rx) = channel::bounded(REPLAY_CONTROL_CAPACITY);
+        let player = Player {
+            events: replay.events,
+            pacing,
+            read_tx: read_tx.clone(),
+            control: control_rx,
+        };
+        let span = tracing::debug_span!("replay_player");
+        let builder = std::thread::Builder::new().name("replay-player".to_string());
+        let spawned = builder.spawn(move || {
+            let _guard = span.enter();
+            player.run();
+        });
+        if let Err(err) = &spawned {
+            let _ = read_tx.send(ReadEvent::Error(err.to_string()));
+        }
+        Self {
+            read_rx: Some(read_rx),
+            control: control_tx,
+            stopped: spawned.is_err(),
+            pacing,
+        }
+    }
+
+    pub(crate) fn is_running(&self) -> bool {
+        !self.stopped
+    }
+
+    /// Input is discarded, except that in step mode it advances the playback.
+    pub(crate) fn input(&self, data: &[u8]) {
+        if self.pacing == ReplayPacing::Step && !data.is_empty() {
+            let _ = self.control.try_send(ReplayControl::Step);
+        }
+    }
+
+    pub(crate) fn take_read_rx(&mut self) -> Option<channel::Receiver<ReadEvent>> {
+        self.read_rx.take()
+    }
+
+    pub(crate) fn stop(&mut self) {
+        self.stopped = true;
+        let _ = self.control.try_send(ReplayControl::Stop);
+    }
+}
+
+struct Player {
+    events: Vec<ReplayEvent>,
+    pacing: ReplayPacing,
+    read_tx: channel::Sender<ReadEvent>,
+    control: channel::Receiver<ReplayControl>,
+}
+
+impl Player {
+    fn run(self) {
+        let started = Instant::now();
+        let gated_by_input = self
+            .events
+            .iter()
+            .any(|event| event.kind == ReplayEventKind::Input);
+        let mut played_output = false;
+        for event in &self.events {
+            let proceed = match self.pacing {
+                ReplayPacing::Speed(speed) => self.wait_until(started + event.at.div_f64(speed)),
+                ReplayPacing::Step => match event.kind {
+                    ReplayEventKind::Input if gated_by_input => self.wait_for_step(),
+                    ReplayEventKind::Output(_) if !gated_by_input && played_output => {
+                        self.wait_for_step()
+                    }
+                    _ => true,
+                },
+            };
+            if !proceed {
+                debug!("Replay stopped");
+                return;
+            }
+            let sent = match &event.kind {
+                ReplayEventKind::Output(data) => {
+                    played_output = true;
+                    self.read_tx.send(ReadEvent::Data(data.clone()))
+                }
+                ReplayEventKind::Resize(cols, rows) => {
+                    self.read_tx.send(ReadEvent::Resize(*cols, *rows))
+                }
+                ReplayEventKind::Input => Ok(()),
+            };
+            if sent.is_err() {
+                return;
+            }
+        }
+        debug!("Replay finished");
+    }
+
+    /// Returns false once the replay is stopped or dropped.
+    fn wait_until(&self, deadline: Instant) -> bool {
+        loop {
+            match self.control.recv_deadline(deadline) {
+                Ok(ReplayControl::Step) => continue,
+                Ok(ReplayControl::Stop) | Err(channel::RecvTimeoutError::Disconnected) => {
+                    return false;
+                }
+                Err(channel::RecvTimeoutError::Timeout) => return true,
+            }
+        }
+    }
+
+    fn wait_for_step(&self) -> bool {
+        matches!(self.control.recv(), Ok(ReplayControl::Step))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outputs(replay: &Replay) -> Vec<&[u8]> {
+        replay
+            .events
+            .iter()
+            .filter_map(|event| match &event.kind {
+                ReplayEventKind::Output(data) => Some(data.as_slice()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn drain(rx: &channel::Receiver<ReadEvent>) -> Vec<u8> {
+        let mut out = Vec::new();
+        while let Ok(event) = rx.recv_timeout(Duration::from_millis(200)) {
+            match event {
+                ReadEvent::Data(data) => out.extend(data),
+                ReadEvent::Eof | ReadEvent::Error(_) => break,
+                ReadEvent::Resize(..) => {}
+            }
+        }
+        out
+    }
+
+    const CAST: &str = concat!(
+        r#"{"version": 2, "width": 40, "height": 10}"#,
+        "\n",
+        r#"[0.0, "o", "$ "]"#,
+        "\n",
+        r#"[0.5, "i", "ls\r"]"#,
+        "\n",
+        r#"[0.6, "o", "ls\r\nfile\r\n"]"#,
+        "\n",
+        r#"[0.7, "m", "marker"]"#,
+        "\n",
+        r#"[1.25, "r", "80x24"]"#,
+        "\n"
+    );
+
+    #[test]
+    fn test_parses_cast_header_and_events() {
+        let replay = Replay::parse(CAST.as_bytes(), 120, 40).unwrap();
+
+        assert_eq!(
+            (replay.format, replay.cols, replay.rows),
+            (ReplayFormat::Cast, 40, 10)
+        );
+        assert_eq!(outputs(&replay), vec![&b"$ "[..], &b"ls\r\nfile\r\n"[..]]);
+        assert_eq!(
+            replay.events.last().unwrap().kind,
+            ReplayEventKind::Resize(80, 24)
+        );
+        let summary = replay.summary();
+        assert_eq!((summary.events, summary.duration_ms), (2, 1250));
+    }
+
+    #[test]
+    fn test_rejects_malformed_cast_lines() {
+        let text = format!(
+            "{}\n[0.1, \"o\"]\n",
+            r#"{"version": 2, "width": 40, "height": 10}"#
+        );
+        let err = Replay::parse(text.as_bytes(), 80, 24).unwrap_err();
+        assert!(err.starts_with("line 2:"), "{err}");
+
+        let err = Replay::parse(br#"{"version": 3, "term": {}}"#, 80, 24).unwrap_err();
+        assert!(err.contains("version 3"), "{err}");
+    }
+
+    #[test]
+    fn test_raw_logs_play_line_by_line() {
+        let replay = Replay::parse(b"one\r\ntwo\r\nthree", 100, 30).unwrap();
+
+        assert_eq!(
+            (replay.format, replay.cols, replay.rows),
+            (ReplayFormat::Raw, 100, 30)
+        );
+        assert_eq!(
+            outputs(&replay),
+            vec![&b"one\r\n"[..], &b"two\r\n"[..], &b"three"[..]]
+        );
+    }
+
+    #[test]
+    fn test_timed_replay_plays_everything_and_stays_open_until_stopped() {
+        let replay = Replay::parse(CAST.as_bytes(), 80, 24).unwrap();
+        let mut handle = ReplayHandle::start(replay, ReplayPacing::Speed(100.0));
+        let rx = handle.take_read_rx().unwrap();
+
+        assert_eq!(drain(&rx), b"$ ls\r\nfile\r\n");
+        assert!(handle.is_running());
+        handle.stop();
+        assert!(!handle.is_running());
+    }
+
+    #[test]
+    fn test_step_replay_waits_for_input_at_recorded_keystrokes() {
+        let replay = Replay::parse(CAST.as_bytes(), 80, 24).unwrap();
+        let mut handle = ReplayHandle::start(replay, ReplayPacing::Step);
+        let rx = handle.take_read_rx().unwrap();
+
+        assert_eq!(drain(&rx), b"$ ");
+        assert!(handle.is_running());
+
+        handle.input(b"l");
+        assert_eq!(drain(&rx), b"ls\r\nfile\r\n");
+    }
+}
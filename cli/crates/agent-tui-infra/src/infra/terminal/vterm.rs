@@ -14,14 +14,19 @@ use tattoy_wezterm_term::color::ColorPalette;
 
 use crate::domain::core::CellStyle;
 use crate::domain::core::Color;
+use crate::domain::core::LogicalScreen;
 use crate::domain::core::ScreenGrid;
 use crate::domain::core::ScreenSnapshot;
 use crate::usecases::ports::TerminalEngine;
 
+use super::image::Palette;
+
 #[derive(Debug, Clone)]
 pub struct Cell {
     pub char: char,
     pub style: CellStyle,
+    /// Double-width character that also covers the next column.
+    pub wide: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -44,6 +49,13 @@ impl ScreenGrid for ScreenBuffer {
             .and_then(|r| r.get(col))
             .map(|c| (c.char, c.style))
     }
+
+    fn is_wide(&self, row: usize, col: usize) -> bool {
+        self.cells
+            .get(row)
+            .and_then(|r| r.get(col))
+            .is_some_and(|c| c.wide)
+    }
 }
 
 pub use crate::domain::core::CursorPosition;
@@ -138,6 +150,30 @@ impl VirtualTerminal {
         output
     }
 
+    /// Visible rows with soft-wrapped rows joined into logical lines.
+    pub fn logical_screen(&self) -> LogicalScreen {
+        let buffer = self.screen_buffer();
+        let (cols, _) = self.size();
+        let rows = buffer
+            .cells
+            .iter()
+            .map(|row| row.iter().map(|cell| cell.char).collect::<String>())
+            .zip(self.wrapped_rows());
+        LogicalScreen::from_rows(cols, rows)
+    }
+
+    fn wrapped_rows(&self) -> Vec<bool> {
+        let screen = self.terminal.screen();
+        let rows = screen.physical_rows;
+        let total_lines = screen.scrollback_rows();
+        let start = total_lines.saturating_sub(rows);
+        screen
+            .lines_in_phys_range(start..start + rows)
+            .iter()
+            .map(|line| line.last_cell_was_wrapped())
+            .collect()
+    }
+
     pub fn screen_buffer(&self) -> ScreenBuffer {
         let screen = self.terminal.screen();
         let rows = screen.physical_rows;
@@ -153,6 +189,7 @@ impl VirtualTerminal {
             row_cells.resize_with(cols, || Cell {
                 char: ' ',
                 style: CellStyle::default(),
+                wide: false,
             });
 
             for cell in line.visible_cells() {
@@ -162,7 +199,11 @@ impl VirtualTerminal {
                 }
                 let ch = cell.str().chars().next().unwrap_or(' ');
                 let style = style_from_attrs(cell.attrs());
-                row_cells[idx] = Cell { char: ch, style };
+                row_cells[idx] = Cell {
+                    char: ch,
+                    style,
+                    wide: cell.width() > 1,
+                };
             }
 
             cells.push(row_cells);
@@ -186,6 +227,11 @@ impl VirtualTerminal {
         }
     }
 
+    /// Colors currently in effect, including any the program set with OSC 4/10/11.
+    pub fn palette(&self) -> Palette {
+        Palette::from(&self.terminal.palette())
+    }
+
     pub fn resize(&mut self, cols: u16, rows: u16) {
         let size = TerminalSize {
             rows: rows as usize,
@@ -239,6 +285,27 @@ impl TerminalEngine for VirtualTerminal {
     }
 }
 
+impl From<&ColorPalette> for Palette {
+    fn from(palette: &ColorPalette) -> Self {
+        let rgb = |color: &tattoy_wezterm_term::color::SrgbaTuple| {
+            let (r, g, b, _) = color.as_rgba_u8();
+            [r, g, b]
+        };
+        Self {
+            colors: palette.colors.0.each_ref().map(rgb),
+            foreground: rgb(&palette.foreground),
+            background: rgb(&palette.background),
+            cursor: rgb(&palette.cursor_bg),
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self::from(&ColorPalette::default())
+    }
+}
+
 fn style_from_attrs(attrs: &tattoy_wezterm_term::CellAttributes) -> CellStyle {
     let bold = matches!(attrs.intensity(), Intensity::Bold);
     let underline = !matches!(attrs.underline(), Underline::None);
@@ -272,6 +339,16 @@ fn convert_color(color: ColorAttribute) -> Option<Color> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_screen_buffer_marks_wide_characters() {
+        let mut term = VirtualTerminal::new(10, 2);
+        term.process("漢a".as_bytes());
+        let buffer = term.screen_buffer();
+        assert!(buffer.is_wide(0, 0));
+        assert!(!buffer.is_wide(0, 1));
+        assert_eq!(buffer.cell(0, 2).map(|(ch, _)| ch), Some('a'));
+    }
+
     #[test]
     fn test_basic_terminal() {
         let mut term = VirtualTerminal::new(80, 24);
@@ -298,4 +375,24 @@ mod tests {
         assert!(buffer.cells[0][0].style.bold);
         assert_eq!(buffer.cells[0][0].char, 'B');
     }
+
+    #[test]
+    fn test_logical_screen_joins_soft_wrapped_rows() {
+        let mut term = VirtualTerminal::new(10, 5);
+        term.process(b"/usr/local/bin/tool\r\nnext");
+
+        assert_eq!(term.screen_text(), "/usr/local\n/bin/tool\nnext");
+        let logical = term.logical_screen();
+        assert_eq!(logical.text(), "/usr/local/bin/tool\nnext");
+        assert_eq!((logical.lines[0].start_row, logical.lines[0].rows), (0, 2));
+        assert_eq!(logical.lines[1].start_row, 2);
+    }
+
+    #[test]
+    fn test_logical_screen_keeps_hard_newlines_at_full_width() {
+        let mut term = VirtualTerminal::new(10, 5);
+        term.process(b"0123456789\r\nnext");
+
+        assert_eq!(term.logical_screen().text(), "0123456789\nnext");
+    }
 }
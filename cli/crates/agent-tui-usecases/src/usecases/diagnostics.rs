@@ -2,8 +2,10 @@
 
 use std::sync::Arc;
 
+use crate::domain::SessionInput;
 use crate::domain::TerminalWriteInput;
 use crate::domain::TerminalWriteOutput;
+use crate::domain::TtyStateOutput;
 use crate::usecases::ports::SessionError;
 use crate::usecases::ports::SessionRepository;
 
@@ -34,12 +36,38 @@ impl<R: SessionRepository> TerminalWriteUseCase for TerminalWriteUseCaseImpl<R>
     }
 }
 
+pub trait TtyStateUseCase: Send + Sync {
+    fn execute(&self, input: SessionInput) -> Result<TtyStateOutput, SessionError>;
+}
+
+pub struct TtyStateUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> TtyStateUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> TtyStateUseCase for TtyStateUseCaseImpl<R> {
+    fn execute(&self, input: SessionInput) -> Result<TtyStateOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        Ok(TtyStateOutput {
+            session_id: session.session_id(),
+            tty_state: session.tty_state(),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     use crate::domain::SessionId;
+    use crate::domain::TtyState;
     use crate::test_support::MockError;
+    use crate::test_support::MockSession;
     use crate::test_support::MockSessionRepository;
     #[test]
     fn test_terminal_write_usecase_returns_error_when_no_active_session() {
@@ -72,4 +100,28 @@ mod tests {
         let result = usecase.execute(input);
         assert!(matches!(result, Err(SessionError::NotFound(_))));
     }
+
+    #[test]
+    fn test_tty_state_usecase_reports_session_termios() {
+        let session = MockSession::builder("tty")
+            .with_tty_state(TtyState {
+                echo: false,
+                icanon: true,
+                isig: true,
+            })
+            .build();
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = TtyStateUseCaseImpl::new(repo);
+
+        let output = usecase.execute(SessionInput { session_id: None }).unwrap();
+
+        assert_eq!(output.session_id.as_str(), "tty");
+        let state = output.tty_state.unwrap();
+        assert!(!state.echo);
+        assert!(state.is_password_prompt());
+    }
 }
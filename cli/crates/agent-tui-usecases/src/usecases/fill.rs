@@ -0,0 +1,290 @@
+//! Fill use case: answer a sequence of labelled prompts.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::domain::FillAction;
+use crate::domain::FillField;
+use crate::domain::FillInput;
+use crate::domain::FillOutput;
+use crate::domain::FillStep;
+use crate::domain::SelectInput;
+use crate::domain::core::ScreenSnapshot;
+use crate::usecases::ports::Clock;
+use crate::usecases::ports::SessionError;
+use crate::usecases::ports::SessionOps;
+use crate::usecases::ports::SessionRepository;
+use crate::usecases::select::select_item;
+use crate::usecases::select::settle;
+
+/// Quiet time before a prompt counts as fully drawn.
+const PROMPT_IDLE_MS: u64 = 30;
+/// How long to wait for the screen to react to an answer.
+const ANSWER_SETTLE_MS: u64 = 1000;
+const SELECT_MAX_STEPS: u32 = 200;
+
+/// Watches the screen for the labels in order of appearance, not in the order
+/// given: whichever pending label shows up on the cursor line (or lowest on the
+/// screen) is answered next. The run stops when every field is answered, the
+/// process exits, the budget runs out, or the screen sits on a prompt no field
+/// matches.
+pub trait FillUseCase: Send + Sync {
+    fn execute(&self, input: FillInput) -> Result<FillOutput, SessionError>;
+}
+
+pub struct FillUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R: SessionRepository> FillUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>, clock: Arc<dyn Clock>) -> Self {
+        Self { repository, clock }
+    }
+
+    fn answer(&self, session: &dyn SessionOps, action: &FillAction) -> Result<bool, SessionError> {
+        match action {
+            FillAction::Answer(text) => {
+                if !text.is_empty() {
+                    session.type_text(text)?;
+                }
+                session.keystroke("Enter")?;
+            }
+            FillAction::Keys(keys) => {
+                for key in keys {
+                    session.keystroke(key)?;
+                }
+            }
+            FillAction::Select(item) => {
+                let output = select_item(
+                    session,
+                    self.clock.as_ref(),
+                    &SelectInput {
+                        session_id: None,
+                        item: item.clone(),
+                        up_key: "ArrowUp".to_string(),
+                        down_key: "ArrowDown".to_string(),
+                        submit: true,
+                        region: None,
+                        max_steps: SELECT_MAX_STEPS,
+                        settle_ms: ANSWER_SETTLE_MS,
+                    },
+                )?;
+                return Ok(output.found);
+            }
+        }
+        Ok(true)
+    }
+}
+
+impl<R: SessionRepository> FillUseCase for FillUseCaseImpl<R> {
+    fn execute(&self, input: FillInput) -> Result<FillOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        let start = self.clock.now();
+        let timeout = Duration::from_millis(input.timeout_ms);
+        let subscription = session.stream_subscribe();
+
+        let mut pending = input.fields;
+        let mut steps = Vec::new();
+        let mut unexpected = Vec::new();
+        while !pending.is_empty() && self.clock.elapsed(start) < timeout {
+            session.update()?;
+            let snapshot = session.screen_snapshot();
+            let idle_ms = session.screen_idle_ms();
+
+            if let Some((index, row)) = match_prompt(&snapshot, &pending) {
+                if idle_ms < PROMPT_IDLE_MS {
+                    let _ = subscription.wait(Some(Duration::from_millis(10)));
+                    continue;
+                }
+                let field = pending.remove(index);
+                let before = session.screen_version();
+                let answered = self.answer(session.as_ref(), &field.action)?;
+                settle(
+                    session.as_ref(),
+                    self.clock.as_ref(),
+                    &subscription,
+                    before,
+                    Duration::from_millis(ANSWER_SETTLE_MS),
+                )?;
+                steps.push(FillStep {
+                    label: field.label,
+                    prompt: snapshot.row_text(usize::from(row)).trim().to_string(),
+                    row,
+                    answered,
+                    elapsed_ms: elapsed_ms(self.clock.as_ref(), start),
+                });
+                if !answered {
+                    break;
+                }
+                continue;
+            }
+
+            if !session.is_running() {
+                break;
+            }
+            if idle_ms >= input.prompt_timeout_ms {
+                unexpected.extend(active_line(&snapshot));
+                break;
+            }
+            let _ = subscription.wait(Some(Duration::from_millis(50)));
+        }
+
+        Ok(FillOutput {
+            steps,
+            unexpected,
+            missing: pending.into_iter().map(|field| field.label).collect(),
+            elapsed_ms: elapsed_ms(self.clock.as_ref(), start),
+        })
+    }
+}
+
+/// The pending field whose label is on the cursor row, else the one matched
+/// lowest on the screen; ties go to the longest label.
+fn match_prompt(snapshot: &ScreenSnapshot, pending: &[FillField]) -> Option<(usize, u16)> {
+    let cursor = snapshot.cursor.visible.then_some(snapshot.cursor.row);
+    let rows: Vec<String> = (0..usize::from(snapshot.rows))
+        .map(|row| snapshot.row_text(row).to_lowercase())
+        .collect();
+    pending
+        .iter()
+        .enumerate()
+        .filter_map(|(index, field)| {
+            let label = field.label.to_lowercase();
+            let row = rows.iter().rposition(|text| text.contains(&label))?;
+            let row = u16::try_from(row).ok()?;
+            Some((index, row, label.len()))
+        })
+        .max_by_key(|(_, row, len)| (Some(*row) == cursor, *row, *len))
+        .map(|(index, row, _)| (index, row))
+}
+
+/// The line the program is waiting on: the cursor row, else the last non-blank row.
+fn active_line(snapshot: &ScreenSnapshot) -> Option<String> {
+    let text =
+        |row: usize| Some(snapshot.row_text(row).trim().to_string()).filter(|t| !t.is_empty());
+    snapshot
+        .cursor
+        .visible
+        .then(|| text(usize::from(snapshot.cursor.row)))
+        .flatten()
+        .or_else(|| (0..usize::from(snapshot.rows)).rev().find_map(text))
+}
+
+fn elapsed_ms(clock: &dyn Clock, start: std::time::Instant) -> u64 {
+    u64::try_from(clock.elapsed(start).as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::CursorPosition;
+    use crate::test_support::MockSession;
+    use crate::test_support::MockSessionRepository;
+    use std::time::Instant;
+
+    struct TestClock;
+
+    impl Clock for TestClock {
+        fn now(&self) -> Instant {
+            Instant::now()
+        }
+    }
+
+    fn screen(text: &str, cursor_row: u16, inverse_row: Option<usize>) -> ScreenSnapshot {
+        let mut snapshot = ScreenSnapshot::from_text(
+            text,
+            30,
+            6,
+            CursorPosition {
+                row: cursor_row,
+                col: 0,
+                visible: inverse_row.is_none(),
+            },
+        );
+        if let Some(row) = inverse_row {
+            for cell in &mut snapshot.cells[row] {
+                cell.style.inverse = true;
+            }
+        }
+        snapshot
+    }
+
+    fn field(label: &str, action: FillAction) -> FillField {
+        FillField {
+            label: label.to_string(),
+            action,
+        }
+    }
+
+    fn fill(session: MockSession, fields: Vec<FillField>) -> (FillOutput, Arc<MockSession>) {
+        let session = Arc::new(session);
+        let repository = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(session.clone())
+                .build(),
+        );
+        let usecase = FillUseCaseImpl::new(repository, Arc::new(TestClock));
+        let output = usecase
+            .execute(FillInput {
+                session_id: None,
+                fields,
+                timeout_ms: 5000,
+                prompt_timeout_ms: 50,
+            })
+            .unwrap();
+        (output, session)
+    }
+
+    #[test]
+    fn test_fill_answers_prompts_in_screen_order() {
+        let framework = "✔ Project name: demo\n? Framework\n  react\n  vue";
+        let session = MockSession::builder("wizard")
+            .with_screen_snapshot(screen("? Project name:", 0, None))
+            .with_screen_idle_ms(100)
+            .with_keystroke_screens(vec![
+                screen(framework, 1, Some(2)),
+                screen(framework, 1, Some(3)),
+                screen(&format!("{framework}\n? Install deps? (y/N)"), 4, None),
+                screen("Done.", 0, None),
+            ])
+            .build();
+
+        let (output, session) = fill(
+            session,
+            vec![
+                field("install deps", FillAction::Keys(vec!["Enter".to_string()])),
+                field("framework", FillAction::Select("vue".to_string())),
+                field("project name", FillAction::Answer("demo".to_string())),
+            ],
+        );
+
+        assert!(output.completed());
+        let labels: Vec<&str> = output.steps.iter().map(|s| s.label.as_str()).collect();
+        assert_eq!(labels, vec!["project name", "framework", "install deps"]);
+        assert_eq!(output.steps[1].prompt, "? Framework");
+        assert_eq!(
+            session.keystrokes(),
+            vec!["Enter", "ArrowDown", "Enter", "Enter"]
+        );
+    }
+
+    #[test]
+    fn test_fill_reports_unexpected_prompt_and_missing_labels() {
+        let session = MockSession::builder("wizard")
+            .with_screen_snapshot(screen("Setup\n? License:", 1, None))
+            .with_screen_idle_ms(100)
+            .build();
+
+        let (output, session) = fill(
+            session,
+            vec![field("project name", FillAction::Answer(String::new()))],
+        );
+
+        assert!(!output.completed());
+        assert!(output.steps.is_empty());
+        assert_eq!(output.unexpected, vec!["? License:"]);
+        assert_eq!(output.missing, vec!["project name"]);
+        assert!(session.keystrokes().is_empty());
+    }
+}
@@ -0,0 +1,246 @@
+//! Nearest-match diagnostics for failed waits and assertions.
+
+use crate::domain::MatchDiagnostics;
+use crate::domain::TextMatch;
+use crate::domain::core::LogicalScreen;
+
+pub const MAX_NEAREST_MATCHES: usize = 3;
+
+/// Builds diagnostics from `screen`, reporting match positions as physical row/col
+/// even when the lines were unwrapped.
+pub fn match_diagnostics(
+    screen: &LogicalScreen,
+    needle: Option<&str>,
+    screen_idle_ms: u64,
+) -> MatchDiagnostics {
+    let text = screen.text();
+    let nearest_matches = needle
+        .map(|needle| nearest_matches(&text, needle, MAX_NEAREST_MATCHES))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|m| {
+            let (row, col) = screen
+                .physical_position(usize::from(m.row), usize::from(m.col))
+                .unwrap_or((m.row, m.col));
+            TextMatch { row, col, ..m }
+        })
+        .collect();
+    MatchDiagnostics {
+        screen: text,
+        nearest_matches,
+        still_visible: Vec::new(),
+        screen_idle_ms,
+    }
+}
+
+/// Builds diagnostics for text that should have left `screen`: the physical
+/// positions where it still appears, in reading order.
+pub fn text_gone_diagnostics(
+    screen: &LogicalScreen,
+    needle: &str,
+    screen_idle_ms: u64,
+) -> MatchDiagnostics {
+    let still_visible = screen
+        .find(needle)
+        .into_iter()
+        .take(MAX_NEAREST_MATCHES)
+        .map(|span| TextMatch {
+            text: needle.to_string(),
+            row: span.row,
+            col: span.col,
+            distance: 0,
+        })
+        .collect();
+    MatchDiagnostics {
+        screen: screen.text(),
+        nearest_matches: Vec::new(),
+        still_visible,
+        screen_idle_ms,
+    }
+}
+
+/// Returns the best approximate occurrence of `needle` per screen row, ordered by
+/// edit distance. Rows whose best match needs more than half the needle's length
+/// in edits are dropped.
+pub fn nearest_matches(screen: &str, needle: &str, limit: usize) -> Vec<TextMatch> {
+    let needle: Vec<char> = needle.chars().collect();
+    if needle.is_empty() || limit == 0 {
+        return Vec::new();
+    }
+    let max_distance = (needle.len() / 2).max(1);
+
+    let mut matches: Vec<TextMatch> = screen
+        .split('\n')
+        .enumerate()
+        .filter_map(|(row, line)| {
+            let line: Vec<char> = line.chars().collect();
+            let (distance, start, end) = best_substring_match(&needle, &line)?;
+            if distance > max_distance {
+                return None;
+            }
+            Some(TextMatch {
+                text: line[start..end].iter().collect(),
+                row: u16::try_from(row).unwrap_or(u16::MAX),
+                col: u16::try_from(start).unwrap_or(u16::MAX),
+                distance,
+            })
+        })
+        .collect();
+
+    matches.sort_by_key(|m| (m.distance, m.row, m.col));
+    matches.truncate(limit);
+    matches
+}
+
+/// Approximate substring search (Sellers): edit distance between `needle` and the
+/// closest substring of `line`, returned as `(distance, start, end)`.
+fn best_substring_match(needle: &[char], line: &[char]) -> Option<(usize, usize, usize)> {
+    if line.is_empty() {
+        return None;
+    }
+    let m = needle.len();
+    let mut prev_dist: Vec<usize> = (0..=m).collect();
+    let mut prev_start = vec![0usize; m + 1];
+    let mut cur_dist = vec![0usize; m + 1];
+    let mut cur_start = vec![0usize; m + 1];
+    let mut best: Option<(usize, usize, usize)> = None;
+
+    for (j, &ch) in line.iter().enumerate() {
+        let end = j + 1;
+        cur_dist[0] = 0;
+        cur_start[0] = end;
+        for i in 1..=m {
+            let substitution = prev_dist[i - 1] + usize::from(needle[i - 1] != ch);
+            let skip_needle = cur_dist[i - 1] + 1;
+            let skip_line = prev_dist[i] + 1;
+            if substitution <= skip_needle && substitution <= skip_line {
+                cur_dist[i] = substitution;
+                cur_start[i] = prev_start[i - 1];
+            } else if skip_needle <= skip_line {
+                cur_dist[i] = skip_needle;
+                cur_start[i] = cur_start[i - 1];
+            } else {
+                cur_dist[i] = skip_line;
+                cur_start[i] = prev_start[i];
+            }
+        }
+        let candidate = (cur_dist[m], cur_start[m], end);
+        if best.is_none_or(|current| is_closer(candidate, current, m)) {
+            best = Some(candidate);
+        }
+        std::mem::swap(&mut prev_dist, &mut cur_dist);
+        std::mem::swap(&mut prev_start, &mut cur_start);
+    }
+
+    best.filter(|(_, start, end)| start < end)
+}
+
+/// Prefers lower distance; for the same start, prefers a length closer to the needle.
+fn is_closer(candidate: (usize, usize, usize), current: (usize, usize, usize), m: usize) -> bool {
+    let (distance, start, end) = candidate;
+    let (best_distance, best_start, best_end) = current;
+    distance < best_distance
+        || (distance == best_distance
+            && start == best_start
+            && (end - start).abs_diff(m) < (best_end - best_start).abs_diff(m))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nearest_matches_reports_typo_with_position() {
+        let screen = "Welcome\n\n  Press Continue? to proceed";
+        let matches = nearest_matches(screen, "Continue!", 3);
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].text, "Continue?");
+        assert_eq!(matches[0].row, 2);
+        assert_eq!(matches[0].col, 8);
+        assert_eq!(matches[0].distance, 1);
+    }
+
+    #[test]
+    fn test_nearest_matches_exact_match_has_zero_distance() {
+        let matches = nearest_matches("Loading...", "Loading", 3);
+        assert_eq!(matches[0].distance, 0);
+        assert_eq!(matches[0].col, 0);
+        assert_eq!(matches[0].text, "Loading");
+    }
+
+    #[test]
+    fn test_nearest_matches_orders_by_distance_and_limits() {
+        let screen = "Save file\nSave\nSafe mode\nQuit";
+        let matches = nearest_matches(screen, "Save", 2);
+
+        assert_eq!(matches.len(), 2);
+        assert_eq!((matches[0].row, matches[0].distance), (0, 0));
+        assert_eq!((matches[1].row, matches[1].distance), (1, 0));
+    }
+
+    #[test]
+    fn test_nearest_matches_drops_distant_rows() {
+        let matches = nearest_matches("completely unrelated", "Continue", 3);
+        assert!(matches.is_empty());
+    }
+
+    #[test]
+    fn test_match_diagnostics_without_needle_keeps_screen() {
+        let screen = LogicalScreen::from_text("Ready", 80);
+        let diagnostics = match_diagnostics(&screen, None, 250);
+        assert_eq!(diagnostics.screen, "Ready");
+        assert!(diagnostics.nearest_matches.is_empty());
+        assert_eq!(diagnostics.screen_idle_ms, 250);
+    }
+
+    #[test]
+    fn test_match_diagnostics_maps_unwrapped_positions_to_physical_rows() {
+        let screen = LogicalScreen::from_rows(
+            8,
+            vec![
+                ("prompt".to_string(), false),
+                ("abcdefgh".to_string(), true),
+                ("Continue".to_string(), false),
+            ],
+        );
+        let diagnostics = match_diagnostics(&screen, Some("Continue!"), 0);
+
+        let nearest = &diagnostics.nearest_matches[0];
+        assert_eq!((nearest.row, nearest.col), (2, 0));
+        assert_eq!(diagnostics.screen, "prompt\nabcdefghContinue");
+    }
+
+    #[test]
+    fn test_text_gone_diagnostics_reports_where_text_remains() {
+        let screen = LogicalScreen::from_rows(
+            8,
+            vec![
+                ("Loading".to_string(), false),
+                ("x Loadin".to_string(), true),
+                ("g Loading".to_string(), false),
+            ],
+        );
+        let diagnostics = text_gone_diagnostics(&screen, "Loading", 90);
+
+        assert!(diagnostics.nearest_matches.is_empty());
+        let positions = diagnostics
+            .still_visible
+            .iter()
+            .map(|m| (m.row, m.col, m.text.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            positions,
+            vec![(0, 0, "Loading"), (1, 2, "Loading"), (2, 2, "Loading")]
+        );
+        assert_eq!(diagnostics.screen_idle_ms, 90);
+    }
+
+    #[test]
+    fn test_text_gone_diagnostics_ignores_near_misses() {
+        let screen = LogicalScreen::from_text("Loaded\nLoadin", 80);
+        let diagnostics = text_gone_diagnostics(&screen, "Loading", 0);
+        assert!(diagnostics.still_visible.is_empty());
+        assert!(diagnostics.nearest_matches.is_empty());
+    }
+}
@@ -1,7 +1,10 @@
 //! Application use cases orchestrating domain and ports.
 
 mod diagnostics;
+mod fill;
 mod input;
+mod match_diagnostics;
+mod select;
 mod session;
 mod shutdown;
 mod snapshot;
@@ -11,6 +14,10 @@ mod wait_condition;
 
 pub use diagnostics::TerminalWriteUseCase;
 pub use diagnostics::TerminalWriteUseCaseImpl;
+pub use diagnostics::TtyStateUseCase;
+pub use diagnostics::TtyStateUseCaseImpl;
+pub use fill::FillUseCase;
+pub use fill::FillUseCaseImpl;
 pub use input::KeydownUseCase;
 pub use input::KeydownUseCaseImpl;
 pub use input::KeystrokeUseCase;
@@ -19,6 +26,11 @@ pub use input::KeyupUseCase;
 pub use input::KeyupUseCaseImpl;
 pub use input::TypeUseCase;
 pub use input::TypeUseCaseImpl;
+pub use match_diagnostics::match_diagnostics;
+pub use match_diagnostics::nearest_matches;
+pub use match_diagnostics::text_gone_diagnostics;
+pub use select::SelectUseCase;
+pub use select::SelectUseCaseImpl;
 pub use session::AssertUseCase;
 pub use session::AssertUseCaseImpl;
 pub use session::AttachUseCase;
@@ -27,18 +39,42 @@ pub use session::CleanupUseCase;
 pub use session::CleanupUseCaseImpl;
 pub use session::KillUseCase;
 pub use session::KillUseCaseImpl;
+pub use session::RecordStartUseCase;
+pub use session::RecordStartUseCaseImpl;
+pub use session::RecordStopUseCase;
+pub use session::RecordStopUseCaseImpl;
+pub use session::ReplayUseCase;
+pub use session::ReplayUseCaseImpl;
 pub use session::ResizeUseCase;
 pub use session::ResizeUseCaseImpl;
 pub use session::RestartUseCase;
 pub use session::RestartUseCaseImpl;
 pub use session::SessionsUseCase;
 pub use session::SessionsUseCaseImpl;
+pub use session::SignalUseCase;
+pub use session::SignalUseCaseImpl;
 pub use session::SpawnUseCase;
 pub use session::SpawnUseCaseImpl;
+pub use session::TraceStartUseCase;
+pub use session::TraceStartUseCaseImpl;
+pub use session::TraceStopUseCase;
+pub use session::TraceStopUseCaseImpl;
 pub use shutdown::ShutdownUseCase;
 pub use shutdown::ShutdownUseCaseImpl;
+pub use snapshot::AssertScreenUseCase;
+pub use snapshot::AssertScreenUseCaseImpl;
+pub use snapshot::ElementsUseCase;
+pub use snapshot::ElementsUseCaseImpl;
+pub use snapshot::FindUseCase;
+pub use snapshot::FindUseCaseImpl;
+pub use snapshot::PanesUseCase;
+pub use snapshot::PanesUseCaseImpl;
+pub use snapshot::SnapshotDiffUseCase;
+pub use snapshot::SnapshotDiffUseCaseImpl;
 pub use snapshot::SnapshotUseCase;
 pub use snapshot::SnapshotUseCaseImpl;
+pub use snapshot::TableUseCase;
+pub use snapshot::TableUseCaseImpl;
 pub use spawn_error::SpawnError;
 pub use wait::WaitUseCase;
 pub use wait::WaitUseCaseImpl;
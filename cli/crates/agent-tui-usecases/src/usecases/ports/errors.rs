@@ -43,6 +43,12 @@ pub enum TerminalError {
         #[source]
         source: Option<ErrorSource>,
     },
+    #[error("Failed to signal process: {reason}")]
+    Signal {
+        reason: String,
+        #[source]
+        source: Option<ErrorSource>,
+    },
 }
 
 impl TerminalError {
@@ -53,6 +59,7 @@ impl TerminalError {
             TerminalError::Write { .. } => "write",
             TerminalError::Read { .. } => "read",
             TerminalError::Resize { .. } => "resize",
+            TerminalError::Signal { .. } => "signal",
         }
     }
 
@@ -61,7 +68,8 @@ impl TerminalError {
             TerminalError::Open { reason, .. }
             | TerminalError::Write { reason, .. }
             | TerminalError::Read { reason, .. }
-            | TerminalError::Resize { reason, .. } => reason,
+            | TerminalError::Resize { reason, .. }
+            | TerminalError::Signal { reason, .. } => reason,
             TerminalError::Spawn { reason, .. } => reason,
         }
     }
@@ -93,8 +101,17 @@ pub enum SessionError {
     Terminal(#[from] TerminalError),
     #[error("Invalid key: {0}")]
     InvalidKey(String),
+    #[error("Region not found: {region}")]
+    RegionNotFound {
+        region: String,
+        available: Vec<String>,
+    },
     #[error("Session limit reached: maximum {0} sessions allowed")]
     LimitReached(usize),
+    #[error("Cannot record to {path}: {reason}")]
+    Recording { path: String, reason: String },
+    #[error("Cannot replay {path}: {reason}")]
+    Replay { path: String, reason: String },
     #[error("Persistence error during {operation}: {reason}")]
     Persistence {
         operation: String,
@@ -4,9 +4,19 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
+use crate::domain::ImageFormat;
+use crate::domain::ProcessSignal;
+use crate::domain::Recording;
+use crate::domain::ReplayPacing;
+use crate::domain::ReplaySummary;
+use crate::domain::TraceSummary;
 use crate::domain::core::CursorPosition;
+use crate::domain::core::LogicalScreen;
+use crate::domain::core::ScreenSnapshot;
+use crate::domain::core::ScreenState;
 use crate::domain::session_types::SessionId;
 use crate::domain::session_types::SessionInfo;
+use crate::domain::session_types::TtyState;
 
 use super::SessionError;
 
@@ -41,7 +51,12 @@ pub type StreamWaiterHandle = Arc<dyn StreamWaiter>;
 pub trait SessionOps: Send + Sync {
     fn update(&self) -> Result<(), SessionError>;
     fn screen_text(&self) -> String;
+    fn logical_screen(&self) -> LogicalScreen;
     fn screen_render(&self) -> String;
+    /// The screen drawn with the session's palette, styles and cursor, as an
+    /// image or an HTML fragment.
+    fn screen_image(&self, format: ImageFormat) -> Vec<u8>;
+    fn screen_snapshot(&self) -> ScreenSnapshot;
     fn terminal_write(&self, data: &[u8]) -> Result<(), SessionError>;
     fn terminal_try_read(&self, buf: &mut [u8], timeout_ms: i32) -> Result<usize, SessionError>;
     fn stream_read(
@@ -57,11 +72,34 @@ pub trait SessionOps: Send + Sync {
     fn keyup(&self, key: &str) -> Result<(), SessionError>;
     fn is_running(&self) -> bool;
     fn resize(&self, cols: u16, rows: u16) -> Result<(), SessionError>;
+    /// Deliver `signal` to the session's child process group.
+    fn signal(&self, signal: ProcessSignal) -> Result<(), SessionError>;
     fn cursor(&self) -> CursorPosition;
     fn session_id(&self) -> SessionId;
     fn command(&self) -> String;
     fn size(&self) -> (u16, u16);
     fn live_preview_snapshot(&self) -> LivePreviewSnapshot;
+    fn tty_state(&self) -> Option<TtyState>;
+    fn screen_idle_ms(&self) -> u64;
+    fn screen_version(&self) -> u64;
+    fn screen_state(&self) -> ScreenState;
+    /// The retained screen that was current at `version`, if still in history.
+    fn screen_state_at(&self, version: u64) -> Option<ScreenState>;
+    /// Start writing an asciinema v2 recording to `path`, beginning with the
+    /// current screen. Fails with `SessionError::Recording` if the file cannot be
+    /// created or the session is already being recorded.
+    fn record_start(&self, path: &str, include_input: bool) -> Result<(), SessionError>;
+    /// Finish the current recording, if any.
+    fn record_stop(&self) -> Option<Recording>;
+    /// Start collecting a trace bundle (actions with before/after screens and
+    /// the raw output) that `trace_stop` writes to `path`. Fails with
+    /// `SessionError::Recording` if the session is already being traced.
+    fn trace_start(&self, path: &str) -> Result<(), SessionError>;
+    /// Write the current trace, if any; fails if the bundle cannot be written.
+    fn trace_stop(&self) -> Result<Option<TraceSummary>, SessionError>;
+    /// Log a finished wait in the command timeline (and the trace, with `before`
+    /// as the screen when the wait started).
+    fn record_wait(&self, condition: &str, found: bool, elapsed_ms: u64, before: &str);
 }
 
 pub type SessionHandle = Arc<dyn SessionOps>;
@@ -79,6 +117,18 @@ pub trait SessionRepository: Send + Sync {
         rows: u16,
     ) -> Result<(SessionId, u32), SessionError>;
 
+    /// Start a session whose output is played back from a `.cast` file or raw
+    /// byte log instead of a child process. Input only matters with
+    /// `ReplayPacing::Step`, where each write advances the playback.
+    fn spawn_replay(
+        &self,
+        path: &str,
+        pacing: ReplayPacing,
+        session_id: Option<String>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(SessionId, ReplaySummary), SessionError>;
+
     fn get(&self, session_id: &SessionId) -> Result<SessionHandle, SessionError>;
     fn active(&self) -> Result<SessionHandle, SessionError>;
     fn resolve(&self, session_id: Option<&SessionId>) -> Result<SessionHandle, SessionError>;
@@ -6,6 +6,9 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 use super::mock_error::MockError;
+use crate::domain::ReplayFormat;
+use crate::domain::ReplayPacing;
+use crate::domain::ReplaySummary;
 use crate::domain::SessionId;
 use crate::domain::SessionInfo;
 use crate::usecases::ports::SessionError;
@@ -24,6 +27,7 @@ pub struct MockSessionRepository {
     session_count: usize,
     spawn_result: Option<(SessionId, u32)>,
     session_handle: Option<SessionHandle>,
+    handle_is_active: bool,
 
     spawn_calls: AtomicUsize,
     resolve_calls: AtomicUsize,
@@ -118,6 +122,34 @@ impl SessionRepository for MockSessionRepository {
         Err(SessionError::LimitReached(0))
     }
 
+    fn spawn_replay(
+        &self,
+        _path: &str,
+        _pacing: ReplayPacing,
+        session_id: Option<String>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(SessionId, ReplaySummary), SessionError> {
+        self.spawn_calls.fetch_add(1, Ordering::SeqCst);
+
+        if let Some(ref err) = self.spawn_error {
+            return Err(err.to_session_error());
+        }
+
+        let id = session_id
+            .map(SessionId::new)
+            .or_else(|| self.spawn_result.as_ref().map(|(id, _)| id.clone()))
+            .unwrap_or_else(|| SessionId::new("replay"));
+        let summary = ReplaySummary {
+            format: ReplayFormat::Cast,
+            cols,
+            rows,
+            events: 1,
+            duration_ms: 0,
+        };
+        Ok((id, summary))
+    }
+
     fn get(&self, session_id: &SessionId) -> Result<SessionHandle, SessionError> {
         self.get_calls.fetch_add(1, Ordering::SeqCst);
 
@@ -151,7 +183,11 @@ impl SessionRepository for MockSessionRepository {
                 .session_handle
                 .clone()
                 .ok_or_else(|| SessionError::NotFound(id.as_str().to_string())),
-            None => Err(SessionError::NoActiveSession),
+            None => self
+                .session_handle
+                .clone()
+                .filter(|_| self.handle_is_active)
+                .ok_or(SessionError::NoActiveSession),
         }
     }
 
@@ -236,6 +272,19 @@ impl MockSessionRepositoryBuilder {
         self
     }
 
+    pub fn with_session_handle(mut self, handle: SessionHandle) -> Self {
+        self.repo.session_handle = Some(handle);
+        self
+    }
+
+    /// Like `with_session_handle`, but also resolves requests without a session
+    /// id to `handle`, as if it were the active session.
+    pub fn with_active_session_handle(mut self, handle: SessionHandle) -> Self {
+        self.repo.session_handle = Some(handle);
+        self.repo.handle_is_active = true;
+        self
+    }
+
     pub fn build(self) -> MockSessionRepository {
         self.repo
     }
@@ -254,6 +303,25 @@ mod tests {
         assert_eq!(repo.resolve_call_count(), 1);
     }
 
+    #[test]
+    fn test_mock_repository_resolves_handle_without_id_only_when_active() {
+        let handle: SessionHandle = std::sync::Arc::new(super::super::MockSession::new("s1"));
+
+        let repo = MockSessionRepository::builder()
+            .with_session_handle(handle.clone())
+            .build();
+        assert!(repo.resolve(Some(&SessionId::new("s1"))).is_ok());
+        assert!(matches!(
+            repo.resolve(None),
+            Err(SessionError::NoActiveSession)
+        ));
+
+        let repo = MockSessionRepository::builder()
+            .with_active_session_handle(handle)
+            .build();
+        assert!(repo.resolve(None).is_ok());
+    }
+
     #[test]
     fn test_mock_repository_resolve_with_configured_error() {
         let repo = MockSessionRepository::builder()
@@ -342,6 +410,7 @@ mod tests {
             command: "bash".to_string(),
             pid: 1234,
             running: true,
+            exit_code: None,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             size: TerminalSize::default(),
         }];
@@ -1,7 +1,15 @@
 //! Mock session handle for use case tests.
 
+use crate::domain::ImageFormat;
+use crate::domain::ProcessSignal;
+use crate::domain::Recording;
+use crate::domain::TraceSummary;
 use crate::domain::core::CursorPosition;
+use crate::domain::core::LogicalScreen;
+use crate::domain::core::ScreenSnapshot;
+use crate::domain::core::ScreenState;
 use crate::domain::session_types::SessionId;
+use crate::domain::session_types::TtyState;
 use crate::usecases::ports::LivePreviewSnapshot;
 use crate::usecases::ports::SessionError;
 use crate::usecases::ports::SessionOps;
@@ -10,6 +18,7 @@ use crate::usecases::ports::StreamRead;
 use crate::usecases::ports::StreamWaiter;
 use crate::usecases::ports::StreamWaiterHandle;
 use crate::usecases::ports::TerminalError;
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::time::Duration;
@@ -29,9 +38,23 @@ pub struct MockSession {
     rows: u16,
     cursor: CursorPosition,
     screen_text: String,
+    logical_screen: Option<LogicalScreen>,
+    screen_snapshot: Option<ScreenSnapshot>,
+    tty_state: Option<TtyState>,
+    screen_idle_ms: u64,
+    screen_version: u64,
+    screen_history: Vec<ScreenState>,
     update_error: Option<SessionError>,
     terminal_write_error: Option<SessionError>,
     written_data: Mutex<Vec<Vec<u8>>>,
+    keystrokes: Mutex<Vec<String>>,
+    /// Screens shown after each keystroke, in order; each one bumps the version.
+    keystroke_screens: Mutex<VecDeque<ScreenSnapshot>>,
+    scripted: Mutex<Option<(ScreenSnapshot, u64)>>,
+    recording: Mutex<Option<(String, bool)>>,
+    trace: Mutex<Option<String>>,
+    waits: Mutex<Vec<(String, bool)>>,
+    signals: Mutex<Vec<ProcessSignal>>,
 }
 
 impl MockSession {
@@ -47,9 +70,22 @@ impl MockSession {
                 visible: false,
             },
             screen_text: String::new(),
+            logical_screen: None,
+            screen_snapshot: None,
+            tty_state: None,
+            screen_idle_ms: 0,
+            screen_version: 0,
+            screen_history: Vec::new(),
             update_error: None,
             terminal_write_error: None,
             written_data: Mutex::new(Vec::new()),
+            keystrokes: Mutex::new(Vec::new()),
+            keystroke_screens: Mutex::new(VecDeque::new()),
+            scripted: Mutex::new(None),
+            recording: Mutex::new(None),
+            trace: Mutex::new(None),
+            waits: Mutex::new(Vec::new()),
+            signals: Mutex::new(Vec::new()),
         }
     }
 
@@ -60,6 +96,25 @@ impl MockSession {
     pub fn written_data(&self) -> Vec<Vec<u8>> {
         self.written_data.lock().unwrap().clone()
     }
+
+    pub fn keystrokes(&self) -> Vec<String> {
+        self.keystrokes.lock().unwrap().clone()
+    }
+
+    /// Path and input flag of the recording in progress.
+    pub fn recording(&self) -> Option<(String, bool)> {
+        self.recording.lock().unwrap().clone()
+    }
+
+    /// Conditions and results passed to `record_wait`.
+    pub fn waits(&self) -> Vec<(String, bool)> {
+        self.waits.lock().unwrap().clone()
+    }
+
+    /// Signals passed to `signal`, in order.
+    pub fn signals(&self) -> Vec<ProcessSignal> {
+        self.signals.lock().unwrap().clone()
+    }
 }
 
 impl SessionOps for MockSession {
@@ -78,10 +133,29 @@ impl SessionOps for MockSession {
         self.screen_text.clone()
     }
 
+    fn logical_screen(&self) -> LogicalScreen {
+        self.logical_screen
+            .clone()
+            .unwrap_or_else(|| LogicalScreen::from_text(&self.screen_text, self.cols))
+    }
+
     fn screen_render(&self) -> String {
         self.screen_text.clone()
     }
 
+    fn screen_image(&self, format: ImageFormat) -> Vec<u8> {
+        format!("{}:{}", format.as_str(), self.screen_text).into_bytes()
+    }
+
+    fn screen_snapshot(&self) -> ScreenSnapshot {
+        if let Some((snapshot, _)) = self.scripted.lock().unwrap().as_ref() {
+            return snapshot.clone();
+        }
+        self.screen_snapshot.clone().unwrap_or_else(|| {
+            ScreenSnapshot::from_text(&self.screen_text, self.cols, self.rows, self.cursor)
+        })
+    }
+
     fn terminal_write(&self, data: &[u8]) -> Result<(), SessionError> {
         if let Some(ref err) = self.terminal_write_error {
             Err(SessionError::Terminal(TerminalError::Write {
@@ -117,7 +191,13 @@ impl SessionOps for MockSession {
         Arc::new(MockStreamWaiter)
     }
 
-    fn keystroke(&self, _key: &str) -> Result<(), SessionError> {
+    fn keystroke(&self, key: &str) -> Result<(), SessionError> {
+        self.keystrokes.lock().unwrap().push(key.to_string());
+        if let Some(next) = self.keystroke_screens.lock().unwrap().pop_front() {
+            let mut scripted = self.scripted.lock().unwrap();
+            let bumps = scripted.as_ref().map_or(0, |(_, bumps)| *bumps) + 1;
+            *scripted = Some((next, bumps));
+        }
         Ok(())
     }
 
@@ -142,6 +222,11 @@ impl SessionOps for MockSession {
         Ok(())
     }
 
+    fn signal(&self, signal: ProcessSignal) -> Result<(), SessionError> {
+        self.signals.lock().unwrap().push(signal);
+        Ok(())
+    }
+
     fn cursor(&self) -> CursorPosition {
         self.cursor
     }
@@ -166,6 +251,95 @@ impl SessionOps for MockSession {
             stream_seq: 0,
         }
     }
+
+    fn tty_state(&self) -> Option<TtyState> {
+        self.tty_state
+    }
+
+    fn screen_idle_ms(&self) -> u64 {
+        self.screen_idle_ms
+    }
+
+    fn screen_version(&self) -> u64 {
+        let bumps = self
+            .scripted
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map_or(0, |(_, bumps)| *bumps);
+        self.screen_version + bumps
+    }
+
+    fn screen_state(&self) -> ScreenState {
+        let mut rows: Vec<String> = self.screen_text.split('\n').map(String::from).collect();
+        rows.resize(usize::from(self.rows).max(rows.len()), String::new());
+        ScreenState {
+            version: self.screen_version,
+            rows,
+            cursor: self.cursor,
+        }
+    }
+
+    fn screen_state_at(&self, version: u64) -> Option<ScreenState> {
+        self.screen_history
+            .iter()
+            .rev()
+            .find(|state| state.version <= version)
+            .cloned()
+    }
+
+    fn record_start(&self, path: &str, include_input: bool) -> Result<(), SessionError> {
+        let mut recording = self.recording.lock().unwrap();
+        if let Some((current, _)) = recording.as_ref() {
+            return Err(SessionError::Recording {
+                path: path.to_string(),
+                reason: format!("already recording to {current}"),
+            });
+        }
+        *recording = Some((path.to_string(), include_input));
+        Ok(())
+    }
+
+    fn record_stop(&self) -> Option<Recording> {
+        self.recording
+            .lock()
+            .unwrap()
+            .take()
+            .map(|(path, _)| Recording {
+                path,
+                events: 1,
+                duration_ms: 0,
+            })
+    }
+
+    fn trace_start(&self, path: &str) -> Result<(), SessionError> {
+        let mut trace = self.trace.lock().unwrap();
+        if let Some(current) = trace.as_ref() {
+            return Err(SessionError::Recording {
+                path: path.to_string(),
+                reason: format!("already tracing to {current}"),
+            });
+        }
+        *trace = Some(path.to_string());
+        Ok(())
+    }
+
+    fn trace_stop(&self) -> Result<Option<TraceSummary>, SessionError> {
+        let steps = self.waits.lock().unwrap().len() as u64;
+        Ok(self.trace.lock().unwrap().take().map(|path| TraceSummary {
+            path,
+            steps,
+            output_bytes: 0,
+            duration_ms: 0,
+        }))
+    }
+
+    fn record_wait(&self, condition: &str, found: bool, _elapsed_ms: u64, _before: &str) {
+        self.waits
+            .lock()
+            .unwrap()
+            .push((condition.to_string(), found));
+    }
 }
 
 pub struct MockSessionBuilder {
@@ -184,6 +358,41 @@ impl MockSessionBuilder {
         self
     }
 
+    pub fn with_logical_screen(mut self, screen: LogicalScreen) -> Self {
+        self.session.logical_screen = Some(screen);
+        self
+    }
+
+    pub fn with_screen_snapshot(mut self, snapshot: ScreenSnapshot) -> Self {
+        self.session.screen_snapshot = Some(snapshot);
+        self
+    }
+
+    pub fn with_tty_state(mut self, tty_state: TtyState) -> Self {
+        self.session.tty_state = Some(tty_state);
+        self
+    }
+
+    pub fn with_screen_idle_ms(mut self, idle_ms: u64) -> Self {
+        self.session.screen_idle_ms = idle_ms;
+        self
+    }
+
+    pub fn with_screen_version(mut self, version: u64) -> Self {
+        self.session.screen_version = version;
+        self
+    }
+
+    pub fn with_screen_history(mut self, history: Vec<ScreenState>) -> Self {
+        self.session.screen_history = history;
+        self
+    }
+
+    pub fn with_keystroke_screens(self, screens: Vec<ScreenSnapshot>) -> Self {
+        *self.session.keystroke_screens.lock().unwrap() = screens.into();
+        self
+    }
+
     pub fn with_update_error(mut self, error: SessionError) -> Self {
         self.session.update_error = Some(error);
         self
@@ -0,0 +1,249 @@
+//! Select use case: move a menu highlight onto an item.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::domain::SelectInput;
+use crate::domain::SelectOutput;
+use crate::domain::core::Highlight;
+use crate::domain::core::find_highlight;
+use crate::domain::core::find_panes;
+use crate::domain::core::region_names;
+use crate::domain::core::resolve_region;
+use crate::usecases::ports::Clock;
+use crate::usecases::ports::SessionError;
+use crate::usecases::ports::SessionOps;
+use crate::usecases::ports::SessionRepository;
+use crate::usecases::ports::StreamWaiterHandle;
+
+/// Quiet time after a key before the screen counts as settled.
+const SETTLE_IDLE_MS: u64 = 30;
+
+/// Goes down until the item is highlighted, the highlight stops moving (end of a
+/// list) or comes back to a row already seen (the list wrapped). At the end of a
+/// list it turns around and goes up until the top.
+pub trait SelectUseCase: Send + Sync {
+    fn execute(&self, input: SelectInput) -> Result<SelectOutput, SessionError>;
+}
+
+pub struct SelectUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+    clock: Arc<dyn Clock>,
+}
+
+impl<R: SessionRepository> SelectUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>, clock: Arc<dyn Clock>) -> Self {
+        Self { repository, clock }
+    }
+}
+
+impl<R: SessionRepository> SelectUseCase for SelectUseCaseImpl<R> {
+    fn execute(&self, input: SelectInput) -> Result<SelectOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+        select_item(session.as_ref(), self.clock.as_ref(), &input)
+    }
+}
+
+/// Runs the select search on an already resolved session.
+pub(crate) fn select_item(
+    session: &dyn SessionOps,
+    clock: &dyn Clock,
+    input: &SelectInput,
+) -> Result<SelectOutput, SessionError> {
+    let region = input.region.as_deref();
+    let matches = |highlight: &Option<Highlight>| {
+        highlight
+            .as_ref()
+            .is_some_and(|h| h.text.contains(&input.item))
+    };
+    let mut current = highlight(session, region)?;
+    let mut seen: Vec<Highlight> = current.iter().cloned().collect();
+    let mut steps = 0;
+    let subscription = session.stream_subscribe();
+    let timeout = Duration::from_millis(input.settle_ms);
+
+    'directions: for (pass, key) in [&input.down_key, &input.up_key].into_iter().enumerate() {
+        while current.is_some() && !matches(&current) && steps < input.max_steps {
+            let before = session.screen_version();
+            session.keystroke(key)?;
+            steps += 1;
+            settle(session, clock, &subscription, before, timeout)?;
+
+            let next = highlight(session, region)?;
+            let Some(moved) = next.clone().filter(|next| Some(next) != current.as_ref()) else {
+                current = next;
+                continue 'directions;
+            };
+            let wrapped = pass == 0 && seen.contains(&moved);
+            seen.push(moved);
+            current = next;
+            if wrapped {
+                break 'directions;
+            }
+        }
+        break;
+    }
+
+    let found = matches(&current);
+    let submitted = found && input.submit;
+    if submitted {
+        session.keystroke("Enter")?;
+    }
+    Ok(SelectOutput {
+        found,
+        highlight: current,
+        steps,
+        submitted,
+    })
+}
+
+/// Waits until the screen changed since `before` and then stayed quiet, or until
+/// `timeout` passes.
+pub(crate) fn settle(
+    session: &dyn SessionOps,
+    clock: &dyn Clock,
+    subscription: &StreamWaiterHandle,
+    before: u64,
+    timeout: Duration,
+) -> Result<(), SessionError> {
+    let start = clock.now();
+    loop {
+        session.update()?;
+        let changed = session.screen_version() != before;
+        if (changed && session.screen_idle_ms() >= SETTLE_IDLE_MS)
+            || clock.elapsed(start) >= timeout
+        {
+            return Ok(());
+        }
+        let _ = subscription.wait(Some(Duration::from_millis(10)));
+    }
+}
+
+fn highlight(
+    session: &dyn SessionOps,
+    region: Option<&str>,
+) -> Result<Option<Highlight>, SessionError> {
+    let snapshot = session.screen_snapshot();
+    let Some(region) = region else {
+        return Ok(find_highlight(&snapshot, None));
+    };
+    let panes = find_panes(&snapshot);
+    let pane = resolve_region(&panes, region).ok_or_else(|| SessionError::RegionNotFound {
+        region: region.to_string(),
+        available: region_names(&panes),
+    })?;
+    Ok(find_highlight(&snapshot, Some(pane)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::core::CursorPosition;
+    use crate::domain::core::ScreenSnapshot;
+    use crate::test_support::MockSession;
+    use crate::test_support::MockSessionRepository;
+    use std::time::Instant;
+
+    struct TestClock;
+
+    impl Clock for TestClock {
+        fn now(&self) -> Instant {
+            Instant::now()
+        }
+    }
+
+    const ITEMS: [&str; 3] = ["apple", "banana", "cherry"];
+
+    fn menu(selected: usize) -> ScreenSnapshot {
+        let text = format!(
+            "Fruit\n{}",
+            ITEMS.map(|item| format!("  {:<10}", item)).join("\n")
+        );
+        let mut snapshot = ScreenSnapshot::from_text(
+            &text,
+            12,
+            5,
+            CursorPosition {
+                row: 0,
+                col: 0,
+                visible: false,
+            },
+        );
+        for cell in &mut snapshot.cells[selected + 1] {
+            cell.style.inverse = true;
+        }
+        snapshot
+    }
+
+    fn select(session: MockSession, item: &str, submit: bool) -> (SelectOutput, Arc<MockSession>) {
+        let session = Arc::new(session);
+        let repository = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(session.clone())
+                .build(),
+        );
+        let usecase = SelectUseCaseImpl::new(repository, Arc::new(TestClock));
+        let output = usecase
+            .execute(SelectInput {
+                session_id: None,
+                item: item.to_string(),
+                up_key: "ArrowUp".to_string(),
+                down_key: "ArrowDown".to_string(),
+                submit,
+                region: None,
+                max_steps: 20,
+                settle_ms: 20,
+            })
+            .unwrap();
+        (output, session)
+    }
+
+    #[test]
+    fn test_select_turns_around_at_end_of_list_and_submits() {
+        let session = MockSession::builder("menu")
+            .with_screen_snapshot(menu(1))
+            .with_screen_idle_ms(100)
+            .with_keystroke_screens(vec![menu(2), menu(2), menu(1), menu(0)])
+            .build();
+
+        let (output, session) = select(session, "apple", true);
+
+        assert!(output.found && output.submitted);
+        assert_eq!(output.steps, 4);
+        assert_eq!(output.highlight.map(|h| h.text), Some("apple".to_string()));
+        assert_eq!(
+            session.keystrokes(),
+            vec!["ArrowDown", "ArrowDown", "ArrowUp", "ArrowUp", "Enter"]
+        );
+    }
+
+    #[test]
+    fn test_select_stops_when_list_wraps() {
+        let session = MockSession::builder("menu")
+            .with_screen_snapshot(menu(1))
+            .with_screen_idle_ms(100)
+            .with_keystroke_screens(vec![menu(2), menu(0), menu(1)])
+            .build();
+
+        let (output, session) = select(session, "kiwi", true);
+
+        assert!(!output.found && !output.submitted);
+        assert_eq!(output.steps, 3);
+        assert_eq!(output.highlight.map(|h| h.row), Some(2));
+        assert_eq!(session.keystrokes().len(), 3);
+    }
+
+    #[test]
+    fn test_select_already_highlighted_presses_nothing() {
+        let session = MockSession::builder("menu")
+            .with_screen_snapshot(menu(1))
+            .build();
+
+        let (output, session) = select(session, "banana", false);
+
+        assert!(output.found);
+        assert_eq!(output.steps, 0);
+        assert!(session.keystrokes().is_empty());
+    }
+}
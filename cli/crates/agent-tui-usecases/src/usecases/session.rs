@@ -11,18 +11,31 @@ use crate::domain::CleanupFailure;
 use crate::domain::CleanupInput;
 use crate::domain::CleanupOutput;
 use crate::domain::KillOutput;
+use crate::domain::RecordStartInput;
+use crate::domain::RecordStartOutput;
+use crate::domain::RecordStopOutput;
+use crate::domain::ReplayInput;
+use crate::domain::ReplayOutput;
 use crate::domain::ResizeInput;
 use crate::domain::ResizeOutput;
 use crate::domain::RestartOutput;
 use crate::domain::SessionInput;
 use crate::domain::SessionsOutput;
+use crate::domain::SignalInput;
+use crate::domain::SignalOutput;
 use crate::domain::SpawnInput;
 use crate::domain::SpawnOutput;
+use crate::domain::TraceStartInput;
+use crate::domain::TraceStartOutput;
+use crate::domain::TraceStopOutput;
 use crate::usecases::SpawnError;
+use crate::usecases::match_diagnostics::match_diagnostics;
 use crate::usecases::ports::SessionError;
 use crate::usecases::ports::SessionRepository;
 use crate::usecases::ports::SpawnErrorKind;
 use crate::usecases::ports::TerminalError;
+use crate::usecases::wait_condition::ScreenScope;
+use crate::usecases::wait_condition::screen_lines;
 
 pub trait SpawnUseCase: Send + Sync {
     fn execute(&self, input: SpawnInput) -> Result<SpawnOutput, SpawnError>;
@@ -85,6 +98,33 @@ impl<R: SessionRepository> SpawnUseCase for SpawnUseCaseImpl<R> {
     }
 }
 
+pub trait ReplayUseCase: Send + Sync {
+    fn execute(&self, input: ReplayInput) -> Result<ReplayOutput, SessionError>;
+}
+
+pub struct ReplayUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> ReplayUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> ReplayUseCase for ReplayUseCaseImpl<R> {
+    fn execute(&self, input: ReplayInput) -> Result<ReplayOutput, SessionError> {
+        let (session_id, replay) = self.repository.spawn_replay(
+            &input.path,
+            input.pacing,
+            input.session_id.map(|id| id.to_string()),
+            input.cols,
+            input.rows,
+        )?;
+        Ok(ReplayOutput { session_id, replay })
+    }
+}
+
 pub trait SessionsUseCase: Send + Sync {
     fn execute(&self) -> SessionsOutput;
 }
@@ -238,6 +278,139 @@ impl<R: SessionRepository> ResizeUseCase for ResizeUseCaseImpl<R> {
     }
 }
 
+pub trait SignalUseCase: Send + Sync {
+    fn execute(&self, input: SignalInput) -> Result<SignalOutput, SessionError>;
+}
+
+pub struct SignalUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> SignalUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> SignalUseCase for SignalUseCaseImpl<R> {
+    fn execute(&self, input: SignalInput) -> Result<SignalOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.signal(input.signal)?;
+
+        Ok(SignalOutput {
+            session_id: session.session_id(),
+            success: true,
+            signal: input.signal,
+        })
+    }
+}
+
+pub trait RecordStartUseCase: Send + Sync {
+    fn execute(&self, input: RecordStartInput) -> Result<RecordStartOutput, SessionError>;
+}
+
+pub struct RecordStartUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> RecordStartUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> RecordStartUseCase for RecordStartUseCaseImpl<R> {
+    fn execute(&self, input: RecordStartInput) -> Result<RecordStartOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+        session.record_start(&input.path, input.include_input)?;
+
+        Ok(RecordStartOutput {
+            session_id: session.session_id(),
+            path: input.path,
+        })
+    }
+}
+
+pub trait RecordStopUseCase: Send + Sync {
+    fn execute(&self, input: SessionInput) -> Result<RecordStopOutput, SessionError>;
+}
+
+pub struct RecordStopUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> RecordStopUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> RecordStopUseCase for RecordStopUseCaseImpl<R> {
+    fn execute(&self, input: SessionInput) -> Result<RecordStopOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        Ok(RecordStopOutput {
+            session_id: session.session_id(),
+            recording: session.record_stop(),
+        })
+    }
+}
+
+pub trait TraceStartUseCase: Send + Sync {
+    fn execute(&self, input: TraceStartInput) -> Result<TraceStartOutput, SessionError>;
+}
+
+pub struct TraceStartUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> TraceStartUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> TraceStartUseCase for TraceStartUseCaseImpl<R> {
+    fn execute(&self, input: TraceStartInput) -> Result<TraceStartOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+        session.trace_start(&input.path)?;
+
+        Ok(TraceStartOutput {
+            session_id: session.session_id(),
+            path: input.path,
+        })
+    }
+}
+
+pub trait TraceStopUseCase: Send + Sync {
+    fn execute(&self, input: SessionInput) -> Result<TraceStopOutput, SessionError>;
+}
+
+pub struct TraceStopUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> TraceStopUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> TraceStopUseCase for TraceStopUseCaseImpl<R> {
+    fn execute(&self, input: SessionInput) -> Result<TraceStopOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        Ok(TraceStopOutput {
+            session_id: session.session_id(),
+            trace: session.trace_stop()?,
+        })
+    }
+}
+
 pub trait CleanupUseCase: Send + Sync {
     fn execute(&self, input: CleanupInput) -> CleanupOutput;
 }
@@ -295,32 +468,50 @@ impl<R: SessionRepository> AssertUseCase for AssertUseCaseImpl<R> {
     fn execute(&self, input: AssertInput) -> Result<AssertOutput, SessionError> {
         let condition = format!("{}:{}", input.condition_type.as_str(), input.value);
 
-        let passed = match input.condition_type {
+        match input.condition_type {
             AssertConditionType::Text => {
                 let session = self.repository.resolve(input.session_id.as_ref())?;
                 session.update()?;
-                let screen = session.screen_text();
-                screen.contains(&input.value)
+                let scope = ScreenScope {
+                    unwrap: input.unwrap,
+                    region: None,
+                };
+                let screen = screen_lines(session.as_ref(), scope)?;
+                let passed = screen.text().contains(&input.value);
+                let diagnostics = (!passed).then(|| {
+                    match_diagnostics(&screen, Some(&input.value), session.screen_idle_ms())
+                });
+                Ok(AssertOutput {
+                    passed,
+                    condition,
+                    diagnostics,
+                })
             }
             AssertConditionType::Session => {
                 let sessions = self.repository.list();
-                sessions
+                let passed = sessions
                     .iter()
-                    .any(|s| s.id.as_str() == input.value && s.is_active())
+                    .any(|s| s.id.as_str() == input.value && s.is_active());
+                Ok(AssertOutput {
+                    passed,
+                    condition,
+                    diagnostics: None,
+                })
             }
-        };
-
-        Ok(AssertOutput { passed, condition })
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::ProcessSignal;
+    use crate::domain::ReplayPacing;
     use crate::domain::SessionId;
     use crate::domain::SessionInfo;
     use crate::domain::TerminalSize;
     use crate::test_support::MockError;
+    use crate::test_support::MockSession;
     use crate::test_support::MockSessionRepository;
     use std::collections::HashMap;
 
@@ -599,6 +790,7 @@ mod tests {
                 command: "bash".to_string(),
                 pid: 1001,
                 running: true,
+                exit_code: None,
                 created_at: "2024-01-01T00:00:00Z".to_string(),
                 size: TerminalSize::default(),
             },
@@ -607,6 +799,7 @@ mod tests {
                 command: "vim".to_string(),
                 pid: 1002,
                 running: true,
+                exit_code: None,
                 created_at: "2024-01-01T01:00:00Z".to_string(),
                 size: TerminalSize::try_new(120, 40).unwrap(),
             },
@@ -636,6 +829,7 @@ mod tests {
             command: "sleep".to_string(),
             pid: 999,
             running: true,
+            exit_code: None,
             created_at: "2024-01-01T00:00:00Z".to_string(),
             size: TerminalSize::default(),
         }];
@@ -765,4 +959,163 @@ mod tests {
         let result = usecase.execute(input);
         assert!(matches!(result, Err(SessionError::NotFound(_))));
     }
+
+    #[test]
+    fn test_signal_usecase_delivers_signal_to_session() {
+        let session = Arc::new(MockSession::new("sig"));
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_session_handle(session.clone())
+                .build(),
+        );
+        let usecase = SignalUseCaseImpl::new(repo);
+
+        let output = usecase
+            .execute(SignalInput {
+                session_id: Some(SessionId::new("sig")),
+                signal: ProcessSignal::Term,
+            })
+            .expect("signal should succeed");
+
+        assert!(output.success);
+        assert_eq!(output.signal, ProcessSignal::Term);
+        assert_eq!(session.signals(), vec![ProcessSignal::Term]);
+    }
+
+    #[test]
+    fn test_assert_usecase_text_failure_includes_diagnostics() {
+        let session = MockSession::builder("assert")
+            .with_screen_text("Proceed? [Continue?]")
+            .with_screen_idle_ms(40)
+            .build();
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = AssertUseCaseImpl::new(repo);
+
+        let output = usecase
+            .execute(AssertInput {
+                session_id: None,
+                condition_type: AssertConditionType::Text,
+                value: "Continue!".to_string(),
+                unwrap: false,
+            })
+            .unwrap();
+
+        assert!(!output.passed);
+        let diagnostics = output.diagnostics.expect("failure diagnostics");
+        assert_eq!(diagnostics.screen_idle_ms, 40);
+        assert_eq!(diagnostics.nearest_matches[0].text, "Continue?");
+        assert_eq!(diagnostics.nearest_matches[0].col, 10);
+    }
+
+    #[test]
+    fn test_assert_usecase_text_success_has_no_diagnostics() {
+        let session = MockSession::builder("assert")
+            .with_screen_text("Ready")
+            .build();
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = AssertUseCaseImpl::new(repo);
+
+        let output = usecase
+            .execute(AssertInput {
+                session_id: None,
+                condition_type: AssertConditionType::Text,
+                value: "Ready".to_string(),
+                unwrap: false,
+            })
+            .unwrap();
+
+        assert!(output.passed);
+        assert!(output.diagnostics.is_none());
+    }
+
+    #[test]
+    fn test_record_usecases_start_once_and_stop_with_summary() {
+        let session = Arc::new(MockSession::new("rec"));
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(session.clone())
+                .build(),
+        );
+        let start = RecordStartUseCaseImpl::new(repo.clone());
+        let stop = RecordStopUseCaseImpl::new(repo);
+        let input = RecordStartInput {
+            session_id: None,
+            path: "/tmp/run.cast".to_string(),
+            include_input: true,
+        };
+
+        let output = start.execute(input.clone()).unwrap();
+        assert_eq!(output.path, "/tmp/run.cast");
+        assert_eq!(
+            session.recording(),
+            Some(("/tmp/run.cast".to_string(), true))
+        );
+        assert!(matches!(
+            start.execute(input),
+            Err(SessionError::Recording { .. })
+        ));
+
+        let stopped = stop.execute(SessionInput { session_id: None }).unwrap();
+        assert_eq!(stopped.recording.unwrap().path, "/tmp/run.cast");
+        let again = stop.execute(SessionInput { session_id: None }).unwrap();
+        assert!(again.recording.is_none());
+    }
+
+    #[test]
+    fn test_trace_usecases_start_once_and_stop_with_summary() {
+        let session = Arc::new(MockSession::new("trace"));
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(session.clone())
+                .build(),
+        );
+        let start = TraceStartUseCaseImpl::new(repo.clone());
+        let stop = TraceStopUseCaseImpl::new(repo);
+        let input = TraceStartInput {
+            session_id: None,
+            path: "/tmp/trace.zip".to_string(),
+        };
+
+        let output = start.execute(input.clone()).unwrap();
+        assert_eq!(output.path, "/tmp/trace.zip");
+        assert!(matches!(
+            start.execute(input),
+            Err(SessionError::Recording { .. })
+        ));
+
+        let stopped = stop.execute(SessionInput { session_id: None }).unwrap();
+        assert_eq!(stopped.trace.unwrap().path, "/tmp/trace.zip");
+        let again = stop.execute(SessionInput { session_id: None }).unwrap();
+        assert!(again.trace.is_none());
+    }
+
+    #[test]
+    fn test_replay_usecase_passes_through_limit_errors() {
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_spawn_error(MockError::LimitReached(2))
+                .build(),
+        );
+        let usecase = ReplayUseCaseImpl::new(repo.clone());
+        let input = ReplayInput {
+            path: "/tmp/run.cast".to_string(),
+            pacing: ReplayPacing::Step,
+            session_id: None,
+            cols: 80,
+            rows: 24,
+        };
+
+        let result = usecase.execute(input);
+
+        assert!(matches!(result, Err(SessionError::LimitReached(2))));
+        assert_eq!(repo.spawn_call_count(), 1);
+    }
 }
@@ -2,10 +2,30 @@
 
 use std::sync::Arc;
 
+use crate::domain::AssertScreenInput;
+use crate::domain::AssertScreenOutput;
+use crate::domain::ElementsOutput;
+use crate::domain::FindInput;
+use crate::domain::FindOutput;
+use crate::domain::PanesOutput;
+use crate::domain::ScreenImage;
+use crate::domain::SessionInput;
+use crate::domain::SnapshotDiffInput;
+use crate::domain::SnapshotDiffOutput;
 use crate::domain::SnapshotInput;
 use crate::domain::SnapshotOutput;
+use crate::domain::TableInput;
+use crate::domain::TableOutput;
+use crate::domain::core::Golden;
+use crate::domain::core::ScreenDiff;
+use crate::domain::core::detect_elements;
+use crate::domain::core::extract_table;
+use crate::domain::core::find_panes;
 use crate::usecases::ports::SessionError;
 use crate::usecases::ports::SessionRepository;
+use crate::usecases::wait_condition::ScreenScope;
+use crate::usecases::wait_condition::region_pane;
+use crate::usecases::wait_condition::screen_lines;
 
 pub trait SnapshotUseCase: Send + Sync {
     fn execute(&self, input: SnapshotInput) -> Result<SnapshotOutput, SessionError>;
@@ -27,7 +47,17 @@ impl<R: SessionRepository> SnapshotUseCase for SnapshotUseCaseImpl<R> {
 
         session.update()?;
 
-        let screenshot = session.screen_text();
+        let region = input
+            .region
+            .as_deref()
+            .map(|region| region_pane(session.as_ref(), region))
+            .transpose()?;
+        let logical_screen = (input.unwrap && region.is_none()).then(|| session.logical_screen());
+        let screenshot = match (&region, &logical_screen) {
+            (Some((_, lines)), _) => lines.text(),
+            (None, Some(screen)) => screen.text(),
+            (None, None) => session.screen_text(),
+        };
         let session_id = session.session_id();
 
         let cursor = if input.include_cursor {
@@ -47,6 +77,199 @@ impl<R: SessionRepository> SnapshotUseCase for SnapshotUseCaseImpl<R> {
             screenshot,
             cursor,
             rendered,
+            logical_screen,
+            region: region.map(|(pane, _)| pane),
+            screen_version: session.screen_version(),
+            highlights: input
+                .include_highlights
+                .then(|| session.screen_snapshot().inverse_spans()),
+            image: input.image.map(|format| ScreenImage {
+                format,
+                data: session.screen_image(format),
+            }),
+        })
+    }
+}
+
+pub trait SnapshotDiffUseCase: Send + Sync {
+    fn execute(&self, input: SnapshotDiffInput) -> Result<SnapshotDiffOutput, SessionError>;
+}
+
+pub struct SnapshotDiffUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> SnapshotDiffUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> SnapshotDiffUseCase for SnapshotDiffUseCaseImpl<R> {
+    fn execute(&self, input: SnapshotDiffInput) -> Result<SnapshotDiffOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        let current = session.screen_state();
+        let base = session.screen_state_at(input.since);
+        Ok(SnapshotDiffOutput {
+            session_id: session.session_id(),
+            diff: ScreenDiff::between(input.since, base.as_ref(), &current),
+        })
+    }
+}
+
+pub trait AssertScreenUseCase: Send + Sync {
+    fn execute(&self, input: AssertScreenInput) -> Result<AssertScreenOutput, SessionError>;
+}
+
+pub struct AssertScreenUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> AssertScreenUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> AssertScreenUseCase for AssertScreenUseCaseImpl<R> {
+    fn execute(&self, input: AssertScreenInput) -> Result<AssertScreenOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        let mut expected = input.golden;
+        expected.merge_masks(input.masks);
+        let styles = input.styles || !expected.styles.is_empty();
+        let actual =
+            Golden::from_snapshot(&session.screen_snapshot(), expected.masks.clone(), styles);
+        let diffs = expected.compare(&actual, styles);
+        Ok(AssertScreenOutput {
+            session_id: session.session_id(),
+            matched: diffs.is_empty(),
+            styles,
+            diffs,
+            golden: actual.render(),
+        })
+    }
+}
+
+pub trait FindUseCase: Send + Sync {
+    fn execute(&self, input: FindInput) -> Result<FindOutput, SessionError>;
+}
+
+pub struct FindUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> FindUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> FindUseCase for FindUseCaseImpl<R> {
+    fn execute(&self, input: FindInput) -> Result<FindOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        let scope = ScreenScope {
+            unwrap: input.unwrap,
+            region: input.region.as_deref(),
+        };
+        let matches = screen_lines(session.as_ref(), scope)?.find(&input.text);
+        Ok(FindOutput {
+            session_id: session.session_id(),
+            matches,
+        })
+    }
+}
+
+pub trait ElementsUseCase: Send + Sync {
+    fn execute(&self, input: SessionInput) -> Result<ElementsOutput, SessionError>;
+}
+
+pub struct ElementsUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> ElementsUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> ElementsUseCase for ElementsUseCaseImpl<R> {
+    fn execute(&self, input: SessionInput) -> Result<ElementsOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        Ok(ElementsOutput {
+            session_id: session.session_id(),
+            screen_version: session.screen_version(),
+            elements: detect_elements(&session.screen_snapshot()),
+        })
+    }
+}
+
+pub trait PanesUseCase: Send + Sync {
+    fn execute(&self, input: SessionInput) -> Result<PanesOutput, SessionError>;
+}
+
+pub struct PanesUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> PanesUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> PanesUseCase for PanesUseCaseImpl<R> {
+    fn execute(&self, input: SessionInput) -> Result<PanesOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        Ok(PanesOutput {
+            session_id: session.session_id(),
+            screen_version: session.screen_version(),
+            panes: find_panes(&session.screen_snapshot()),
+        })
+    }
+}
+
+pub trait TableUseCase: Send + Sync {
+    fn execute(&self, input: TableInput) -> Result<TableOutput, SessionError>;
+}
+
+pub struct TableUseCaseImpl<R: SessionRepository> {
+    repository: Arc<R>,
+}
+
+impl<R: SessionRepository> TableUseCaseImpl<R> {
+    pub fn new(repository: Arc<R>) -> Self {
+        Self { repository }
+    }
+}
+
+impl<R: SessionRepository> TableUseCase for TableUseCaseImpl<R> {
+    fn execute(&self, input: TableInput) -> Result<TableOutput, SessionError> {
+        let session = self.repository.resolve(input.session_id.as_ref())?;
+        session.update()?;
+
+        let region = input
+            .region
+            .as_deref()
+            .map(|region| region_pane(session.as_ref(), region))
+            .transpose()?
+            .map(|(pane, _)| pane);
+        let table = extract_table(&session.screen_snapshot(), region.as_ref());
+        Ok(TableOutput {
+            session_id: session.session_id(),
+            screen_version: session.screen_version(),
+            region,
+            table,
         })
     }
 }
@@ -54,8 +277,32 @@ impl<R: SessionRepository> SnapshotUseCase for SnapshotUseCaseImpl<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::core::CursorPosition;
+    use crate::domain::core::GoldenMask;
+    use crate::domain::core::LogicalScreen;
+    use crate::domain::core::ScreenState;
+    use crate::test_support::MockSession;
     use crate::test_support::MockSessionRepository;
 
+    fn wrapped_repository() -> Arc<MockSessionRepository> {
+        let screen = LogicalScreen::from_rows(
+            10,
+            vec![
+                ("/usr/local".to_string(), true),
+                ("/bin/tool".to_string(), false),
+            ],
+        );
+        let session = MockSession::builder("wrapped")
+            .with_screen_text("/usr/local\n/bin/tool")
+            .with_logical_screen(screen)
+            .build();
+        Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        )
+    }
+
     #[test]
     fn test_snapshot_usecase_returns_error_when_no_session() {
         let repository = Arc::new(MockSessionRepository::new());
@@ -66,4 +313,222 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_snapshot_usecase_unwrap_joins_wrapped_rows() {
+        let usecase = SnapshotUseCaseImpl::new(wrapped_repository());
+
+        let output = usecase
+            .execute(SnapshotInput {
+                unwrap: true,
+                ..SnapshotInput::default()
+            })
+            .unwrap();
+
+        assert_eq!(output.screenshot, "/usr/local/bin/tool");
+        let lines = output.logical_screen.unwrap().lines;
+        assert_eq!((lines[0].start_row, lines[0].rows), (0, 2));
+    }
+
+    #[test]
+    fn test_find_usecase_reports_physical_span_for_wrapped_match() {
+        let usecase = FindUseCaseImpl::new(wrapped_repository());
+
+        let wrapped = usecase
+            .execute(FindInput {
+                session_id: None,
+                text: "local/bin".to_string(),
+                unwrap: true,
+                region: None,
+            })
+            .unwrap();
+        assert_eq!(wrapped.matches.len(), 1);
+        let span = wrapped.matches[0];
+        assert_eq!(
+            (span.row, span.col, span.end_row, span.end_col),
+            (0, 5, 1, 3)
+        );
+
+        let physical = usecase
+            .execute(FindInput {
+                session_id: None,
+                text: "local/bin".to_string(),
+                unwrap: false,
+                region: None,
+            })
+            .unwrap();
+        assert!(physical.matches.is_empty());
+    }
+
+    fn panes_repository() -> Arc<MockSessionRepository> {
+        let session = MockSession::builder("panes")
+            .with_screen_text(
+                "┌─ Files ─┐┌─ Log ───┐\n\
+                 │ ready   ││ ready   │\n\
+                 └─────────┘└─────────┘",
+            )
+            .build();
+        Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        )
+    }
+
+    #[test]
+    fn test_snapshot_and_find_limited_to_region() {
+        let repository = panes_repository();
+
+        let snapshot = SnapshotUseCaseImpl::new(Arc::clone(&repository))
+            .execute(SnapshotInput {
+                region: Some("log".to_string()),
+                ..SnapshotInput::default()
+            })
+            .unwrap();
+        assert_eq!(snapshot.screenshot, " ready");
+        assert_eq!(snapshot.region.map(|pane| pane.id), Some("p2".to_string()));
+
+        let found = FindUseCaseImpl::new(Arc::clone(&repository))
+            .execute(FindInput {
+                session_id: None,
+                text: "ready".to_string(),
+                unwrap: false,
+                region: Some("right".to_string()),
+            })
+            .unwrap();
+        assert_eq!(found.matches.len(), 1);
+        assert_eq!((found.matches[0].row, found.matches[0].col), (1, 13));
+
+        let missing = SnapshotUseCaseImpl::new(repository).execute(SnapshotInput {
+            region: Some("Help".to_string()),
+            ..SnapshotInput::default()
+        });
+        assert!(matches!(missing, Err(SessionError::RegionNotFound { .. })));
+    }
+
+    #[test]
+    fn test_table_usecase_reads_table_in_region() {
+        let session = MockSession::builder("table")
+            .with_screen_text(
+                "┌─ Info ──┐┌─ Procs ──────┐\n\
+                 │ up 3d   ││ PID  NAME    │\n\
+                 │ load 1  ││  42  vim     │\n\
+                 │         ││ 977  cargo   │\n\
+                 └─────────┘└──────────────┘",
+            )
+            .with_screen_version(5)
+            .build();
+        let repository = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = TableUseCaseImpl::new(repository);
+
+        let output = usecase
+            .execute(TableInput {
+                session_id: None,
+                region: Some("Procs".to_string()),
+            })
+            .unwrap();
+
+        assert_eq!(output.region.map(|pane| pane.id), Some("p2".to_string()));
+        let table = output.table.unwrap();
+        assert_eq!(table.header_row, 1);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[1].cells, vec!["977", "cargo"]);
+    }
+
+    #[test]
+    fn test_snapshot_diff_usecase_returns_changed_rows_since_version() {
+        let cursor = CursorPosition {
+            row: 1,
+            col: 0,
+            visible: true,
+        };
+        let base = ScreenState {
+            version: 12,
+            rows: vec!["Menu".to_string(), "> One".to_string()],
+            cursor,
+        };
+        let session = MockSession::builder("diff")
+            .with_screen_text("Menu\n> Two")
+            .with_screen_version(30)
+            .with_screen_history(vec![base])
+            .build();
+        let repository = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = SnapshotDiffUseCaseImpl::new(repository);
+
+        let output = usecase
+            .execute(SnapshotDiffInput {
+                session_id: None,
+                since: 20,
+            })
+            .unwrap();
+
+        let diff = output.diff;
+        assert!(!diff.full);
+        assert_eq!((diff.from_version, diff.to_version), (12, 30));
+        assert_eq!(diff.rows.len(), 1);
+        assert_eq!(diff.rows[0].row, 1);
+        assert_eq!(diff.rows[0].new, "> Two");
+    }
+
+    #[test]
+    fn test_assert_screen_usecase_applies_extra_masks_and_renders_golden() {
+        let session = MockSession::builder("golden")
+            .with_screen_text("Build 42 done\nok")
+            .build();
+        let repository = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = AssertScreenUseCaseImpl::new(repository);
+        let input = |masks| AssertScreenInput {
+            session_id: None,
+            golden: Golden::parse("Build 7 done\nok\n").unwrap(),
+            masks,
+            styles: false,
+        };
+
+        let output = usecase.execute(input(Vec::new())).unwrap();
+        assert!(!output.matched);
+        assert_eq!(output.diffs[0].actual, "Build 42 done");
+
+        let mask = GoldenMask::parse("regex \\d+").unwrap();
+        let output = usecase.execute(input(vec![mask])).unwrap();
+        assert!(output.matched);
+        assert!(!output.styles);
+        assert_eq!(output.golden, "%mask regex \\d+\n%%\nBuild 42 done\nok\n");
+    }
+
+    #[test]
+    fn test_elements_usecase_detects_widgets_on_current_screen() {
+        let session = MockSession::builder("elements")
+            .with_screen_text("Save changes?\n[ Yes ]  [ No ]")
+            .with_screen_version(9)
+            .build();
+        let repository = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = ElementsUseCaseImpl::new(repository);
+
+        let output = usecase.execute(SessionInput { session_id: None }).unwrap();
+
+        assert_eq!(output.screen_version, 9);
+        let labels: Vec<&str> = output
+            .elements
+            .iter()
+            .map(|element| element.label.as_str())
+            .collect();
+        assert_eq!(labels, vec!["Yes", "No"]);
+        assert_eq!(output.elements[1].ref_id, "e2");
+    }
 }
@@ -5,12 +5,16 @@ use std::time::Duration;
 
 use crate::domain::WaitInput;
 use crate::domain::WaitOutput;
+use crate::usecases::match_diagnostics::match_diagnostics;
+use crate::usecases::match_diagnostics::text_gone_diagnostics;
 use crate::usecases::ports::Clock;
 use crate::usecases::ports::SessionError;
 use crate::usecases::ports::SessionRepository;
+use crate::usecases::wait_condition::ScreenScope;
 use crate::usecases::wait_condition::StableTracker;
 use crate::usecases::wait_condition::WaitCondition;
 use crate::usecases::wait_condition::check_condition;
+use crate::usecases::wait_condition::screen_lines;
 
 pub trait WaitUseCase: Send + Sync {
     fn execute(&self, input: WaitInput) -> Result<WaitOutput, SessionError>;
@@ -36,26 +40,41 @@ impl<R: SessionRepository> WaitUseCase for WaitUseCaseImpl<R> {
         let condition = WaitCondition::parse(input.condition, input.text.as_deref())
             .map_err(|e| SessionError::InvalidKey(e.to_string()))?;
 
+        let scope = ScreenScope {
+            unwrap: input.unwrap,
+            region: input.region.as_deref(),
+        };
         let mut stable_tracker = StableTracker::new(3);
         let poll_interval = Duration::from_millis(50);
         let subscription = session.stream_subscribe();
+        let before = session.screen_text();
 
         loop {
             session.update()?;
 
-            if check_condition(session.as_ref(), &condition, &mut stable_tracker) {
+            if check_condition(session.as_ref(), &condition, &mut stable_tracker, scope) {
                 let elapsed_ms = self.clock.elapsed_ms(start);
+                session.record_wait(&condition.describe(), true, elapsed_ms, &before);
                 return Ok(WaitOutput {
                     found: true,
                     elapsed_ms,
+                    diagnostics: None,
                 });
             }
 
             if self.clock.elapsed(start) >= timeout {
                 let elapsed_ms = self.clock.elapsed_ms(start);
+                let screen = screen_lines(session.as_ref(), scope)?;
+                let idle_ms = session.screen_idle_ms();
+                let diagnostics = match &condition {
+                    WaitCondition::TextGone(text) => text_gone_diagnostics(&screen, text, idle_ms),
+                    _ => match_diagnostics(&screen, condition.text(), idle_ms),
+                };
+                session.record_wait(&condition.describe(), false, elapsed_ms, &before);
                 return Ok(WaitOutput {
                     found: false,
                     elapsed_ms,
+                    diagnostics: Some(diagnostics),
                 });
             }
 
@@ -69,6 +88,7 @@ mod tests {
     use super::*;
     use crate::domain::SessionId;
     use crate::test_support::MockError;
+    use crate::test_support::MockSession;
     use crate::test_support::MockSessionRepository;
     use std::time::Instant;
 
@@ -99,6 +119,8 @@ mod tests {
             text: Some("loading".to_string()),
             timeout_ms: 5000,
             condition: None,
+            unwrap: false,
+            region: None,
         };
 
         let result = usecase.execute(input);
@@ -120,6 +142,8 @@ mod tests {
             text: Some("ready".to_string()),
             timeout_ms: 1000,
             condition: None,
+            unwrap: false,
+            region: None,
         };
 
         let result = usecase.execute(input);
@@ -137,11 +161,83 @@ mod tests {
             text: None,
             timeout_ms: 5000,
             condition: Some(crate::domain::WaitConditionType::Stable),
+            unwrap: false,
+            region: None,
         };
 
         let result = usecase.execute(input);
         assert!(matches!(result, Err(SessionError::NoActiveSession)));
     }
 
+    #[test]
+    fn test_wait_usecase_timeout_returns_nearest_matches() {
+        let session = MockSession::builder("typo")
+            .with_screen_text("Save changes?\n  [Continue?]  [Cancel]")
+            .with_screen_idle_ms(1200)
+            .build();
+        let session = Arc::new(session);
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_active_session_handle(session.clone())
+                .build(),
+        );
+        let usecase = WaitUseCaseImpl::new(repo, Arc::new(TestClock));
+
+        let input = WaitInput {
+            session_id: None,
+            text: Some("Continue!".to_string()),
+            timeout_ms: 0,
+            condition: None,
+            unwrap: false,
+            region: None,
+        };
+
+        let output = usecase.execute(input).unwrap();
+        assert!(!output.found);
+        let diagnostics = output.diagnostics.expect("timeout diagnostics");
+        assert_eq!(diagnostics.screen_idle_ms, 1200);
+        assert!(diagnostics.screen.contains("[Continue?]"));
+        let nearest = &diagnostics.nearest_matches[0];
+        assert_eq!(nearest.text, "Continue?");
+        assert_eq!((nearest.row, nearest.col, nearest.distance), (1, 3, 1));
+        assert_eq!(
+            session.waits(),
+            vec![("text \"Continue!\"".to_string(), false)]
+        );
+    }
+
+    #[test]
+    fn test_wait_usecase_text_gone_timeout_reports_where_text_remains() {
+        let session = MockSession::builder("busy")
+            .with_screen_text("Saving...\n  Loading  Loading")
+            .build();
+        let repo = Arc::new(
+            MockSessionRepository::builder()
+                .with_session_handle(Arc::new(session))
+                .build(),
+        );
+        let usecase = WaitUseCaseImpl::new(repo, Arc::new(TestClock));
+
+        let input = WaitInput {
+            session_id: Some(SessionId::new("busy")),
+            text: Some("Loading".to_string()),
+            timeout_ms: 0,
+            condition: Some(crate::domain::WaitConditionType::TextGone),
+            unwrap: false,
+            region: None,
+        };
+
+        let output = usecase.execute(input).unwrap();
+        assert!(!output.found);
+        let diagnostics = output.diagnostics.expect("timeout diagnostics");
+        assert!(diagnostics.nearest_matches.is_empty());
+        let positions = diagnostics
+            .still_visible
+            .iter()
+            .map(|m| (m.row, m.col))
+            .collect::<Vec<_>>();
+        assert_eq!(positions, vec![(1, 2), (1, 11)]);
+    }
+
     // WaitCondition parsing is covered in wait_condition.rs tests.
 }
@@ -6,6 +6,12 @@ use std::hash::Hash;
 use std::hash::Hasher;
 
 use crate::domain::WaitConditionType;
+use crate::domain::core::LogicalScreen;
+use crate::domain::core::Pane;
+use crate::domain::core::find_panes;
+use crate::domain::core::region_names;
+use crate::domain::core::resolve_region;
+use crate::usecases::ports::SessionError;
 use crate::usecases::ports::SessionOps;
 
 #[derive(Debug, thiserror::Error)]
@@ -19,6 +25,7 @@ pub enum WaitCondition {
     Text(String),
     Stable,
     TextGone(String),
+    EchoOff,
 }
 
 impl WaitCondition {
@@ -33,6 +40,7 @@ impl WaitCondition {
                 )
             }
             Some(WaitConditionType::Stable) => Ok(WaitCondition::Stable),
+            Some(WaitConditionType::EchoOff) => Ok(WaitCondition::EchoOff),
             Some(WaitConditionType::TextGone) => {
                 text.map(|t| WaitCondition::TextGone(t.to_string())).ok_or(
                     WaitConditionParseError::MissingText(WaitConditionType::TextGone),
@@ -43,6 +51,23 @@ impl WaitCondition {
                 .unwrap_or(WaitCondition::Stable)),
         }
     }
+
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            WaitCondition::Text(text) | WaitCondition::TextGone(text) => Some(text),
+            WaitCondition::Stable | WaitCondition::EchoOff => None,
+        }
+    }
+
+    /// Short form for timelines, e.g. `text "Ready"` or `stable`.
+    pub fn describe(&self) -> String {
+        match self {
+            WaitCondition::Text(text) => format!("text {text:?}"),
+            WaitCondition::TextGone(text) => format!("gone {text:?}"),
+            WaitCondition::Stable => "stable".to_string(),
+            WaitCondition::EchoOff => "echo-off".to_string(),
+        }
+    }
 }
 
 #[derive(Default)]
@@ -79,24 +104,70 @@ impl StableTracker {
     }
 }
 
+/// The part of the screen a command reads.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ScreenScope<'a> {
+    /// Join rows the terminal soft-wrapped into logical lines.
+    pub unwrap: bool,
+    /// Only the interior of this pane (see `resolve_region`); `unwrap` does not apply.
+    pub region: Option<&'a str>,
+}
+
+/// Screen lines used for matching: soft-wrapped rows are joined when `unwrap` is set,
+/// otherwise every physical row is its own line. Positions always map to the full screen.
+pub fn screen_lines<S: SessionOps + ?Sized>(
+    session: &S,
+    scope: ScreenScope<'_>,
+) -> Result<LogicalScreen, SessionError> {
+    match scope.region {
+        Some(region) => region_pane(session, region).map(|(_, lines)| lines),
+        None if scope.unwrap => Ok(session.logical_screen()),
+        None => {
+            let (cols, _) = session.size();
+            Ok(LogicalScreen::from_text(&session.screen_text(), cols))
+        }
+    }
+}
+
+/// Resolves `region` against the panes currently on screen.
+pub fn region_pane<S: SessionOps + ?Sized>(
+    session: &S,
+    region: &str,
+) -> Result<(Pane, LogicalScreen), SessionError> {
+    let snapshot = session.screen_snapshot();
+    let panes = find_panes(&snapshot);
+    let pane = resolve_region(&panes, region).ok_or_else(|| SessionError::RegionNotFound {
+        region: region.to_string(),
+        available: region_names(&panes),
+    })?;
+    Ok((pane.clone(), pane.lines(&snapshot)))
+}
+
+/// A region that is not on screen yet counts as the condition not being met.
 pub fn check_condition<S: SessionOps + ?Sized>(
     session: &S,
     condition: &WaitCondition,
     stable_tracker: &mut StableTracker,
+    scope: ScreenScope<'_>,
 ) -> bool {
     let _ = session.update();
-    let screen = session.screen_text();
+    let Ok(screen) = screen_lines(session, scope) else {
+        return false;
+    };
+    let screen = screen.text();
 
     match condition {
         WaitCondition::Text(text) => screen.contains(text),
         WaitCondition::Stable => stable_tracker.add_hash(&screen),
         WaitCondition::TextGone(text) => !screen.contains(text),
+        WaitCondition::EchoOff => session.tty_state().is_some_and(|state| !state.echo),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::TtyState;
     use crate::test_support::MockSession;
 
     #[test]
@@ -110,6 +181,7 @@ mod tests {
             &session,
             &WaitCondition::Text("World".to_string()),
             &mut tracker,
+            ScreenScope::default(),
         );
 
         assert!(result);
@@ -126,11 +198,46 @@ mod tests {
             &session,
             &WaitCondition::Text("Missing".to_string()),
             &mut tracker,
+            ScreenScope::default(),
         );
 
         assert!(!result);
     }
 
+    #[test]
+    fn test_check_condition_unwrap_matches_across_wrapped_rows() {
+        let screen = LogicalScreen::from_rows(
+            10,
+            vec![
+                ("/usr/local".to_string(), true),
+                ("/bin/tool".to_string(), false),
+            ],
+        );
+        let session = MockSession::builder("test")
+            .with_screen_text("/usr/local\n/bin/tool")
+            .with_logical_screen(screen)
+            .build();
+        let condition = WaitCondition::Text("local/bin".to_string());
+        let mut tracker = StableTracker::new(3);
+
+        assert!(!check_condition(
+            &session,
+            &condition,
+            &mut tracker,
+            ScreenScope::default()
+        ));
+        let unwrapped = ScreenScope {
+            unwrap: true,
+            region: None,
+        };
+        assert!(check_condition(
+            &session,
+            &condition,
+            &mut tracker,
+            unwrapped
+        ));
+    }
+
     #[test]
     fn test_check_condition_text_gone_when_absent() {
         let session = MockSession::builder("test")
@@ -142,6 +249,7 @@ mod tests {
             &session,
             &WaitCondition::TextGone("Loading".to_string()),
             &mut tracker,
+            ScreenScope::default(),
         );
 
         assert!(result);
@@ -158,11 +266,44 @@ mod tests {
             &session,
             &WaitCondition::TextGone("Loading".to_string()),
             &mut tracker,
+            ScreenScope::default(),
         );
 
         assert!(!result);
     }
 
+    #[test]
+    fn test_check_condition_echo_off() {
+        let session = MockSession::builder("test")
+            .with_tty_state(TtyState {
+                echo: false,
+                icanon: true,
+                isig: true,
+            })
+            .build();
+        let mut tracker = StableTracker::new(3);
+
+        assert!(check_condition(
+            &session,
+            &WaitCondition::EchoOff,
+            &mut tracker,
+            ScreenScope::default(),
+        ));
+    }
+
+    #[test]
+    fn test_check_condition_echo_off_without_tty_state() {
+        let session = MockSession::new("test");
+        let mut tracker = StableTracker::new(3);
+
+        assert!(!check_condition(
+            &session,
+            &WaitCondition::EchoOff,
+            &mut tracker,
+            ScreenScope::default(),
+        ));
+    }
+
     #[test]
     fn test_check_condition_stable_requires_multiple_same_hashes() {
         let session = MockSession::builder("test")
@@ -173,17 +314,20 @@ mod tests {
         assert!(!check_condition(
             &session,
             &WaitCondition::Stable,
-            &mut tracker
+            &mut tracker,
+            ScreenScope::default(),
         ));
         assert!(!check_condition(
             &session,
             &WaitCondition::Stable,
-            &mut tracker
+            &mut tracker,
+            ScreenScope::default(),
         ));
         assert!(check_condition(
             &session,
             &WaitCondition::Stable,
-            &mut tracker
+            &mut tracker,
+            ScreenScope::default(),
         ));
     }
 
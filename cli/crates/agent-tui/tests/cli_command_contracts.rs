@@ -29,6 +29,30 @@ struct CommandCase {
 
 fn no_setup(_: &TestHarness) {}
 
+fn setup_screen_image(harness: &TestHarness) {
+    harness.set_success_response(
+        "snapshot",
+        json!({
+            "session_id": "session-1",
+            "screenshot": "ready",
+            "screen_version": 3,
+            "image": { "format": "svg", "data": "PHN2Zy8+" }
+        }),
+    );
+}
+
+fn setup_screen_html(harness: &TestHarness) {
+    harness.set_success_response(
+        "snapshot",
+        json!({
+            "session_id": "session-1",
+            "screenshot": "ready",
+            "screen_version": 3,
+            "image": { "format": "html", "data": "PHByZT5yZWFkeTwvcHJlPgo=" }
+        }),
+    );
+}
+
 fn setup_running_session(harness: &TestHarness) {
     harness.set_success_response(
         "sessions",
@@ -169,28 +193,41 @@ fn command_paths_match_expected_matrix() {
     collect_command_paths(&command, None, &mut discovered);
 
     let expected = BTreeSet::from([
+        "assert-screen".to_string(),
         "completions".to_string(),
         "daemon".to_string(),
         "daemon restart".to_string(),
         "daemon start".to_string(),
         "daemon stop".to_string(),
         "env".to_string(),
+        "fill".to_string(),
+        "find".to_string(),
         "kill".to_string(),
         "live".to_string(),
         "live start".to_string(),
         "live status".to_string(),
         "live stop".to_string(),
         "press".to_string(),
+        "record".to_string(),
+        "record start".to_string(),
+        "record stop".to_string(),
         "resize".to_string(),
         "restart".to_string(),
         "run".to_string(),
         "screenshot".to_string(),
+        "script".to_string(),
+        "script run".to_string(),
+        "select".to_string(),
         "sessions".to_string(),
         "sessions attach".to_string(),
         "sessions cleanup".to_string(),
         "sessions list".to_string(),
         "sessions show".to_string(),
         "sessions switch".to_string(),
+        "trace".to_string(),
+        "trace show".to_string(),
+        "trace start".to_string(),
+        "trace stop".to_string(),
         "type".to_string(),
         "version".to_string(),
         "wait".to_string(),
@@ -216,6 +253,36 @@ fn rpc_contract_matrix_covers_full_working_surface() {
             expected_method: "snapshot",
             setup: no_setup,
         },
+        CommandCase {
+            args: &["screenshot", "--since", "10"],
+            expected_method: "snapshot_diff",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["screenshot", "--elements"],
+            expected_method: "elements",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["screenshot", "--panes"],
+            expected_method: "panes",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["screenshot", "--table"],
+            expected_method: "table",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["screenshot", "--image", "svg", "--out", "/dev/null"],
+            expected_method: "snapshot",
+            setup: setup_screen_image,
+        },
+        CommandCase {
+            args: &["--format", "html", "screenshot"],
+            expected_method: "snapshot",
+            setup: setup_screen_html,
+        },
         CommandCase {
             args: &["resize", "--cols", "88", "--rows", "22"],
             expected_method: "resize",
@@ -241,6 +308,62 @@ fn rpc_contract_matrix_covers_full_working_surface() {
             expected_method: "keyup",
             setup: no_setup,
         },
+        CommandCase {
+            args: &["select", "--enter", "Open file"],
+            expected_method: "select",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["run", "--record", "/dev/null", "bash"],
+            expected_method: "record_start",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["run", "--replay", "/dev/null", "--step"],
+            expected_method: "replay",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["record", "start", "--input", "/dev/null"],
+            expected_method: "record_start",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["record", "stop"],
+            expected_method: "record_stop",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &[
+                "assert-screen",
+                "--golden",
+                "tests/fixtures/golden/screen.txt",
+                "--mask-rect",
+                "0,0,4x1",
+            ],
+            expected_method: "assert_screen",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["trace", "start", "--out", "/dev/null"],
+            expected_method: "trace_start",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["trace", "stop"],
+            expected_method: "trace_stop",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["script", "run", "tests/fixtures/script/smoke.yaml"],
+            expected_method: "spawn",
+            setup: no_setup,
+        },
+        CommandCase {
+            args: &["fill", "tests/fixtures/fill/answers.yaml"],
+            expected_method: "fill",
+            setup: no_setup,
+        },
         CommandCase {
             args: &["type", "hello"],
             expected_method: "type",
@@ -251,6 +374,11 @@ fn rpc_contract_matrix_covers_full_working_surface() {
             expected_method: "wait",
             setup: no_setup,
         },
+        CommandCase {
+            args: &["find", "done"],
+            expected_method: "find",
+            setup: no_setup,
+        },
         CommandCase {
             args: &["wait", "--stable"],
             expected_method: "wait",
@@ -368,6 +496,55 @@ fn standalone_version_env_and_completions_contract() {
         .stderr(predicate::str::contains("cannot be used with"));
 }
 
+#[test]
+fn standalone_trace_show_reports_unreadable_bundles() {
+    let env = StandaloneEnv::new();
+
+    env.run(&["trace", "show", "/nonexistent/trace.zip"])
+        .code(74)
+        .stderr(predicate::str::contains("Cannot read trace"));
+    env.run(&["trace", "show", "Cargo.toml"])
+        .code(74)
+        .stderr(predicate::str::contains("not a trace bundle"));
+}
+
+#[test]
+fn script_run_reports_failing_step_and_junit() {
+    let harness = TestHarness::new();
+    let dir = TempDir::new().unwrap();
+    let script = dir.path().join("flow.yaml");
+    let junit = dir.path().join("junit.xml");
+    fs::write(
+        &script,
+        "steps:\n  - press: Enter\n  - assert: missing\n  - type: never\n",
+    )
+    .unwrap();
+
+    harness
+        .run(&[
+            "script",
+            "run",
+            script.to_str().unwrap(),
+            "--junit",
+            junit.to_str().unwrap(),
+        ])
+        .code(1)
+        .stdout(predicate::str::contains("(skipped)"))
+        .stderr(predicate::str::contains("failed at step 2"));
+    harness.assert_method_called("keystroke");
+    let xml = fs::read_to_string(&junit).unwrap();
+    assert!(
+        xml.contains("tests=\"3\" failures=\"1\" skipped=\"1\""),
+        "{xml}"
+    );
+
+    fs::write(&script, "steps:\n  - press: Enter\n    type: x\n").unwrap();
+    harness
+        .run(&["script", "run", script.to_str().unwrap()])
+        .code(64)
+        .stderr(predicate::str::contains("expected exactly one action"));
+}
+
 #[test]
 fn standalone_daemon_commands_contract() {
     let env = StandaloneEnv::new();
@@ -437,8 +614,17 @@ fn help_entrypoints_remain_valid() {
         &["restart", "--help"],
         &["press", "--help"],
         &["type", "--help"],
+        &["select", "--help"],
+        &["fill", "--help"],
         &["wait", "--help"],
+        &["assert-screen", "--help"],
         &["kill", "--help"],
+        &["record", "--help"],
+        &["record", "start", "--help"],
+        &["trace", "--help"],
+        &["trace", "show", "--help"],
+        &["script", "--help"],
+        &["script", "run", "--help"],
         &["sessions", "--help"],
         &["sessions", "help"],
         &["live", "--help"],
@@ -268,6 +268,154 @@ impl MockDaemon {
                     "elapsed_ms": 100
                 })),
             );
+            h.insert(
+                "snapshot_diff".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "from_version": 10,
+                    "screen_version": 24,
+                    "full": false,
+                    "rows": [{ "row": 0, "old": "Loading", "new": "Ready" }],
+                    "cursor": { "row": 0, "col": 5, "visible": true },
+                    "cursor_delta": { "rows": 0, "cols": -2 }
+                })),
+            );
+            h.insert(
+                "elements".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "screen_version": 24,
+                    "elements": [{
+                        "ref": "e1",
+                        "kind": "button",
+                        "label": "OK",
+                        "row": 3,
+                        "col": 4,
+                        "width": 6,
+                        "height": 1,
+                        "value": null,
+                        "checked": null,
+                        "selected": false,
+                        "focused": true,
+                        "children": []
+                    }]
+                })),
+            );
+            h.insert(
+                "panes".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "screen_version": 24,
+                    "panes": [{
+                        "id": "p1",
+                        "title": "Files",
+                        "row": 0,
+                        "col": 0,
+                        "width": 20,
+                        "height": 10,
+                        "parent": null,
+                        "focused": true
+                    }]
+                })),
+            );
+            h.insert(
+                "table".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "screen_version": 24,
+                    "region": null,
+                    "header_row": 0,
+                    "columns": [
+                        { "name": "PID", "col": 0, "width": 6 },
+                        { "name": "NAME", "col": 6, "width": 10 }
+                    ],
+                    "rows": [{ "PID": "42", "NAME": "vim" }],
+                    "screen_rows": [1],
+                    "highlighted": 0
+                })),
+            );
+            h.insert(
+                "select".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "found": true,
+                    "selected": "Open file",
+                    "row": 3,
+                    "steps": 2,
+                    "submitted": true
+                })),
+            );
+            h.insert(
+                "fill".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "completed": true,
+                    "transcript": [{
+                        "label": "Project name",
+                        "prompt": "? Project name:",
+                        "row": 0,
+                        "answered": true,
+                        "elapsed_ms": 12
+                    }],
+                    "unexpected": [],
+                    "missing": [],
+                    "elapsed_ms": 40
+                })),
+            );
+            h.insert(
+                "find".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "matches": [{ "row": 0, "col": 0, "end_row": 0, "end_col": 3 }]
+                })),
+            );
+            h.insert(
+                "record_start".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "path": "/dev/null"
+                })),
+            );
+            h.insert(
+                "replay".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "format": "cast",
+                    "cols": super::TEST_COLS,
+                    "rows": super::TEST_ROWS,
+                    "events": 42,
+                    "duration_ms": 12300
+                })),
+            );
+            h.insert(
+                "record_stop".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "recording": { "path": "/dev/null", "events": 12, "duration_ms": 3400 }
+                })),
+            );
+            h.insert(
+                "assert_screen".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "matched": true,
+                    "styles": false,
+                    "diff": [],
+                    "golden": "Ready\n"
+                })),
+            );
+            h.insert(
+                "trace_start".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "path": "/dev/null"
+                })),
+            );
+            h.insert(
+                "trace_stop".to_string(),
+                MockResponse::Success(serde_json::json!({
+                    "session_id": super::TEST_SESSION_ID,
+                    "trace": { "path": "/dev/null", "steps": 4, "output_bytes": 512, "duration_ms": 2100 }
+                })),
+            );
             h.insert(
                 "resize".to_string(),
                 MockResponse::Success(serde_json::json!({
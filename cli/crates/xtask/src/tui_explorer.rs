@@ -1,11 +1,27 @@
+use agent_tui_adapters::adapters::rpc::params;
+use agent_tui_adapters::domain::ProcessSignal;
+use agent_tui_infra::infra::ipc::ClientError;
+use agent_tui_infra::infra::ipc::DaemonClient;
+use agent_tui_infra::infra::ipc::DaemonClientConfig;
+use agent_tui_infra::infra::ipc::UnixSocketClient;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
 use chrono::Utc;
 use clap::Args;
 use clap::Subcommand;
+use clap::ValueEnum;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use regex::Regex;
+use serde::Deserialize;
 use serde::Serialize;
+use serde::de::DeserializeOwned;
 use serde_json::Value;
 use sha2::Digest;
 use sha2::Sha256;
 use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::env;
@@ -15,6 +31,10 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
 use std::process::Stdio;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
 use std::time::Duration;
 use std::time::Instant;
 
@@ -36,6 +56,9 @@ const DEFAULT_SAFE_ACTIONS: [&str; 8] = [
 
 const DEFAULT_RISKY_ACTIONS: [&str; 4] = ["q", "Ctrl+C", "Ctrl+D", "F10"];
 
+/// Daemon session cap used when `AGENT_TUI_MAX_SESSIONS` is unset.
+const DEFAULT_MAX_SESSIONS: usize = 16;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ExplorerErrorKind {
     Spec,
@@ -120,11 +143,64 @@ pub struct DiscoverArgs {
     /// Allow risky actions (q, Ctrl+C, Ctrl+D, F10).
     #[arg(long)]
     allow_risky: bool,
+    /// Concurrent sessions used to probe paths (capped by the free AGENT_TUI_MAX_SESSIONS slots).
+    #[arg(long, default_value_t = 1)]
+    jobs: usize,
+    /// Regex replacement applied before hashing, as REGEX=REPLACEMENT (split at
+    /// the last '='; repeatable).
+    #[arg(long = "replace", value_name = "REGEX=REPLACEMENT", value_parser = parse_replacement)]
+    replacements: Vec<Replacement>,
+    /// Screen rectangle blanked before hashing, as ROW,COL,WIDTH,HEIGHT (repeatable).
+    #[arg(long = "ignore-region", value_name = "ROW,COL,WIDTH,HEIGHT", value_parser = parse_region)]
+    ignore_regions: Vec<Region>,
+    /// Leave the cursor position out of the state hash.
+    #[arg(long)]
+    ignore_cursor: bool,
+    /// Treat screens whose cells match at least this fraction (0-1] as one state.
+    #[arg(long, value_name = "RATIO")]
+    similarity: Option<f64>,
+    /// Order in which queued paths are probed.
+    #[arg(long, value_enum, default_value_t = Strategy::Bfs)]
+    strategy: Strategy,
+    /// Seed for `--strategy random`.
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+    /// Also try actions read off each screen: hotkeys such as `[q]uit`,
+    /// `F1 Help` or `^X Exit`, and sample text for input prompts.
+    #[arg(long)]
+    screen_actions: bool,
+    /// How to reach the daemon.
+    #[arg(long, value_enum, default_value_t = RunnerKind::Auto)]
+    runner: RunnerKind,
+}
+
+#[derive(ValueEnum, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+enum Strategy {
+    /// Breadth-first: shortest paths first.
+    Bfs,
+    /// Depth-first: follow the newest path to `--max-depth` before backtracking.
+    Dfs,
+    /// Random walk over queued paths, reproducible with `--seed`.
+    Random,
+    /// Expand states whose screens differ most from those already seen.
+    Novelty,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum RunnerKind {
+    /// JSON-RPC when a daemon is already running, otherwise the CLI.
+    Auto,
+    /// JSON-RPC to a running daemon over the IPC transport.
+    Rpc,
+    /// The `agent-tui` executable, which starts the daemon when needed.
+    Cli,
 }
 
 #[derive(Args, Debug, Clone)]
 pub struct VerifyArgs {
-    /// Path to the markdown acceptance spec.
+    /// Path to the markdown acceptance spec, or an `agent-tui script` file
+    /// (.yaml, .yml or .toml) replayed as a single scenario.
     #[arg(long)]
     spec: PathBuf,
     /// Run only one scenario by exact name.
@@ -136,20 +212,155 @@ pub struct VerifyArgs {
     /// Stop replay on first failing scenario.
     #[arg(long, default_value_t = true)]
     fail_fast: bool,
+    /// Report formats to write into the output directory (repeatable or comma-separated).
+    #[arg(
+        long = "report",
+        value_enum,
+        value_delimiter = ',',
+        default_value = "json"
+    )]
+    reports: Vec<ReportFormat>,
+    /// Concurrent sessions used to replay scenarios (capped by the free AGENT_TUI_MAX_SESSIONS slots).
+    #[arg(long, default_value_t = 1)]
+    jobs: usize,
+    /// Minimize each failing scenario by replaying subsets of its steps and
+    /// write the results to shrunk.md.
+    #[arg(long)]
+    shrink: bool,
+    /// How to reach the daemon.
+    #[arg(long, value_enum, default_value_t = RunnerKind::Auto)]
+    runner: RunnerKind,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ReportFormat {
+    /// verify-report.json
+    Json,
+    /// verify-report.xml (JUnit XML)
+    Junit,
+    /// verify-report.tap (TAP version 13)
+    Tap,
+}
+
+/// Screen normalizers applied before state hashing. Declared with the
+/// `discover` flags or a spec's `normalize` frontmatter key (inline JSON).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct Normalizers {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    replace: Vec<Replacement>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ignore_regions: Vec<Region>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    ignore_cursor: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    similarity: Option<f64>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct Replacement {
+    pattern: String,
+    with: String,
+}
+
+/// Zero-based screen rectangle, in cells.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+struct Region {
+    row: usize,
+    col: usize,
+    width: usize,
+    height: usize,
 }
 
+/// Compiled form of [`Normalizers`].
+#[derive(Debug, Clone, Default)]
+struct ScreenNormalizer {
+    replace: Vec<(Regex, String)>,
+    ignore_regions: Vec<Region>,
+    ignore_cursor: bool,
+    similarity: Option<f64>,
+}
+
+const SCHEMA_V1: &str = "v1";
+const SCHEMA_V2: &str = "v2";
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum Step {
     Expect(String),
     Press(String),
     Type(String),
     WaitStable,
+    // Steps below require schema v2.
+    ExpectAbsent(String),
+    ExpectRegex(String),
+    WaitFor {
+        text: String,
+        timeout_ms: Option<u64>,
+    },
+    Resize {
+        cols: u16,
+        rows: u16,
+    },
+    Sleep(u64),
+    Paste(String),
+    Signal(String),
+    ExpectCursor {
+        row: i64,
+        col: i64,
+        visible: Option<bool>,
+    },
+    ExpectExit(i64),
+    Screenshot(String),
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl Step {
+    fn is_v1(&self) -> bool {
+        matches!(
+            self,
+            Step::Expect(_) | Step::Press(_) | Step::Type(_) | Step::WaitStable
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
 struct Scenario {
     name: String,
+    /// Runs before `steps`; a failure here fails the scenario.
+    setup: Vec<Step>,
     steps: Vec<Step>,
+    /// Runs after `steps` even when they fail.
+    teardown: Vec<Step>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Block {
+    Setup,
+    Steps,
+    Teardown,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WaitForArgs {
+    text: String,
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ResizeArgs {
+    cols: u16,
+    rows: u16,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CursorArgs {
+    row: i64,
+    col: i64,
+    visible: Option<bool>,
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -171,6 +382,11 @@ struct DiscoverConfig {
     out_dir: PathBuf,
     allow_risky: bool,
     default_timeout_ms: u64,
+    jobs: usize,
+    normalizers: Normalizers,
+    strategy: Strategy,
+    seed: u64,
+    screen_actions: bool,
 }
 
 #[derive(Serialize, Debug)]
@@ -183,15 +399,96 @@ struct DiscoverReport {
     out_dir: String,
     acceptance_spec: String,
     trace_file: String,
+    state_graph: StateGraphFiles,
+    coverage: Coverage,
     risky_actions_blocked: Vec<String>,
 }
 
+/// What a discovery run reached.
+#[derive(Serialize, Debug)]
+struct Coverage {
+    strategy: Strategy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+    distinct_screens: usize,
+    distinct_anchors: usize,
+    anchors: Vec<String>,
+    error_paths: usize,
+    max_depth_reached: usize,
+    /// Queued paths left unprobed when a budget ran out.
+    frontier_remaining: usize,
+    /// Distinct actions read off screens (`--screen-actions`).
+    screen_actions: Vec<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct StateGraphFiles {
+    json: String,
+    dot: String,
+    mermaid: String,
+}
+
+/// Navigation model built during discovery: one node per unique state (or per
+/// distinct error) and one edge per `from -> to` pair, labelled with every
+/// action that makes that transition.
+#[derive(Serialize, Debug, Default)]
+struct StateGraph {
+    nodes: Vec<GraphNode>,
+    edges: Vec<GraphEdge>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct GraphNode {
+    id: String,
+    state_hash: Option<String>,
+    anchor: Option<String>,
+    excerpt: Vec<String>,
+    /// First action path that reached this node.
+    path: Vec<String>,
+    /// Set once any of the node's actions was probed; unexpanded nodes sit at
+    /// the depth or state budget, not necessarily at a dead end.
+    expanded: bool,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct GraphEdge {
+    from: String,
+    to: String,
+    actions: Vec<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct StepTiming {
+    index: usize,
+    step: String,
+    duration_ms: u64,
+}
+
 #[derive(Serialize, Debug)]
 struct VerifyScenarioResult {
     name: String,
     passed: bool,
     failed_step: Option<usize>,
     message: Option<String>,
+    duration_ms: u64,
+    steps: Vec<StepTiming>,
+    /// Screen captured when the scenario failed, relative to the output directory.
+    screenshot: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shrink: Option<ShrinkSummary>,
+}
+
+/// Outcome of `--shrink` for one failing scenario.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+struct ShrinkSummary {
+    /// False when the failure did not recur on the first replay; nothing was shrunk.
+    reproduced: bool,
+    original_steps: usize,
+    minimal_steps: usize,
+    replays: usize,
+    /// Name of the minimized scenario in `shrunk.md`.
+    scenario: Option<String>,
 }
 
 #[derive(Serialize, Debug)]
@@ -202,6 +499,38 @@ struct VerifyReport {
     passed_scenarios: usize,
     failed_scenarios: usize,
     results: Vec<VerifyScenarioResult>,
+    /// Spec holding the minimized scenarios written by `--shrink`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    shrunk_spec: Option<String>,
+}
+
+struct VerifyOptions<'a> {
+    scenario: Option<&'a str>,
+    fail_fast: bool,
+    jobs: usize,
+    shrink: bool,
+}
+
+impl Default for VerifyOptions<'_> {
+    fn default() -> Self {
+        Self {
+            scenario: None,
+            fail_fast: true,
+            jobs: 1,
+            shrink: false,
+        }
+    }
+}
+
+/// Result of an `agent-tui script run`, reduced to what a verify report needs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct ScriptOutcome {
+    name: String,
+    passed: bool,
+    failed_step: Option<usize>,
+    message: Option<String>,
+    duration_ms: u64,
+    steps: Vec<StepTiming>,
 }
 
 #[derive(Serialize, Debug)]
@@ -211,6 +540,8 @@ struct TraceRecord {
     depth: usize,
     state_hash: String,
     anchor: Option<String>,
+    /// Screen after normalizers, with line breaks kept; empty on error.
+    normalized_screen: String,
     error: Option<String>,
 }
 
@@ -224,6 +555,10 @@ trait Runner {
     ) -> Result<String, ExplorerError>;
     fn press(&mut self, session_id: &str, key: &str) -> Result<(), ExplorerError>;
     fn type_text(&mut self, session_id: &str, text: &str) -> Result<(), ExplorerError>;
+    /// Writes `text` as one bracketed paste rather than as keystrokes.
+    fn paste(&mut self, session_id: &str, text: &str) -> Result<(), ExplorerError>;
+    /// Sends a real signal (a `ProcessSignal` name) to the session's process group.
+    fn signal(&mut self, session_id: &str, signal: &str) -> Result<(), ExplorerError>;
     fn wait_stable(&mut self, session_id: &str, timeout_ms: u64) -> Result<(), ExplorerError>;
     fn wait_for_text(
         &mut self,
@@ -231,8 +566,25 @@ trait Runner {
         text: &str,
         timeout_ms: u64,
     ) -> Result<bool, ExplorerError>;
+    fn wait_for_gone(
+        &mut self,
+        session_id: &str,
+        text: &str,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError>;
+    fn resize(&mut self, session_id: &str, cols: u16, rows: u16) -> Result<(), ExplorerError>;
+    /// Waits up to `timeout_ms` for the session to exit; `None` if it is still running.
+    fn exit_code(
+        &mut self,
+        session_id: &str,
+        timeout_ms: u64,
+    ) -> Result<Option<i64>, ExplorerError>;
     fn screenshot(&mut self, session_id: &str) -> Result<(String, Cursor), ExplorerError>;
     fn kill(&mut self, session_id: &str) -> Result<(), ExplorerError>;
+    fn run_script(&mut self, path: &Path) -> Result<ScriptOutcome, ExplorerError>;
+    /// Sessions the daemon holds, running or exited; each takes a slot under
+    /// `AGENT_TUI_MAX_SESSIONS`.
+    fn session_count(&mut self) -> Result<usize, ExplorerError>;
 }
 
 #[derive(Clone, Debug, Default)]
@@ -242,7 +594,7 @@ struct Cursor {
     visible: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct AgentTuiRunner {
     executable: String,
     base_args: Vec<String>,
@@ -347,6 +699,66 @@ impl AgentTuiRunner {
         let output = self.run_raw(args, false)?;
         parse_json_payload(&output.stdout)
     }
+
+    /// Runs a one-step `script run` against the session, for actions the CLI only
+    /// offers as script steps (`signal`, raw `paste`).
+    fn run_script_step(&self, session_id: &str, step: &str) -> Result<(), ExplorerError> {
+        static NEXT_SCRIPT: AtomicUsize = AtomicUsize::new(0);
+        let path = env::temp_dir().join(format!(
+            "tui-explorer-{}-{}.yaml",
+            std::process::id(),
+            NEXT_SCRIPT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&path, format!("steps:\n  - {step}\n")).map_err(|error| {
+            ExplorerError::scenario(format!("failed to write {}: {error}", path.display()))
+        })?;
+        let args = vec![
+            "-s".to_string(),
+            session_id.to_string(),
+            "script".to_string(),
+            "run".to_string(),
+            path.display().to_string(),
+        ];
+        let result = self.run_raw(&args, false);
+        let _ = fs::remove_file(&path);
+        result.map(|_| ())
+    }
+
+    fn wait_assert(
+        &self,
+        session_id: &str,
+        text: &str,
+        gone: bool,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError> {
+        let mut args = vec![
+            "-s".to_string(),
+            session_id.to_string(),
+            "wait".to_string(),
+            "--assert".to_string(),
+        ];
+        if gone {
+            args.push("--gone".to_string());
+        }
+        args.push(text.to_string());
+        args.push("-t".to_string());
+        args.push(timeout_ms.to_string());
+
+        let output = self.run_raw(&args, true)?;
+        if output.status == 0 {
+            let payload = parse_json_payload(&output.stdout)?;
+            return Ok(payload
+                .get("found")
+                .and_then(Value::as_bool)
+                .unwrap_or(true));
+        }
+
+        if is_unavailable_text(&output.stdout, &output.stderr) {
+            return Err(ExplorerError::unavailable("daemon unavailable"));
+        }
+
+        Ok(false)
+    }
 }
 
 impl Runner for AgentTuiRunner {
@@ -410,6 +822,17 @@ impl Runner for AgentTuiRunner {
         Ok(())
     }
 
+    fn paste(&mut self, session_id: &str, text: &str) -> Result<(), ExplorerError> {
+        self.run_script_step(
+            session_id,
+            &format!("paste: {}", yaml_quote(&bracketed_paste(text))),
+        )
+    }
+
+    fn signal(&mut self, session_id: &str, signal: &str) -> Result<(), ExplorerError> {
+        self.run_script_step(session_id, &format!("signal: {signal}"))
+    }
+
     fn wait_stable(&mut self, session_id: &str, timeout_ms: u64) -> Result<(), ExplorerError> {
         let args = vec![
             "-s".to_string(),
@@ -429,30 +852,48 @@ impl Runner for AgentTuiRunner {
         text: &str,
         timeout_ms: u64,
     ) -> Result<bool, ExplorerError> {
+        self.wait_assert(session_id, text, false, timeout_ms)
+    }
+
+    fn wait_for_gone(
+        &mut self,
+        session_id: &str,
+        text: &str,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError> {
+        self.wait_assert(session_id, text, true, timeout_ms)
+    }
+
+    fn resize(&mut self, session_id: &str, cols: u16, rows: u16) -> Result<(), ExplorerError> {
         let args = vec![
             "-s".to_string(),
             session_id.to_string(),
-            "wait".to_string(),
-            "--assert".to_string(),
-            text.to_string(),
-            "-t".to_string(),
-            timeout_ms.to_string(),
+            "resize".to_string(),
+            "--cols".to_string(),
+            cols.to_string(),
+            "--rows".to_string(),
+            rows.to_string(),
         ];
+        let _ = self.run_json(&args)?;
+        Ok(())
+    }
 
-        let output = self.run_raw(&args, true)?;
-        if output.status == 0 {
-            let payload = parse_json_payload(&output.stdout)?;
-            return Ok(payload
-                .get("found")
-                .and_then(Value::as_bool)
-                .unwrap_or(true));
-        }
-
-        if is_unavailable_text(&output.stdout, &output.stderr) {
-            return Err(ExplorerError::unavailable("daemon unavailable"));
+    fn exit_code(
+        &mut self,
+        session_id: &str,
+        timeout_ms: u64,
+    ) -> Result<Option<i64>, ExplorerError> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        loop {
+            let payload = self.run_json(&["sessions".to_string()])?;
+            if let Some(code) = session_exit_code(&payload, session_id)? {
+                return Ok(Some(code));
+            }
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            pause(Duration::from_millis(100));
         }
-
-        Ok(false)
     }
 
     fn screenshot(&mut self, session_id: &str) -> Result<(String, Cursor), ExplorerError> {
@@ -465,26 +906,7 @@ impl Runner for AgentTuiRunner {
         ];
 
         let payload = self.run_json(&args)?;
-        let screenshot = payload
-            .get("screenshot")
-            .and_then(Value::as_str)
-            .ok_or_else(|| ExplorerError::scenario("screenshot response missing screenshot field"))?
-            .to_string();
-
-        let cursor = payload
-            .get("cursor")
-            .and_then(Value::as_object)
-            .map(|cursor| Cursor {
-                row: cursor.get("row").and_then(Value::as_i64).unwrap_or(0),
-                col: cursor.get("col").and_then(Value::as_i64).unwrap_or(0),
-                visible: cursor
-                    .get("visible")
-                    .and_then(Value::as_bool)
-                    .unwrap_or(false),
-            })
-            .unwrap_or_default();
-
-        Ok((screenshot, cursor))
+        parse_screenshot_payload(&payload)
     }
 
     fn kill(&mut self, session_id: &str) -> Result<(), ExplorerError> {
@@ -492,1242 +914,4405 @@ impl Runner for AgentTuiRunner {
         let _ = self.run_raw(&args, true)?;
         Ok(())
     }
-}
 
-pub fn run(root: &Path, command: TuiExplorerCommands) -> i32 {
-    let result = match command {
-        TuiExplorerCommands::Discover(args) => run_discover(root, args),
-        TuiExplorerCommands::Verify(args) => run_verify(root, args),
-    };
+    fn run_script(&mut self, path: &Path) -> Result<ScriptOutcome, ExplorerError> {
+        let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        let args = vec![
+            "script".to_string(),
+            "run".to_string(),
+            path.display().to_string(),
+        ];
+        let output = self.run_raw(&args, true)?;
+        if is_unavailable_text(&output.stdout, &output.stderr) {
+            return Err(ExplorerError::unavailable("daemon unavailable"));
+        }
 
-    match result {
-        Ok(()) => EXIT_SUCCESS,
-        Err(error) => {
-            eprintln!("{}", error.message);
-            match error.kind {
-                ExplorerErrorKind::Spec => EXIT_SPEC_ERROR,
-                ExplorerErrorKind::Scenario => EXIT_SCENARIO_FAILED,
-                ExplorerErrorKind::Unavailable => EXIT_UNAVAILABLE,
+        // A passing run prints the report on stdout; a failing one on stderr.
+        let report = match output.status {
+            0 => parse_json_payload(&output.stdout)?,
+            1 => parse_json_payload(&output.stderr)?,
+            _ => {
+                let error = parse_json_payload(&output.stderr).unwrap_or_default();
+                return Err(ExplorerError::spec(
+                    error
+                        .get("error")
+                        .and_then(Value::as_str)
+                        .unwrap_or("agent-tui script run rejected the script")
+                        .to_string(),
+                ));
             }
-        }
+        };
+        Ok(script_outcome_from_report(&report))
+    }
+
+    fn session_count(&mut self) -> Result<usize, ExplorerError> {
+        let payload = self.run_json(&["sessions".to_string()])?;
+        Ok(listed_sessions(&payload))
     }
 }
 
-fn run_discover(root: &Path, args: DiscoverArgs) -> Result<(), ExplorerError> {
-    let out_dir = ensure_out_dir(args.out.unwrap_or_else(default_out_dir))?;
-    let config = DiscoverConfig {
-        command: args.command,
-        cwd: args.cwd,
-        cols: args.cols,
-        rows: args.rows,
-        max_depth: args.max_depth,
-        max_states: args.max_states,
-        branch_limit: args.branch_limit,
-        time_budget_sec: args.time_budget_sec,
-        out_dir,
-        allow_risky: args.allow_risky,
-        default_timeout_ms: 3000,
-    };
+/// Drives the daemon over JSON-RPC on the configured IPC transport (Unix socket,
+/// or WebSocket with `AGENT_TUI_TRANSPORT=ws`). Text and stability waits are a
+/// single blocking `wait` request that the daemon resolves from screen updates,
+/// so the runner never polls for them; only exit waits follow the session's
+/// output stream. Script files still go through `cli`, since
+/// `agent-tui script run` executes them client-side.
+#[derive(Clone)]
+struct RpcRunner<C = UnixSocketClient> {
+    client: C,
+    cli: AgentTuiRunner,
+}
 
-    let mut runner = AgentTuiRunner::new(root);
-    let (report, _spec, _traces) = discover_with_runner(&config, &mut runner)?;
-    let report_path = write_discover_report(&config.out_dir, &report)?;
+impl<C: DaemonClient> RpcRunner<C> {
+    fn with_client(client: C, cli: AgentTuiRunner) -> Self {
+        Self { client, cli }
+    }
 
-    print_json(&report)?;
-    println!("discover report: {}", report_path.display());
+    fn call<P: Serialize>(&mut self, method: &str, params: P) -> Result<Value, ExplorerError> {
+        self.call_with_config(method, params, &DaemonClientConfig::default())
+    }
 
-    Ok(())
-}
+    fn call_with_config<P: Serialize>(
+        &mut self,
+        method: &str,
+        params: P,
+        config: &DaemonClientConfig,
+    ) -> Result<Value, ExplorerError> {
+        let params = serde_json::to_value(params).map_err(|error| {
+            ExplorerError::scenario(format!("failed to encode {method} params: {error}"))
+        })?;
+        self.client
+            .call_with_config(method, Some(params), config)
+            .map_err(rpc_error)
+    }
 
-fn run_verify(root: &Path, args: VerifyArgs) -> Result<(), ExplorerError> {
-    let spec = parse_spec_file(&args.spec)?;
-    let out_dir = ensure_out_dir(args.out.unwrap_or_else(default_out_dir))?;
+    /// Runs a daemon-side `wait`, which blocks until the condition holds or
+    /// `timeout_ms` passes; the read timeout is stretched to cover it.
+    fn wait(
+        &mut self,
+        session_id: &str,
+        text: Option<&str>,
+        condition: Option<&str>,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError> {
+        let config = DaemonClientConfig::default().with_read_timeout(
+            Duration::from_millis(timeout_ms) + DaemonClientConfig::default().read_timeout(),
+        );
+        let result = self.call_with_config(
+            "wait",
+            params::WaitParams {
+                session: Some(session_id.to_string()),
+                text: text.map(str::to_string),
+                timeout_ms,
+                condition: condition.map(str::to_string),
+                unwrap: false,
+                region: None,
+            },
+            &config,
+        )?;
+        Ok(result
+            .get("found")
+            .and_then(Value::as_bool)
+            .unwrap_or(false))
+    }
 
-    let mut runner = AgentTuiRunner::new(root);
-    let report = verify_with_runner(
-        &spec,
-        &args.spec,
-        &mut runner,
-        &out_dir,
-        args.scenario.as_deref(),
-        args.fail_fast,
-    )?;
-
-    let report_path = write_verify_report(&out_dir, &report)?;
-    print_json(&report)?;
-    println!("verify report: {}", report_path.display());
+    fn expect_success(result: &Value, action: &str) -> Result<(), ExplorerError> {
+        match result.get("success").and_then(Value::as_bool) {
+            Some(true) => return Ok(()),
+            Some(false) => {}
+            None => {
+                return Err(ExplorerError::scenario(format!(
+                    "{action} response missing success field: {result}"
+                )));
+            }
+        }
+        Err(ExplorerError::scenario(format!(
+            "{action} failed: {}",
+            result
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or("no details")
+        )))
+    }
 
-    if report.failed_scenarios > 0 {
-        return Err(ExplorerError::scenario(
-            "verify completed with failing scenarios",
-        ));
+    fn session_exit_code(&mut self, session_id: &str) -> Result<Option<i64>, ExplorerError> {
+        let payload = self.client.call("sessions", None).map_err(rpc_error)?;
+        session_exit_code(&payload, session_id)
     }
 
-    Ok(())
+    /// Follows the session's live preview stream until it reports `closed`, the
+    /// stream ends, or `timeout` passes. Returns `false` if the stream could not
+    /// be opened, so callers fall back to polling.
+    fn wait_stream_closed(&mut self, session_id: &str, timeout: Duration) -> bool {
+        let Ok(mut stream) = self.client.call_stream(
+            "live_preview_stream",
+            Some(serde_json::json!({ "session": session_id })),
+        ) else {
+            return false;
+        };
+        let Some(abort) = stream.abort_handle() else {
+            return false;
+        };
+        let (sender, receiver) = std::sync::mpsc::sync_channel(1);
+        // The reader blocks between events and stops within one poll interval of
+        // being aborted; the scope joins it before returning.
+        thread::scope(|scope| {
+            scope.spawn(move || {
+                while let Ok(Some(event)) = stream.next_result() {
+                    if event.get("event").and_then(Value::as_str) == Some("closed") {
+                        break;
+                    }
+                }
+                let _ = sender.send(());
+            });
+            let closed = receiver.recv_timeout(timeout).is_ok();
+            abort.abort();
+            closed
+        })
+    }
 }
 
-fn print_json<T: Serialize>(value: &T) -> Result<(), ExplorerError> {
-    let output = serde_json::to_string_pretty(value).map_err(|error| {
-        ExplorerError::scenario(format!("failed to serialize JSON output: {error}"))
-    })?;
-    println!("{output}");
-    Ok(())
+fn rpc_error(error: ClientError) -> ExplorerError {
+    match &error {
+        ClientError::DaemonNotRunning => ExplorerError::unavailable("daemon unavailable"),
+        ClientError::ConnectionFailed(io)
+            if !matches!(
+                io.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock
+            ) =>
+        {
+            ExplorerError::unavailable(format!("daemon unavailable: {error}"))
+        }
+        _ => ExplorerError::scenario(format!("daemon rpc failed: {error}")),
+    }
 }
 
-fn default_out_dir() -> PathBuf {
-    let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
-    PathBuf::from(".agent-tui").join("discover").join(stamp)
-}
+impl<C: DaemonClient> Runner for RpcRunner<C> {
+    fn spawn(
+        &mut self,
+        command: &str,
+        cwd: Option<&str>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<String, ExplorerError> {
+        let cwd = cwd.map(|path| {
+            std::path::absolute(path)
+                .unwrap_or_else(|_| PathBuf::from(path))
+                .display()
+                .to_string()
+        });
+        let result = self.call(
+            "spawn",
+            params::SpawnParams {
+                command: "sh".to_string(),
+                args: vec!["-lc".to_string(), command.to_string()],
+                cwd,
+                session: None,
+                cols,
+                rows,
+            },
+        )?;
+        let session_id = result
+            .get("session_id")
+            .and_then(Value::as_str)
+            .ok_or_else(|| {
+                ExplorerError::scenario("spawn response did not include a session_id")
+            })?;
+        if session_id.trim().is_empty() {
+            return Err(ExplorerError::scenario(
+                "spawn response included an empty session_id",
+            ));
+        }
+        Ok(session_id.to_string())
+    }
 
-fn ensure_out_dir(path: PathBuf) -> Result<PathBuf, ExplorerError> {
-    fs::create_dir_all(&path).map_err(|error| {
-        ExplorerError::scenario(format!(
-            "failed to create output directory {}: {error}",
-            path.display()
-        ))
-    })?;
-    Ok(path)
-}
+    fn press(&mut self, session_id: &str, key: &str) -> Result<(), ExplorerError> {
+        let result = self.call(
+            "keystroke",
+            params::KeyParams {
+                key: key.to_string(),
+                session: Some(session_id.to_string()),
+            },
+        )?;
+        Self::expect_success(&result, "press")
+    }
 
-fn parse_json_payload(stdout: &str) -> Result<Value, ExplorerError> {
-    let payload = stdout.trim();
-    if payload.is_empty() {
-        return Ok(Value::Object(serde_json::Map::new()));
+    fn type_text(&mut self, session_id: &str, text: &str) -> Result<(), ExplorerError> {
+        let result = self.call(
+            "type",
+            params::TypeParams {
+                text: text.to_string(),
+                session: Some(session_id.to_string()),
+            },
+        )?;
+        Self::expect_success(&result, "type")
     }
-    serde_json::from_str::<Value>(payload).map_err(|error| {
-        ExplorerError::scenario(format!("invalid JSON response from agent-tui: {error}"))
-    })
-}
 
-fn join_args(args: &[String]) -> String {
-    args.join(" ")
-}
+    fn paste(&mut self, session_id: &str, text: &str) -> Result<(), ExplorerError> {
+        let result = self.call(
+            "pty_write",
+            params::PtyWriteParams {
+                session: Some(session_id.to_string()),
+                data: STANDARD.encode(bracketed_paste(text)),
+            },
+        )?;
+        Self::expect_success(&result, "paste")
+    }
 
-fn is_unavailable_text(stdout: &str, stderr: &str) -> bool {
-    let combined = format!("{}\n{}", stdout.to_lowercase(), stderr.to_lowercase());
-    combined.contains("daemon not running") || combined.contains("connection refused")
-}
+    fn signal(&mut self, session_id: &str, signal: &str) -> Result<(), ExplorerError> {
+        let result = self.call(
+            "signal",
+            params::SignalParams {
+                signal: signal.to_string(),
+                session: Some(session_id.to_string()),
+            },
+        )?;
+        Self::expect_success(&result, "signal")
+    }
 
-fn strip_ansi(value: &str) -> String {
-    let mut out = String::with_capacity(value.len());
-    let mut chars = value.chars().peekable();
+    fn wait_stable(&mut self, session_id: &str, timeout_ms: u64) -> Result<(), ExplorerError> {
+        let _ = self.wait(session_id, None, Some("stable"), timeout_ms)?;
+        Ok(())
+    }
 
-    while let Some(ch) = chars.next() {
-        if ch != '\u{1b}' {
-            out.push(ch);
-            continue;
-        }
+    fn wait_for_text(
+        &mut self,
+        session_id: &str,
+        text: &str,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError> {
+        self.wait(session_id, Some(text), None, timeout_ms)
+    }
 
-        if chars.peek() == Some(&'[') {
-            let _ = chars.next();
-            for code in chars.by_ref() {
-                let byte = code as u32;
-                if (0x40..=0x7e).contains(&byte) {
-                    break;
-                }
+    fn wait_for_gone(
+        &mut self,
+        session_id: &str,
+        text: &str,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError> {
+        self.wait(session_id, Some(text), Some("text_gone"), timeout_ms)
+    }
+
+    fn resize(&mut self, session_id: &str, cols: u16, rows: u16) -> Result<(), ExplorerError> {
+        let _ = self.call(
+            "resize",
+            params::ResizeParams {
+                cols,
+                rows,
+                session: Some(session_id.to_string()),
+            },
+        )?;
+        Ok(())
+    }
+
+    fn exit_code(
+        &mut self,
+        session_id: &str,
+        timeout_ms: u64,
+    ) -> Result<Option<i64>, ExplorerError> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+        if let Some(code) = self.session_exit_code(session_id)? {
+            return Ok(Some(code));
+        }
+        let streamed = self.wait_stream_closed(session_id, Duration::from_millis(timeout_ms));
+        // The exit status lands shortly after the output stream closes; without a
+        // stream this is a plain polling loop.
+        let interval = Duration::from_millis(if streamed { 20 } else { 100 });
+        loop {
+            if let Some(code) = self.session_exit_code(session_id)? {
+                return Ok(Some(code));
             }
-            continue;
+            if Instant::now() >= deadline {
+                return Ok(None);
+            }
+            pause(interval);
         }
-
-        let _ = chars.next();
     }
 
-    out
-}
+    fn screenshot(&mut self, session_id: &str) -> Result<(String, Cursor), ExplorerError> {
+        let payload = self.call(
+            "snapshot",
+            params::SnapshotParams {
+                session: Some(session_id.to_string()),
+                strip_ansi: true,
+                include_cursor: true,
+                ..Default::default()
+            },
+        )?;
+        parse_screenshot_payload(&payload)
+    }
 
-fn normalize_screenshot(value: &str) -> String {
-    strip_ansi(value)
-        .split_whitespace()
-        .collect::<Vec<_>>()
-        .join(" ")
-}
+    fn kill(&mut self, session_id: &str) -> Result<(), ExplorerError> {
+        // Like `agent-tui kill`, a session that is already gone is not an error.
+        match self.call(
+            "kill",
+            params::SessionParams {
+                session: Some(session_id.to_string()),
+            },
+        ) {
+            Err(error) if error.kind == ExplorerErrorKind::Unavailable => Err(error),
+            _ => Ok(()),
+        }
+    }
 
-fn compute_state_hash(screenshot: &str, cursor: &Cursor, cols: u16, rows: u16) -> String {
-    let normalized = normalize_screenshot(screenshot);
-    let visible = if cursor.visible { 1 } else { 0 };
-    let payload = format!(
-        "{normalized}|{}:{}:{visible}|{cols}:{rows}",
-        cursor.row, cursor.col
-    );
+    fn run_script(&mut self, path: &Path) -> Result<ScriptOutcome, ExplorerError> {
+        self.cli.run_script(path)
+    }
 
-    let mut hasher = Sha256::new();
-    hasher.update(payload.as_bytes());
-    let digest = hasher.finalize();
-    format!("{digest:x}")
+    fn session_count(&mut self) -> Result<usize, ExplorerError> {
+        let payload = self.client.call("sessions", None).map_err(rpc_error)?;
+        Ok(listed_sessions(&payload))
+    }
 }
 
-fn contains_clock_like_token(line: &str) -> bool {
-    for token in line.split_whitespace() {
-        let trimmed = token.trim_matches(|ch: char| !ch.is_ascii_digit() && ch != ':');
-        if trimmed.is_empty() {
-            continue;
-        }
+/// The runner picked by `--runner`.
+#[derive(Clone)]
+enum DaemonRunner<C = UnixSocketClient> {
+    Rpc(RpcRunner<C>),
+    Cli(AgentTuiRunner),
+}
 
-        let mut parts = trimmed.split(':');
-        let hour = parts.next();
-        let minute = parts.next();
-        let extra = parts.next();
+impl DaemonRunner {
+    fn new(root: &Path, kind: RunnerKind) -> Result<Self, ExplorerError> {
+        Self::select(root, kind, UnixSocketClient::connect)
+    }
+}
 
-        if let (Some(hour), Some(minute), None) = (hour, minute, extra) {
-            let hour_ok =
-                (hour.len() == 1 || hour.len() == 2) && hour.chars().all(|ch| ch.is_ascii_digit());
-            let minute_ok = minute.len() == 2 && minute.chars().all(|ch| ch.is_ascii_digit());
-            if hour_ok && minute_ok {
-                return true;
+impl<C: DaemonClient> DaemonRunner<C> {
+    /// `connect` reaches an already running daemon. It must not autostart one:
+    /// that would re-exec this binary rather than `agent-tui`.
+    fn select(
+        root: &Path,
+        kind: RunnerKind,
+        connect: impl FnOnce() -> Result<C, ClientError>,
+    ) -> Result<Self, ExplorerError> {
+        let cli = AgentTuiRunner::new(root);
+        match kind {
+            RunnerKind::Cli => Ok(Self::Cli(cli)),
+            RunnerKind::Rpc => {
+                let client = connect().map_err(rpc_error)?;
+                Ok(Self::Rpc(RpcRunner::with_client(client, cli)))
             }
+            RunnerKind::Auto => Ok(match connect() {
+                Ok(client) => Self::Rpc(RpcRunner::with_client(client, cli)),
+                Err(_) => Self::Cli(cli),
+            }),
         }
     }
-
-    false
 }
 
-fn pick_anchor(screenshot: &str) -> Option<String> {
-    for raw in screenshot.lines() {
-        let line = raw.trim();
-        if line.is_empty() {
-            continue;
-        }
-        if line.len() < 3 || line.len() > 120 {
-            continue;
-        }
-        if contains_clock_like_token(line) {
-            continue;
-        }
-
-        let digits = line.chars().filter(|ch| ch.is_ascii_digit()).count();
-        let ratio = digits as f64 / line.len() as f64;
-        if ratio > 0.45 {
-            continue;
+macro_rules! dispatch {
+    ($self:ident, $runner:ident => $call:expr) => {
+        match $self {
+            DaemonRunner::Rpc($runner) => $call,
+            DaemonRunner::Cli($runner) => $call,
         }
+    };
+}
 
-        if line.chars().any(|ch| ch.is_ascii_alphabetic()) {
-            return Some(line.to_string());
-        }
+impl<C: DaemonClient> Runner for DaemonRunner<C> {
+    fn spawn(
+        &mut self,
+        command: &str,
+        cwd: Option<&str>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<String, ExplorerError> {
+        dispatch!(self, runner => runner.spawn(command, cwd, cols, rows))
     }
 
-    for raw in screenshot.lines() {
-        let line = raw.trim();
-        if line.is_empty() {
-            continue;
-        }
-        let anchor = if line.len() > 80 { &line[..80] } else { line };
-        return Some(anchor.to_string());
+    fn press(&mut self, session_id: &str, key: &str) -> Result<(), ExplorerError> {
+        dispatch!(self, runner => runner.press(session_id, key))
     }
 
-    None
-}
+    fn type_text(&mut self, session_id: &str, text: &str) -> Result<(), ExplorerError> {
+        dispatch!(self, runner => runner.type_text(session_id, text))
+    }
 
-fn now_rfc3339() -> String {
-    Utc::now().to_rfc3339()
-}
+    fn paste(&mut self, session_id: &str, text: &str) -> Result<(), ExplorerError> {
+        dispatch!(self, runner => runner.paste(session_id, text))
+    }
 
-fn parse_scalar(raw_value: &str) -> Result<Value, ExplorerError> {
-    let value = raw_value.trim();
-    if value.starts_with('"') && value.ends_with('"') {
-        let parsed = serde_json::from_str::<String>(value).map_err(|error| {
-            ExplorerError::spec(format!("invalid quoted scalar '{value}': {error}"))
-        })?;
-        return Ok(Value::String(parsed));
+    fn signal(&mut self, session_id: &str, signal: &str) -> Result<(), ExplorerError> {
+        dispatch!(self, runner => runner.signal(session_id, signal))
     }
 
-    if value == "true" {
-        return Ok(Value::Bool(true));
+    fn wait_stable(&mut self, session_id: &str, timeout_ms: u64) -> Result<(), ExplorerError> {
+        dispatch!(self, runner => runner.wait_stable(session_id, timeout_ms))
     }
-    if value == "false" {
-        return Ok(Value::Bool(false));
+
+    fn wait_for_text(
+        &mut self,
+        session_id: &str,
+        text: &str,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError> {
+        dispatch!(self, runner => runner.wait_for_text(session_id, text, timeout_ms))
     }
-    if value == "null" {
-        return Ok(Value::Null);
+
+    fn wait_for_gone(
+        &mut self,
+        session_id: &str,
+        text: &str,
+        timeout_ms: u64,
+    ) -> Result<bool, ExplorerError> {
+        dispatch!(self, runner => runner.wait_for_gone(session_id, text, timeout_ms))
     }
 
-    if let Ok(parsed) = value.parse::<i64>() {
-        return Ok(Value::Number(parsed.into()));
+    fn resize(&mut self, session_id: &str, cols: u16, rows: u16) -> Result<(), ExplorerError> {
+        dispatch!(self, runner => runner.resize(session_id, cols, rows))
     }
 
-    Ok(Value::String(value.to_string()))
-}
+    fn exit_code(
+        &mut self,
+        session_id: &str,
+        timeout_ms: u64,
+    ) -> Result<Option<i64>, ExplorerError> {
+        dispatch!(self, runner => runner.exit_code(session_id, timeout_ms))
+    }
 
-fn parse_frontmatter_and_body(
-    text: &str,
-) -> Result<(BTreeMap<String, Value>, Vec<String>), ExplorerError> {
-    let lines = text
-        .lines()
-        .map(std::string::ToString::to_string)
-        .collect::<Vec<_>>();
-    if lines.is_empty() || lines[0].trim() != "---" {
-        return Err(ExplorerError::spec(
-            "spec must start with frontmatter delimiter '---'",
-        ));
+    fn screenshot(&mut self, session_id: &str) -> Result<(String, Cursor), ExplorerError> {
+        dispatch!(self, runner => runner.screenshot(session_id))
     }
 
-    let mut end_idx: Option<usize> = None;
-    for (index, line) in lines.iter().enumerate().skip(1) {
-        if line.trim() == "---" {
-            end_idx = Some(index);
-            break;
-        }
+    fn kill(&mut self, session_id: &str) -> Result<(), ExplorerError> {
+        dispatch!(self, runner => runner.kill(session_id))
     }
 
-    let Some(end_idx) = end_idx else {
-        return Err(ExplorerError::spec("frontmatter is not closed with '---'"));
-    };
+    fn run_script(&mut self, path: &Path) -> Result<ScriptOutcome, ExplorerError> {
+        dispatch!(self, runner => runner.run_script(path))
+    }
 
-    let mut frontmatter = BTreeMap::new();
-    for line in lines.iter().take(end_idx).skip(1) {
-        let stripped = line.trim();
-        if stripped.is_empty() {
-            continue;
-        }
-        let Some((key, value)) = stripped.split_once(':') else {
-            return Err(ExplorerError::spec(format!(
-                "invalid frontmatter line: {line}"
-            )));
-        };
-        frontmatter.insert(key.trim().to_string(), parse_scalar(value)?);
+    fn session_count(&mut self) -> Result<usize, ExplorerError> {
+        dispatch!(self, runner => runner.session_count())
     }
+}
 
-    Ok((frontmatter, lines[(end_idx + 1)..].to_vec()))
+/// Reads a `snapshot` result, as returned by the daemon and by `screenshot --json`.
+fn parse_screenshot_payload(payload: &Value) -> Result<(String, Cursor), ExplorerError> {
+    let screenshot = payload
+        .get("screenshot")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ExplorerError::scenario("screenshot response missing screenshot field"))?
+        .to_string();
+
+    let cursor = payload
+        .get("cursor")
+        .and_then(Value::as_object)
+        .map(|cursor| Cursor {
+            row: cursor.get("row").and_then(Value::as_i64).unwrap_or(0),
+            col: cursor.get("col").and_then(Value::as_i64).unwrap_or(0),
+            visible: cursor
+                .get("visible")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        })
+        .unwrap_or_default();
+
+    Ok((screenshot, cursor))
 }
 
-fn parse_quoted_step(raw_value: &str, kind: &str, line: &str) -> Result<String, ExplorerError> {
-    let value = raw_value.trim();
-    if !value.starts_with('"') || !value.ends_with('"') {
-        return Err(ExplorerError::spec(format!(
-            "{kind} step must use a double-quoted string: {line}"
-        )));
-    }
-
-    serde_json::from_str::<String>(value).map_err(|error| {
-        ExplorerError::spec(format!("invalid quoted value in step '{line}': {error}"))
-    })
+/// Counts the entries of a `sessions` result.
+fn listed_sessions(payload: &Value) -> usize {
+    payload
+        .get("sessions")
+        .and_then(Value::as_array)
+        .map_or(0, Vec::len)
 }
 
-fn is_openspec_expectation_line(line: &str) -> bool {
-    for prefix in [
-        "- **WHEN**",
-        "- **THEN**",
-        "- **AND**",
-        "- **SHOULD**",
-        "- WHEN",
-        "- THEN",
-        "- AND",
-        "- SHOULD",
-    ] {
-        if line.starts_with(prefix) {
-            return true;
-        }
+/// Reads a `sessions` result; `None` while the session is still running.
+fn session_exit_code(payload: &Value, session_id: &str) -> Result<Option<i64>, ExplorerError> {
+    let session = payload
+        .get("sessions")
+        .and_then(Value::as_array)
+        .and_then(|sessions| {
+            sessions
+                .iter()
+                .find(|session| session.get("id").and_then(Value::as_str) == Some(session_id))
+        })
+        .ok_or_else(|| {
+            ExplorerError::scenario(format!("session {session_id} is no longer listed"))
+        })?;
+    if session
+        .get("running")
+        .and_then(Value::as_bool)
+        .unwrap_or(true)
+    {
+        return Ok(None);
     }
+    // A process killed by a signal has no exit code; report it as -1.
+    Ok(Some(
+        session
+            .get("exit_code")
+            .and_then(Value::as_i64)
+            .unwrap_or(-1),
+    ))
+}
 
-    false
+fn script_outcome_from_report(report: &Value) -> ScriptOutcome {
+    let steps = report
+        .get("steps")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let failed = steps
+        .iter()
+        .find(|step| step.get("status").and_then(Value::as_str) == Some("failed"));
+    ScriptOutcome {
+        name: report
+            .get("name")
+            .and_then(Value::as_str)
+            .unwrap_or("script")
+            .to_string(),
+        passed: report
+            .get("passed")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        failed_step: failed
+            .and_then(|step| step.get("index"))
+            .and_then(Value::as_u64)
+            .map(|index| index as usize),
+        message: failed.map(|step| {
+            format!(
+                "{}: {}",
+                step.get("name").and_then(Value::as_str).unwrap_or("step"),
+                step.get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or("failed")
+            )
+        }),
+        duration_ms: report
+            .get("duration_ms")
+            .and_then(Value::as_u64)
+            .unwrap_or(0),
+        steps: steps
+            .iter()
+            .filter(|step| step.get("status").and_then(Value::as_str) != Some("skipped"))
+            .map(|step| StepTiming {
+                index: step.get("index").and_then(Value::as_u64).unwrap_or(0) as usize,
+                step: step
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .unwrap_or("step")
+                    .to_string(),
+                duration_ms: step.get("duration_ms").and_then(Value::as_u64).unwrap_or(0),
+            })
+            .collect(),
+    }
 }
 
-fn validate_frontmatter(frontmatter: &BTreeMap<String, Value>) -> Result<(), ExplorerError> {
-    for required in [
-        "schema_version",
-        "command",
-        "cols",
-        "rows",
-        "default_timeout_ms",
-        "generated_at",
-        "generator",
-    ] {
-        if !frontmatter.contains_key(required) {
-            return Err(ExplorerError::spec(format!(
-                "missing required frontmatter key: {required}"
-            )));
+pub fn run(root: &Path, command: TuiExplorerCommands) -> i32 {
+    let result = match command {
+        TuiExplorerCommands::Discover(args) => run_discover(root, args),
+        TuiExplorerCommands::Verify(args) => run_verify(root, args),
+    };
+
+    match result {
+        Ok(()) => EXIT_SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error.message);
+            match error.kind {
+                ExplorerErrorKind::Spec => EXIT_SPEC_ERROR,
+                ExplorerErrorKind::Scenario => EXIT_SCENARIO_FAILED,
+                ExplorerErrorKind::Unavailable => EXIT_UNAVAILABLE,
+            }
         }
     }
+}
 
-    if frontmatter.get("schema_version").and_then(Value::as_str) != Some("v1") {
-        return Err(ExplorerError::spec(
-            "unsupported schema_version, expected 'v1'",
-        ));
-    }
+fn run_discover(root: &Path, args: DiscoverArgs) -> Result<(), ExplorerError> {
+    let out_dir = ensure_out_dir(args.out.unwrap_or_else(default_out_dir))?;
+    let mut runner = DaemonRunner::new(root, args.runner)?;
+    let config = DiscoverConfig {
+        command: args.command,
+        cwd: args.cwd,
+        cols: args.cols,
+        rows: args.rows,
+        max_depth: args.max_depth,
+        max_states: args.max_states,
+        branch_limit: args.branch_limit,
+        time_budget_sec: args.time_budget_sec,
+        out_dir,
+        allow_risky: args.allow_risky,
+        default_timeout_ms: 3000,
+        jobs: effective_jobs(args.jobs, session_limit(), runner.session_count()?)?,
+        normalizers: Normalizers {
+            replace: args.replacements,
+            ignore_regions: args.ignore_regions,
+            ignore_cursor: args.ignore_cursor,
+            similarity: args.similarity,
+        },
+        strategy: args.strategy,
+        seed: args.seed,
+        screen_actions: args.screen_actions,
+    };
 
-    let command = frontmatter
-        .get("command")
-        .and_then(Value::as_str)
-        .unwrap_or_default();
-    if command.trim().is_empty() {
-        return Err(ExplorerError::spec(
-            "frontmatter 'command' must be a non-empty string",
-        ));
-    }
+    let (report, _spec, _traces) = discover_with_runner(&config, &mut runner)?;
+    let report_path = write_discover_report(&config.out_dir, &report)?;
 
-    for field in ["cols", "rows", "default_timeout_ms"] {
-        let value = frontmatter.get(field).and_then(Value::as_i64).unwrap_or(0);
-        if value <= 0 {
-            return Err(ExplorerError::spec(format!(
-                "frontmatter '{field}' must be a positive integer"
-            )));
+    print_json(&report)?;
+    println!("discover report: {}", report_path.display());
+
+    Ok(())
+}
+
+fn run_verify(root: &Path, args: VerifyArgs) -> Result<(), ExplorerError> {
+    let spec = if is_script_path(&args.spec) {
+        if args.scenario.is_some() {
+            return Err(ExplorerError::spec(
+                "--scenario applies to markdown specs, not scripts",
+            ));
+        }
+        if args.shrink {
+            return Err(ExplorerError::spec(
+                "--shrink applies to markdown specs, not scripts",
+            ));
+        }
+        None
+    } else {
+        Some(parse_spec_file(&args.spec)?)
+    };
+    let out_dir = ensure_out_dir(args.out.unwrap_or_else(default_out_dir))?;
+
+    let mut runner = DaemonRunner::new(root, args.runner)?;
+    let report = match &spec {
+        Some(spec) => {
+            let jobs = effective_jobs(args.jobs, session_limit(), runner.session_count()?)?;
+            verify_with_runner(
+                spec,
+                &args.spec,
+                &mut runner,
+                &out_dir,
+                VerifyOptions {
+                    scenario: args.scenario.as_deref(),
+                    fail_fast: args.fail_fast,
+                    jobs,
+                    shrink: args.shrink,
+                },
+            )?
         }
+        None => verify_script_with_runner(&args.spec, &mut runner, &out_dir)?,
+    };
+
+    print_json(&report)?;
+    for format in dedup_formats(&args.reports) {
+        let report_path = write_verify_report(&out_dir, &report, format)?;
+        println!("verify report: {}", report_path.display());
     }
 
-    if let Some(cwd) = frontmatter.get("cwd")
-        && !cwd.is_null()
-        && cwd.as_str().is_none()
-    {
-        return Err(ExplorerError::spec(
-            "frontmatter 'cwd' must be a string when present",
+    if report.failed_scenarios > 0 {
+        return Err(ExplorerError::scenario(
+            "verify completed with failing scenarios",
         ));
     }
 
     Ok(())
 }
 
-fn parse_spec_text(text: &str) -> Result<Spec, ExplorerError> {
-    let (frontmatter, body) = parse_frontmatter_and_body(text)?;
-    validate_frontmatter(&frontmatter)?;
-
-    let mut scenarios: Vec<Scenario> = Vec::new();
-    let mut current_name: Option<String> = None;
-    let mut current_steps: Vec<Step> = Vec::new();
+/// Clamps `--jobs` to the session slots the daemon has left, `limit` minus the
+/// `live` sessions it already holds, so workers never race for slots.
+fn effective_jobs(requested: usize, limit: usize, live: usize) -> Result<usize, ExplorerError> {
+    if requested == 0 {
+        return Err(ExplorerError::spec("--jobs must be at least 1"));
+    }
+    let free = limit.saturating_sub(live);
+    if free == 0 {
+        return Err(ExplorerError::scenario(format!(
+            "daemon session limit reached ({live} of {limit} sessions in use); kill idle sessions first"
+        )));
+    }
+    Ok(requested.min(free))
+}
 
-    let flush_current = |scenarios: &mut Vec<Scenario>,
-                         current_name: &mut Option<String>,
-                         current_steps: &mut Vec<Step>|
-     -> Result<(), ExplorerError> {
-        let Some(name) = current_name.clone() else {
-            return Ok(());
-        };
-        if current_steps.is_empty() {
-            return Err(ExplorerError::spec(format!(
-                "scenario '{name}' has no steps"
-            )));
-        }
-        scenarios.push(Scenario {
-            name,
-            steps: current_steps.clone(),
-        });
-        *current_name = None;
-        current_steps.clear();
-        Ok(())
-    };
+/// The daemon's session cap, `AGENT_TUI_MAX_SESSIONS` or its default.
+fn session_limit() -> usize {
+    env::var("AGENT_TUI_MAX_SESSIONS")
+        .ok()
+        .and_then(|value| value.trim().parse::<usize>().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_MAX_SESSIONS)
+}
 
-    for raw in body {
-        let line = raw.trim();
-        if line.is_empty() {
-            continue;
+/// Runs `task` over `items` on up to `jobs` workers, each driving its own clone
+/// of `runner`. Results keep item order; items not started once `stop` is set
+/// are `None`. With one job everything runs inline on `runner`.
+fn run_parallel<R, I, T>(
+    runner: &mut R,
+    jobs: usize,
+    items: &[I],
+    stop: &AtomicBool,
+    task: impl Fn(&mut R, &I) -> T + Sync,
+) -> Vec<Option<T>>
+where
+    R: Runner + Clone + Send,
+    I: Sync,
+    T: Send,
+{
+    let mut results = items.iter().map(|_| None).collect::<Vec<_>>();
+    if jobs <= 1 || items.len() <= 1 {
+        for (slot, item) in results.iter_mut().zip(items) {
+            if stop.load(Ordering::SeqCst) {
+                break;
+            }
+            *slot = Some(task(runner, item));
         }
+        return results;
+    }
 
-        if let Some(rest) = line.strip_prefix("## Scenario:") {
-            flush_current(&mut scenarios, &mut current_name, &mut current_steps)?;
-            let name = rest.trim();
-            if name.is_empty() {
-                return Err(ExplorerError::spec("scenario header has empty name"));
+    let next = AtomicUsize::new(0);
+    let task = &task;
+    thread::scope(|scope| {
+        let handles = (0..jobs.min(items.len()))
+            .map(|_| {
+                let mut worker = runner.clone();
+                let next = &next;
+                scope.spawn(move || {
+                    let mut done = Vec::new();
+                    while !stop.load(Ordering::SeqCst) {
+                        let index = next.fetch_add(1, Ordering::SeqCst);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        done.push((index, task(&mut worker, item)));
+                    }
+                    done
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            match handle.join() {
+                Ok(done) => {
+                    for (index, value) in done {
+                        results[index] = Some(value);
+                    }
+                }
+                Err(panic) => std::panic::resume_unwind(panic),
             }
-            current_name = Some(name.to_string());
-            continue;
         }
+    });
+    results
+}
 
-        if current_name.is_none() {
-            return Err(ExplorerError::spec(format!(
-                "unexpected content before first scenario: {line}"
-            )));
-        }
+fn print_json<T: Serialize>(value: &T) -> Result<(), ExplorerError> {
+    let output = serde_json::to_string_pretty(value).map_err(|error| {
+        ExplorerError::scenario(format!("failed to serialize JSON output: {error}"))
+    })?;
+    println!("{output}");
+    Ok(())
+}
 
-        if line == "### Expectation" || line == "### Expectations" {
-            continue;
-        }
+fn default_out_dir() -> PathBuf {
+    let stamp = Utc::now().format("%Y%m%d-%H%M%S").to_string();
+    PathBuf::from(".agent-tui").join("discover").join(stamp)
+}
 
-        if is_openspec_expectation_line(line) {
-            continue;
-        }
+fn ensure_out_dir(path: PathBuf) -> Result<PathBuf, ExplorerError> {
+    fs::create_dir_all(&path).map_err(|error| {
+        ExplorerError::scenario(format!(
+            "failed to create output directory {}: {error}",
+            path.display()
+        ))
+    })?;
+    Ok(path)
+}
 
-        if line == "- wait_stable: true" {
-            current_steps.push(Step::WaitStable);
-            continue;
-        }
+fn parse_json_payload(stdout: &str) -> Result<Value, ExplorerError> {
+    let payload = stdout.trim();
+    if payload.is_empty() {
+        return Ok(Value::Object(serde_json::Map::new()));
+    }
+    serde_json::from_str::<Value>(payload).map_err(|error| {
+        ExplorerError::scenario(format!("invalid JSON response from agent-tui: {error}"))
+    })
+}
 
-        if let Some(rest) = line.strip_prefix("- expect:") {
-            let value = parse_quoted_step(rest, "expect", line)?;
-            current_steps.push(Step::Expect(value));
-            continue;
-        }
+fn join_args(args: &[String]) -> String {
+    args.join(" ")
+}
+
+fn is_unavailable_text(stdout: &str, stderr: &str) -> bool {
+    let combined = format!("{}\n{}", stdout.to_lowercase(), stderr.to_lowercase());
+    combined.contains("daemon not running") || combined.contains("connection refused")
+}
+
+fn strip_ansi(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars().peekable();
 
-        if let Some(rest) = line.strip_prefix("- press:") {
-            let value = parse_quoted_step(rest, "press", line)?;
-            current_steps.push(Step::Press(value));
+    while let Some(ch) = chars.next() {
+        if ch != '\u{1b}' {
+            out.push(ch);
             continue;
         }
 
-        if let Some(rest) = line.strip_prefix("- type:") {
-            let value = parse_quoted_step(rest, "type", line)?;
-            current_steps.push(Step::Type(value));
+        if chars.peek() == Some(&'[') {
+            let _ = chars.next();
+            for code in chars.by_ref() {
+                let byte = code as u32;
+                if (0x40..=0x7e).contains(&byte) {
+                    break;
+                }
+            }
             continue;
         }
 
-        return Err(ExplorerError::spec(format!("invalid step syntax: {line}")));
+        let _ = chars.next();
     }
 
-    flush_current(&mut scenarios, &mut current_name, &mut current_steps)?;
-
-    if scenarios.is_empty() {
-        return Err(ExplorerError::spec("spec contains no scenarios"));
-    }
+    out
+}
 
-    Ok(Spec {
-        frontmatter,
-        scenarios,
+fn parse_replacement(value: &str) -> Result<Replacement, String> {
+    let (pattern, with) = value
+        .rsplit_once('=')
+        .ok_or_else(|| "expected REGEX=REPLACEMENT".to_string())?;
+    Ok(Replacement {
+        pattern: pattern.to_string(),
+        with: with.to_string(),
     })
 }
 
-fn parse_spec_file(path: &Path) -> Result<Spec, ExplorerError> {
-    let text = fs::read_to_string(path).map_err(|error| {
-        ExplorerError::spec(format!(
-            "failed to read spec file {}: {error}",
-            path.display()
-        ))
-    })?;
-    parse_spec_text(&text)
+fn parse_region(value: &str) -> Result<Region, String> {
+    let parts = value
+        .split(',')
+        .map(|part| part.trim().parse::<usize>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| format!("expected ROW,COL,WIDTH,HEIGHT: {error}"))?;
+    let [row, col, width, height] = parts[..] else {
+        return Err("expected ROW,COL,WIDTH,HEIGHT".to_string());
+    };
+    Ok(Region {
+        row,
+        col,
+        width,
+        height,
+    })
 }
 
-fn scalar_to_markdown(value: &Value) -> Result<String, ExplorerError> {
-    match value {
-        Value::String(text) => serde_json::to_string(text).map_err(|error| {
-            ExplorerError::spec(format!("failed to render string scalar: {error}"))
-        }),
-        Value::Null => Ok("null".to_string()),
-        Value::Bool(boolean) => Ok(if *boolean {
-            "true".to_string()
-        } else {
-            "false".to_string()
-        }),
-        Value::Number(number) => Ok(number.to_string()),
-        _ => Err(ExplorerError::spec("unsupported frontmatter value type")),
+impl Normalizers {
+    fn is_empty(&self) -> bool {
+        *self == Self::default()
     }
-}
-
-fn quote_for_markdown(value: &str, context: &str) -> Result<String, ExplorerError> {
-    serde_json::to_string(value)
-        .map_err(|error| ExplorerError::spec(format!("failed to render {context}: {error}")))
-}
 
-fn render_expectation_block(steps: &[Step]) -> Result<Vec<String>, ExplorerError> {
-    let mut action_summary = Vec::new();
-    let mut expected_texts = Vec::new();
-
-    for step in steps {
-        match step {
-            Step::Expect(value) => expected_texts.push(quote_for_markdown(value, "expectation")?),
-            Step::Press(value) => {
-                action_summary.push(format!("press {}", quote_for_markdown(value, "press")?))
-            }
-            Step::Type(value) => {
-                action_summary.push(format!("type {}", quote_for_markdown(value, "type")?))
-            }
-            Step::WaitStable => action_summary.push("wait_stable".to_string()),
+    fn compile(&self) -> Result<ScreenNormalizer, ExplorerError> {
+        let replace = self
+            .replace
+            .iter()
+            .map(|replacement| {
+                Regex::new(&replacement.pattern)
+                    .map(|regex| (regex, replacement.with.clone()))
+                    .map_err(|error| {
+                        ExplorerError::spec(format!(
+                            "invalid normalizer regex {}: {error}",
+                            replacement.pattern
+                        ))
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if let Some(similarity) = self.similarity
+            && !(similarity > 0.0 && similarity <= 1.0)
+        {
+            return Err(ExplorerError::spec(
+                "normalizer similarity must be in (0, 1]",
+            ));
         }
+        Ok(ScreenNormalizer {
+            replace,
+            ignore_regions: self.ignore_regions.clone(),
+            ignore_cursor: self.ignore_cursor,
+            similarity: self.similarity,
+        })
     }
 
-    let when_clause = if action_summary.is_empty() {
-        "the scenario starts from a fresh session".to_string()
-    } else {
-        format!("the operator replays: {}", action_summary.join(", "))
-    };
-
-    let then_clause = if expected_texts.is_empty() {
-        "the scenario reaches a stable terminal state".to_string()
-    } else {
-        format!("the screen contains {}", expected_texts.join(", "))
-    };
-
-    let should_clause = if expected_texts.is_empty() {
-        "run the machine steps below without additional text assertions".to_string()
-    } else {
-        format!(
-            "execute machine checks: {}",
-            expected_texts
-                .iter()
-                .map(|value| format!("expect {value}"))
-                .collect::<Vec<_>>()
-                .join(", ")
-        )
-    };
-
-    Ok(vec![
-        "### Expectation".to_string(),
-        format!("- **WHEN** {when_clause}"),
-        format!("- **THEN** {then_clause}"),
-        format!("- **SHOULD** {should_clause}"),
-    ])
-}
-
-fn render_markdown(spec: &Spec) -> Result<String, ExplorerError> {
-    let mut lines = vec!["---".to_string()];
-
-    for key in [
-        "schema_version",
-        "command",
-        "cwd",
-        "cols",
-        "rows",
-        "default_timeout_ms",
-        "generated_at",
-        "generator",
-    ] {
-        if let Some(value) = spec.frontmatter.get(key) {
-            lines.push(format!("{key}: {}", scalar_to_markdown(value)?));
+    fn from_frontmatter(frontmatter: &BTreeMap<String, Value>) -> Result<Self, ExplorerError> {
+        match frontmatter.get("normalize") {
+            None | Some(Value::Null) => Ok(Self::default()),
+            Some(value) => serde_json::from_value(value.clone()).map_err(|error| {
+                ExplorerError::spec(format!("invalid frontmatter 'normalize': {error}"))
+            }),
         }
     }
+}
 
-    lines.push("---".to_string());
-    lines.push(String::new());
+impl ScreenNormalizer {
+    /// Whether `normalize` changes screen text beyond stripping ANSI.
+    fn rewrites_screen(&self) -> bool {
+        !self.replace.is_empty() || !self.ignore_regions.is_empty()
+    }
 
-    for scenario in &spec.scenarios {
-        lines.push(format!("## Scenario: {}", scenario.name));
-        lines.extend(render_expectation_block(&scenario.steps)?);
-        lines.push(String::new());
-        for step in &scenario.steps {
-            match step {
-                Step::WaitStable => lines.push("- wait_stable: true".to_string()),
-                Step::Expect(value) => lines.push(format!(
-                    "- expect: {}",
-                    quote_for_markdown(value, "expect step")?
-                )),
-                Step::Press(value) => lines.push(format!(
-                    "- press: {}",
-                    quote_for_markdown(value, "press step")?
-                )),
-                Step::Type(value) => lines.push(format!(
-                    "- type: {}",
-                    quote_for_markdown(value, "type step")?
-                )),
+    /// Strips ANSI, blanks ignored regions, then applies replacements. Line
+    /// breaks are kept and trailing whitespace is trimmed.
+    fn normalize(&self, screenshot: &str) -> String {
+        let mut lines = strip_ansi(screenshot)
+            .lines()
+            .map(str::to_string)
+            .collect::<Vec<_>>();
+        for region in &self.ignore_regions {
+            for line in lines.iter_mut().skip(region.row).take(region.height) {
+                *line = line
+                    .chars()
+                    .enumerate()
+                    .map(|(col, ch)| {
+                        if col >= region.col && col < region.col + region.width {
+                            ' '
+                        } else {
+                            ch
+                        }
+                    })
+                    .collect();
             }
         }
-        lines.push(String::new());
+        let mut text = lines
+            .iter()
+            .map(|line| line.trim_end())
+            .collect::<Vec<_>>()
+            .join("\n");
+        for (regex, with) in &self.replace {
+            text = regex.replace_all(&text, with.as_str()).into_owned();
+        }
+        text
     }
 
-    Ok(format!("{}\n", lines.join("\n").trim_end()))
-}
-
-fn scenario_name(path: &[String], index: usize) -> String {
-    if path.is_empty() {
-        return format!("Path {index}: root");
+    /// Fraction of cells that match when both screens are laid over one grid.
+    fn similarity(left: &str, right: &str) -> f64 {
+        let left = left.lines().collect::<Vec<_>>();
+        let right = right.lines().collect::<Vec<_>>();
+        let mut total = 0usize;
+        let mut same = 0usize;
+        for row in 0..left.len().max(right.len()) {
+            let a = left
+                .get(row)
+                .map_or_else(Vec::new, |line| line.chars().collect());
+            let b = right
+                .get(row)
+                .map_or_else(Vec::new, |line| line.chars().collect());
+            for col in 0..a.len().max(b.len()) {
+                total += 1;
+                if a.get(col) == b.get(col) {
+                    same += 1;
+                }
+            }
+        }
+        if total == 0 {
+            return 1.0;
+        }
+        same as f64 / total as f64
     }
-    let compact = path
-        .iter()
-        .take(4)
-        .cloned()
-        .collect::<Vec<_>>()
-        .join(" -> ");
-    format!("Path {index}: {compact}")
 }
 
-fn steps_for_action(action: &str) -> Vec<Step> {
-    vec![Step::Press(action.to_string()), Step::WaitStable]
+fn normalize_screenshot(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
 }
 
-fn discover_with_runner<R: Runner>(
-    config: &DiscoverConfig,
-    runner: &mut R,
-) -> Result<(DiscoverReport, Spec, Vec<TraceRecord>), ExplorerError> {
-    let mut actions = DEFAULT_SAFE_ACTIONS
-        .iter()
-        .map(|item| (*item).to_string())
-        .collect::<Vec<_>>();
-
-    let blocked_risky_actions = if config.allow_risky {
-        actions.extend(
-            DEFAULT_RISKY_ACTIONS
-                .iter()
-                .map(|item| (*item).to_string())
-                .collect::<Vec<_>>(),
-        );
-        Vec::new()
+/// Hashes a screen already passed through [`ScreenNormalizer::normalize`].
+fn compute_state_hash(
+    normalized_screen: &str,
+    cursor: &Cursor,
+    ignore_cursor: bool,
+    cols: u16,
+    rows: u16,
+) -> String {
+    let normalized = normalize_screenshot(normalized_screen);
+    let cursor = if ignore_cursor {
+        "-".to_string()
     } else {
-        DEFAULT_RISKY_ACTIONS
-            .iter()
-            .map(|item| (*item).to_string())
-            .collect::<Vec<_>>()
+        let visible = if cursor.visible { 1 } else { 0 };
+        format!("{}:{}:{visible}", cursor.row, cursor.col)
     };
+    let payload = format!("{normalized}|{cursor}|{cols}:{rows}");
 
-    let timeout = Duration::from_secs(config.time_budget_sec);
-    let start = Instant::now();
+    let mut hasher = Sha256::new();
+    hasher.update(payload.as_bytes());
+    let digest = hasher.finalize();
+    format!("{digest:x}")
+}
 
-    let mut queue: VecDeque<Vec<String>> = VecDeque::new();
-    queue.push_back(Vec::new());
+fn contains_clock_like_token(line: &str) -> bool {
+    for token in line.split_whitespace() {
+        let trimmed = token.trim_matches(|ch: char| !ch.is_ascii_digit() && ch != ':');
+        if trimmed.is_empty() {
+            continue;
+        }
 
-    let mut visited_hashes = HashSet::new();
-    let mut traces = Vec::new();
-    let mut scenarios = Vec::new();
-    let mut states_explored = 0usize;
+        let mut parts = trimmed.split(':');
+        let hour = parts.next();
+        let minute = parts.next();
+        let extra = parts.next();
 
-    while let Some(path) = queue.pop_front() {
-        if states_explored >= config.max_states {
-            break;
-        }
-        if start.elapsed() >= timeout {
-            break;
+        if let (Some(hour), Some(minute), None) = (hour, minute, extra) {
+            let hour_ok =
+                (hour.len() == 1 || hour.len() == 2) && hour.chars().all(|ch| ch.is_ascii_digit());
+            let minute_ok = minute.len() == 2 && minute.chars().all(|ch| ch.is_ascii_digit());
+            if hour_ok && minute_ok {
+                return true;
+            }
         }
+    }
 
-        states_explored += 1;
+    false
+}
 
-        let mut session_id: Option<String> = None;
-        let mut state_hash = String::new();
-        let mut anchor: Option<String> = None;
-        let mut error_message: Option<String> = None;
+fn pick_anchor(screenshot: &str) -> Option<String> {
+    for raw in screenshot.lines() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line.len() < 3 || line.len() > 120 {
+            continue;
+        }
+        if contains_clock_like_token(line) {
+            continue;
+        }
 
-        let execution = (|| -> Result<(), ExplorerError> {
-            let id = runner.spawn(
-                &config.command,
-                config.cwd.as_deref(),
-                config.cols,
-                config.rows,
-            )?;
-            session_id = Some(id.clone());
+        let digits = line.chars().filter(|ch| ch.is_ascii_digit()).count();
+        let ratio = digits as f64 / line.len() as f64;
+        if ratio > 0.45 {
+            continue;
+        }
 
-            for action in &path {
-                runner.press(&id, action)?;
-                runner.wait_stable(&id, config.default_timeout_ms.min(1000))?;
-            }
+        if line.chars().any(|ch| ch.is_ascii_alphabetic()) {
+            return Some(line.to_string());
+        }
+    }
 
-            runner.wait_stable(&id, config.default_timeout_ms.min(1000))?;
-            let (screenshot, cursor) = runner.screenshot(&id)?;
-            state_hash = compute_state_hash(&screenshot, &cursor, config.cols, config.rows);
-            anchor = pick_anchor(&screenshot);
+    for raw in screenshot.lines() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let anchor = if line.len() > 80 { &line[..80] } else { line };
+        return Some(anchor.to_string());
+    }
 
-            Ok(())
-        })();
+    None
+}
 
-        if let Some(id) = session_id.as_ref() {
-            let _ = runner.kill(id);
-        }
+/// First few non-empty screen lines, trimmed, for graph node previews.
+fn screen_excerpt(screenshot: &str) -> Vec<String> {
+    strip_ansi(screenshot)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(3)
+        .map(|line| line.chars().take(60).collect())
+        .collect()
+}
 
-        if let Err(error) = execution {
-            if error.kind == ExplorerErrorKind::Unavailable {
-                return Err(error);
-            }
-            error_message = Some(error.to_string());
+impl StateGraph {
+    fn state_node(
+        &mut self,
+        state_hash: &str,
+        anchor: Option<&str>,
+        excerpt: Vec<String>,
+        path: &[String],
+    ) -> String {
+        if let Some(node) = self
+            .nodes
+            .iter()
+            .find(|node| node.state_hash.as_deref() == Some(state_hash))
+        {
+            return node.id.clone();
         }
-
-        traces.push(TraceRecord {
-            timestamp: now_rfc3339(),
-            path: path.clone(),
-            depth: path.len(),
-            state_hash: state_hash.clone(),
-            anchor: anchor.clone(),
-            error: error_message.clone(),
+        let id = format!(
+            "s{}",
+            self.nodes.iter().filter(|n| n.error.is_none()).count()
+        );
+        self.nodes.push(GraphNode {
+            id: id.clone(),
+            state_hash: Some(state_hash.to_string()),
+            anchor: anchor.map(str::to_string),
+            excerpt,
+            path: path.to_vec(),
+            expanded: false,
+            error: None,
         });
+        id
+    }
 
-        if error_message.is_some() {
-            continue;
+    fn error_node(&mut self, message: &str, path: &[String]) -> String {
+        if let Some(node) = self
+            .nodes
+            .iter()
+            .find(|node| node.error.as_deref() == Some(message))
+        {
+            return node.id.clone();
         }
+        let id = format!(
+            "e{}",
+            self.nodes.iter().filter(|n| n.error.is_some()).count()
+        );
+        self.nodes.push(GraphNode {
+            id: id.clone(),
+            state_hash: None,
+            anchor: None,
+            excerpt: Vec::new(),
+            path: path.to_vec(),
+            expanded: false,
+            error: Some(message.to_string()),
+        });
+        id
+    }
 
-        if visited_hashes.contains(&state_hash) {
-            continue;
+    fn mark_expanded(&mut self, id: &str) {
+        if let Some(node) = self.nodes.iter_mut().find(|node| node.id == id) {
+            node.expanded = true;
         }
-        visited_hashes.insert(state_hash);
+    }
 
-        if !path.is_empty()
-            && let Some(anchor) = anchor
+    fn add_edge(&mut self, from: &str, to: &str, action: &str) {
+        if let Some(edge) = self
+            .edges
+            .iter_mut()
+            .find(|edge| edge.from == from && edge.to == to)
         {
-            let mut steps = Vec::new();
-            for action in &path {
-                steps.extend(steps_for_action(action));
-            }
-            steps.push(Step::Expect(anchor));
-            scenarios.push(Scenario {
-                name: scenario_name(&path, scenarios.len() + 1),
-                steps,
-            });
+            if !edge.actions.iter().any(|existing| existing == action) {
+                edge.actions.push(action.to_string());
+            }
+            return;
         }
+        self.edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            actions: vec![action.to_string()],
+        });
+    }
+}
 
-        if path.len() >= config.max_depth {
-            continue;
+fn graph_node_title(node: &GraphNode) -> String {
+    match (&node.error, &node.anchor, &node.state_hash) {
+        (Some(error), _, _) => format!("error: {}", error.chars().take(60).collect::<String>()),
+        (None, Some(anchor), _) => anchor.clone(),
+        (None, None, _) => "(blank screen)".to_string(),
+    }
+}
+
+fn short_hash(node: &GraphNode) -> &str {
+    node.state_hash
+        .as_deref()
+        .map_or("", |hash| &hash[..hash.len().min(8)])
+}
+
+fn dot_escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+fn render_graph_dot(graph: &StateGraph) -> String {
+    let mut lines = vec![
+        "digraph tui {".to_string(),
+        "  rankdir=LR;".to_string(),
+        "  node [shape=box, fontname=\"monospace\"];".to_string(),
+    ];
+    for node in &graph.nodes {
+        if node.error.is_some() {
+            lines.push(format!(
+                "  {} [label=\"{}\", shape=octagon, color=red, fontcolor=red];",
+                node.id,
+                dot_escape(&graph_node_title(node))
+            ));
+        } else {
+            lines.push(format!(
+                "  {} [label=\"{}\\n{}\", tooltip=\"{}\"{}];",
+                node.id,
+                dot_escape(&graph_node_title(node)),
+                short_hash(node),
+                dot_escape(&node.excerpt.join("\n")),
+                if node.expanded { "" } else { ", style=dashed" }
+            ));
         }
+    }
+    for edge in &graph.edges {
+        lines.push(format!(
+            "  {} -> {} [label=\"{}\"];",
+            edge.from,
+            edge.to,
+            dot_escape(&edge.actions.join(", "))
+        ));
+    }
+    lines.push("}".to_string());
+    format!("{}\n", lines.join("\n"))
+}
+
+/// Entity-escapes everything that can end a Mermaid label or edge text early;
+/// `#` goes first since the entities themselves start with it.
+fn mermaid_escape(value: &str) -> String {
+    value
+        .replace('#', "#35;")
+        .replace('&', "#amp;")
+        .replace('"', "#quot;")
+        .replace('<', "#lt;")
+        .replace('>', "#gt;")
+        .replace('|', "#124;")
+        .replace('[', "#91;")
+        .replace(']', "#93;")
+        .replace('(', "#40;")
+        .replace(')', "#41;")
+        .replace('{', "#123;")
+        .replace('}', "#125;")
+        .replace("\r\n", "<br/>")
+        .replace('\n', "<br/>")
+}
 
-        for action in actions.iter().take(config.branch_limit) {
-            let mut next = path.clone();
-            next.push(action.clone());
-            queue.push_back(next);
+fn render_graph_mermaid(graph: &StateGraph) -> String {
+    let mut lines = vec!["flowchart LR".to_string()];
+    for node in &graph.nodes {
+        let title = mermaid_escape(&graph_node_title(node));
+        if node.error.is_some() {
+            lines.push(format!("  {}{{{{\"{title}\"}}}}:::error", node.id));
+        } else {
+            let class = if node.expanded { "" } else { ":::unexpanded" };
+            lines.push(format!(
+                "  {}[\"{title}<br/>{}\"]{class}",
+                node.id,
+                short_hash(node)
+            ));
         }
     }
+    for edge in &graph.edges {
+        lines.push(format!(
+            "  {} -->|{}| {}",
+            edge.from,
+            mermaid_escape(&edge.actions.join(", ")),
+            edge.to
+        ));
+    }
+    lines.push("  classDef error stroke:#c00,color:#c00".to_string());
+    lines.push("  classDef unexpanded stroke-dasharray:4".to_string());
+    format!("{}\n", lines.join("\n"))
+}
 
-    let generated_at = now_rfc3339();
-    let spec = Spec {
-        frontmatter: BTreeMap::from([
-            (
-                "schema_version".to_string(),
-                Value::String("v1".to_string()),
-            ),
-            ("command".to_string(), Value::String(config.command.clone())),
-            (
-                "cwd".to_string(),
-                config
-                    .cwd
-                    .as_ref()
-                    .map(|cwd| Value::String(cwd.clone()))
-                    .unwrap_or(Value::Null),
-            ),
-            (
-                "cols".to_string(),
-                Value::Number(i64::from(config.cols).into()),
-            ),
-            (
-                "rows".to_string(),
-                Value::Number(i64::from(config.rows).into()),
-            ),
-            (
-                "default_timeout_ms".to_string(),
-                Value::Number((config.default_timeout_ms as i64).into()),
-            ),
-            ("generated_at".to_string(), Value::String(generated_at)),
-            (
-                "generator".to_string(),
-                Value::String("tui-explorer/1".to_string()),
-            ),
-        ]),
-        scenarios,
+fn write_state_graph(out_dir: &Path, graph: &StateGraph) -> Result<StateGraphFiles, ExplorerError> {
+    let json = serde_json::to_string_pretty(graph).map_err(|error| {
+        ExplorerError::scenario(format!("failed to serialize state graph: {error}"))
+    })?;
+    let write = |name: &str, contents: String| -> Result<String, ExplorerError> {
+        let path = out_dir.join(name);
+        fs::write(&path, contents).map_err(|error| {
+            ExplorerError::scenario(format!(
+                "failed to write state graph {}: {error}",
+                path.display()
+            ))
+        })?;
+        Ok(path.display().to_string())
     };
 
-    let acceptance_path = config.out_dir.join("acceptance.md");
-    let trace_path = config.out_dir.join("trace.jsonl");
+    Ok(StateGraphFiles {
+        json: write("state-graph.json", format!("{json}\n"))?,
+        dot: write("state-graph.dot", render_graph_dot(graph))?,
+        mermaid: write("state-graph.mmd", render_graph_mermaid(graph))?,
+    })
+}
 
-    let markdown = render_markdown(&spec)?;
-    fs::write(&acceptance_path, markdown).map_err(|error| {
-        ExplorerError::scenario(format!(
-            "failed to write acceptance spec {}: {error}",
-            acceptance_path.display()
-        ))
-    })?;
+fn now_rfc3339() -> String {
+    Utc::now().to_rfc3339()
+}
 
-    let mut trace_lines = String::new();
-    for trace in &traces {
-        let line = serde_json::to_string(trace).map_err(|error| {
-            ExplorerError::scenario(format!("failed to serialize trace: {error}"))
+fn parse_scalar(raw_value: &str) -> Result<Value, ExplorerError> {
+    let value = raw_value.trim();
+    if value.starts_with('"') && value.ends_with('"') {
+        let parsed = serde_json::from_str::<String>(value).map_err(|error| {
+            ExplorerError::spec(format!("invalid quoted scalar '{value}': {error}"))
         })?;
-        trace_lines.push_str(&line);
-        trace_lines.push('\n');
+        return Ok(Value::String(parsed));
     }
 
-    fs::write(&trace_path, trace_lines).map_err(|error| {
-        ExplorerError::scenario(format!(
-            "failed to write trace file {}: {error}",
-            trace_path.display()
-        ))
-    })?;
+    if value.starts_with('{') || value.starts_with('[') {
+        return serde_json::from_str::<Value>(value).map_err(|error| {
+            ExplorerError::spec(format!("invalid inline JSON '{value}': {error}"))
+        });
+    }
 
-    let report = DiscoverReport {
-        command: config.command.clone(),
-        cwd: config.cwd.clone(),
-        states_explored,
-        scenarios_generated: spec.scenarios.len(),
-        unique_hashes: visited_hashes.len(),
-        out_dir: config.out_dir.display().to_string(),
-        acceptance_spec: acceptance_path.display().to_string(),
-        trace_file: trace_path.display().to_string(),
-        risky_actions_blocked: blocked_risky_actions,
-    };
+    if value == "true" {
+        return Ok(Value::Bool(true));
+    }
+    if value == "false" {
+        return Ok(Value::Bool(false));
+    }
+    if value == "null" {
+        return Ok(Value::Null);
+    }
 
-    Ok((report, spec, traces))
-}
+    if let Ok(parsed) = value.parse::<i64>() {
+        return Ok(Value::Number(parsed.into()));
+    }
 
-fn value_string(frontmatter: &BTreeMap<String, Value>, key: &str) -> Result<String, ExplorerError> {
-    let Some(value) = frontmatter.get(key) else {
-        return Err(ExplorerError::spec(format!(
-            "missing frontmatter key: {key}"
-        )));
-    };
-    let Some(parsed) = value.as_str() else {
-        return Err(ExplorerError::spec(format!(
-            "frontmatter '{key}' must be a string"
-        )));
-    };
-    Ok(parsed.to_string())
+    Ok(Value::String(value.to_string()))
 }
 
-fn value_optional_string(
-    frontmatter: &BTreeMap<String, Value>,
-    key: &str,
-) -> Result<Option<String>, ExplorerError> {
-    let Some(value) = frontmatter.get(key) else {
-        return Ok(None);
-    };
-    if value.is_null() {
-        return Ok(None);
+fn parse_frontmatter_and_body(
+    text: &str,
+) -> Result<(BTreeMap<String, Value>, Vec<String>), ExplorerError> {
+    let lines = text
+        .lines()
+        .map(std::string::ToString::to_string)
+        .collect::<Vec<_>>();
+    if lines.is_empty() || lines[0].trim() != "---" {
+        return Err(ExplorerError::spec(
+            "spec must start with frontmatter delimiter '---'",
+        ));
     }
-    let Some(parsed) = value.as_str() else {
-        return Err(ExplorerError::spec(format!(
-            "frontmatter '{key}' must be a string"
-        )));
+
+    let mut end_idx: Option<usize> = None;
+    for (index, line) in lines.iter().enumerate().skip(1) {
+        if line.trim() == "---" {
+            end_idx = Some(index);
+            break;
+        }
+    }
+
+    let Some(end_idx) = end_idx else {
+        return Err(ExplorerError::spec("frontmatter is not closed with '---'"));
     };
-    Ok(Some(parsed.to_string()))
+
+    let mut frontmatter = BTreeMap::new();
+    for line in lines.iter().take(end_idx).skip(1) {
+        let stripped = line.trim();
+        if stripped.is_empty() {
+            continue;
+        }
+        let Some((key, value)) = stripped.split_once(':') else {
+            return Err(ExplorerError::spec(format!(
+                "invalid frontmatter line: {line}"
+            )));
+        };
+        frontmatter.insert(key.trim().to_string(), parse_scalar(value)?);
+    }
+
+    Ok((frontmatter, lines[(end_idx + 1)..].to_vec()))
 }
 
-fn value_u64(frontmatter: &BTreeMap<String, Value>, key: &str) -> Result<u64, ExplorerError> {
-    let Some(value) = frontmatter.get(key) else {
-        return Err(ExplorerError::spec(format!(
-            "missing frontmatter key: {key}"
-        )));
-    };
-    let Some(parsed) = value.as_u64() else {
-        return Err(ExplorerError::spec(format!(
-            "frontmatter '{key}' must be a positive integer"
-        )));
-    };
-    if parsed == 0 {
+fn parse_quoted_step(raw_value: &str, kind: &str, line: &str) -> Result<String, ExplorerError> {
+    let value = raw_value.trim();
+    if !value.starts_with('"') || !value.ends_with('"') {
         return Err(ExplorerError::spec(format!(
-            "frontmatter '{key}' must be greater than zero"
+            "{kind} step must use a double-quoted string: {line}"
         )));
     }
-    Ok(parsed)
+
+    serde_json::from_str::<String>(value).map_err(|error| {
+        ExplorerError::spec(format!("invalid quoted value in step '{line}': {error}"))
+    })
 }
 
-fn verify_with_runner<R: Runner>(
-    spec: &Spec,
-    spec_path: &Path,
-    runner: &mut R,
-    out_dir: &Path,
-    scenario_filter: Option<&str>,
-    fail_fast: bool,
-) -> Result<VerifyReport, ExplorerError> {
-    let target_scenarios = if let Some(name) = scenario_filter {
-        let filtered = spec
-            .scenarios
-            .iter()
-            .filter(|scenario| scenario.name == name)
-            .cloned()
-            .collect::<Vec<_>>();
-        if filtered.is_empty() {
+fn is_openspec_expectation_line(line: &str) -> bool {
+    for prefix in [
+        "- **WHEN**",
+        "- **THEN**",
+        "- **AND**",
+        "- **SHOULD**",
+        "- WHEN",
+        "- THEN",
+        "- AND",
+        "- SHOULD",
+    ] {
+        if line.starts_with(prefix) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn validate_frontmatter(frontmatter: &BTreeMap<String, Value>) -> Result<(), ExplorerError> {
+    for required in [
+        "schema_version",
+        "command",
+        "cols",
+        "rows",
+        "default_timeout_ms",
+        "generated_at",
+        "generator",
+    ] {
+        if !frontmatter.contains_key(required) {
             return Err(ExplorerError::spec(format!(
-                "scenario '{name}' not found in spec"
+                "missing required frontmatter key: {required}"
             )));
         }
-        filtered
-    } else {
-        spec.scenarios.clone()
-    };
-
-    let failure_dir = out_dir.join("failures");
-    fs::create_dir_all(&failure_dir).map_err(|error| {
-        ExplorerError::scenario(format!(
-            "failed to create failure directory {}: {error}",
-            failure_dir.display()
-        ))
-    })?;
+    }
 
-    let timeout_ms = value_u64(&spec.frontmatter, "default_timeout_ms")?;
-    let command = value_string(&spec.frontmatter, "command")?;
-    let cwd = value_optional_string(&spec.frontmatter, "cwd")?;
-    let cols = value_u64(&spec.frontmatter, "cols")? as u16;
-    let rows = value_u64(&spec.frontmatter, "rows")? as u16;
+    let version = frontmatter.get("schema_version").and_then(Value::as_str);
+    if version != Some(SCHEMA_V1) && version != Some(SCHEMA_V2) {
+        return Err(ExplorerError::spec(
+            "unsupported schema_version, expected 'v1' or 'v2'",
+        ));
+    }
 
-    let mut results = Vec::new();
+    let command = frontmatter
+        .get("command")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    if command.trim().is_empty() {
+        return Err(ExplorerError::spec(
+            "frontmatter 'command' must be a non-empty string",
+        ));
+    }
 
-    for scenario in target_scenarios {
-        let mut scenario_result = VerifyScenarioResult {
-            name: scenario.name.clone(),
-            passed: true,
-            failed_step: None,
-            message: None,
-        };
-        let mut session_id: Option<String> = None;
-        let mut failing_step_index: Option<usize> = None;
-
-        let execution = (|| -> Result<(), ExplorerError> {
-            let id = runner.spawn(&command, cwd.as_deref(), cols, rows)?;
-            session_id = Some(id.clone());
-
-            for (index, step) in scenario.steps.iter().enumerate() {
-                let step_number = index + 1;
-                failing_step_index = Some(step_number);
-                match step {
-                    Step::Press(key) => runner.press(&id, key)?,
-                    Step::Type(text) => runner.type_text(&id, text)?,
-                    Step::WaitStable => {
-                        runner.wait_stable(&id, timeout_ms)?;
-                        continue;
-                    }
-                    Step::Expect(text) => {
-                        let found = runner.wait_for_text(&id, text, timeout_ms)?;
-                        if !found {
-                            return Err(ExplorerError::scenario(format!(
-                                "expectation not met: {text}"
-                            )));
-                        }
-                    }
-                }
+    for field in ["cols", "rows", "default_timeout_ms"] {
+        let value = frontmatter.get(field).and_then(Value::as_i64).unwrap_or(0);
+        if value <= 0 {
+            return Err(ExplorerError::spec(format!(
+                "frontmatter '{field}' must be a positive integer"
+            )));
+        }
+    }
 
-                runner.wait_stable(&id, timeout_ms).map_err(|error| {
-                    ExplorerError::scenario(format!(
-                        "post-step stabilization failed at step {step_number}: {error}"
-                    ))
-                })?;
+    if let Some(cwd) = frontmatter.get("cwd")
+        && !cwd.is_null()
+        && cwd.as_str().is_none()
+    {
+        return Err(ExplorerError::spec(
+            "frontmatter 'cwd' must be a string when present",
+        ));
+    }
+
+    Normalizers::from_frontmatter(frontmatter)?.compile()?;
+
+    Ok(())
+}
+
+/// Wraps `text` in bracketed-paste markers so the app receives it as one paste.
+fn bracketed_paste(text: &str) -> String {
+    format!("\x1b[200~{text}\x1b[201~")
+}
+
+fn parse_json_step<T: DeserializeOwned>(
+    raw_value: &str,
+    kind: &str,
+    line: &str,
+) -> Result<T, ExplorerError> {
+    serde_json::from_str::<T>(raw_value.trim()).map_err(|error| {
+        ExplorerError::spec(format!("invalid {kind} value in step '{line}': {error}"))
+    })
+}
+
+fn parse_step(line: &str) -> Result<Step, ExplorerError> {
+    if line == "- wait_stable: true" {
+        return Ok(Step::WaitStable);
+    }
+
+    let invalid = || ExplorerError::spec(format!("invalid step syntax: {line}"));
+    let (key, rest) = line
+        .strip_prefix("- ")
+        .and_then(|item| item.split_once(':'))
+        .ok_or_else(invalid)?;
+
+    let step = match key {
+        "expect" => Step::Expect(parse_quoted_step(rest, key, line)?),
+        "press" => Step::Press(parse_quoted_step(rest, key, line)?),
+        "type" => Step::Type(parse_quoted_step(rest, key, line)?),
+        "paste" => Step::Paste(parse_quoted_step(rest, key, line)?),
+        "expect_absent" => Step::ExpectAbsent(parse_quoted_step(rest, key, line)?),
+        "expect_regex" => {
+            let pattern = parse_quoted_step(rest, key, line)?;
+            Regex::new(&pattern).map_err(|error| {
+                ExplorerError::spec(format!("invalid regex in step '{line}': {error}"))
+            })?;
+            Step::ExpectRegex(pattern)
+        }
+        "wait_for" if rest.trim_start().starts_with('{') => {
+            let args: WaitForArgs = parse_json_step(rest, key, line)?;
+            Step::WaitFor {
+                text: args.text,
+                timeout_ms: args.timeout_ms,
+            }
+        }
+        "wait_for" => Step::WaitFor {
+            text: parse_quoted_step(rest, key, line)?,
+            timeout_ms: None,
+        },
+        "resize" => {
+            let args: ResizeArgs = parse_json_step(rest, key, line)?;
+            if args.cols == 0 || args.rows == 0 {
+                return Err(ExplorerError::spec(format!(
+                    "resize step needs positive cols and rows: {line}"
+                )));
+            }
+            Step::Resize {
+                cols: args.cols,
+                rows: args.rows,
+            }
+        }
+        "sleep" => Step::Sleep(parse_json_step(rest, key, line)?),
+        "signal" => {
+            let name = parse_quoted_step(rest, key, line)?;
+            let signal = ProcessSignal::parse(&name).ok_or_else(|| {
+                ExplorerError::spec(format!(
+                    "unsupported signal '{name}', expected one of {}",
+                    ProcessSignal::NAMES.join(", ")
+                ))
+            })?;
+            Step::Signal(signal.as_str().to_string())
+        }
+        "expect_cursor" => {
+            let args: CursorArgs = parse_json_step(rest, key, line)?;
+            Step::ExpectCursor {
+                row: args.row,
+                col: args.col,
+                visible: args.visible,
             }
+        }
+        "expect_exit" => Step::ExpectExit(parse_json_step(rest, key, line)?),
+        "screenshot" => {
+            let name = parse_quoted_step(rest, key, line)?;
+            // The name becomes a file name, so it needs something to slugify.
+            if !name.chars().any(|ch| ch.is_ascii_alphanumeric()) {
+                return Err(ExplorerError::spec(format!(
+                    "screenshot step needs a name with letters or digits: {line}"
+                )));
+            }
+            Step::Screenshot(name)
+        }
+        _ => return Err(invalid()),
+    };
+    Ok(step)
+}
 
-            Ok(())
-        })();
+fn parse_spec_text(text: &str) -> Result<Spec, ExplorerError> {
+    let (frontmatter, body) = parse_frontmatter_and_body(text)?;
+    validate_frontmatter(&frontmatter)?;
+    let is_v1 = frontmatter.get("schema_version").and_then(Value::as_str) == Some(SCHEMA_V1);
+
+    let mut scenarios: Vec<Scenario> = Vec::new();
+    let mut current: Option<Scenario> = None;
+    let mut block = Block::Steps;
+
+    let flush_current = |scenarios: &mut Vec<Scenario>,
+                         current: &mut Option<Scenario>|
+     -> Result<(), ExplorerError> {
+        let Some(scenario) = current.take() else {
+            return Ok(());
+        };
+        if scenario.steps.is_empty() {
+            return Err(ExplorerError::spec(format!(
+                "scenario '{}' has no steps",
+                scenario.name
+            )));
+        }
+        scenarios.push(scenario);
+        Ok(())
+    };
 
-        if let Some(id) = session_id.as_ref() {
-            let _ = runner.kill(id);
+    for raw in body {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
         }
 
-        if let Err(error) = execution {
-            if error.kind == ExplorerErrorKind::Unavailable {
-                return Err(error);
+        if let Some(rest) = line.strip_prefix("## Scenario:") {
+            flush_current(&mut scenarios, &mut current)?;
+            let name = rest.trim();
+            if name.is_empty() {
+                return Err(ExplorerError::spec("scenario header has empty name"));
             }
-            scenario_result.passed = false;
-            let failed_step = failing_step_index.unwrap_or(0);
-            scenario_result.failed_step = Some(failed_step);
-            scenario_result.message = Some(error.to_string());
+            current = Some(Scenario {
+                name: name.to_string(),
+                ..Scenario::default()
+            });
+            block = Block::Steps;
+            continue;
+        }
 
-            let failure_path = failure_dir.join(format!(
-                "{}-step-{}.txt",
-                slugify(&scenario.name),
-                scenario_result.failed_step.unwrap_or(0)
-            ));
-            let failure_text = format!(
-                "scenario: {}\nstep: {}\nerror: {}\n",
-                scenario.name,
-                scenario_result.failed_step.unwrap_or(0),
-                scenario_result.message.clone().unwrap_or_default()
-            );
-            fs::write(&failure_path, failure_text).map_err(|write_error| {
-                ExplorerError::scenario(format!(
-                    "failed to write failure artifact {}: {write_error}",
-                    failure_path.display()
-                ))
-            })?;
+        let Some(scenario) = current.as_mut() else {
+            return Err(ExplorerError::spec(format!(
+                "unexpected content before first scenario: {line}"
+            )));
+        };
+
+        if line == "### Expectation" || line == "### Expectations" {
+            continue;
         }
 
-        let stop_on_failure = fail_fast && !scenario_result.passed;
-        results.push(scenario_result);
-        if stop_on_failure {
-            break;
+        if is_openspec_expectation_line(line) {
+            continue;
+        }
+
+        let heading = match line {
+            "### Setup" => Some(Block::Setup),
+            "### Steps" => Some(Block::Steps),
+            "### Teardown" => Some(Block::Teardown),
+            _ => None,
+        };
+        if let Some(heading) = heading {
+            if is_v1 {
+                return Err(ExplorerError::spec(format!(
+                    "'{line}' requires schema_version v2"
+                )));
+            }
+            block = heading;
+            continue;
+        }
+
+        let step = parse_step(line)?;
+        if is_v1 && !step.is_v1() {
+            return Err(ExplorerError::spec(format!(
+                "step requires schema_version v2: {line}"
+            )));
+        }
+        match block {
+            Block::Setup => scenario.setup.push(step),
+            Block::Steps => scenario.steps.push(step),
+            Block::Teardown => scenario.teardown.push(step),
         }
     }
 
-    let passed = results.iter().filter(|result| result.passed).count();
-    let failed = results.len().saturating_sub(passed);
+    flush_current(&mut scenarios, &mut current)?;
 
-    Ok(VerifyReport {
-        spec_path: spec_path.display().to_string(),
-        out_dir: out_dir.display().to_string(),
-        total_scenarios: results.len(),
-        passed_scenarios: passed,
-        failed_scenarios: failed,
-        results,
+    if scenarios.is_empty() {
+        return Err(ExplorerError::spec("spec contains no scenarios"));
+    }
+
+    Ok(Spec {
+        frontmatter,
+        scenarios,
     })
 }
 
-fn slugify(value: &str) -> String {
-    let mut slug = String::new();
-    let mut previous_dash = false;
+fn parse_spec_file(path: &Path) -> Result<Spec, ExplorerError> {
+    let text = fs::read_to_string(path).map_err(|error| {
+        ExplorerError::spec(format!(
+            "failed to read spec file {}: {error}",
+            path.display()
+        ))
+    })?;
+    parse_spec_text(&text)
+}
 
-    for ch in value.trim().chars() {
-        if ch.is_ascii_alphanumeric() {
-            slug.push(ch.to_ascii_lowercase());
-            previous_dash = false;
-            continue;
-        }
+fn scalar_to_markdown(value: &Value) -> Result<String, ExplorerError> {
+    match value {
+        Value::String(text) => serde_json::to_string(text).map_err(|error| {
+            ExplorerError::spec(format!("failed to render string scalar: {error}"))
+        }),
+        Value::Null => Ok("null".to_string()),
+        Value::Bool(boolean) => Ok(if *boolean {
+            "true".to_string()
+        } else {
+            "false".to_string()
+        }),
+        Value::Number(number) => Ok(number.to_string()),
+        Value::Array(_) | Value::Object(_) => Ok(value.to_string()),
+    }
+}
 
-        if !previous_dash {
-            slug.push('-');
-            previous_dash = true;
+fn quote_for_markdown(value: &str, context: &str) -> Result<String, ExplorerError> {
+    serde_json::to_string(value)
+        .map_err(|error| ExplorerError::spec(format!("failed to render {context}: {error}")))
+}
+
+fn render_expectation_block(steps: &[&Step]) -> Result<Vec<String>, ExplorerError> {
+    let mut action_summary = Vec::new();
+    let mut expected_texts = Vec::new();
+    let mut other_checks = Vec::new();
+
+    for step in steps {
+        match step {
+            Step::Expect(value) => expected_texts.push(quote_for_markdown(value, "expectation")?),
+            Step::Press(value) => {
+                action_summary.push(format!("press {}", quote_for_markdown(value, "press")?))
+            }
+            Step::Type(value) => {
+                action_summary.push(format!("type {}", quote_for_markdown(value, "type")?))
+            }
+            Step::WaitStable => action_summary.push("wait_stable".to_string()),
+            Step::Paste(value) => {
+                action_summary.push(format!("paste {}", quote_for_markdown(value, "paste")?))
+            }
+            Step::Signal(name) => action_summary.push(format!("signal {name}")),
+            Step::Resize { cols, rows } => action_summary.push(format!("resize {cols}x{rows}")),
+            Step::Sleep(ms) => action_summary.push(format!("sleep {ms}ms")),
+            Step::Screenshot(name) => action_summary.push(format!(
+                "screenshot {}",
+                quote_for_markdown(name, "screenshot")?
+            )),
+            Step::WaitFor { text, .. } => other_checks.push(format!(
+                "wait_for {}",
+                quote_for_markdown(text, "wait_for")?
+            )),
+            Step::ExpectAbsent(value) => other_checks.push(format!(
+                "expect_absent {}",
+                quote_for_markdown(value, "expect_absent")?
+            )),
+            Step::ExpectRegex(pattern) => other_checks.push(format!(
+                "expect_regex {}",
+                quote_for_markdown(pattern, "expect_regex")?
+            )),
+            Step::ExpectCursor { row, col, .. } => {
+                other_checks.push(format!("expect_cursor {row},{col}"))
+            }
+            Step::ExpectExit(code) => other_checks.push(format!("expect_exit {code}")),
         }
     }
 
-    let slug = slug.trim_matches('-').to_string();
-    if slug.is_empty() {
-        "scenario".to_string()
+    let when_clause = if action_summary.is_empty() {
+        "the scenario starts from a fresh session".to_string()
     } else {
-        slug
+        format!("the operator replays: {}", action_summary.join(", "))
+    };
+
+    let then_clause = if !expected_texts.is_empty() {
+        format!("the screen contains {}", expected_texts.join(", "))
+    } else if !other_checks.is_empty() {
+        "the terminal satisfies the machine checks below".to_string()
+    } else {
+        "the scenario reaches a stable terminal state".to_string()
+    };
+
+    let checks = expected_texts
+        .iter()
+        .map(|value| format!("expect {value}"))
+        .chain(other_checks)
+        .collect::<Vec<_>>();
+    let should_clause = if checks.is_empty() {
+        "run the machine steps below without additional text assertions".to_string()
+    } else {
+        format!("execute machine checks: {}", checks.join(", "))
+    };
+
+    Ok(vec![
+        "### Expectation".to_string(),
+        format!("- **WHEN** {when_clause}"),
+        format!("- **THEN** {then_clause}"),
+        format!("- **SHOULD** {should_clause}"),
+    ])
+}
+
+fn render_step(step: &Step) -> Result<String, ExplorerError> {
+    let quoted = |key: &str, value: &str| -> Result<String, ExplorerError> {
+        Ok(format!(
+            "- {key}: {}",
+            quote_for_markdown(value, &format!("{key} step"))?
+        ))
+    };
+    match step {
+        Step::WaitStable => Ok("- wait_stable: true".to_string()),
+        Step::Expect(value) => quoted("expect", value),
+        Step::Press(value) => quoted("press", value),
+        Step::Type(value) => quoted("type", value),
+        Step::Paste(value) => quoted("paste", value),
+        Step::ExpectAbsent(value) => quoted("expect_absent", value),
+        Step::ExpectRegex(pattern) => quoted("expect_regex", pattern),
+        Step::Signal(name) => quoted("signal", name),
+        Step::Screenshot(name) => quoted("screenshot", name),
+        Step::WaitFor {
+            text,
+            timeout_ms: None,
+        } => quoted("wait_for", text),
+        Step::WaitFor {
+            text,
+            timeout_ms: Some(timeout_ms),
+        } => Ok(format!(
+            "- wait_for: {}",
+            serde_json::json!({ "text": text, "timeout_ms": timeout_ms })
+        )),
+        Step::Resize { cols, rows } => Ok(format!(
+            "- resize: {}",
+            serde_json::json!({ "cols": cols, "rows": rows })
+        )),
+        Step::Sleep(ms) => Ok(format!("- sleep: {ms}")),
+        Step::ExpectCursor { row, col, visible } => {
+            let mut args = serde_json::json!({ "row": row, "col": col });
+            if let Some(visible) = visible {
+                args["visible"] = Value::Bool(*visible);
+            }
+            Ok(format!("- expect_cursor: {args}"))
+        }
+        Step::ExpectExit(code) => Ok(format!("- expect_exit: {code}")),
+    }
+}
+
+fn render_markdown(spec: &Spec) -> Result<String, ExplorerError> {
+    let mut lines = vec!["---".to_string()];
+
+    for key in [
+        "schema_version",
+        "command",
+        "cwd",
+        "cols",
+        "rows",
+        "default_timeout_ms",
+        "generated_at",
+        "generator",
+        "normalize",
+    ] {
+        if let Some(value) = spec.frontmatter.get(key) {
+            lines.push(format!("{key}: {}", scalar_to_markdown(value)?));
+        }
+    }
+
+    lines.push("---".to_string());
+    lines.push(String::new());
+
+    for scenario in &spec.scenarios {
+        lines.push(format!("## Scenario: {}", scenario.name));
+        let all_steps = scenario
+            .setup
+            .iter()
+            .chain(&scenario.steps)
+            .chain(&scenario.teardown)
+            .collect::<Vec<_>>();
+        lines.extend(render_expectation_block(&all_steps)?);
+        lines.push(String::new());
+        if scenario.setup.is_empty() && scenario.teardown.is_empty() {
+            for step in &scenario.steps {
+                lines.push(render_step(step)?);
+            }
+        } else {
+            for (heading, steps) in [
+                ("### Setup", &scenario.setup),
+                ("### Steps", &scenario.steps),
+                ("### Teardown", &scenario.teardown),
+            ] {
+                if steps.is_empty() {
+                    continue;
+                }
+                lines.push(heading.to_string());
+                for step in steps {
+                    lines.push(render_step(step)?);
+                }
+            }
+        }
+        lines.push(String::new());
+    }
+
+    Ok(format!("{}\n", lines.join("\n").trim_end()))
+}
+
+fn scenario_name(path: &[String], index: usize) -> String {
+    if path.is_empty() {
+        return format!("Path {index}: root");
+    }
+    let compact = path
+        .iter()
+        .take(4)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(" -> ");
+    format!("Path {index}: {compact}")
+}
+
+/// Prefix marking a discovery action that types text instead of pressing a key.
+const TYPE_ACTION_PREFIX: &str = "type:";
+
+fn steps_for_action(action: &str) -> Vec<Step> {
+    let input = match action.strip_prefix(TYPE_ACTION_PREFIX) {
+        Some(text) => Step::Type(text.to_string()),
+        None => Step::Press(action.to_string()),
+    };
+    vec![input, Step::WaitStable]
+}
+
+fn apply_action<R: Runner>(runner: &mut R, id: &str, action: &str) -> Result<(), ExplorerError> {
+    match action.strip_prefix(TYPE_ACTION_PREFIX) {
+        Some(text) => runner.type_text(id, text),
+        None => runner.press(id, action),
     }
 }
 
-fn write_discover_report(
-    out_dir: &Path,
-    report: &DiscoverReport,
-) -> Result<PathBuf, ExplorerError> {
-    let path = out_dir.join("discover-report.json");
-    let mut serialized = serde_json::to_string_pretty(report).map_err(|error| {
-        ExplorerError::scenario(format!("failed to serialize discover report: {error}"))
-    })?;
-    serialized.push('\n');
+/// Queued discovery paths, popped in the order `strategy` asks for.
+struct Frontier {
+    strategy: Strategy,
+    entries: VecDeque<(Vec<String>, f64)>,
+    rng: StdRng,
+}
+
+impl Frontier {
+    fn new(strategy: Strategy, seed: u64) -> Self {
+        Self {
+            strategy,
+            entries: VecDeque::from([(Vec::new(), 1.0)]),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Queues the children of one state; `novelty` is that state's score.
+    fn push_children(&mut self, children: Vec<Vec<String>>, novelty: f64) {
+        if self.strategy == Strategy::Dfs {
+            // Popped from the back, so the first action is explored first.
+            self.entries
+                .extend(children.into_iter().rev().map(|path| (path, novelty)));
+        } else {
+            self.entries
+                .extend(children.into_iter().map(|path| (path, novelty)));
+        }
+    }
+
+    fn pop(&mut self) -> Option<Vec<String>> {
+        let index = match self.strategy {
+            Strategy::Bfs => 0,
+            Strategy::Dfs => self.entries.len().checked_sub(1)?,
+            Strategy::Random if self.entries.is_empty() => return None,
+            Strategy::Random => self.rng.random_range(0..self.entries.len()),
+            // Highest score wins; ties go to the oldest entry.
+            Strategy::Novelty => self
+                .entries
+                .iter()
+                .enumerate()
+                .fold(
+                    None,
+                    |best: Option<(usize, f64)>, (index, (_, score))| match best {
+                        Some((_, best_score)) if best_score >= *score => best,
+                        _ => Some((index, *score)),
+                    },
+                )
+                .map(|(index, _)| index)?,
+        };
+        self.entries.remove(index).map(|(path, _)| path)
+    }
+}
+
+/// Reads candidate actions off a screen.
+struct ActionDetector {
+    hotkey: Regex,
+    function_key: Regex,
+    caret: Regex,
+    input: Regex,
+    risky_label: Regex,
+}
+
+impl ActionDetector {
+    fn new() -> Result<Self, ExplorerError> {
+        let compile = |pattern: &str| {
+            Regex::new(pattern).map_err(|error| {
+                ExplorerError::spec(format!("invalid action pattern {pattern}: {error}"))
+            })
+        };
+        Ok(Self {
+            // `[q]uit`, `(o)pen`
+            hotkey: compile(r"[\[(]([A-Za-z0-9])[\])]([A-Za-z]+)")?,
+            // `F1 Help`, `F5:Refresh`
+            function_key: compile(r"\b(F(?:1[0-2]|[1-9]))\b\s*[:=-]?\s*([A-Za-z]+)")?,
+            // `^X Exit`
+            caret: compile(r"\^([A-Z])\s+([A-Za-z]+)")?,
+            // `Name: ____`, `Search: [   ]`, or a prompt ending in a colon
+            input: compile(
+                r"(?i)\b(email|password|number|age|count|amount|port|path|file|name|search|query|filter|find|input|value)\b[^:\n]{0,20}:\s*(?:_{2,}|\[\s*\]|$)",
+            )?,
+            risky_label: compile(r"(?i)^(quit|exit|close|kill|delete|remove|abort)")?,
+        })
+    }
+
+    /// Returns `(action, risky)` pairs in screen order, without duplicates.
+    fn detect(&self, screen: &str) -> Vec<(String, bool)> {
+        let mut found: Vec<(String, bool)> = Vec::new();
+        let mut push = |action: String, label: &str| {
+            if !found.iter().any(|(existing, _)| *existing == action) {
+                let risky = self.risky_label.is_match(label);
+                found.push((action, risky));
+            }
+        };
+        for line in screen.lines() {
+            for captures in self.hotkey.captures_iter(line) {
+                let label = format!("{}{}", &captures[1], &captures[2]);
+                push(captures[1].to_string(), &label);
+            }
+            for captures in self.function_key.captures_iter(line) {
+                push(captures[1].to_string(), &captures[2]);
+            }
+            for captures in self.caret.captures_iter(line) {
+                push(format!("Ctrl+{}", &captures[1]), &captures[2]);
+            }
+            if let Some(captures) = self.input.captures(line) {
+                let sample = match captures[1].to_ascii_lowercase().as_str() {
+                    "email" => "user@example.com",
+                    "password" => "secret123",
+                    "number" | "age" | "count" | "amount" | "port" => "42",
+                    "path" | "file" => "/tmp",
+                    _ => "hello",
+                };
+                push(format!("{TYPE_ACTION_PREFIX}{sample}"), "");
+            }
+        }
+        found
+    }
+}
+
+struct PathProbe {
+    state_hash: String,
+    /// ANSI-stripped screen, for screen-derived actions.
+    screen: String,
+    normalized_screen: String,
+    anchor: Option<String>,
+    excerpt: Vec<String>,
+    error: Option<String>,
+}
+
+/// Replays `path` in a fresh session and hashes the screen it lands on. Only an
+/// unavailable daemon is an error; anything else is recorded on the probe.
+fn probe_path<R: Runner>(
+    runner: &mut R,
+    config: &DiscoverConfig,
+    normalizer: &ScreenNormalizer,
+    path: &[String],
+) -> Result<PathProbe, ExplorerError> {
+    let mut session_id: Option<String> = None;
+    let mut probe = PathProbe {
+        state_hash: String::new(),
+        screen: String::new(),
+        normalized_screen: String::new(),
+        anchor: None,
+        excerpt: Vec::new(),
+        error: None,
+    };
+
+    let execution = (|| -> Result<(), ExplorerError> {
+        let id = runner.spawn(
+            &config.command,
+            config.cwd.as_deref(),
+            config.cols,
+            config.rows,
+        )?;
+        session_id = Some(id.clone());
+
+        for action in path {
+            apply_action(runner, &id, action)?;
+            runner.wait_stable(&id, config.default_timeout_ms.min(1000))?;
+        }
+
+        runner.wait_stable(&id, config.default_timeout_ms.min(1000))?;
+        let (screenshot, cursor) = runner.screenshot(&id)?;
+        probe.normalized_screen = normalizer.normalize(&screenshot);
+        probe.state_hash = compute_state_hash(
+            &probe.normalized_screen,
+            &cursor,
+            normalizer.ignore_cursor,
+            config.cols,
+            config.rows,
+        );
+        probe.anchor = pick_anchor(&screenshot);
+        probe.excerpt = screen_excerpt(&screenshot);
+        probe.screen = strip_ansi(&screenshot);
+
+        Ok(())
+    })();
+
+    if let Some(id) = session_id.as_ref() {
+        let _ = runner.kill(id);
+    }
+
+    if let Err(error) = execution {
+        if error.kind == ExplorerErrorKind::Unavailable {
+            return Err(error);
+        }
+        probe.error = Some(error.to_string());
+    }
+
+    Ok(probe)
+}
+
+fn discover_with_runner<R: Runner + Clone + Send>(
+    config: &DiscoverConfig,
+    runner: &mut R,
+) -> Result<(DiscoverReport, Spec, Vec<TraceRecord>), ExplorerError> {
+    let mut actions = DEFAULT_SAFE_ACTIONS
+        .iter()
+        .map(|item| (*item).to_string())
+        .collect::<Vec<_>>();
+
+    let mut blocked_risky_actions = if config.allow_risky {
+        actions.extend(
+            DEFAULT_RISKY_ACTIONS
+                .iter()
+                .map(|item| (*item).to_string())
+                .collect::<Vec<_>>(),
+        );
+        Vec::new()
+    } else {
+        DEFAULT_RISKY_ACTIONS
+            .iter()
+            .map(|item| (*item).to_string())
+            .collect::<Vec<_>>()
+    };
+
+    let timeout = Duration::from_secs(config.time_budget_sec);
+    let start = Instant::now();
+
+    let mut frontier = Frontier::new(config.strategy, config.seed);
+    let detector = ActionDetector::new()?;
+    let mut screen_actions: Vec<String> = Vec::new();
+    let mut anchors = std::collections::BTreeSet::new();
+    let mut max_depth_reached = 0usize;
+
+    let mut visited_hashes = HashSet::new();
+    let mut traces = Vec::new();
+    let mut scenarios = Vec::new();
+    let mut states_explored = 0usize;
+
+    let normalizer = config.normalizers.compile()?;
+    // Screens of visited states, for `similarity` matching and novelty scores.
+    let keep_screens = normalizer.similarity.is_some() || config.strategy == Strategy::Novelty;
+    let mut visited_screens: Vec<(String, String)> = Vec::new();
+    let mut graph = StateGraph::default();
+    let mut node_by_path: HashMap<Vec<String>, String> = HashMap::new();
+
+    let stop = AtomicBool::new(false);
+    // Probe batches of `jobs` paths and fold the results in pop order. For BFS
+    // the outcome matches a sequential run; other strategies pick each batch
+    // before its members' children are queued.
+    while !frontier.is_empty() {
+        if states_explored >= config.max_states {
+            break;
+        }
+        if start.elapsed() >= timeout {
+            break;
+        }
+
+        let batch_size = config
+            .jobs
+            .min(config.max_states - states_explored)
+            .min(frontier.len());
+        let batch = (0..batch_size)
+            .filter_map(|_| frontier.pop())
+            .collect::<Vec<_>>();
+        states_explored += batch.len();
+
+        let probes = run_parallel(runner, config.jobs, &batch, &stop, |runner, path| {
+            let probe = probe_path(runner, config, &normalizer, path);
+            if probe.is_err() {
+                stop.store(true, Ordering::SeqCst);
+            }
+            probe
+        });
+
+        for (path, probe) in batch.into_iter().zip(probes) {
+            let Some(probe) = probe else {
+                continue;
+            };
+            let PathProbe {
+                mut state_hash,
+                screen,
+                normalized_screen,
+                anchor,
+                excerpt,
+                error: error_message,
+            } = probe?;
+
+            if error_message.is_none()
+                && let Some(threshold) = normalizer.similarity
+                && !visited_hashes.contains(&state_hash)
+                && let Some((hash, _)) = visited_screens.iter().find(|(_, screen)| {
+                    ScreenNormalizer::similarity(screen, &normalized_screen) >= threshold
+                })
+            {
+                state_hash = hash.clone();
+            }
+
+            let node_id = match &error_message {
+                Some(message) => graph.error_node(message, &path),
+                None => graph.state_node(&state_hash, anchor.as_deref(), excerpt, &path),
+            };
+            if let Some((action, parent)) = path.split_last()
+                && let Some(from) = node_by_path.get(parent)
+            {
+                let from = from.clone();
+                graph.mark_expanded(&from);
+                graph.add_edge(&from, &node_id, action);
+            }
+            node_by_path.insert(path.clone(), node_id.clone());
+
+            traces.push(TraceRecord {
+                timestamp: now_rfc3339(),
+                path: path.clone(),
+                depth: path.len(),
+                state_hash: state_hash.clone(),
+                anchor: anchor.clone(),
+                normalized_screen: normalized_screen.clone(),
+                error: error_message.clone(),
+            });
+
+            if error_message.is_some() {
+                continue;
+            }
+
+            if visited_hashes.contains(&state_hash) {
+                continue;
+            }
+            visited_hashes.insert(state_hash.clone());
+            max_depth_reached = max_depth_reached.max(path.len());
+            if let Some(anchor) = &anchor {
+                anchors.insert(anchor.clone());
+            }
+
+            // Distance to the closest screen seen so far, in (0, 1].
+            let novelty = visited_screens
+                .iter()
+                .map(|(_, seen)| 1.0 - ScreenNormalizer::similarity(seen, &normalized_screen))
+                .fold(1.0, f64::min);
+            if keep_screens {
+                visited_screens.push((state_hash, normalized_screen));
+            }
+
+            if !path.is_empty()
+                && let Some(anchor) = anchor
+            {
+                let mut steps = Vec::new();
+                for action in &path {
+                    steps.extend(steps_for_action(action));
+                }
+                steps.push(Step::Expect(anchor));
+                scenarios.push(Scenario {
+                    name: scenario_name(&path, scenarios.len() + 1),
+                    steps,
+                    ..Scenario::default()
+                });
+            }
+
+            if path.len() >= config.max_depth {
+                continue;
+            }
+
+            // Screen actions first, then the default set; `branch_limit` caps
+            // the combined list.
+            let mut candidates: Vec<String> = Vec::new();
+            if config.screen_actions {
+                for (action, risky) in detector.detect(&screen) {
+                    let risky = risky || DEFAULT_RISKY_ACTIONS.contains(&action.as_str());
+                    if risky && !config.allow_risky {
+                        if !blocked_risky_actions.contains(&action) {
+                            blocked_risky_actions.push(action);
+                        }
+                        continue;
+                    }
+                    candidates.push(action);
+                }
+            }
+            for action in &actions {
+                if !candidates.contains(action) {
+                    candidates.push(action.clone());
+                }
+            }
+            candidates.truncate(config.branch_limit);
+            for action in &candidates {
+                if !actions.contains(action) && !screen_actions.contains(action) {
+                    screen_actions.push(action.clone());
+                }
+            }
+            let children = candidates
+                .into_iter()
+                .map(|action| {
+                    let mut next = path.clone();
+                    next.push(action);
+                    next
+                })
+                .collect();
+            frontier.push_children(children, novelty);
+        }
+    }
+
+    let generated_at = now_rfc3339();
+    let mut spec = Spec {
+        frontmatter: BTreeMap::from([
+            (
+                "schema_version".to_string(),
+                Value::String(SCHEMA_V2.to_string()),
+            ),
+            ("command".to_string(), Value::String(config.command.clone())),
+            (
+                "cwd".to_string(),
+                config
+                    .cwd
+                    .as_ref()
+                    .map(|cwd| Value::String(cwd.clone()))
+                    .unwrap_or(Value::Null),
+            ),
+            (
+                "cols".to_string(),
+                Value::Number(i64::from(config.cols).into()),
+            ),
+            (
+                "rows".to_string(),
+                Value::Number(i64::from(config.rows).into()),
+            ),
+            (
+                "default_timeout_ms".to_string(),
+                Value::Number((config.default_timeout_ms as i64).into()),
+            ),
+            ("generated_at".to_string(), Value::String(generated_at)),
+            (
+                "generator".to_string(),
+                Value::String("tui-explorer/1".to_string()),
+            ),
+        ]),
+        scenarios,
+    };
+    if !config.normalizers.is_empty() {
+        let normalize = serde_json::to_value(&config.normalizers).map_err(|error| {
+            ExplorerError::scenario(format!("failed to serialize normalizers: {error}"))
+        })?;
+        spec.frontmatter.insert("normalize".to_string(), normalize);
+    }
+
+    let acceptance_path = config.out_dir.join("acceptance.md");
+    let trace_path = config.out_dir.join("trace.jsonl");
+
+    let markdown = render_markdown(&spec)?;
+    fs::write(&acceptance_path, markdown).map_err(|error| {
+        ExplorerError::scenario(format!(
+            "failed to write acceptance spec {}: {error}",
+            acceptance_path.display()
+        ))
+    })?;
+
+    let mut trace_lines = String::new();
+    for trace in &traces {
+        let line = serde_json::to_string(trace).map_err(|error| {
+            ExplorerError::scenario(format!("failed to serialize trace: {error}"))
+        })?;
+        trace_lines.push_str(&line);
+        trace_lines.push('\n');
+    }
+
+    fs::write(&trace_path, trace_lines).map_err(|error| {
+        ExplorerError::scenario(format!(
+            "failed to write trace file {}: {error}",
+            trace_path.display()
+        ))
+    })?;
+
+    let state_graph = write_state_graph(&config.out_dir, &graph)?;
+
+    let report = DiscoverReport {
+        command: config.command.clone(),
+        cwd: config.cwd.clone(),
+        states_explored,
+        scenarios_generated: spec.scenarios.len(),
+        unique_hashes: visited_hashes.len(),
+        out_dir: config.out_dir.display().to_string(),
+        acceptance_spec: acceptance_path.display().to_string(),
+        trace_file: trace_path.display().to_string(),
+        state_graph,
+        coverage: Coverage {
+            strategy: config.strategy,
+            seed: (config.strategy == Strategy::Random).then_some(config.seed),
+            distinct_screens: visited_hashes.len(),
+            distinct_anchors: anchors.len(),
+            anchors: anchors.into_iter().collect(),
+            error_paths: traces.iter().filter(|trace| trace.error.is_some()).count(),
+            max_depth_reached,
+            frontier_remaining: frontier.len(),
+            screen_actions,
+        },
+        risky_actions_blocked: blocked_risky_actions,
+    };
+
+    Ok((report, spec, traces))
+}
+
+fn value_string(frontmatter: &BTreeMap<String, Value>, key: &str) -> Result<String, ExplorerError> {
+    let Some(value) = frontmatter.get(key) else {
+        return Err(ExplorerError::spec(format!(
+            "missing frontmatter key: {key}"
+        )));
+    };
+    let Some(parsed) = value.as_str() else {
+        return Err(ExplorerError::spec(format!(
+            "frontmatter '{key}' must be a string"
+        )));
+    };
+    Ok(parsed.to_string())
+}
+
+fn value_optional_string(
+    frontmatter: &BTreeMap<String, Value>,
+    key: &str,
+) -> Result<Option<String>, ExplorerError> {
+    let Some(value) = frontmatter.get(key) else {
+        return Ok(None);
+    };
+    if value.is_null() {
+        return Ok(None);
+    }
+    let Some(parsed) = value.as_str() else {
+        return Err(ExplorerError::spec(format!(
+            "frontmatter '{key}' must be a string"
+        )));
+    };
+    Ok(Some(parsed.to_string()))
+}
+
+fn value_u64(frontmatter: &BTreeMap<String, Value>, key: &str) -> Result<u64, ExplorerError> {
+    let Some(value) = frontmatter.get(key) else {
+        return Err(ExplorerError::spec(format!(
+            "missing frontmatter key: {key}"
+        )));
+    };
+    let Some(parsed) = value.as_u64() else {
+        return Err(ExplorerError::spec(format!(
+            "frontmatter '{key}' must be a positive integer"
+        )));
+    };
+    if parsed == 0 {
+        return Err(ExplorerError::spec(format!(
+            "frontmatter '{key}' must be greater than zero"
+        )));
+    }
+    Ok(parsed)
+}
+
+struct StepContext<'a> {
+    timeout_ms: u64,
+    out_dir: &'a Path,
+    scenario: &'a str,
+    normalizer: &'a ScreenNormalizer,
+}
+
+fn pause(duration: Duration) {
+    let deadline = Instant::now() + duration;
+    loop {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return;
+        }
+        std::thread::park_timeout(remaining);
+    }
+}
+
+fn run_step<R: Runner>(
+    runner: &mut R,
+    id: &str,
+    step: &Step,
+    step_number: usize,
+    context: &StepContext<'_>,
+) -> Result<(), ExplorerError> {
+    let timeout_ms = context.timeout_ms;
+    // Input steps settle the screen before the next step; checks do not.
+    let settle = match step {
+        Step::Press(key) => {
+            runner.press(id, key)?;
+            true
+        }
+        Step::Type(text) => {
+            runner.type_text(id, text)?;
+            true
+        }
+        Step::Paste(text) => {
+            runner.paste(id, text)?;
+            true
+        }
+        Step::Signal(name) => {
+            runner.signal(id, name)?;
+            true
+        }
+        Step::Resize { cols, rows } => {
+            runner.resize(id, *cols, *rows)?;
+            true
+        }
+        Step::WaitStable => {
+            runner.wait_stable(id, timeout_ms)?;
+            false
+        }
+        Step::Expect(text) => {
+            if !runner.wait_for_text(id, text, timeout_ms)? {
+                return Err(ExplorerError::scenario(format!(
+                    "expectation not met: {text}"
+                )));
+            }
+            true
+        }
+        Step::WaitFor {
+            text,
+            timeout_ms: step_timeout,
+        } => {
+            let limit = step_timeout.unwrap_or(timeout_ms);
+            if !runner.wait_for_text(id, text, limit)? {
+                return Err(ExplorerError::scenario(format!(
+                    "timed out after {limit}ms waiting for: {text}"
+                )));
+            }
+            false
+        }
+        Step::ExpectAbsent(text) => {
+            if !runner.wait_for_gone(id, text, timeout_ms)? {
+                return Err(ExplorerError::scenario(format!(
+                    "text still present: {text}"
+                )));
+            }
+            false
+        }
+        Step::ExpectRegex(pattern) => {
+            let regex = Regex::new(pattern).map_err(|error| {
+                ExplorerError::spec(format!("invalid regex {pattern}: {error}"))
+            })?;
+            let (screen, _) = runner.screenshot(id)?;
+            if !regex.is_match(&strip_ansi(&screen)) {
+                return Err(ExplorerError::scenario(format!(
+                    "no match for regex: {pattern}"
+                )));
+            }
+            false
+        }
+        Step::ExpectCursor { row, col, visible } => {
+            let (_, cursor) = runner.screenshot(id)?;
+            if cursor.row != *row
+                || cursor.col != *col
+                || visible.is_some_and(|visible| visible != cursor.visible)
+            {
+                return Err(ExplorerError::scenario(format!(
+                    "cursor at {},{} (visible: {}), expected {row},{col}",
+                    cursor.row, cursor.col, cursor.visible
+                )));
+            }
+            false
+        }
+        Step::ExpectExit(code) => {
+            match runner.exit_code(id, timeout_ms)? {
+                Some(actual) if actual == *code => {}
+                Some(actual) => {
+                    return Err(ExplorerError::scenario(format!(
+                        "expected exit code {code}, got {actual}"
+                    )));
+                }
+                None => {
+                    return Err(ExplorerError::scenario(format!(
+                        "process still running after {timeout_ms}ms, expected exit code {code}"
+                    )));
+                }
+            }
+            false
+        }
+        Step::Sleep(ms) => {
+            pause(Duration::from_millis(*ms));
+            false
+        }
+        Step::Screenshot(name) => {
+            let (screen, _) = runner.screenshot(id)?;
+            let screens_dir = context.out_dir.join("screens");
+            let stem = format!("{}-{}", slugify(context.scenario), slugify(name));
+            let mut files = vec![(screens_dir.join(format!("{stem}.txt")), strip_ansi(&screen))];
+            if context.normalizer.rewrites_screen() {
+                files.push((
+                    screens_dir.join(format!("{stem}.normalized.txt")),
+                    context.normalizer.normalize(&screen),
+                ));
+            }
+            fs::create_dir_all(&screens_dir).map_err(|error| {
+                ExplorerError::scenario(format!(
+                    "failed to create {}: {error}",
+                    screens_dir.display()
+                ))
+            })?;
+            for (path, text) in files {
+                fs::write(&path, text).map_err(|error| {
+                    ExplorerError::scenario(format!(
+                        "failed to write screenshot {}: {error}",
+                        path.display()
+                    ))
+                })?;
+            }
+            false
+        }
+    };
+
+    if settle {
+        runner.wait_stable(id, timeout_ms).map_err(|error| {
+            ExplorerError::scenario(format!(
+                "post-step stabilization failed at step {step_number}: {error}"
+            ))
+        })?;
+    }
+    Ok(())
+}
+
+struct ScenarioLaunch<'a> {
+    timeout_ms: u64,
+    command: String,
+    cwd: Option<String>,
+    cols: u16,
+    rows: u16,
+    out_dir: &'a Path,
+    normalizer: ScreenNormalizer,
+}
+
+/// Replays one scenario in a fresh session. Only an unavailable daemon is an
+/// error; step failures are reported on the result, along with the failing
+/// screen when `record_failure` is set.
+fn verify_scenario<R: Runner>(
+    runner: &mut R,
+    scenario: &Scenario,
+    launch: &ScenarioLaunch<'_>,
+    record_failure: bool,
+) -> Result<(VerifyScenarioResult, Option<String>), ExplorerError> {
+    let ScenarioLaunch {
+        timeout_ms,
+        ref command,
+        ref cwd,
+        cols,
+        rows,
+        out_dir,
+        ref normalizer,
+    } = *launch;
+
+    let mut scenario_result = VerifyScenarioResult {
+        name: scenario.name.clone(),
+        passed: true,
+        failed_step: None,
+        message: None,
+        duration_ms: 0,
+        steps: Vec::new(),
+        screenshot: None,
+        shrink: None,
+    };
+    let mut session_id: Option<String> = None;
+    let mut failing_step_index: Option<usize> = None;
+    let scenario_started = Instant::now();
+    let context = StepContext {
+        timeout_ms,
+        out_dir,
+        scenario: &scenario.name,
+        normalizer,
+    };
+    // Setup, steps and teardown share one step numbering.
+    let main_steps = scenario
+        .setup
+        .iter()
+        .chain(&scenario.steps)
+        .collect::<Vec<_>>();
+
+    let execution = (|| -> Result<(), ExplorerError> {
+        let id = runner.spawn(command, cwd.as_deref(), cols, rows)?;
+        session_id = Some(id.clone());
+
+        for (index, step) in main_steps.iter().enumerate() {
+            failing_step_index = Some(index + 1);
+            let started = Instant::now();
+            let outcome = run_step(runner, &id, step, index + 1, &context);
+            scenario_result.steps.push(StepTiming {
+                index: index + 1,
+                step: step_label(step),
+                duration_ms: elapsed_ms(started),
+            });
+            outcome?;
+        }
+
+        Ok(())
+    })();
+
+    // Capture the failing screen before teardown changes it.
+    let failure_screen = match (&execution, session_id.as_ref()) {
+        (Err(error), Some(id))
+            if record_failure && error.kind != ExplorerErrorKind::Unavailable =>
+        {
+            runner
+                .screenshot(id)
+                .ok()
+                .map(|(screen, _)| strip_ansi(&screen))
+        }
+        _ => None,
+    };
+
+    let mut teardown_failure: Option<(usize, ExplorerError)> = None;
+    if let Some(id) = session_id.as_ref()
+        && !matches!(&execution, Err(error) if error.kind == ExplorerErrorKind::Unavailable)
+    {
+        for (offset, step) in scenario.teardown.iter().enumerate() {
+            let step_number = main_steps.len() + offset + 1;
+            let started = Instant::now();
+            let outcome = run_step(runner, id, step, step_number, &context);
+            scenario_result.steps.push(StepTiming {
+                index: step_number,
+                step: step_label(step),
+                duration_ms: elapsed_ms(started),
+            });
+            if let Err(error) = outcome {
+                teardown_failure = Some((step_number, error));
+                break;
+            }
+        }
+    }
+    let execution = match (execution, teardown_failure) {
+        (Ok(()), Some((step_number, error))) => {
+            failing_step_index = Some(step_number);
+            Err(error)
+        }
+        (execution, _) => execution,
+    };
+    scenario_result.duration_ms = elapsed_ms(scenario_started);
+
+    if let Some(id) = session_id.as_ref() {
+        let _ = runner.kill(id);
+    }
+
+    if let Err(error) = execution {
+        if error.kind == ExplorerErrorKind::Unavailable {
+            return Err(error);
+        }
+        scenario_result.passed = false;
+        let failed_step = failing_step_index.unwrap_or(0);
+        scenario_result.failed_step = Some(failed_step);
+        scenario_result.message = Some(error.to_string());
+    }
+
+    Ok((scenario_result, failure_screen))
+}
+
+/// Writes the failure note and, if captured, the failing screen of a scenario
+/// that made it into the report.
+fn write_failure_artifacts(
+    failure_dir: &Path,
+    scenario_result: &mut VerifyScenarioResult,
+    failure_screen: Option<String>,
+) -> Result<(), ExplorerError> {
+    let failed_step = scenario_result.failed_step.unwrap_or(0);
+    let slug = slugify(&scenario_result.name);
+    if let Some(screen) = failure_screen {
+        let name = format!("{slug}-step-{failed_step}.screen.txt");
+        let screen_path = failure_dir.join(&name);
+        fs::write(&screen_path, screen).map_err(|write_error| {
+            ExplorerError::scenario(format!(
+                "failed to write failure screenshot {}: {write_error}",
+                screen_path.display()
+            ))
+        })?;
+        scenario_result.screenshot = Some(format!("failures/{name}"));
+    }
+
+    let failure_path = failure_dir.join(format!("{slug}-step-{failed_step}.txt"));
+    let failure_text = format!(
+        "scenario: {}\nstep: {failed_step}\nerror: {}\n",
+        scenario_result.name,
+        scenario_result.message.clone().unwrap_or_default()
+    );
+    fs::write(&failure_path, failure_text).map_err(|write_error| {
+        ExplorerError::scenario(format!(
+            "failed to write failure artifact {}: {write_error}",
+            failure_path.display()
+        ))
+    })
+}
+
+fn verify_with_runner<R: Runner + Clone + Send>(
+    spec: &Spec,
+    spec_path: &Path,
+    runner: &mut R,
+    out_dir: &Path,
+    options: VerifyOptions<'_>,
+) -> Result<VerifyReport, ExplorerError> {
+    let VerifyOptions {
+        scenario: scenario_filter,
+        fail_fast,
+        jobs,
+        shrink,
+    } = options;
+    let target_scenarios = if let Some(name) = scenario_filter {
+        let filtered = spec
+            .scenarios
+            .iter()
+            .filter(|scenario| scenario.name == name)
+            .cloned()
+            .collect::<Vec<_>>();
+        if filtered.is_empty() {
+            return Err(ExplorerError::spec(format!(
+                "scenario '{name}' not found in spec"
+            )));
+        }
+        filtered
+    } else {
+        spec.scenarios.clone()
+    };
+
+    let failure_dir = out_dir.join("failures");
+    fs::create_dir_all(&failure_dir).map_err(|error| {
+        ExplorerError::scenario(format!(
+            "failed to create failure directory {}: {error}",
+            failure_dir.display()
+        ))
+    })?;
+
+    let launch = ScenarioLaunch {
+        timeout_ms: value_u64(&spec.frontmatter, "default_timeout_ms")?,
+        command: value_string(&spec.frontmatter, "command")?,
+        cwd: value_optional_string(&spec.frontmatter, "cwd")?,
+        cols: value_u64(&spec.frontmatter, "cols")? as u16,
+        rows: value_u64(&spec.frontmatter, "rows")? as u16,
+        out_dir,
+        normalizer: Normalizers::from_frontmatter(&spec.frontmatter)?.compile()?,
+    };
+
+    let stop = AtomicBool::new(false);
+    let outcomes = run_parallel(
+        runner,
+        jobs,
+        &target_scenarios,
+        &stop,
+        |runner, scenario| {
+            let outcome = verify_scenario(runner, scenario, &launch, true);
+            if outcome
+                .as_ref()
+                .map_or(true, |(result, _)| fail_fast && !result.passed)
+            {
+                stop.store(true, Ordering::SeqCst);
+            }
+            outcome
+        },
+    );
+
+    // Fold in spec order: with fail-fast, scenarios after the first failure are
+    // dropped even if a parallel worker already ran them, and leave no failure
+    // artifacts behind.
+    let mut results = Vec::new();
+    for outcome in outcomes.into_iter().flatten() {
+        let (mut scenario_result, failure_screen) = outcome?;
+        if !scenario_result.passed {
+            write_failure_artifacts(&failure_dir, &mut scenario_result, failure_screen)?;
+        }
+        let stop_on_failure = fail_fast && !scenario_result.passed;
+        results.push(scenario_result);
+        if stop_on_failure {
+            break;
+        }
+    }
+
+    let mut shrunk_spec = None;
+    if shrink {
+        let mut shrunk = Vec::new();
+        for result in results.iter_mut().filter(|result| !result.passed) {
+            let Some(scenario) = target_scenarios
+                .iter()
+                .find(|scenario| scenario.name == result.name)
+            else {
+                continue;
+            };
+            if let Some((summary, minimal)) = shrink_scenario(runner, scenario, result, &launch)? {
+                result.shrink = Some(summary);
+                shrunk.extend(minimal);
+            }
+        }
+        if !shrunk.is_empty() {
+            let spec = Spec {
+                frontmatter: spec.frontmatter.clone(),
+                scenarios: shrunk,
+            };
+            let path = out_dir.join("shrunk.md");
+            fs::write(&path, render_markdown(&spec)?).map_err(|error| {
+                ExplorerError::scenario(format!(
+                    "failed to write shrunk spec {}: {error}",
+                    path.display()
+                ))
+            })?;
+            shrunk_spec = Some(path.display().to_string());
+        }
+    }
+
+    let passed = results.iter().filter(|result| result.passed).count();
+    let failed = results.len().saturating_sub(passed);
+
+    Ok(VerifyReport {
+        spec_path: spec_path.display().to_string(),
+        out_dir: out_dir.display().to_string(),
+        total_scenarios: results.len(),
+        passed_scenarios: passed,
+        failed_scenarios: failed,
+        results,
+        shrunk_spec,
+    })
+}
+
+/// Upper bound on replays spent minimizing one scenario.
+const SHRINK_MAX_REPLAYS: usize = 100;
+
+/// What makes two failures "the same": the step that failed and its message,
+/// with step numbers masked since they shift as steps are removed.
+fn failure_signature(scenario: &Scenario, result: &VerifyScenarioResult) -> Option<(Step, String)> {
+    let index = result.failed_step?.checked_sub(1)?;
+    let step = scenario
+        .setup
+        .iter()
+        .chain(&scenario.steps)
+        .chain(&scenario.teardown)
+        .nth(index)?
+        .clone();
+    let message = result
+        .message
+        .as_deref()?
+        .split_whitespace()
+        .map(|word| {
+            if word.chars().all(|ch| ch.is_ascii_digit()) {
+                "N"
+            } else {
+                word
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    Some((step, message))
+}
+
+/// Delta-debugs the failing scenario's `steps` (setup and teardown are kept)
+/// down to a smallest list that still fails the same way. Returns `None` when
+/// the failure is in setup or teardown.
+fn shrink_scenario<R: Runner>(
+    runner: &mut R,
+    scenario: &Scenario,
+    result: &VerifyScenarioResult,
+    launch: &ScenarioLaunch<'_>,
+) -> Result<Option<(ShrinkSummary, Option<Scenario>)>, ExplorerError> {
+    let Some(signature) = failure_signature(scenario, result) else {
+        return Ok(None);
+    };
+    let Some(failed_at) = result
+        .failed_step
+        .and_then(|step| step.checked_sub(scenario.setup.len() + 1))
+        .filter(|index| *index < scenario.steps.len())
+    else {
+        return Ok(None);
+    };
+
+    let mut replays = 0usize;
+    let mut reproduces = |steps: &[Step]| -> Result<bool, ExplorerError> {
+        if replays >= SHRINK_MAX_REPLAYS {
+            return Ok(false);
+        }
+        replays += 1;
+        let candidate = Scenario {
+            steps: steps.to_vec(),
+            ..scenario.clone()
+        };
+        let (outcome, _) = verify_scenario(runner, &candidate, launch, false)?;
+        Ok(!outcome.passed && failure_signature(&candidate, &outcome).as_ref() == Some(&signature))
+    };
+
+    // Steps after the failing one never ran, so start from the prefix.
+    let prefix = scenario.steps[..=failed_at].to_vec();
+    if !reproduces(&prefix)? {
+        let summary = ShrinkSummary {
+            reproduced: false,
+            original_steps: scenario.steps.len(),
+            minimal_steps: scenario.steps.len(),
+            replays,
+            scenario: None,
+        };
+        return Ok(Some((summary, None)));
+    }
+    let minimal = ddmin(prefix, &mut reproduces)?;
+
+    let name = format!("{} (shrunk)", scenario.name);
+    let summary = ShrinkSummary {
+        reproduced: true,
+        original_steps: scenario.steps.len(),
+        minimal_steps: minimal.len(),
+        replays,
+        scenario: Some(name.clone()),
+    };
+    let shrunk = Scenario {
+        name,
+        steps: minimal,
+        ..scenario.clone()
+    };
+    Ok(Some((summary, Some(shrunk))))
+}
+
+/// Zeller's ddmin: returns a 1-minimal subsequence of `items` for which
+/// `reproduces` holds, assuming it holds for `items`.
+fn ddmin<T: Clone>(
+    items: Vec<T>,
+    reproduces: &mut impl FnMut(&[T]) -> Result<bool, ExplorerError>,
+) -> Result<Vec<T>, ExplorerError> {
+    let mut current = items;
+    let mut granularity = 2usize;
+    while current.len() >= 2 {
+        let chunk_len = current.len().div_ceil(granularity);
+        let chunks = current
+            .chunks(chunk_len)
+            .map(<[T]>::to_vec)
+            .collect::<Vec<_>>();
+
+        let mut reduced = None;
+        for chunk in &chunks {
+            if reproduces(chunk)? {
+                reduced = Some((chunk.clone(), 2));
+                break;
+            }
+        }
+        // With two chunks each complement is the other chunk, already tried.
+        if reduced.is_none() && chunks.len() > 2 {
+            for skip in 0..chunks.len() {
+                let complement = chunks
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| *index != skip)
+                    .flat_map(|(_, chunk)| chunk.iter().cloned())
+                    .collect::<Vec<_>>();
+                if reproduces(&complement)? {
+                    reduced = Some((complement, (granularity - 1).max(2)));
+                    break;
+                }
+            }
+        }
+
+        match reduced {
+            Some((next, next_granularity)) => {
+                current = next;
+                granularity = next_granularity;
+            }
+            None if granularity >= current.len() => break,
+            None => granularity = (granularity * 2).min(current.len()),
+        }
+    }
+    Ok(current)
+}
+
+fn is_script_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "yaml" | "yml" | "toml"))
+}
+
+/// Replay an `agent-tui script` file as a one-scenario verify run.
+fn verify_script_with_runner<R: Runner>(
+    script_path: &Path,
+    runner: &mut R,
+    out_dir: &Path,
+) -> Result<VerifyReport, ExplorerError> {
+    let outcome = runner.run_script(script_path)?;
+    let result = VerifyScenarioResult {
+        name: outcome.name,
+        passed: outcome.passed,
+        failed_step: outcome.failed_step,
+        message: outcome.message,
+        duration_ms: outcome.duration_ms,
+        steps: outcome.steps,
+        screenshot: None,
+        shrink: None,
+    };
+
+    if !result.passed {
+        let failure_dir = out_dir.join("failures");
+        fs::create_dir_all(&failure_dir).map_err(|error| {
+            ExplorerError::scenario(format!(
+                "failed to create failure directory {}: {error}",
+                failure_dir.display()
+            ))
+        })?;
+        let failed_step = result.failed_step.unwrap_or(0);
+        let failure_path =
+            failure_dir.join(format!("{}-step-{failed_step}.txt", slugify(&result.name)));
+        let failure_text = format!(
+            "script: {}\nstep: {failed_step}\nerror: {}\n",
+            result.name,
+            result.message.clone().unwrap_or_default()
+        );
+        fs::write(&failure_path, failure_text).map_err(|error| {
+            ExplorerError::scenario(format!(
+                "failed to write failure artifact {}: {error}",
+                failure_path.display()
+            ))
+        })?;
+    }
+
+    let passed = usize::from(result.passed);
+    Ok(VerifyReport {
+        spec_path: script_path.display().to_string(),
+        out_dir: out_dir.display().to_string(),
+        total_scenarios: 1,
+        passed_scenarios: passed,
+        failed_scenarios: 1 - passed,
+        results: vec![result],
+        shrunk_spec: None,
+    })
+}
+
+fn slugify(value: &str) -> String {
+    let mut slug = String::new();
+    let mut previous_dash = false;
+
+    for ch in value.trim().chars() {
+        if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+            previous_dash = false;
+            continue;
+        }
+
+        if !previous_dash {
+            slug.push('-');
+            previous_dash = true;
+        }
+    }
+
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() {
+        "scenario".to_string()
+    } else {
+        slug
+    }
+}
+
+fn write_discover_report(
+    out_dir: &Path,
+    report: &DiscoverReport,
+) -> Result<PathBuf, ExplorerError> {
+    let path = out_dir.join("discover-report.json");
+    let mut serialized = serde_json::to_string_pretty(report).map_err(|error| {
+        ExplorerError::scenario(format!("failed to serialize discover report: {error}"))
+    })?;
+    serialized.push('\n');
+
+    fs::write(&path, serialized).map_err(|error| {
+        ExplorerError::scenario(format!(
+            "failed to write discover report {}: {error}",
+            path.display()
+        ))
+    })?;
+
+    Ok(path)
+}
+
+fn dedup_formats(formats: &[ReportFormat]) -> Vec<ReportFormat> {
+    let mut unique = Vec::new();
+    for format in formats {
+        if !unique.contains(format) {
+            unique.push(*format);
+        }
+    }
+    unique
+}
+
+fn write_verify_report(
+    out_dir: &Path,
+    report: &VerifyReport,
+    format: ReportFormat,
+) -> Result<PathBuf, ExplorerError> {
+    let (file_name, contents) = match format {
+        ReportFormat::Json => {
+            let mut serialized = serde_json::to_string_pretty(report).map_err(|error| {
+                ExplorerError::scenario(format!("failed to serialize verify report: {error}"))
+            })?;
+            serialized.push('\n');
+            ("verify-report.json", serialized)
+        }
+        ReportFormat::Junit => ("verify-report.xml", render_junit(report, out_dir)),
+        ReportFormat::Tap => ("verify-report.tap", render_tap(report)),
+    };
+    let path = out_dir.join(file_name);
+
+    fs::write(&path, contents).map_err(|error| {
+        ExplorerError::scenario(format!(
+            "failed to write verify report {}: {error}",
+            path.display()
+        ))
+    })?;
+
+    Ok(path)
+}
+
+/// One `<testcase>` per scenario. Step timings are suite `<properties>` (the
+/// schema allows none on a test case) keyed `<scenario>.step.<n>`. Failures
+/// carry the step and message, embed the failure screen in `<system-out>` and
+/// reference the screen file as an attachment.
+fn render_junit(report: &VerifyReport, out_dir: &Path) -> String {
+    let seconds = |ms: u64| format!("{:.3}", ms as f64 / 1000.0);
+    let suite = xml_escape(&report.spec_path);
+    let total_ms = report.results.iter().map(|result| result.duration_ms).sum();
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites>\n");
+    xml.push_str(&format!(
+        "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{}\" time=\"{}\">\n",
+        report.total_scenarios,
+        report.failed_scenarios,
+        seconds(total_ms)
+    ));
+    if report.results.iter().any(|result| !result.steps.is_empty()) {
+        xml.push_str("    <properties>\n");
+        for result in &report.results {
+            for timing in &result.steps {
+                xml.push_str(&format!(
+                    "      <property name=\"{}.step.{}\" value=\"{} ({}ms)\"/>\n",
+                    xml_escape(&result.name),
+                    timing.index,
+                    xml_escape(&timing.step),
+                    timing.duration_ms
+                ));
+            }
+        }
+        xml.push_str("    </properties>\n");
+    }
+    for result in &report.results {
+        xml.push_str(&format!(
+            "    <testcase classname=\"{suite}\" name=\"{}\" time=\"{}\">\n",
+            xml_escape(&result.name),
+            seconds(result.duration_ms)
+        ));
+        if !result.passed {
+            let message = result.message.as_deref().unwrap_or("scenario failed");
+            xml.push_str(&format!(
+                "      <failure message=\"step {}: {}\"/>\n",
+                result.failed_step.unwrap_or(0),
+                xml_escape(message)
+            ));
+        }
+        if let Some(screenshot) = &result.screenshot {
+            let path = out_dir.join(screenshot);
+            let screen = fs::read_to_string(&path).unwrap_or_default();
+            xml.push_str(&format!(
+                "      <system-out>{}\n[[ATTACHMENT|{}]]</system-out>\n",
+                xml_escape(&screen),
+                xml_escape(&path.display().to_string())
+            ));
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+fn render_tap(report: &VerifyReport) -> String {
+    let mut tap = format!("TAP version 13\n1..{}\n", report.results.len());
+    for (index, result) in report.results.iter().enumerate() {
+        let status = if result.passed { "ok" } else { "not ok" };
+        tap.push_str(&format!(
+            "{status} {} - {}\n",
+            index + 1,
+            result.name.replace('#', "\\#")
+        ));
+        tap.push_str("  ---\n");
+        tap.push_str(&format!("  duration_ms: {}\n", result.duration_ms));
+        if let Some(step) = result.failed_step {
+            tap.push_str(&format!("  failed_step: {step}\n"));
+        }
+        if let Some(message) = &result.message {
+            tap.push_str(&format!("  message: {}\n", yaml_quote(message)));
+        }
+        if let Some(screenshot) = &result.screenshot {
+            tap.push_str(&format!("  screenshot: {}\n", yaml_quote(screenshot)));
+        }
+        if !result.steps.is_empty() {
+            tap.push_str("  steps:\n");
+            for timing in &result.steps {
+                tap.push_str(&format!(
+                    "    - {{ index: {}, step: {}, duration_ms: {} }}\n",
+                    timing.index,
+                    yaml_quote(&timing.step),
+                    timing.duration_ms
+                ));
+            }
+        }
+        tap.push_str("  ...\n");
+    }
+    tap
+}
+
+fn yaml_quote(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_else(|_| "\"\"".to_string())
+}
+
+fn xml_escape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c if c.is_control() && c != '\n' && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn step_label(step: &Step) -> String {
+    match step {
+        Step::Expect(text) => format!("expect {}", yaml_quote(text)),
+        Step::Press(key) => format!("press {}", yaml_quote(key)),
+        Step::Type(text) => format!("type {}", yaml_quote(text)),
+        Step::WaitStable => "wait_stable".to_string(),
+        Step::ExpectAbsent(text) => format!("expect_absent {}", yaml_quote(text)),
+        Step::ExpectRegex(pattern) => format!("expect_regex {}", yaml_quote(pattern)),
+        Step::WaitFor { text, .. } => format!("wait_for {}", yaml_quote(text)),
+        Step::Resize { cols, rows } => format!("resize {cols}x{rows}"),
+        Step::Sleep(ms) => format!("sleep {ms}"),
+        Step::Paste(text) => format!("paste {}", yaml_quote(text)),
+        Step::Signal(name) => format!("signal {name}"),
+        Step::ExpectCursor { row, col, .. } => format!("expect_cursor {row},{col}"),
+        Step::ExpectExit(code) => format!("expect_exit {code}"),
+        Step::Screenshot(name) => format!("screenshot {}", yaml_quote(name)),
+    }
+}
+
+fn elapsed_ms(started: Instant) -> u64 {
+    u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_tui_infra::infra::ipc::MockClient;
+    use serde_json::json;
+    use std::collections::HashMap;
+    use tempfile::tempdir;
+
+    const VALID_SPEC: &str = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncwd: \"/tmp\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Basic\n- wait_stable: true\n- press: \"Enter\"\n- expect: \"OK\"\n";
+
+    #[derive(Default, Clone)]
+    struct FakeRunner {
+        screen_by_path: HashMap<Vec<String>, String>,
+        current_path: Vec<String>,
+        spawn_count: usize,
+        kill_count: usize,
+        resizes: Vec<(u16, u16)>,
+        exit_code: Option<i64>,
+        failing_keys: Vec<String>,
+        /// Keys that take a while to press, so parallel scenarios finish out of order.
+        slow_keys: Vec<String>,
+        live_sessions: usize,
+        /// Computes the screen from the session number (1-based) and path when
+        /// set, instead of `screen_by_path`.
+        screen_fn: Option<fn(usize, &[String]) -> String>,
+    }
+
+    impl FakeRunner {
+        fn new(screen_by_path: HashMap<Vec<String>, String>) -> Self {
+            Self {
+                screen_by_path,
+                current_path: Vec::new(),
+                spawn_count: 0,
+                kill_count: 0,
+                resizes: Vec::new(),
+                exit_code: None,
+                failing_keys: Vec::new(),
+                slow_keys: Vec::new(),
+                live_sessions: 0,
+                screen_fn: None,
+            }
+        }
+
+        fn screen(&self) -> String {
+            if let Some(screen_fn) = self.screen_fn {
+                return screen_fn(self.spawn_count, &self.current_path);
+            }
+            self.screen_by_path
+                .get(&self.current_path)
+                .cloned()
+                .unwrap_or_default()
+        }
+    }
+
+    impl Runner for FakeRunner {
+        fn spawn(
+            &mut self,
+            _command: &str,
+            _cwd: Option<&str>,
+            _cols: u16,
+            _rows: u16,
+        ) -> Result<String, ExplorerError> {
+            self.spawn_count += 1;
+            self.current_path.clear();
+            Ok(format!("session-{}", self.spawn_count))
+        }
+
+        fn press(&mut self, _session_id: &str, key: &str) -> Result<(), ExplorerError> {
+            if self.failing_keys.iter().any(|failing| failing == key) {
+                return Err(ExplorerError::scenario(format!("press {key} failed")));
+            }
+            if self.slow_keys.iter().any(|slow| slow == key) {
+                pause(Duration::from_millis(100));
+            }
+            self.current_path.push(key.to_string());
+            Ok(())
+        }
+
+        fn type_text(&mut self, _session_id: &str, text: &str) -> Result<(), ExplorerError> {
+            self.current_path.push(format!("TYPE:{text}"));
+            Ok(())
+        }
+
+        fn paste(&mut self, _session_id: &str, text: &str) -> Result<(), ExplorerError> {
+            self.current_path.push(format!("PASTE:{text}"));
+            Ok(())
+        }
+
+        fn signal(&mut self, _session_id: &str, signal: &str) -> Result<(), ExplorerError> {
+            // Ctrl+C and friends are plain keys here, as for a child with the tty
+            // in raw mode; only a signal ends the session, as a signal death.
+            self.current_path.push(format!("SIGNAL:{signal}"));
+            self.exit_code.get_or_insert(-1);
+            Ok(())
+        }
+
+        fn wait_stable(
+            &mut self,
+            _session_id: &str,
+            _timeout_ms: u64,
+        ) -> Result<(), ExplorerError> {
+            Ok(())
+        }
+
+        fn wait_for_text(
+            &mut self,
+            _session_id: &str,
+            text: &str,
+            _timeout_ms: u64,
+        ) -> Result<bool, ExplorerError> {
+            Ok(self.screen().contains(text))
+        }
+
+        fn wait_for_gone(
+            &mut self,
+            _session_id: &str,
+            text: &str,
+            _timeout_ms: u64,
+        ) -> Result<bool, ExplorerError> {
+            Ok(!self.screen().contains(text))
+        }
+
+        fn resize(&mut self, _session_id: &str, cols: u16, rows: u16) -> Result<(), ExplorerError> {
+            self.resizes.push((cols, rows));
+            Ok(())
+        }
+
+        fn exit_code(
+            &mut self,
+            _session_id: &str,
+            _timeout_ms: u64,
+        ) -> Result<Option<i64>, ExplorerError> {
+            Ok(self.exit_code)
+        }
+
+        fn screenshot(&mut self, _session_id: &str) -> Result<(String, Cursor), ExplorerError> {
+            Ok((self.screen(), Cursor::default()))
+        }
+
+        fn kill(&mut self, _session_id: &str) -> Result<(), ExplorerError> {
+            self.kill_count += 1;
+            Ok(())
+        }
+
+        fn run_script(&mut self, _path: &Path) -> Result<ScriptOutcome, ExplorerError> {
+            let report = serde_json::json!({
+                "name": "login",
+                "passed": self.screen().contains("Welcome"),
+                "steps": [
+                    {"index": 1, "name": "press Enter", "status": "passed"},
+                    {"index": 2, "name": "wait \"Welcome\"", "status": "failed", "message": "timed out after 3000ms"}
+                ]
+            });
+            Ok(script_outcome_from_report(&report))
+        }
+
+        fn session_count(&mut self) -> Result<usize, ExplorerError> {
+            Ok(self.live_sessions)
+        }
+    }
+
+    fn assert_ok<T>(result: Result<T, ExplorerError>) -> T {
+        match result {
+            Ok(value) => value,
+            Err(error) => panic!("expected Ok(_), got error: {error}"),
+        }
+    }
+
+    fn as_map(entries: Vec<(Vec<String>, &str)>) -> HashMap<Vec<String>, String> {
+        entries
+            .into_iter()
+            .map(|(path, screen)| (path, screen.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_valid_v1_spec() {
+        let spec = assert_ok(parse_spec_text(VALID_SPEC));
+        assert_eq!(
+            spec.frontmatter
+                .get("schema_version")
+                .and_then(Value::as_str),
+            Some("v1")
+        );
+        assert_eq!(spec.scenarios.len(), 1);
+        assert!(matches!(spec.scenarios[0].steps[1], Step::Press(_)));
+    }
+
+    #[test]
+    fn parse_missing_required_frontmatter() {
+        let bad = VALID_SPEC.replace("rows: 40\n", "");
+        let result = parse_spec_text(&bad);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_invalid_step_syntax() {
+        let bad = VALID_SPEC.replace("- press: \"Enter\"", "- press: Enter");
+        let result = parse_spec_text(&bad);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_unsupported_schema_version() {
+        let bad = VALID_SPEC.replace("schema_version: \"v1\"", "schema_version: \"v3\"");
+        let result = parse_spec_text(&bad);
+        assert!(result.is_err());
+    }
+
+    const V2_SPEC: &str = "---\nschema_version: \"v2\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Basic\n### Setup\n- wait_for: {\"text\":\"Menu\",\"timeout_ms\":500}\n### Steps\n- expect_regex: \"^Me.u\"\n- resize: {\"cols\":100,\"rows\":30}\n- paste: \"hi\"\n- sleep: 1\n- screenshot: \"menu\"\n- signal: \"INT\"\n- expect_absent: \"Menu\"\n- expect_cursor: {\"row\":0,\"col\":0,\"visible\":false}\n- expect_exit: 0\n### Teardown\n- press: \"q\"\n";
+
+    #[test]
+    fn parse_v2_spec_with_blocks_round_trips() {
+        let spec = assert_ok(parse_spec_text(V2_SPEC));
+        let scenario = &spec.scenarios[0];
+        assert_eq!(
+            scenario.setup,
+            vec![Step::WaitFor {
+                text: "Menu".to_string(),
+                timeout_ms: Some(500),
+            }]
+        );
+        assert_eq!(scenario.steps.len(), 9);
+        assert_eq!(
+            scenario.steps[1],
+            Step::Resize {
+                cols: 100,
+                rows: 30
+            }
+        );
+        assert_eq!(scenario.steps[8], Step::ExpectExit(0));
+        assert_eq!(scenario.teardown, vec![Step::Press("q".to_string())]);
+
+        let rendered = assert_ok(render_markdown(&spec));
+        assert!(rendered.contains("### Setup\n- wait_for: {"));
+        let reparsed = assert_ok(parse_spec_text(&rendered));
+        assert_eq!(reparsed.scenarios, spec.scenarios);
+    }
+
+    #[test]
+    fn parse_v1_rejects_v2_vocabulary() {
+        for addition in ["- sleep: 10\n", "### Teardown\n- press: \"q\"\n"] {
+            let bad = format!("{VALID_SPEC}{addition}");
+            let Err(error) = parse_spec_text(&bad) else {
+                panic!("expected v1 to reject {addition:?}");
+            };
+            assert!(error.message.contains("requires schema_version v2"));
+        }
+
+        let bad = V2_SPEC.replace("- signal: \"INT\"", "- signal: \"USR1\"");
+        assert!(parse_spec_text(&bad).is_err());
+    }
+
+    #[test]
+    fn parse_step_reads_each_v2_step_kind() {
+        let cases = [
+            (
+                r#"- expect_absent: "Menu""#,
+                Step::ExpectAbsent("Menu".to_string()),
+            ),
+            (
+                r#"- expect_regex: "^Me.u$""#,
+                Step::ExpectRegex("^Me.u$".to_string()),
+            ),
+            (
+                r#"- wait_for: "Ready""#,
+                Step::WaitFor {
+                    text: "Ready".to_string(),
+                    timeout_ms: None,
+                },
+            ),
+            (
+                r#"- wait_for: {"text":"Ready","timeout_ms":250}"#,
+                Step::WaitFor {
+                    text: "Ready".to_string(),
+                    timeout_ms: Some(250),
+                },
+            ),
+            (
+                r#"- resize: {"cols":100,"rows":30}"#,
+                Step::Resize {
+                    cols: 100,
+                    rows: 30,
+                },
+            ),
+            ("- sleep: 25", Step::Sleep(25)),
+            (
+                r#"- paste: "line one\nline two""#,
+                Step::Paste("line one\nline two".to_string()),
+            ),
+            (r#"- signal: "QUIT""#, Step::Signal("QUIT".to_string())),
+            (r#"- signal: "sigterm""#, Step::Signal("TERM".to_string())),
+            (
+                r#"- expect_cursor: {"row":2,"col":5}"#,
+                Step::ExpectCursor {
+                    row: 2,
+                    col: 5,
+                    visible: None,
+                },
+            ),
+            (
+                r#"- expect_cursor: {"row":0,"col":0,"visible":true}"#,
+                Step::ExpectCursor {
+                    row: 0,
+                    col: 0,
+                    visible: Some(true),
+                },
+            ),
+            ("- expect_exit: -1", Step::ExpectExit(-1)),
+            (
+                r#"- screenshot: "main menu""#,
+                Step::Screenshot("main menu".to_string()),
+            ),
+        ];
+        for (line, expected) in cases {
+            let step = assert_ok(parse_step(line));
+            assert_eq!(step, expected, "{line}");
+            assert!(!step.is_v1(), "{line}");
+            let rendered = assert_ok(render_step(&step));
+            assert_eq!(assert_ok(parse_step(&rendered)), step, "{rendered}");
+        }
+    }
+
+    #[test]
+    fn parse_step_rejects_malformed_v2_steps() {
+        for line in [
+            "- expect_absent: Menu",
+            r#"- expect_regex: "(unclosed""#,
+            "- wait_for: Ready",
+            r#"- wait_for: {"timeout_ms":250}"#,
+            r#"- wait_for: {"text":"Ready","timeout":250}"#,
+            r#"- resize: {"cols":0,"rows":30}"#,
+            r#"- resize: {"cols":100}"#,
+            "- resize: 100x30",
+            "- sleep: -5",
+            r#"- sleep: "10""#,
+            "- paste: hi",
+            r#"- signal: "USR1""#,
+            "- signal: INT",
+            r#"- expect_cursor: {"row":1}"#,
+            r#"- expect_cursor: {"row":1,"col":2,"shape":"bar"}"#,
+            "- expect_exit: zero",
+            r#"- screenshot: """#,
+            r#"- screenshot: "!!!""#,
+            "- shake: true",
+            "press: \"Enter\"",
+        ] {
+            assert!(
+                parse_step(line).is_err(),
+                "expected '{line}' to be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn render_markdown_round_trips_v1_spec_upgraded_to_v2() {
+        let v1 = assert_ok(parse_spec_text(VALID_SPEC));
+        let rendered = assert_ok(render_markdown(&v1));
+        assert_eq!(assert_ok(parse_spec_text(&rendered)), v1);
+
+        let mut v2 = v1.clone();
+        v2.frontmatter.insert(
+            "schema_version".to_string(),
+            Value::String(SCHEMA_V2.to_string()),
+        );
+        let scenario = &mut v2.scenarios[0];
+        scenario.setup = vec![Step::WaitFor {
+            text: "Menu".to_string(),
+            timeout_ms: Some(500),
+        }];
+        scenario.steps.push(Step::ExpectAbsent("Error".to_string()));
+        scenario.teardown = vec![Step::Press("q".to_string())];
+
+        let rendered = assert_ok(render_markdown(&v2));
+        assert!(rendered.starts_with("---\nschema_version: \"v2\"\n"));
+        assert!(rendered.contains(
+            "### Steps\n- wait_stable: true\n- press: \"Enter\"\n- expect: \"OK\"\n- expect_absent: \"Error\"\n### Teardown\n"
+        ));
+        let reparsed = assert_ok(parse_spec_text(&rendered));
+        assert_eq!(reparsed, v2);
+        assert_eq!(reparsed.scenarios[0].steps[..3], v1.scenarios[0].steps[..]);
+
+        // The upgraded spec no longer parses as v1.
+        let downgraded = rendered.replace("schema_version: \"v2\"", "schema_version: \"v1\"");
+        assert!(parse_spec_text(&downgraded).is_err());
+    }
+
+    #[test]
+    fn parse_allows_openspec_expectation_block() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Basic\n### Expectation\n- **WHEN** the operator replays: press \"Enter\", wait_stable\n- **THEN** the screen contains \"OK\"\n- **SHOULD** execute machine checks: expect \"OK\"\n- press: \"Enter\"\n- wait_stable: true\n- expect: \"OK\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        assert_eq!(spec.scenarios.len(), 1);
+        assert!(matches!(spec.scenarios[0].steps[0], Step::Press(_)));
+        assert!(matches!(spec.scenarios[0].steps[1], Step::WaitStable));
+        assert!(matches!(spec.scenarios[0].steps[2], Step::Expect(_)));
+    }
+
+    #[test]
+    fn discovery_dedupes_hashes_and_respects_limits() {
+        let screens = as_map(vec![
+            (Vec::new(), "Main Menu"),
+            (vec!["Enter".to_string()], "Main Menu"),
+            (vec!["Tab".to_string()], "Settings"),
+        ]);
+        let mut runner = FakeRunner::new(screens);
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+
+        let config = DiscoverConfig {
+            command: "printf app".to_string(),
+            cwd: None,
+            cols: 120,
+            rows: 40,
+            max_depth: 1,
+            max_states: 10,
+            branch_limit: 2,
+            time_budget_sec: 30,
+            out_dir: temp.path().to_path_buf(),
+            allow_risky: false,
+            default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers::default(),
+            strategy: Strategy::Bfs,
+            seed: 0,
+            screen_actions: false,
+        };
+
+        let (report, spec, traces) = assert_ok(discover_with_runner(&config, &mut runner));
+        assert!(report.states_explored >= 1);
+        assert_eq!(report.unique_hashes, 2);
+        assert!(traces.len() >= spec.scenarios.len());
+    }
+
+    #[test]
+    fn discovery_safe_allowlist_only() {
+        let screens = as_map(vec![
+            (Vec::new(), "Menu"),
+            (vec!["Enter".to_string()], "Menu Enter"),
+            (vec!["Tab".to_string()], "Menu Tab"),
+        ]);
+        let mut runner = FakeRunner::new(screens);
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+
+        let config = DiscoverConfig {
+            command: "printf app".to_string(),
+            cwd: None,
+            cols: 120,
+            rows: 40,
+            max_depth: 1,
+            max_states: 5,
+            branch_limit: 2,
+            time_budget_sec: 30,
+            out_dir: temp.path().to_path_buf(),
+            allow_risky: false,
+            default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers::default(),
+            strategy: Strategy::Bfs,
+            seed: 0,
+            screen_actions: false,
+        };
+
+        let (_report, spec, _traces) = assert_ok(discover_with_runner(&config, &mut runner));
+        for scenario in spec.scenarios {
+            for step in scenario.steps {
+                if let Step::Press(key) = step {
+                    assert!(DEFAULT_SAFE_ACTIONS.contains(&key.as_str()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn discovery_is_deterministic_for_same_input() {
+        let screens = as_map(vec![
+            (Vec::new(), "Menu"),
+            (vec!["Enter".to_string()], "Item A"),
+            (vec!["Tab".to_string()], "Item B"),
+        ]);
+
+        let temp1 = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let temp2 = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+
+        let config1 = DiscoverConfig {
+            command: "printf app".to_string(),
+            cwd: None,
+            cols: 120,
+            rows: 40,
+            max_depth: 1,
+            max_states: 5,
+            branch_limit: 2,
+            time_budget_sec: 30,
+            out_dir: temp1.path().to_path_buf(),
+            allow_risky: false,
+            default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers::default(),
+            strategy: Strategy::Bfs,
+            seed: 0,
+            screen_actions: false,
+        };
+
+        let config2 = DiscoverConfig {
+            out_dir: temp2.path().to_path_buf(),
+            ..config1.clone()
+        };
+
+        let (_report1, spec1, _traces1) = assert_ok(discover_with_runner(
+            &config1,
+            &mut FakeRunner::new(screens.clone()),
+        ));
+        let (_report2, spec2, _traces2) = assert_ok(discover_with_runner(
+            &config2,
+            &mut FakeRunner::new(screens),
+        ));
+
+        let signature1 = spec1
+            .scenarios
+            .iter()
+            .map(|scenario| {
+                let step_signature = scenario
+                    .steps
+                    .iter()
+                    .map(|step| match step {
+                        Step::Expect(value) => format!("expect:{value}"),
+                        Step::Press(value) => format!("press:{value}"),
+                        Step::Type(value) => format!("type:{value}"),
+                        Step::WaitStable => "wait_stable:true".to_string(),
+                        other => step_label(other),
+                    })
+                    .collect::<Vec<_>>();
+                (scenario.name.clone(), step_signature)
+            })
+            .collect::<Vec<_>>();
+
+        let signature2 = spec2
+            .scenarios
+            .iter()
+            .map(|scenario| {
+                let step_signature = scenario
+                    .steps
+                    .iter()
+                    .map(|step| match step {
+                        Step::Expect(value) => format!("expect:{value}"),
+                        Step::Press(value) => format!("press:{value}"),
+                        Step::Type(value) => format!("type:{value}"),
+                        Step::WaitStable => "wait_stable:true".to_string(),
+                        other => step_label(other),
+                    })
+                    .collect::<Vec<_>>();
+                (scenario.name.clone(), step_signature)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(signature1, signature2);
+    }
+
+    #[test]
+    fn markdown_roundtrip_with_escaped_text() {
+        let spec = Spec {
+            frontmatter: BTreeMap::from([
+                (
+                    "schema_version".to_string(),
+                    Value::String("v1".to_string()),
+                ),
+                (
+                    "command".to_string(),
+                    Value::String("printf app".to_string()),
+                ),
+                ("cwd".to_string(), Value::String("/tmp".to_string())),
+                ("cols".to_string(), Value::Number(120.into())),
+                ("rows".to_string(), Value::Number(40.into())),
+                ("default_timeout_ms".to_string(), Value::Number(3000.into())),
+                (
+                    "generated_at".to_string(),
+                    Value::String("2026-02-18T00:00:00Z".to_string()),
+                ),
+                (
+                    "generator".to_string(),
+                    Value::String("tui-explorer/1".to_string()),
+                ),
+            ]),
+            scenarios: vec![Scenario {
+                name: "Quoted".to_string(),
+                steps: vec![
+                    Step::Type("he said \"hello\" \\\\".to_string()),
+                    Step::WaitStable,
+                    Step::Expect("done".to_string()),
+                ],
+                ..Scenario::default()
+            }],
+        };
+
+        let text = assert_ok(render_markdown(&spec));
+        let parsed = assert_ok(parse_spec_text(&text));
+
+        match &parsed.scenarios[0].steps[0] {
+            Step::Type(value) => assert_eq!(value, "he said \"hello\" \\\\"),
+            _ => panic!("unexpected first step kind"),
+        }
+    }
+
+    #[test]
+    fn render_markdown_includes_openspec_expectation_block() {
+        let spec = Spec {
+            frontmatter: BTreeMap::from([
+                (
+                    "schema_version".to_string(),
+                    Value::String("v1".to_string()),
+                ),
+                (
+                    "command".to_string(),
+                    Value::String("printf app".to_string()),
+                ),
+                ("cwd".to_string(), Value::Null),
+                ("cols".to_string(), Value::Number(120.into())),
+                ("rows".to_string(), Value::Number(40.into())),
+                ("default_timeout_ms".to_string(), Value::Number(3000.into())),
+                (
+                    "generated_at".to_string(),
+                    Value::String("2026-02-18T00:00:00Z".to_string()),
+                ),
+                (
+                    "generator".to_string(),
+                    Value::String("tui-explorer/1".to_string()),
+                ),
+            ]),
+            scenarios: vec![Scenario {
+                name: "Basic".to_string(),
+                steps: vec![
+                    Step::Press("Enter".to_string()),
+                    Step::WaitStable,
+                    Step::Expect("OK".to_string()),
+                ],
+                ..Scenario::default()
+            }],
+        };
+
+        let text = assert_ok(render_markdown(&spec));
+        assert!(text.contains("### Expectation"));
+        assert!(text.contains("- **WHEN**"));
+        assert!(text.contains("- **THEN**"));
+        assert!(text.contains("- **SHOULD**"));
+        assert!(text.contains("- expect: \"OK\""));
+    }
+
+    #[test]
+    fn verify_happy_path() {
+        let spec = assert_ok(parse_spec_text(VALID_SPEC));
+        let mut runner = FakeRunner::new(as_map(vec![
+            (Vec::new(), "OK"),
+            (vec!["Enter".to_string()], "OK"),
+        ]));
+
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("acceptance.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions::default(),
+        ));
+
+        assert_eq!(report.failed_scenarios, 0);
+        assert_eq!(report.passed_scenarios, 1);
+    }
 
-    fs::write(&path, serialized).map_err(|error| {
-        ExplorerError::scenario(format!(
-            "failed to write discover report {}: {error}",
-            path.display()
-        ))
-    })?;
+    #[test]
+    fn verify_happy_path_with_openspec_expectation_lines() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Basic\n### Expectation\n- **WHEN** replaying the path: press \"Enter\", wait_stable\n- **THEN** the screen contains \"OK\"\n- **SHOULD** execute machine checks: expect \"OK\"\n- press: \"Enter\"\n- wait_stable: true\n- expect: \"OK\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        let mut runner = FakeRunner::new(as_map(vec![
+            (Vec::new(), "OK"),
+            (vec!["Enter".to_string()], "OK"),
+        ]));
 
-    Ok(path)
-}
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
 
-fn write_verify_report(out_dir: &Path, report: &VerifyReport) -> Result<PathBuf, ExplorerError> {
-    let path = out_dir.join("verify-report.json");
-    let mut serialized = serde_json::to_string_pretty(report).map_err(|error| {
-        ExplorerError::scenario(format!("failed to serialize verify report: {error}"))
-    })?;
-    serialized.push('\n');
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("acceptance.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions::default(),
+        ));
 
-    fs::write(&path, serialized).map_err(|error| {
-        ExplorerError::scenario(format!(
-            "failed to write verify report {}: {error}",
-            path.display()
-        ))
-    })?;
+        assert_eq!(report.failed_scenarios, 0);
+        assert_eq!(report.passed_scenarios, 1);
+    }
 
-    Ok(path)
-}
+    #[test]
+    fn verify_fail_fast_writes_failure_artifact() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: First\n- expect: \"NOPE\"\n\n## Scenario: Second\n- expect: \"YES\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        let mut runner = FakeRunner::new(as_map(vec![(Vec::new(), "YES")]));
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::collections::HashMap;
-    use tempfile::tempdir;
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
 
-    const VALID_SPEC: &str = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncwd: \"/tmp\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Basic\n- wait_stable: true\n- press: \"Enter\"\n- expect: \"OK\"\n";
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("acceptance.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions::default(),
+        ));
 
-    #[derive(Default)]
-    struct FakeRunner {
-        screen_by_path: HashMap<Vec<String>, String>,
-        current_path: Vec<String>,
-        spawn_count: usize,
-        kill_count: usize,
-    }
+        assert_eq!(report.failed_scenarios, 1);
+        assert_eq!(report.total_scenarios, 1);
 
-    impl FakeRunner {
-        fn new(screen_by_path: HashMap<Vec<String>, String>) -> Self {
-            Self {
-                screen_by_path,
-                current_path: Vec::new(),
-                spawn_count: 0,
-                kill_count: 0,
-            }
-        }
+        let failures_dir = temp.path().join("failures");
+        let entries = match fs::read_dir(failures_dir) {
+            Ok(iter) => iter.collect::<Result<Vec<_>, _>>(),
+            Err(error) => panic!("failed to read failures directory: {error}"),
+        };
+        let entries = match entries {
+            Ok(entries) => entries,
+            Err(error) => panic!("failed to collect failure entries: {error}"),
+        };
 
-        fn screen(&self) -> String {
-            self.screen_by_path
-                .get(&self.current_path)
-                .cloned()
-                .unwrap_or_default()
-        }
+        assert!(!entries.is_empty());
     }
 
-    impl Runner for FakeRunner {
-        fn spawn(
-            &mut self,
-            _command: &str,
-            _cwd: Option<&str>,
-            _cols: u16,
-            _rows: u16,
-        ) -> Result<String, ExplorerError> {
-            self.spawn_count += 1;
-            self.current_path.clear();
-            Ok(format!("session-{}", self.spawn_count))
-        }
+    #[test]
+    fn verify_reports_render_junit_and_tap_with_failure_screen() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Good\n- expect: \"Menu\"\n\n## Scenario: Bad <one>\n- press: \"Enter\"\n- expect: \"Done\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        let mut runner = FakeRunner::new(as_map(vec![
+            (Vec::new(), "Menu"),
+            (vec!["Enter".to_string()], "Still <loading>"),
+        ]));
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
 
-        fn press(&mut self, _session_id: &str, key: &str) -> Result<(), ExplorerError> {
-            self.current_path.push(key.to_string());
-            Ok(())
-        }
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("acceptance.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions {
+                fail_fast: false,
+                ..VerifyOptions::default()
+            },
+        ));
 
-        fn type_text(&mut self, _session_id: &str, text: &str) -> Result<(), ExplorerError> {
-            self.current_path.push(format!("TYPE:{text}"));
-            Ok(())
-        }
+        let bad = &report.results[1];
+        assert_eq!(bad.failed_step, Some(2));
+        assert_eq!(
+            bad.steps
+                .iter()
+                .map(|timing| timing.step.as_str())
+                .collect::<Vec<_>>(),
+            vec!["press \"Enter\"", "expect \"Done\""]
+        );
+        assert_eq!(
+            bad.screenshot.as_deref(),
+            Some("failures/bad-one-step-2.screen.txt")
+        );
 
-        fn wait_stable(
-            &mut self,
-            _session_id: &str,
-            _timeout_ms: u64,
-        ) -> Result<(), ExplorerError> {
-            Ok(())
-        }
+        let junit = render_junit(&report, temp.path());
+        assert!(junit.contains("tests=\"2\" failures=\"1\""));
+        assert!(junit.contains("name=\"Bad &lt;one&gt;\""));
+        assert!(junit.contains("<failure message=\"step 2: expectation not met: Done\"/>"));
+        assert!(junit.contains("<system-out>Still &lt;loading&gt;"));
+        assert!(junit.contains("[[ATTACHMENT|"));
 
-        fn wait_for_text(
-            &mut self,
-            _session_id: &str,
-            text: &str,
-            _timeout_ms: u64,
-        ) -> Result<bool, ExplorerError> {
-            Ok(self.screen().contains(text))
-        }
+        let tap = render_tap(&report);
+        assert!(tap.starts_with("TAP version 13\n1..2\nok 1 - Good\n"));
+        assert!(tap.contains("not ok 2 - Bad <one>\n"));
+        assert!(tap.contains("  failed_step: 2\n"));
 
-        fn screenshot(&mut self, _session_id: &str) -> Result<(String, Cursor), ExplorerError> {
-            Ok((self.screen(), Cursor::default()))
+        for format in [ReportFormat::Json, ReportFormat::Junit, ReportFormat::Tap] {
+            assert_ok(write_verify_report(temp.path(), &report, format));
         }
+        assert!(temp.path().join("verify-report.xml").exists());
+        assert!(temp.path().join("verify-report.tap").exists());
+        assert_eq!(
+            dedup_formats(&[ReportFormat::Tap, ReportFormat::Json, ReportFormat::Tap]),
+            vec![ReportFormat::Tap, ReportFormat::Json]
+        );
+    }
 
-        fn kill(&mut self, _session_id: &str) -> Result<(), ExplorerError> {
-            self.kill_count += 1;
-            Ok(())
+    fn failed_report(name: &str, message: &str) -> VerifyReport {
+        VerifyReport {
+            spec_path: "acceptance.md".to_string(),
+            out_dir: "out".to_string(),
+            total_scenarios: 1,
+            passed_scenarios: 0,
+            failed_scenarios: 1,
+            results: vec![VerifyScenarioResult {
+                name: name.to_string(),
+                passed: false,
+                failed_step: Some(2),
+                message: Some(message.to_string()),
+                duration_ms: 1500,
+                steps: vec![
+                    StepTiming {
+                        index: 1,
+                        step: "press \"Enter\"".to_string(),
+                        duration_ms: 10,
+                    },
+                    StepTiming {
+                        index: 2,
+                        step: "expect \"<ok>\"".to_string(),
+                        duration_ms: 1490,
+                    },
+                ],
+                screenshot: None,
+                shrink: None,
+            }],
+            shrunk_spec: None,
         }
     }
 
-    fn assert_ok<T>(result: Result<T, ExplorerError>) -> T {
-        match result {
-            Ok(value) => value,
-            Err(error) => panic!("expected Ok(_), got error: {error}"),
-        }
+    #[test]
+    fn render_junit_puts_step_properties_on_the_suite() {
+        let junit = render_junit(&failed_report("Login", "boom"), Path::new("out"));
+        let suite_open = junit.find("<testsuite ").unwrap_or(usize::MAX);
+        let properties = junit.find("<properties>").unwrap_or(usize::MAX);
+        let testcase = junit.find("<testcase ").unwrap_or(usize::MAX);
+        assert!(suite_open < properties && properties < testcase, "{junit}");
+        assert_eq!(junit.matches("<properties>").count(), 1);
+        assert!(junit.contains(
+            "<property name=\"Login.step.2\" value=\"expect &quot;&lt;ok&gt;&quot; (1490ms)\"/>"
+        ));
+        let case = &junit[testcase..];
+        assert!(!case.contains("<property"));
     }
 
-    fn as_map(entries: Vec<(Vec<String>, &str)>) -> HashMap<Vec<String>, String> {
-        entries
-            .into_iter()
-            .map(|(path, screen)| (path, screen.to_string()))
-            .collect()
+    #[test]
+    fn render_junit_escapes_failure_messages() {
+        let junit = render_junit(
+            &failed_report("Quotes \"&\" <tags>", "expected \"a < b\" & 'c' > d\u{7}"),
+            Path::new("out"),
+        );
+        assert!(junit.contains(
+            "<failure message=\"step 2: expected &quot;a &lt; b&quot; &amp; &apos;c&apos; &gt; d\"/>"
+        ));
+        assert!(junit.contains("name=\"Quotes &quot;&amp;&quot; &lt;tags&gt;\""));
+        assert!(!junit.contains('\u{7}'));
     }
 
     #[test]
-    fn parse_valid_v1_spec() {
-        let spec = assert_ok(parse_spec_text(VALID_SPEC));
+    fn render_tap_reports_not_ok_with_diagnostics() {
+        let mut report = failed_report("Login # retry", "expected \"Welcome\"\nsaw: Error");
+        report.results[0].screenshot = Some("failures/login-step-2.screen.txt".to_string());
+        let tap = render_tap(&report);
         assert_eq!(
-            spec.frontmatter
-                .get("schema_version")
-                .and_then(Value::as_str),
-            Some("v1")
+            tap,
+            "TAP version 13\n1..1\nnot ok 1 - Login \\# retry\n  ---\n  duration_ms: 1500\n  failed_step: 2\n  message: \"expected \\\"Welcome\\\"\\nsaw: Error\"\n  screenshot: \"failures/login-step-2.screen.txt\"\n  steps:\n    - { index: 1, step: \"press \\\"Enter\\\"\", duration_ms: 10 }\n    - { index: 2, step: \"expect \\\"<ok>\\\"\", duration_ms: 1490 }\n  ...\n"
         );
-        assert_eq!(spec.scenarios.len(), 1);
-        assert!(matches!(spec.scenarios[0].steps[1], Step::Press(_)));
     }
 
     #[test]
-    fn parse_missing_required_frontmatter() {
-        let bad = VALID_SPEC.replace("rows: 40\n", "");
-        let result = parse_spec_text(&bad);
-        assert!(result.is_err());
+    fn verify_screenshot_keeps_raw_screen_next_to_normalized_one() {
+        let spec = assert_ok(parse_spec_text(
+            "---\nschema_version: \"v2\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\nnormalize: {\"replace\":[{\"pattern\":\"\\\\d+%\",\"with\":\"N%\"}]}\n---\n\n## Scenario: Progress\n- screenshot: \"bar\"\n",
+        ));
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let mut runner = FakeRunner::new(as_map(vec![(vec![], "\u{1b}[1mLoading 42%\u{1b}[0m")]));
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("spec.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions::default(),
+        ));
+        assert_eq!(report.failed_scenarios, 0, "{:?}", report.results);
+        let read = |name: &str| {
+            assert_ok(
+                fs::read_to_string(temp.path().join("screens").join(name))
+                    .map_err(|error| ExplorerError::scenario(error.to_string())),
+            )
+        };
+        assert_eq!(read("progress-bar.txt"), "Loading 42%");
+        assert_eq!(read("progress-bar.normalized.txt"), "Loading N%");
     }
 
     #[test]
-    fn parse_invalid_step_syntax() {
-        let bad = VALID_SPEC.replace("- press: \"Enter\"", "- press: Enter");
-        let result = parse_spec_text(&bad);
-        assert!(result.is_err());
+    fn verify_v2_runs_setup_steps_and_teardown() {
+        let spec = assert_ok(parse_spec_text(V2_SPEC));
+        let temp = assert_ok(tempfile::tempdir().map_err(|error| {
+            ExplorerError::scenario(format!("failed to create tempdir: {error}"))
+        }));
+        let mut runner = FakeRunner::new(as_map(vec![
+            (vec![], "Menu"),
+            (vec!["PASTE:hi".to_string()], "Menu hi"),
+            (
+                vec!["PASTE:hi".to_string(), "SIGNAL:INT".to_string()],
+                "Bye",
+            ),
+        ]));
+        runner.exit_code = Some(0);
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("spec.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions {
+                fail_fast: false,
+                ..VerifyOptions::default()
+            },
+        ));
+        assert_eq!(report.failed_scenarios, 0, "{:?}", report.results);
+        assert_eq!(report.results[0].steps.len(), 11);
+        assert_eq!(runner.resizes, vec![(100, 30)]);
+        assert_eq!(runner.current_path.last().map(String::as_str), Some("q"));
+        let screen = assert_ok(
+            fs::read_to_string(temp.path().join("screens/basic-menu.txt"))
+                .map_err(|error| ExplorerError::scenario(error.to_string())),
+        );
+        assert_eq!(screen, "Menu hi");
+    }
+
+    #[test]
+    fn verify_runs_teardown_after_failing_step() {
+        let spec_text = "---\nschema_version: \"v2\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Broken\n### Setup\n- expect: \"Menu\"\n### Steps\n- press: \"Enter\"\n- expect: \"Done\"\n- press: \"Never\"\n### Teardown\n- press: \"Escape\"\n- press: \"q\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        let temp = assert_ok(tempfile::tempdir().map_err(|error| {
+            ExplorerError::scenario(format!("failed to create tempdir: {error}"))
+        }));
+        let mut runner = FakeRunner::new(as_map(vec![
+            (vec![], "Menu"),
+            (vec!["Enter".to_string()], "Loading"),
+        ]));
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("spec.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions::default(),
+        ));
+        let result = &report.results[0];
+        assert!(!result.passed);
+        assert_eq!(result.failed_step, Some(3));
+        assert_eq!(result.message.as_deref(), Some("expectation not met: Done"));
+        // Steps after the failure are skipped, teardown still runs in order and
+        // keeps the shared step numbering, then the session is killed.
+        assert_eq!(runner.current_path, vec!["Enter", "Escape", "q"]);
+        assert_eq!(
+            result
+                .steps
+                .iter()
+                .map(|step| step.index)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3, 5, 6]
+        );
+        assert_eq!(runner.kill_count, 1);
+        // The failure screen is taken before teardown changes it.
+        let screen = assert_ok(
+            fs::read_to_string(temp.path().join("failures/broken-step-3.screen.txt"))
+                .map_err(|error| ExplorerError::scenario(error.to_string())),
+        );
+        assert_eq!(screen, "Loading");
+    }
+
+    #[test]
+    fn verify_signal_step_signals_raw_mode_child() {
+        let spec_text = "---\nschema_version: \"v2\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Terminate\n### Steps\n- press: \"Ctrl+C\"\n- expect: \"Menu\"\n- signal: \"TERM\"\n- expect_exit: -1\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        // The child reads Ctrl+C as a plain byte and keeps running; only the
+        // signal ends it.
+        let mut runner = FakeRunner::new(as_map(vec![(vec!["Ctrl+C".to_string()], "Menu")]));
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("spec.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions::default(),
+        ));
+        assert!(report.results[0].passed, "{:?}", report.results[0].message);
+        assert_eq!(runner.current_path, vec!["Ctrl+C", "SIGNAL:TERM"]);
     }
 
     #[test]
-    fn parse_unsupported_schema_version() {
-        let bad = VALID_SPEC.replace("schema_version: \"v1\"", "schema_version: \"v2\"");
-        let result = parse_spec_text(&bad);
-        assert!(result.is_err());
+    fn verify_paste_step_is_not_typed() {
+        let spec_text = "---\nschema_version: \"v2\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Paste\n### Steps\n- paste: \"one\\ntwo\"\n- expect: \"Pasted\"\n\n## Scenario: Type\n### Steps\n- type: \"one\\ntwo\"\n- expect: \"Typed\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let mut runner = FakeRunner::new(as_map(vec![
+            (vec!["PASTE:one\ntwo".to_string()], "Pasted"),
+            (vec!["TYPE:one\ntwo".to_string()], "Typed"),
+        ]));
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("spec.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions::default(),
+        ));
+        for result in &report.results {
+            assert!(result.passed, "{}: {:?}", result.name, result.message);
+        }
     }
 
     #[test]
-    fn parse_allows_openspec_expectation_block() {
-        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Basic\n### Expectation\n- **WHEN** the operator replays: press \"Enter\", wait_stable\n- **THEN** the screen contains \"OK\"\n- **SHOULD** execute machine checks: expect \"OK\"\n- press: \"Enter\"\n- wait_stable: true\n- expect: \"OK\"\n";
+    fn verify_scenario_filter() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: One\n- expect: \"NO\"\n\n## Scenario: Two\n- expect: \"YES\"\n";
         let spec = assert_ok(parse_spec_text(spec_text));
-        assert_eq!(spec.scenarios.len(), 1);
-        assert!(matches!(spec.scenarios[0].steps[0], Step::Press(_)));
-        assert!(matches!(spec.scenarios[0].steps[1], Step::WaitStable));
-        assert!(matches!(spec.scenarios[0].steps[2], Step::Expect(_)));
+        let mut runner = FakeRunner::new(as_map(vec![(Vec::new(), "YES")]));
+
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("acceptance.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions {
+                scenario: Some("Two"),
+                ..VerifyOptions::default()
+            },
+        ));
+
+        assert_eq!(report.total_scenarios, 1);
+        assert_eq!(report.failed_scenarios, 0);
     }
 
     #[test]
-    fn discovery_dedupes_hashes_and_respects_limits() {
-        let screens = as_map(vec![
-            (Vec::new(), "Main Menu"),
-            (vec!["Enter".to_string()], "Main Menu"),
-            (vec!["Tab".to_string()], "Settings"),
-        ]);
-        let mut runner = FakeRunner::new(screens);
+    fn discovery_writes_state_graph_with_collapsed_edges_and_errors() {
+        let mut runner = FakeRunner::new(as_map(vec![
+            (Vec::new(), "Menu"),
+            (vec!["Enter".to_string()], "Item A"),
+            (vec!["Tab".to_string()], "Item A"),
+        ]));
+        runner.failing_keys = vec!["ArrowDown".to_string()];
         let temp = match tempdir() {
             Ok(path) => path,
             Err(error) => panic!("failed to create temp dir: {error}"),
         };
-
         let config = DiscoverConfig {
             command: "printf app".to_string(),
             cwd: None,
@@ -1735,262 +5320,600 @@ mod tests {
             rows: 40,
             max_depth: 1,
             max_states: 10,
-            branch_limit: 2,
+            branch_limit: 3,
             time_budget_sec: 30,
             out_dir: temp.path().to_path_buf(),
             allow_risky: false,
             default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers::default(),
+            strategy: Strategy::Bfs,
+            seed: 0,
+            screen_actions: false,
         };
 
-        let (report, spec, traces) = assert_ok(discover_with_runner(&config, &mut runner));
-        assert!(report.states_explored >= 1);
-        assert_eq!(report.unique_hashes, 2);
-        assert!(traces.len() >= spec.scenarios.len());
+        let (report, _spec, _traces) = assert_ok(discover_with_runner(&config, &mut runner));
+        let graph: Value = match fs::read_to_string(&report.state_graph.json)
+            .map_err(|error| error.to_string())
+            .and_then(|text| serde_json::from_str(&text).map_err(|error| error.to_string()))
+        {
+            Ok(graph) => graph,
+            Err(error) => panic!("failed to read state graph: {error}"),
+        };
+        let ids = graph["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .map(|node| (node["id"].clone(), node["expanded"].clone()))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        assert_eq!(
+            ids,
+            vec![
+                (Value::from("s0"), Value::from(true)),
+                (Value::from("s1"), Value::from(false)),
+                (Value::from("e0"), Value::from(false)),
+            ]
+        );
+        assert_eq!(graph["nodes"][0]["excerpt"], serde_json::json!(["Menu"]));
+        assert_eq!(graph["nodes"][2]["error"], "press ArrowDown failed");
+        assert_eq!(
+            graph["edges"],
+            serde_json::json!([
+                {"from": "s0", "to": "s1", "actions": ["Enter", "Tab"]},
+                {"from": "s0", "to": "e0", "actions": ["ArrowDown"]},
+            ])
+        );
+
+        let dot = fs::read_to_string(&report.state_graph.dot).unwrap_or_default();
+        assert!(dot.contains("s0 -> s1 [label=\"Enter, Tab\"];"));
+        assert!(dot.contains("e0 [label=\"error: press ArrowDown failed\", shape=octagon"));
+        let mermaid = fs::read_to_string(&report.state_graph.mermaid).unwrap_or_default();
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("s0 -->|Enter, Tab| s1"));
+        assert!(mermaid.contains("e0{{\"error: press ArrowDown failed\"}}:::error"));
+    }
+
+    fn graph_with_awkward_labels() -> StateGraph {
+        let node = |id: &str, anchor: &str, error: Option<&str>| GraphNode {
+            id: id.to_string(),
+            state_hash: Some("0123456789abcdef".to_string()),
+            anchor: Some(anchor.to_string()),
+            excerpt: vec!["say \"hi\"".to_string(), "[ok] | {x}".to_string()],
+            path: Vec::new(),
+            expanded: true,
+            error: error.map(str::to_string),
+        };
+        StateGraph {
+            nodes: vec![
+                node("s0", "Menu [main] \"quoted\" \\path", None),
+                node("e0", "", Some("press \"q\" failed\nat <prompt> #1")),
+            ],
+            edges: vec![GraphEdge {
+                from: "s0".to_string(),
+                to: "e0".to_string(),
+                actions: vec![
+                    "type:say \"hi\"\n[ok]".to_string(),
+                    "Ctrl+]".to_string(),
+                    "a|b (c)".to_string(),
+                ],
+            }],
+        }
     }
 
     #[test]
-    fn discovery_safe_allowlist_only() {
+    fn render_graph_dot_escapes_quotes_brackets_and_newlines() {
+        let dot = render_graph_dot(&graph_with_awkward_labels());
+        let lines = dot.lines().collect::<Vec<_>>();
+        // One statement per node and edge: no label spills onto a new line.
+        assert_eq!(lines.len(), 3 + 2 + 1 + 1, "{dot}");
+        assert_eq!(
+            lines[3],
+            r#"  s0 [label="Menu [main] \"quoted\" \\path\n01234567", tooltip="say \"hi\"\n[ok] | {x}"];"#
+        );
+        assert_eq!(
+            lines[4],
+            r#"  e0 [label="error: press \"q\" failed\nat <prompt> #1", shape=octagon, color=red, fontcolor=red];"#
+        );
+        assert_eq!(
+            lines[5],
+            r#"  s0 -> e0 [label="type:say \"hi\"\n[ok], Ctrl+], a|b (c)"];"#
+        );
+    }
+
+    #[test]
+    fn render_graph_mermaid_escapes_quotes_brackets_and_newlines() {
+        let mermaid = render_graph_mermaid(&graph_with_awkward_labels());
+        let lines = mermaid.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1 + 2 + 1 + 2, "{mermaid}");
+        assert_eq!(
+            lines[1],
+            r#"  s0["Menu #91;main#93; #quot;quoted#quot; \path<br/>01234567"]"#
+        );
+        assert_eq!(
+            lines[2],
+            r#"  e0{{"error: press #quot;q#quot; failed<br/>at #lt;prompt#gt; #35;1"}}:::error"#
+        );
+        assert_eq!(
+            lines[3],
+            "  s0 -->|type:say #quot;hi#quot;<br/>#91;ok#93;, Ctrl+#93;, a#124;b #40;c#41;| e0"
+        );
+    }
+
+    #[test]
+    fn screen_normalizer_applies_regions_replacements_and_similarity() {
+        let normalizers = Normalizers {
+            replace: vec![assert_ok(
+                parse_replacement(r"\d+%=N%").map_err(ExplorerError::spec),
+            )],
+            ignore_regions: vec![assert_ok(
+                parse_region("0,6,3,1").map_err(ExplorerError::spec),
+            )],
+            ignore_cursor: true,
+            similarity: Some(0.9),
+        };
+        let normalizer = assert_ok(normalizers.compile());
+        assert_eq!(
+            normalizer.normalize("\u{1b}[1mTitle pid\u{1b}[0m  \nLoading 42%"),
+            "Title\nLoading N%"
+        );
+        assert_eq!(
+            ScreenNormalizer::similarity("abcd\nefgh", "abcd\nefgX"),
+            0.875
+        );
+
+        // No normalizers keeps the historical hash input.
+        let plain = ScreenNormalizer::default();
+        let cursor = Cursor {
+            row: 1,
+            col: 2,
+            visible: true,
+        };
+        assert_eq!(
+            compute_state_hash(
+                &plain.normalize("\u{1b}[31mA\u{1b}[0m  B\n"),
+                &cursor,
+                false,
+                80,
+                24
+            ),
+            compute_state_hash("A B", &cursor, false, 80, 24)
+        );
+        assert_ne!(
+            compute_state_hash("A B", &cursor, false, 80, 24),
+            compute_state_hash("A B", &cursor, true, 80, 24)
+        );
+
+        let bad = Normalizers {
+            similarity: Some(1.5),
+            ..Normalizers::default()
+        };
+        assert!(bad.compile().is_err());
+    }
+
+    #[test]
+    fn discovery_normalizers_collapse_volatile_screens() {
         let screens = as_map(vec![
-            (Vec::new(), "Menu"),
-            (vec!["Enter".to_string()], "Menu Enter"),
-            (vec!["Tab".to_string()], "Menu Tab"),
+            (Vec::new(), "Menu 10%"),
+            (vec!["Enter".to_string()], "Menu 55%"),
+            (vec!["Tab".to_string()], "Count 1"),
+            (vec!["Tab".to_string(), "Enter".to_string()], "Count 2"),
+            (vec!["Tab".to_string(), "Tab".to_string()], "Count 3"),
         ]);
-        let mut runner = FakeRunner::new(screens);
         let temp = match tempdir() {
             Ok(path) => path,
             Err(error) => panic!("failed to create temp dir: {error}"),
         };
-
         let config = DiscoverConfig {
             command: "printf app".to_string(),
             cwd: None,
             cols: 120,
             rows: 40,
-            max_depth: 1,
-            max_states: 5,
+            max_depth: 2,
+            max_states: 10,
             branch_limit: 2,
             time_budget_sec: 30,
             out_dir: temp.path().to_path_buf(),
             allow_risky: false,
             default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers {
+                replace: vec![Replacement {
+                    pattern: r"\d+%".to_string(),
+                    with: "N%".to_string(),
+                }],
+                similarity: Some(0.8),
+                ..Normalizers::default()
+            },
+            strategy: Strategy::Bfs,
+            seed: 0,
+            screen_actions: false,
         };
 
-        let (_report, spec, _traces) = assert_ok(discover_with_runner(&config, &mut runner));
-        for scenario in spec.scenarios {
-            for step in scenario.steps {
-                if let Step::Press(key) = step {
-                    assert!(DEFAULT_SAFE_ACTIONS.contains(&key.as_str()));
-                }
-            }
-        }
+        let (report, spec, traces) =
+            assert_ok(discover_with_runner(&config, &mut FakeRunner::new(screens)));
+        // "Menu N%" twice, then "Count 1" with "Count 2" and "Count 3" folded in
+        // by similarity.
+        assert_eq!(report.unique_hashes, 2);
+        assert_eq!(traces[1].normalized_screen, "Menu N%");
+        assert_eq!(traces[1].state_hash, traces[0].state_hash);
+        let count_traces = traces
+            .iter()
+            .filter(|trace| trace.normalized_screen.starts_with("Count"))
+            .collect::<Vec<_>>();
+        assert_eq!(count_traces.len(), 3);
+        assert!(
+            count_traces
+                .iter()
+                .all(|trace| trace.state_hash == count_traces[0].state_hash)
+        );
+
+        // The generated spec records the normalizers and parses back.
+        let markdown = assert_ok(
+            fs::read_to_string(&report.acceptance_spec)
+                .map_err(|error| ExplorerError::scenario(error.to_string())),
+        );
+        assert!(markdown.contains(
+            "normalize: {\"replace\":[{\"pattern\":\"\\\\d+%\",\"with\":\"N%\"}],\"similarity\":0.8}"
+        ));
+        let reparsed = assert_ok(parse_spec_text(&markdown));
+        assert_eq!(
+            assert_ok(Normalizers::from_frontmatter(&reparsed.frontmatter)),
+            config.normalizers
+        );
+        assert_eq!(reparsed.scenarios, spec.scenarios);
     }
 
     #[test]
-    fn discovery_is_deterministic_for_same_input() {
-        let screens = as_map(vec![
-            (Vec::new(), "Menu"),
-            (vec!["Enter".to_string()], "Item A"),
-            (vec!["Tab".to_string()], "Item B"),
-        ]);
+    fn frontier_pops_in_strategy_order() {
+        let path = |action: &str| vec![action.to_string()];
+        let drain = |frontier: &mut Frontier| {
+            std::iter::from_fn(|| frontier.pop())
+                .map(|path| path.join(","))
+                .collect::<Vec<_>>()
+        };
 
-        let temp1 = match tempdir() {
-            Ok(path) => path,
-            Err(error) => panic!("failed to create temp dir: {error}"),
+        let mut dfs = Frontier::new(Strategy::Dfs, 0);
+        assert_eq!(dfs.pop(), Some(Vec::new()));
+        dfs.push_children(vec![path("a"), path("b")], 1.0);
+        assert_eq!(dfs.pop(), Some(path("a")));
+        dfs.push_children(vec![vec!["a".to_string(), "c".to_string()]], 1.0);
+        assert_eq!(drain(&mut dfs), vec!["a,c", "b"]);
+
+        let mut novelty = Frontier::new(Strategy::Novelty, 0);
+        assert_eq!(novelty.pop(), Some(Vec::new()));
+        novelty.push_children(vec![path("a"), path("b")], 0.1);
+        novelty.push_children(vec![path("c")], 0.9);
+        novelty.push_children(vec![path("d")], 0.1);
+        assert_eq!(drain(&mut novelty), vec!["c", "a", "b", "d"]);
+
+        let random_order = |seed| {
+            let mut random = Frontier::new(Strategy::Random, seed);
+            random.pop();
+            random.push_children(
+                ["a", "b", "c", "d", "e", "f"]
+                    .into_iter()
+                    .map(path)
+                    .collect(),
+                1.0,
+            );
+            drain(&mut random)
         };
-        let temp2 = match tempdir() {
+        let order = random_order(7);
+        assert_eq!(order.len(), 6);
+        assert_eq!(order, random_order(7));
+    }
+
+    #[test]
+    fn action_detector_reads_hotkeys_and_prompts() {
+        let detector = assert_ok(ActionDetector::new());
+        let detected = detector
+            .detect("[q]uit  [o]pen  F1 Help  ^X Exit\nEmail: ____\nSearch:\nF1 Help again");
+        assert_eq!(
+            detected,
+            vec![
+                ("q".to_string(), true),
+                ("o".to_string(), false),
+                ("F1".to_string(), false),
+                ("Ctrl+X".to_string(), true),
+                ("type:user@example.com".to_string(), false),
+                ("type:hello".to_string(), false),
+            ]
+        );
+        assert!(detector.detect("Status: ready").is_empty());
+    }
+
+    #[test]
+    fn discovery_follows_screen_actions_and_reports_coverage() {
+        let login = "Login\nName: ____\n[q]uit  F1 Help";
+        let screens = as_map(vec![
+            (Vec::new(), login),
+            (vec!["F1".to_string()], "Help"),
+            (vec!["TYPE:hello".to_string()], "Login\nName: hello"),
+            (vec!["Enter".to_string()], "Menu"),
+            (vec!["Tab".to_string()], login),
+        ]);
+        let temp = match tempdir() {
             Ok(path) => path,
             Err(error) => panic!("failed to create temp dir: {error}"),
         };
-
-        let config1 = DiscoverConfig {
+        let config = DiscoverConfig {
             command: "printf app".to_string(),
             cwd: None,
             cols: 120,
             rows: 40,
             max_depth: 1,
-            max_states: 5,
-            branch_limit: 2,
+            max_states: 10,
+            branch_limit: 3,
             time_budget_sec: 30,
-            out_dir: temp1.path().to_path_buf(),
+            out_dir: temp.path().to_path_buf(),
             allow_risky: false,
             default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers::default(),
+            strategy: Strategy::Novelty,
+            seed: 0,
+            screen_actions: true,
         };
 
-        let config2 = DiscoverConfig {
-            out_dir: temp2.path().to_path_buf(),
-            ..config1.clone()
-        };
-
-        let (_report1, spec1, _traces1) = assert_ok(discover_with_runner(
-            &config1,
-            &mut FakeRunner::new(screens.clone()),
-        ));
-        let (_report2, spec2, _traces2) = assert_ok(discover_with_runner(
-            &config2,
-            &mut FakeRunner::new(screens),
-        ));
-
-        let signature1 = spec1
-            .scenarios
-            .iter()
-            .map(|scenario| {
-                let step_signature = scenario
-                    .steps
-                    .iter()
-                    .map(|step| match step {
-                        Step::Expect(value) => format!("expect:{value}"),
-                        Step::Press(value) => format!("press:{value}"),
-                        Step::Type(value) => format!("type:{value}"),
-                        Step::WaitStable => "wait_stable:true".to_string(),
-                    })
-                    .collect::<Vec<_>>();
-                (scenario.name.clone(), step_signature)
-            })
-            .collect::<Vec<_>>();
-
-        let signature2 = spec2
-            .scenarios
+        let (report, spec, traces) =
+            assert_ok(discover_with_runner(&config, &mut FakeRunner::new(screens)));
+        // Two screen actions fill the first two slots; `branch_limit` leaves room
+        // for one default action only.
+        let children = traces
             .iter()
-            .map(|scenario| {
-                let step_signature = scenario
-                    .steps
-                    .iter()
-                    .map(|step| match step {
-                        Step::Expect(value) => format!("expect:{value}"),
-                        Step::Press(value) => format!("press:{value}"),
-                        Step::Type(value) => format!("type:{value}"),
-                        Step::WaitStable => "wait_stable:true".to_string(),
-                    })
-                    .collect::<Vec<_>>();
-                (scenario.name.clone(), step_signature)
-            })
-            .collect::<Vec<_>>();
-
-        assert_eq!(signature1, signature2);
-    }
-
-    #[test]
-    fn markdown_roundtrip_with_escaped_text() {
-        let spec = Spec {
-            frontmatter: BTreeMap::from([
-                (
-                    "schema_version".to_string(),
-                    Value::String("v1".to_string()),
-                ),
-                (
-                    "command".to_string(),
-                    Value::String("printf app".to_string()),
-                ),
-                ("cwd".to_string(), Value::String("/tmp".to_string())),
-                ("cols".to_string(), Value::Number(120.into())),
-                ("rows".to_string(), Value::Number(40.into())),
-                ("default_timeout_ms".to_string(), Value::Number(3000.into())),
-                (
-                    "generated_at".to_string(),
-                    Value::String("2026-02-18T00:00:00Z".to_string()),
-                ),
-                (
-                    "generator".to_string(),
-                    Value::String("tui-explorer/1".to_string()),
-                ),
-            ]),
-            scenarios: vec![Scenario {
-                name: "Quoted".to_string(),
-                steps: vec![
-                    Step::Type("he said \"hello\" \\\\".to_string()),
-                    Step::WaitStable,
-                    Step::Expect("done".to_string()),
-                ],
-            }],
+            .filter(|trace| trace.depth == 1)
+            .map(|trace| trace.path[0].as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(children, vec!["type:hello", "F1", "Enter"]);
+        assert!(children.len() <= config.branch_limit);
+        assert_eq!(report.states_explored, 4);
+        assert!(report.risky_actions_blocked.contains(&"q".to_string()));
+        assert_eq!(report.coverage.strategy, Strategy::Novelty);
+        assert_eq!(report.coverage.seed, None);
+        assert_eq!(report.coverage.distinct_screens, 4);
+        assert_eq!(
+            report.coverage.distinct_anchors,
+            report.coverage.anchors.len()
+        );
+        assert_eq!(report.coverage.error_paths, 0);
+        assert_eq!(report.coverage.max_depth_reached, 1);
+        assert_eq!(report.coverage.frontier_remaining, 0);
+        assert_eq!(report.coverage.screen_actions, vec!["type:hello", "F1"]);
+        assert!(
+            spec.scenarios
+                .iter()
+                .any(|scenario| { scenario.steps.contains(&Step::Type("hello".to_string())) })
+        );
+    }
+
+    #[test]
+    fn parallel_discover_and_verify_keep_sequential_order() {
+        let screens = as_map(vec![
+            (Vec::new(), "Menu"),
+            (vec!["Enter".to_string()], "Item A"),
+            (vec!["Tab".to_string()], "Item B"),
+            (vec!["ArrowDown".to_string()], "Item C"),
+            (vec!["Enter".to_string(), "Enter".to_string()], "Detail A"),
+        ]);
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let config = DiscoverConfig {
+            command: "printf app".to_string(),
+            cwd: None,
+            cols: 120,
+            rows: 40,
+            max_depth: 2,
+            max_states: 12,
+            branch_limit: 3,
+            time_budget_sec: 30,
+            out_dir: temp.path().to_path_buf(),
+            allow_risky: false,
+            default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers::default(),
+            strategy: Strategy::Bfs,
+            seed: 0,
+            screen_actions: false,
         };
 
-        let text = assert_ok(render_markdown(&spec));
-        let parsed = assert_ok(parse_spec_text(&text));
+        let (_, sequential, sequential_traces) = assert_ok(discover_with_runner(
+            &config,
+            &mut FakeRunner::new(screens.clone()),
+        ));
+        let parallel_config = DiscoverConfig { jobs: 4, ..config };
+        let (report, parallel, parallel_traces) = assert_ok(discover_with_runner(
+            &parallel_config,
+            &mut FakeRunner::new(screens.clone()),
+        ));
+        assert_eq!(report.states_explored, 12);
+        assert_eq!(parallel.scenarios, sequential.scenarios);
+        assert_eq!(
+            parallel_traces.iter().map(|t| &t.path).collect::<Vec<_>>(),
+            sequential_traces
+                .iter()
+                .map(|t| &t.path)
+                .collect::<Vec<_>>()
+        );
 
-        match &parsed.scenarios[0].steps[0] {
-            Step::Type(value) => assert_eq!(value, "he said \"hello\" \\\\"),
-            _ => panic!("unexpected first step kind"),
+        // Verify reports in spec order and, with fail-fast, cuts after the
+        // first failing scenario even if later ones already ran.
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: A\n- press: \"Enter\"\n- expect: \"Item A\"\n\n## Scenario: B\n- expect: \"Nope\"\n\n## Scenario: C\n- press: \"Tab\"\n- expect: \"Item B\"\n\n## Scenario: D\n- expect: \"Menu\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
+        for (fail_fast, expected) in [(true, vec!["A", "B"]), (false, vec!["A", "B", "C", "D"])] {
+            let report = assert_ok(verify_with_runner(
+                &spec,
+                Path::new("acceptance.md"),
+                &mut FakeRunner::new(screens.clone()),
+                temp.path(),
+                VerifyOptions {
+                    fail_fast,
+                    jobs: 3,
+                    ..VerifyOptions::default()
+                },
+            ));
+            let names = report
+                .results
+                .iter()
+                .map(|result| result.name.as_str())
+                .collect::<Vec<_>>();
+            assert_eq!(names, expected);
+            assert_eq!(report.failed_scenarios, 1);
         }
     }
 
     #[test]
-    fn render_markdown_includes_openspec_expectation_block() {
-        let spec = Spec {
-            frontmatter: BTreeMap::from([
-                (
-                    "schema_version".to_string(),
-                    Value::String("v1".to_string()),
-                ),
-                (
-                    "command".to_string(),
-                    Value::String("printf app".to_string()),
-                ),
-                ("cwd".to_string(), Value::Null),
-                ("cols".to_string(), Value::Number(120.into())),
-                ("rows".to_string(), Value::Number(40.into())),
-                ("default_timeout_ms".to_string(), Value::Number(3000.into())),
-                (
-                    "generated_at".to_string(),
-                    Value::String("2026-02-18T00:00:00Z".to_string()),
-                ),
-                (
-                    "generator".to_string(),
-                    Value::String("tui-explorer/1".to_string()),
-                ),
-            ]),
-            scenarios: vec![Scenario {
-                name: "Basic".to_string(),
-                steps: vec![
-                    Step::Press("Enter".to_string()),
-                    Step::WaitStable,
-                    Step::Expect("OK".to_string()),
-                ],
-            }],
-        };
-
-        let text = assert_ok(render_markdown(&spec));
-        assert!(text.contains("### Expectation"));
-        assert!(text.contains("- **WHEN**"));
-        assert!(text.contains("- **THEN**"));
-        assert!(text.contains("- **SHOULD**"));
-        assert!(text.contains("- expect: \"OK\""));
+    fn effective_jobs_leaves_room_for_live_sessions() {
+        assert_eq!(assert_ok(effective_jobs(4, 16, 0)), 4);
+        assert_eq!(assert_ok(effective_jobs(4, 16, 14)), 2);
+        assert_eq!(assert_ok(effective_jobs(20, 16, 0)), 16);
+        assert!(effective_jobs(4, 16, 16).is_err());
+        assert!(effective_jobs(0, 16, 0).is_err());
     }
 
     #[test]
-    fn verify_happy_path() {
-        let spec = assert_ok(parse_spec_text(VALID_SPEC));
+    fn verify_parallel_report_keeps_spec_order_when_later_scenarios_finish_first() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: A\n- press: \"Slow\"\n- expect: \"Nope\"\n\n## Scenario: B\n- expect: \"Nope\"\n\n## Scenario: C\n- expect: \"Menu\"\n\n## Scenario: D\n- press: \"Slow\"\n- expect: \"Done\"\n";
+        let spec = assert_ok(parse_spec_text(spec_text));
         let mut runner = FakeRunner::new(as_map(vec![
-            (Vec::new(), "OK"),
-            (vec!["Enter".to_string()], "OK"),
+            (Vec::new(), "Menu"),
+            (vec!["Slow".to_string()], "Done"),
         ]));
+        runner.slow_keys = vec!["Slow".to_string()];
+
+        for _ in 0..3 {
+            let temp = match tempdir() {
+                Ok(path) => path,
+                Err(error) => panic!("failed to create temp dir: {error}"),
+            };
+            let report = assert_ok(verify_with_runner(
+                &spec,
+                Path::new("acceptance.md"),
+                &mut runner.clone(),
+                temp.path(),
+                VerifyOptions {
+                    fail_fast: false,
+                    jobs: 4,
+                    ..VerifyOptions::default()
+                },
+            ));
+            let names = report
+                .results
+                .iter()
+                .map(|result| (result.name.as_str(), result.passed))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                names,
+                vec![("A", false), ("B", false), ("C", true), ("D", true)]
+            );
+        }
 
+        // B fails first, but A comes first in the spec: only A is reported
+        // and only A leaves failure artifacts.
         let temp = match tempdir() {
             Ok(path) => path,
             Err(error) => panic!("failed to create temp dir: {error}"),
         };
-
         let report = assert_ok(verify_with_runner(
             &spec,
             Path::new("acceptance.md"),
             &mut runner,
             temp.path(),
-            None,
-            true,
+            VerifyOptions {
+                fail_fast: true,
+                jobs: 4,
+                ..VerifyOptions::default()
+            },
         ));
-
-        assert_eq!(report.failed_scenarios, 0);
-        assert_eq!(report.passed_scenarios, 1);
+        assert_eq!(report.total_scenarios, 1);
+        assert_eq!(report.results[0].name, "A");
+        let mut artifacts = match fs::read_dir(temp.path().join("failures")) {
+            Ok(entries) => entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.file_name().to_string_lossy().into_owned())
+                .collect::<Vec<_>>(),
+            Err(error) => panic!("failed to read failures directory: {error}"),
+        };
+        artifacts.sort();
+        assert_eq!(artifacts, vec!["a-step-2.screen.txt", "a-step-2.txt"]);
     }
 
     #[test]
-    fn verify_happy_path_with_openspec_expectation_lines() {
-        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Basic\n### Expectation\n- **WHEN** replaying the path: press \"Enter\", wait_stable\n- **THEN** the screen contains \"OK\"\n- **SHOULD** execute machine checks: expect \"OK\"\n- press: \"Enter\"\n- wait_stable: true\n- expect: \"OK\"\n";
+    fn verify_shrink_minimizes_failing_scenario() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Long\n- press: \"A\"\n- press: \"B\"\n- press: \"C\"\n- press: \"D\"\n- wait_stable: true\n- expect: \"ok\"\n- press: \"E\"\n";
         let spec = assert_ok(parse_spec_text(spec_text));
-        let mut runner = FakeRunner::new(as_map(vec![
-            (Vec::new(), "OK"),
-            (vec!["Enter".to_string()], "OK"),
-        ]));
+        let mut runner = FakeRunner::new(HashMap::new());
+        runner.screen_fn = Some(|_, path| {
+            if path.iter().any(|key| key == "C") {
+                "Boom".to_string()
+            } else {
+                "ok".to_string()
+            }
+        });
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+
+        let report = assert_ok(verify_with_runner(
+            &spec,
+            Path::new("acceptance.md"),
+            &mut runner,
+            temp.path(),
+            VerifyOptions {
+                shrink: true,
+                ..VerifyOptions::default()
+            },
+        ));
+
+        let result = &report.results[0];
+        assert_eq!(result.failed_step, Some(6));
+        let Some(summary) = &result.shrink else {
+            panic!("expected a shrink summary");
+        };
+        assert!(summary.reproduced);
+        assert_eq!(summary.original_steps, 7);
+        assert_eq!(summary.minimal_steps, 2);
+        assert_eq!(summary.scenario.as_deref(), Some("Long (shrunk)"));
+
+        let Some(shrunk_path) = &report.shrunk_spec else {
+            panic!("expected shrunk.md");
+        };
+        let shrunk = assert_ok(parse_spec_file(Path::new(shrunk_path)));
+        assert_eq!(
+            shrunk.scenarios[0].steps,
+            vec![Step::Press("C".to_string()), Step::Expect("ok".to_string())]
+        );
+        // Candidate replays do not leave failure artifacts behind.
+        let failures = fs::read_dir(temp.path().join("failures"))
+            .map(|entries| entries.count())
+            .unwrap_or_default();
+        assert_eq!(failures, 2);
+    }
 
+    #[test]
+    fn verify_shrink_stops_at_replay_budget() {
+        // Fails only once every even key below K24 was pressed: ddmin drops the
+        // tail quickly, then needs far more replays than the budget allows to
+        // weed out the interleaved odd keys.
+        let steps = (0..40)
+            .map(|index| format!("- press: \"K{index}\"\n"))
+            .collect::<String>();
+        let spec_text = format!(
+            "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Wide\n{steps}- expect: \"ok\"\n"
+        );
+        let spec = assert_ok(parse_spec_text(&spec_text));
+        let mut runner = FakeRunner::new(HashMap::new());
+        runner.screen_fn = Some(|_, path| {
+            let all_even = (0..24)
+                .step_by(2)
+                .all(|index| path.iter().any(|key| *key == format!("K{index}")));
+            if all_even { "Boom" } else { "ok" }.to_string()
+        });
         let temp = match tempdir() {
             Ok(path) => path,
             Err(error) => panic!("failed to create temp dir: {error}"),
@@ -2001,20 +5924,45 @@ mod tests {
             Path::new("acceptance.md"),
             &mut runner,
             temp.path(),
-            None,
-            true,
+            VerifyOptions {
+                shrink: true,
+                ..VerifyOptions::default()
+            },
         ));
 
-        assert_eq!(report.failed_scenarios, 0);
-        assert_eq!(report.passed_scenarios, 1);
+        let Some(summary) = &report.results[0].shrink else {
+            panic!("expected a shrink summary");
+        };
+        assert!(summary.reproduced);
+        assert_eq!(summary.replays, SHRINK_MAX_REPLAYS);
+        // One verify run plus the budgeted replays.
+        assert_eq!(runner.spawn_count, 1 + SHRINK_MAX_REPLAYS);
+        assert!(summary.minimal_steps < summary.original_steps);
+
+        // What was found by then still reproduces the failure.
+        let Some(shrunk_path) = &report.shrunk_spec else {
+            panic!("expected shrunk.md");
+        };
+        let shrunk = assert_ok(parse_spec_file(Path::new(shrunk_path)));
+        let shrunk_steps = &shrunk.scenarios[0].steps;
+        assert_eq!(shrunk_steps.len(), summary.minimal_steps);
+        for index in (0..24).step_by(2) {
+            assert!(shrunk_steps.contains(&Step::Press(format!("K{index}"))));
+        }
+        assert!(
+            shrunk_steps.len() > 24 / 2 + 1,
+            "not 1-minimal within budget"
+        );
+        assert_eq!(shrunk_steps.last(), Some(&Step::Expect("ok".to_string())));
     }
 
     #[test]
-    fn verify_fail_fast_writes_failure_artifact() {
-        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: First\n- expect: \"NOPE\"\n\n## Scenario: Second\n- expect: \"YES\"\n";
+    fn verify_shrink_leaves_scenario_that_passes_on_replay_unchanged() {
+        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: Flaky\n- press: \"A\"\n- expect: \"ok\"\n- press: \"B\"\n";
         let spec = assert_ok(parse_spec_text(spec_text));
-        let mut runner = FakeRunner::new(as_map(vec![(Vec::new(), "YES")]));
-
+        let mut runner = FakeRunner::new(HashMap::new());
+        // Only the first session shows the failure.
+        runner.screen_fn = Some(|session, _| if session == 1 { "Boom" } else { "ok" }.to_string());
         let temp = match tempdir() {
             Ok(path) => path,
             Err(error) => panic!("failed to create temp dir: {error}"),
@@ -2025,48 +5973,310 @@ mod tests {
             Path::new("acceptance.md"),
             &mut runner,
             temp.path(),
-            None,
-            true,
+            VerifyOptions {
+                shrink: true,
+                ..VerifyOptions::default()
+            },
         ));
 
-        assert_eq!(report.failed_scenarios, 1);
-        assert_eq!(report.total_scenarios, 1);
+        let result = &report.results[0];
+        assert!(!result.passed);
+        assert_eq!(result.failed_step, Some(2));
+        let Some(summary) = &result.shrink else {
+            panic!("expected a shrink summary");
+        };
+        assert_eq!(
+            summary,
+            &ShrinkSummary {
+                reproduced: false,
+                original_steps: 3,
+                minimal_steps: 3,
+                replays: 1,
+                scenario: None,
+            }
+        );
+        assert!(report.shrunk_spec.is_none());
+        assert!(!temp.path().join("shrunk.md").exists());
+    }
 
-        let failures_dir = temp.path().join("failures");
-        let entries = match fs::read_dir(failures_dir) {
-            Ok(iter) => iter.collect::<Result<Vec<_>, _>>(),
-            Err(error) => panic!("failed to read failures directory: {error}"),
+    #[test]
+    fn rpc_runner_sends_daemon_requests() {
+        let mut client = MockClient::new();
+        client.set_response("spawn", json!({ "session_id": "s1", "pid": 42 }));
+        client.set_response(
+            "snapshot",
+            json!({
+                "screenshot": "Ready",
+                "cursor": { "row": 1, "col": 2, "visible": true }
+            }),
+        );
+        client.set_response("wait", json!({ "found": true }));
+        client.set_response(
+            "sessions",
+            json!({ "sessions": [{ "id": "s1", "running": false, "exit_code": 3 }] }),
+        );
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
         };
-        let entries = match entries {
-            Ok(entries) => entries,
-            Err(error) => panic!("failed to collect failure entries: {error}"),
+        let mut runner = RpcRunner::with_client(client.clone(), AgentTuiRunner::new(temp.path()));
+
+        let id = assert_ok(runner.spawn("printf app", Some("/tmp"), 100, 30));
+        assert_eq!(id, "s1");
+        assert_ok(runner.press(&id, "Enter"));
+        assert_ok(runner.type_text(&id, "hello"));
+        assert_ok(runner.wait_stable(&id, 500));
+        assert!(assert_ok(runner.wait_for_gone(&id, "Loading", 500)));
+        let (screen, cursor) = assert_ok(runner.screenshot(&id));
+        assert_eq!(screen, "Ready");
+        assert_eq!((cursor.row, cursor.col, cursor.visible), (1, 2, true));
+        // The mock has no streams, so this falls back to the `sessions` query.
+        assert_eq!(assert_ok(runner.exit_code(&id, 500)), Some(3));
+        assert_ok(runner.kill(&id));
+
+        assert_eq!(
+            client.params_for("spawn"),
+            vec![Some(json!({
+                "command": "sh",
+                "args": ["-lc", "printf app"],
+                "cwd": "/tmp",
+                "cols": 100,
+                "rows": 30
+            }))]
+        );
+        assert_eq!(
+            client.params_for("keystroke"),
+            vec![Some(json!({ "key": "Enter", "session": "s1" }))]
+        );
+        let waits = client.params_for("wait");
+        assert_eq!(
+            waits[0],
+            Some(json!({
+                "session": "s1",
+                "timeout_ms": 500,
+                "condition": "stable",
+                "unwrap": false
+            }))
+        );
+        assert_eq!(
+            waits[1],
+            Some(json!({
+                "session": "s1",
+                "text": "Loading",
+                "timeout_ms": 500,
+                "condition": "text_gone",
+                "unwrap": false
+            }))
+        );
+        assert_eq!(client.call_count("kill"), 1);
+
+        let mut failing = MockClient::new();
+        failing.set_response(
+            "keystroke",
+            json!({ "success": false, "message": "unknown key" }),
+        );
+        let mut runner = RpcRunner::with_client(failing, AgentTuiRunner::new(temp.path()));
+        let error = match runner.press("s1", "Hyper+Q") {
+            Ok(()) => panic!("expected press to fail"),
+            Err(error) => error,
         };
+        assert_eq!(error.kind, ExplorerErrorKind::Scenario);
+        assert!(error.message.contains("unknown key"));
+    }
 
-        assert!(!entries.is_empty());
+    #[test]
+    fn rpc_runner_pastes_and_signals_without_keystrokes() {
+        let mut client = MockClient::new();
+        client.set_response("pty_write", json!({ "success": true, "bytes_written": 19 }));
+        client.set_response("signal", json!({ "success": true, "signal": "TERM" }));
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let mut runner = RpcRunner::with_client(client.clone(), AgentTuiRunner::new(temp.path()));
+
+        assert_ok(runner.paste("s1", "one\ntwo"));
+        assert_ok(runner.signal("s1", "TERM"));
+
+        // The paste is one raw write wrapped in bracketed-paste markers, and the
+        // signal goes to the process group rather than through the tty.
+        assert_eq!(
+            client.params_for("pty_write"),
+            vec![Some(json!({
+                "session": "s1",
+                "data": STANDARD.encode("\x1b[200~one\ntwo\x1b[201~")
+            }))]
+        );
+        assert_eq!(
+            client.params_for("signal"),
+            vec![Some(json!({ "signal": "TERM", "session": "s1" }))]
+        );
+        assert_eq!(client.call_count("type"), 0);
+        assert_eq!(client.call_count("keystroke"), 0);
     }
 
     #[test]
-    fn verify_scenario_filter() {
-        let spec_text = "---\nschema_version: \"v1\"\ncommand: \"printf app\"\ncols: 120\nrows: 40\ndefault_timeout_ms: 3000\ngenerated_at: \"2026-02-18T00:00:00Z\"\ngenerator: \"tui-explorer/1\"\n---\n\n## Scenario: One\n- expect: \"NO\"\n\n## Scenario: Two\n- expect: \"YES\"\n";
-        let spec = assert_ok(parse_spec_text(spec_text));
-        let mut runner = FakeRunner::new(as_map(vec![(Vec::new(), "YES")]));
+    fn runner_auto_falls_back_to_cli_without_daemon() {
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let runner = assert_ok(DaemonRunner::<MockClient>::select(
+            temp.path(),
+            RunnerKind::Auto,
+            || Err(ClientError::DaemonNotRunning),
+        ));
+        assert!(matches!(runner, DaemonRunner::Cli(_)));
+
+        let runner = assert_ok(DaemonRunner::select(temp.path(), RunnerKind::Auto, || {
+            Ok(MockClient::new())
+        }));
+        assert!(matches!(runner, DaemonRunner::Rpc(_)));
 
+        let mut connected = false;
+        let runner = assert_ok(DaemonRunner::<MockClient>::select(
+            temp.path(),
+            RunnerKind::Cli,
+            || {
+                connected = true;
+                Ok(MockClient::new())
+            },
+        ));
+        assert!(matches!(runner, DaemonRunner::Cli(_)));
+        assert!(!connected);
+    }
+
+    #[test]
+    fn runner_rpc_fails_hard_without_daemon() {
         let temp = match tempdir() {
             Ok(path) => path,
             Err(error) => panic!("failed to create temp dir: {error}"),
         };
+        let error = match DaemonRunner::<MockClient>::select(temp.path(), RunnerKind::Rpc, || {
+            Err(ClientError::DaemonNotRunning)
+        }) {
+            Ok(_) => panic!("expected --runner rpc to fail without a daemon"),
+            Err(error) => error,
+        };
+        assert_eq!(error.kind, ExplorerErrorKind::Unavailable);
+    }
 
-        let report = assert_ok(verify_with_runner(
-            &spec,
-            Path::new("acceptance.md"),
+    #[test]
+    fn daemon_runner_dispatches_to_rpc_client() {
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let mut client = MockClient::new();
+        client.set_response("snapshot", json!({ "screenshot": "Menu" }));
+        let mut runner = assert_ok(DaemonRunner::select(temp.path(), RunnerKind::Rpc, || {
+            Ok(client.clone())
+        }));
+
+        let (screen, cursor) = assert_ok(runner.screenshot("s1"));
+        assert_eq!(screen, "Menu");
+        assert!(!cursor.visible);
+        assert_ok(runner.resize("s1", 90, 20));
+        assert_eq!(client.call_count("snapshot"), 1);
+        assert_eq!(
+            client.params_for("resize"),
+            vec![Some(json!({ "cols": 90, "rows": 20, "session": "s1" }))]
+        );
+    }
+
+    #[test]
+    fn rpc_kill_ignores_missing_session() {
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        // A strict mock answers every unconfigured method with an RPC error, like
+        // the daemon does for an unknown session.
+        let client = MockClient::new_strict();
+        let mut runner = RpcRunner::with_client(client.clone(), AgentTuiRunner::new(temp.path()));
+        assert_ok(runner.kill("gone"));
+        assert_eq!(client.call_count("kill"), 1);
+    }
+
+    #[test]
+    fn rpc_action_without_success_field_fails() {
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+        let mut client = MockClient::new();
+        client.set_response("keystroke", json!({ "ok": true }));
+        client.set_response("type", json!({}));
+        let mut runner = RpcRunner::with_client(client, AgentTuiRunner::new(temp.path()));
+
+        for result in [runner.press("s1", "Enter"), runner.type_text("s1", "hi")] {
+            let error = match result {
+                Ok(()) => panic!("expected a response without success to fail"),
+                Err(error) => error,
+            };
+            assert_eq!(error.kind, ExplorerErrorKind::Scenario);
+            assert!(error.message.contains("missing success field"), "{error}");
+        }
+    }
+
+    #[test]
+    fn rpc_error_maps_transport_failures_to_unavailable() {
+        let kind = |error: ClientError| rpc_error(error).kind;
+        assert_eq!(
+            kind(ClientError::DaemonNotRunning),
+            ExplorerErrorKind::Unavailable
+        );
+        assert_eq!(
+            kind(ClientError::ConnectionFailed(std::io::Error::from(
+                std::io::ErrorKind::ConnectionRefused
+            ))),
+            ExplorerErrorKind::Unavailable
+        );
+        // A read timeout is a slow step, not a missing daemon.
+        assert_eq!(
+            kind(ClientError::ConnectionFailed(std::io::Error::from(
+                std::io::ErrorKind::TimedOut
+            ))),
+            ExplorerErrorKind::Scenario
+        );
+        assert_eq!(
+            kind(ClientError::RpcError {
+                code: -32000,
+                message: "Session not found".to_string(),
+                category: None,
+                retryable: false,
+                context: None,
+                suggestion: None,
+            }),
+            ExplorerErrorKind::Scenario
+        );
+    }
+
+    #[test]
+    fn verify_script_reports_failed_step() {
+        let mut runner = FakeRunner::new(as_map(vec![(Vec::new(), "Login")]));
+        let temp = match tempdir() {
+            Ok(path) => path,
+            Err(error) => panic!("failed to create temp dir: {error}"),
+        };
+
+        assert!(is_script_path(Path::new("flows/login.YAML")));
+        assert!(!is_script_path(Path::new("acceptance.md")));
+        let report = assert_ok(verify_script_with_runner(
+            Path::new("login.yaml"),
             &mut runner,
             temp.path(),
-            Some("Two"),
-            true,
         ));
 
         assert_eq!(report.total_scenarios, 1);
-        assert_eq!(report.failed_scenarios, 0);
+        assert_eq!(report.failed_scenarios, 1);
+        assert_eq!(report.results[0].name, "login");
+        assert_eq!(report.results[0].failed_step, Some(2));
+        assert_eq!(
+            report.results[0].message.as_deref(),
+            Some("wait \"Welcome\": timed out after 3000ms")
+        );
+        assert!(temp.path().join("failures/login-step-2.txt").exists());
     }
 
     #[test]
@@ -2090,6 +6300,11 @@ mod tests {
             out_dir: temp.path().to_path_buf(),
             allow_risky: false,
             default_timeout_ms: 3000,
+            jobs: 1,
+            normalizers: Normalizers::default(),
+            strategy: Strategy::Bfs,
+            seed: 0,
+            screen_actions: false,
         };
 
         let (report, _spec, _traces) = assert_ok(discover_with_runner(&config, &mut runner));